@@ -0,0 +1,457 @@
+//! Implementation of each subcommand
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use process::{
+    arch_label, build_process_tree, compare_processes, flatten_tree, format_start_time,
+    get_memory_protect_name, get_memory_state_name, get_memory_type_name, get_priority_name,
+    get_process_memory_regions, get_process_modules, get_process_threads, get_processes,
+    matches_process, prune_tree, ProcessInfo, ProcessSortColumn, SortOrder,
+};
+use serde::Serialize;
+
+use crate::output::print_status;
+use crate::output::{print_listing, Format, Table};
+use crate::{Args, CliError};
+
+/// Gap between the two samples `ps` takes so CPU usage has a baseline
+const CPU_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// JSON body printed by action commands
+#[derive(Serialize)]
+struct ActionResult<'a> {
+    action: &'a str,
+    target: String,
+    success: bool,
+}
+
+fn print_action(
+    format: Format,
+    action: &str,
+    target: String,
+    message: &str,
+) -> Result<(), CliError> {
+    print_status(
+        format,
+        message,
+        &ActionResult {
+            action,
+            target,
+            success: true,
+        },
+    )
+}
+
+/// Sort key for `ps --sort`
+fn parse_sort_column(name: &str) -> Result<ProcessSortColumn, CliError> {
+    match name.to_ascii_lowercase().as_str() {
+        "pid" => Ok(ProcessSortColumn::Pid),
+        "name" => Ok(ProcessSortColumn::Name),
+        "cpu" => Ok(ProcessSortColumn::Cpu),
+        "memory" | "mem" => Ok(ProcessSortColumn::Memory),
+        "threads" => Ok(ProcessSortColumn::Threads),
+        _ => Err(CliError::Usage(format!(
+            "unknown sort column '{}' (expected pid, name, cpu, memory or threads)",
+            name
+        ))),
+    }
+}
+
+/// `ps [filter] [--sort <column>] [--desc] [--tree] [--wide]`
+pub fn ps(args: &Args, format: Format) -> Result<(), CliError> {
+    args.expect(&["--sort", "--desc", "--tree", "--wide", "-w"], 1)?;
+    let query = args.arg(0, "filter").unwrap_or("").to_lowercase();
+    let column = match args.value(&["--sort"]) {
+        Some(name) => parse_sort_column(name)?,
+        None => ProcessSortColumn::Pid,
+    };
+    let order = if args.flag(&["--desc"]) {
+        SortOrder::Descending
+    } else {
+        SortOrder::Ascending
+    };
+    let wide = args.flag(&["--wide", "-w"]);
+
+    // CPU usage is measured between two enumerations
+    get_processes();
+    std::thread::sleep(CPU_SAMPLE_INTERVAL);
+    let processes = get_processes();
+
+    let mut headers = vec![
+        "PID",
+        "PPID",
+        "Name",
+        "CPU %",
+        "Threads",
+        "Memory (MB)",
+        "User",
+    ];
+    if wide {
+        headers.extend([
+            "Session",
+            "Integrity",
+            "Arch",
+            "Started",
+            "Path",
+            "Command Line",
+        ]);
+    } else {
+        headers.push("Path");
+    }
+    let mut table = Table::new(&headers);
+    let mut push_row = |depth: usize, p: &ProcessInfo| {
+        let mut row = vec![
+            p.pid.to_string(),
+            p.parent_pid.to_string(),
+            format!("{}{}", "  ".repeat(depth), p.name),
+            format!("{:.1}", p.cpu_usage),
+            p.thread_count.to_string(),
+            format!("{:.1}", p.memory_mb),
+            p.user.clone().unwrap_or_default(),
+        ];
+        if wide {
+            row.extend([
+                p.session_id.map(|s| s.to_string()).unwrap_or_default(),
+                p.integrity_level.map(|i| i.to_string()).unwrap_or_default(),
+                arch_label(p),
+                format_start_time(p.start_time),
+                p.exe_path.clone(),
+                p.command_line.clone().unwrap_or_default(),
+            ]);
+        } else {
+            row.push(p.exe_path.clone());
+        }
+        table.push(row);
+    };
+
+    if args.flag(&["--tree"]) {
+        let tree = prune_tree(build_process_tree(processes), &query, column, order);
+        let mut rows = Vec::new();
+        flatten_tree(&tree, 0, &HashSet::new(), "", column, order, &mut rows);
+        for (p, state) in &rows {
+            push_row(state.depth, p);
+        }
+        if format.is_delimited() {
+            // CSV and TSV rows cannot nest, so list the tree in display order
+            let flat: Vec<ProcessInfo> = rows.into_iter().map(|(p, _)| p).collect();
+            print_listing(format, &table, &flat)
+        } else {
+            print_listing(format, &table, &tree)
+        }
+    } else {
+        let mut processes: Vec<ProcessInfo> = processes
+            .into_iter()
+            .filter(|p| matches_process(p, &query))
+            .collect();
+        processes.sort_by(|a, b| compare_processes(a, b, column, order));
+        for p in &processes {
+            push_row(0, p);
+        }
+        print_listing(format, &table, &processes)
+    }
+}
+
+/// `threads <pid>`
+pub fn threads(args: &Args, format: Format) -> Result<(), CliError> {
+    args.expect(&[], 1)?;
+    let pid = args.pid(0)?;
+    let threads = get_process_threads(pid).map_err(|e| CliError::Failed(e.to_string()))?;
+
+    let mut table = Table::new(&["TID", "Priority", "Base Priority"]);
+    for t in &threads {
+        table.push(vec![
+            t.thread_id.to_string(),
+            format!("{} ({})", get_priority_name(t.priority), t.priority),
+            t.base_priority.to_string(),
+        ]);
+    }
+    print_listing(format, &table, &threads)
+}
+
+/// `modules <pid>`
+pub fn modules(args: &Args, format: Format) -> Result<(), CliError> {
+    args.expect(&[], 1)?;
+    let pid = args.pid(0)?;
+    let modules = get_process_modules(pid).map_err(|e| CliError::Failed(e.to_string()))?;
+
+    let mut table = Table::new(&["Base", "Size", "Name", "Version", "Company", "Path"]);
+    for m in &modules {
+        table.push(vec![
+            format!("0x{:X}", m.base_address),
+            format!("0x{:X}", m.size),
+            m.name.clone(),
+            m.version.clone().unwrap_or_default(),
+            m.company.clone().unwrap_or_default(),
+            m.path.clone(),
+        ]);
+    }
+    print_listing(format, &table, &modules)
+}
+
+/// `mem <pid>`
+pub fn memory(args: &Args, format: Format) -> Result<(), CliError> {
+    args.expect(&[], 1)?;
+    let pid = args.pid(0)?;
+    let regions = get_process_memory_regions(pid).map_err(|e| CliError::Failed(e.to_string()))?;
+
+    let mut table = Table::new(&[
+        "Base",
+        "Allocation Base",
+        "Size",
+        "State",
+        "Type",
+        "Protect",
+        "Allocation Protect",
+    ]);
+    for r in &regions {
+        table.push(vec![
+            format!("0x{:X}", r.base_address),
+            format!("0x{:X}", r.allocation_base),
+            format!("0x{:X}", r.region_size),
+            get_memory_state_name(r.state).to_string(),
+            get_memory_type_name(r.mem_type).to_string(),
+            get_memory_protect_name(r.protect),
+            get_memory_protect_name(r.allocation_protect),
+        ]);
+    }
+    print_listing(format, &table, &regions)
+}
+
+/// `handles <pid>`
+pub fn handles(args: &Args, format: Format) -> Result<(), CliError> {
+    args.expect(&[], 1)?;
+    let pid = args.pid(0)?;
+    let handles = process::get_process_handles(pid).map_err(|e| CliError::Failed(e.to_string()))?;
+
+    let mut table = Table::new(&["Handle", "Type", "Access"]);
+    for h in &handles {
+        table.push(vec![
+            format!("0x{:X}", h.handle_value),
+            h.object_type_name.clone(),
+            format!("0x{:08X}", h.granted_access),
+        ]);
+    }
+    print_listing(format, &table, &handles)
+}
+
+/// `net [--tcp | --udp] [--state <state>]`
+pub fn net(args: &Args, format: Format) -> Result<(), CliError> {
+    use network::{get_network_connections, NetworkConnection, Protocol};
+
+    args.expect(&["--tcp", "--udp", "--state"], 0)?;
+    let tcp = args.flag(&["--tcp"]);
+    let udp = args.flag(&["--udp"]);
+    let state = args.value(&["--state"]).map(str::to_lowercase);
+
+    let connections: Vec<NetworkConnection> = get_network_connections()
+        .map_err(|e| CliError::Failed(e.to_string()))?
+        .into_iter()
+        .filter(|c| match c.protocol {
+            Protocol::Tcp => tcp || !udp,
+            Protocol::Udp => udp || !tcp,
+        })
+        .filter(|c| match (&state, c.state) {
+            (None, _) => true,
+            (Some(wanted), Some(actual)) => actual.to_string().to_lowercase().contains(wanted),
+            (Some(_), None) => false,
+        })
+        .collect();
+
+    let mut table = Table::new(&["Proto", "Local", "Remote", "State", "PID", "Process"]);
+    for c in &connections {
+        table.push(vec![
+            c.protocol.to_string(),
+            format!("{}:{}", c.local_addr, c.local_port),
+            match c.protocol {
+                Protocol::Tcp => format!("{}:{}", c.remote_addr, c.remote_port),
+                Protocol::Udp => String::from("*:*"),
+            },
+            c.state.map(|s| s.to_string()).unwrap_or_default(),
+            c.pid.to_string(),
+            c.process_name.clone(),
+        ]);
+    }
+    print_listing(format, &table, &connections)
+}
+
+/// `services [--status <status>] [--start-type <type>]`
+pub fn services(args: &Args, format: Format) -> Result<(), CliError> {
+    use service::{get_services, ServiceInfo, ServiceStartType, ServiceStatus};
+
+    args.expect(&["--status", "--start-type"], 0)?;
+    let status = match args.value(&["--status"]) {
+        None => None,
+        Some("running") => Some(ServiceStatus::Running),
+        Some("stopped") => Some(ServiceStatus::Stopped),
+        Some("paused") => Some(ServiceStatus::Paused),
+        Some(other) => {
+            return Err(CliError::Usage(format!(
+                "unknown service status '{}'",
+                other
+            )));
+        }
+    };
+    let start_type = match args.value(&["--start-type"]) {
+        None => None,
+        Some("auto") => Some(ServiceStartType::Auto),
+        Some("manual") => Some(ServiceStartType::Manual),
+        Some("disabled") => Some(ServiceStartType::Disabled),
+        Some(other) => {
+            return Err(CliError::Usage(format!("unknown start type '{}'", other)));
+        }
+    };
+
+    let services: Vec<ServiceInfo> = get_services()
+        .map_err(|e| CliError::Failed(e.to_string()))?
+        .into_iter()
+        .filter(|s| status.is_none_or(|wanted| s.status == wanted))
+        .filter(|s| start_type.is_none_or(|wanted| s.start_type == wanted))
+        .collect();
+
+    let mut table = Table::new(&[
+        "Name",
+        "Display Name",
+        "Status",
+        "Start Type",
+        "PID",
+        "Path",
+    ]);
+    for s in &services {
+        table.push(vec![
+            s.name.clone(),
+            s.display_name.clone(),
+            s.status.to_string(),
+            s.start_type.to_string(),
+            if s.pid > 0 {
+                s.pid.to_string()
+            } else {
+                String::new()
+            },
+            s.binary_path.clone(),
+        ]);
+    }
+    print_listing(format, &table, &services)
+}
+
+/// Run a process action on the PID argument
+fn process_action(
+    args: &Args,
+    format: Format,
+    action: &str,
+    done: &str,
+    run: fn(u32) -> Result<(), process::ProcessError>,
+) -> Result<(), CliError> {
+    args.expect(&[], 1)?;
+    let pid = args.pid(0)?;
+    run(pid)
+        .map_err(|e| CliError::Failed(format!("failed to {} process {}: {}", action, pid, e)))?;
+    print_action(
+        format,
+        action,
+        pid.to_string(),
+        &format!("Process {} {}", pid, done),
+    )
+}
+
+/// `kill <pid>`
+pub fn kill(args: &Args, format: Format) -> Result<(), CliError> {
+    process_action(args, format, "kill", "terminated", process::kill_process)
+}
+
+/// `suspend <pid>`
+pub fn suspend(args: &Args, format: Format) -> Result<(), CliError> {
+    process_action(
+        args,
+        format,
+        "suspend",
+        "suspended",
+        process::suspend_process,
+    )
+}
+
+/// `resume <pid>`
+pub fn resume(args: &Args, format: Format) -> Result<(), CliError> {
+    process_action(args, format, "resume", "resumed", process::resume_process)
+}
+
+/// `inject <pid> <dll> [--method loadlibrary|hijack|manualmap]`
+pub fn inject(args: &Args, format: Format) -> Result<(), CliError> {
+    args.expect(&["--method"], 2)?;
+    let pid = args.pid(0)?;
+    let dll = args.arg(1, "<dll>")?;
+
+    let result = match args.value(&["--method"]).unwrap_or("loadlibrary") {
+        "loadlibrary" => misc::inject_dll(pid, dll),
+        "hijack" => misc::inject_dll_thread_hijack(pid, dll),
+        "manualmap" => misc::inject_dll_manual_map(pid, dll),
+        other => {
+            return Err(CliError::Usage(format!(
+                "unknown injection method '{}' (expected loadlibrary, hijack or manualmap)",
+                other
+            )));
+        }
+    };
+    result.map_err(|e| CliError::Failed(e.to_string()))?;
+    print_action(
+        format,
+        "inject",
+        pid.to_string(),
+        &format!("Injected {} into process {}", dll, pid),
+    )
+}
+
+/// `service start|stop|delete <name>`
+pub fn service(args: &Args, format: Format) -> Result<(), CliError> {
+    args.expect(&[], 2)?;
+    let action = args.arg(0, "service action (start, stop or delete)")?;
+    let name = args.arg(1, "<name>")?;
+
+    let (result, done) = match action {
+        "start" => (service::start_service(name), "started"),
+        "stop" => (service::stop_service(name), "stopped"),
+        "delete" => (service::delete_service(name), "deleted"),
+        other => {
+            return Err(CliError::Usage(format!(
+                "unknown service action '{}' (expected start, stop or delete)",
+                other
+            )));
+        }
+    };
+    result
+        .map_err(|e| CliError::Failed(format!("failed to {} service '{}': {}", action, name, e)))?;
+    print_action(
+        format,
+        action,
+        name.to_string(),
+        &format!("Service '{}' {}", name, done),
+    )
+}
+
+/// `diff <before> [after]`, comparing against the live system without `after`
+pub fn diff(args: &Args, format: Format) -> Result<(), CliError> {
+    use snapshot::Snapshot;
+
+    args.expect(&[], 2)?;
+    let load = |path: &str| {
+        Snapshot::load(std::path::Path::new(path))
+            .map_err(|e| CliError::Failed(format!("cannot open '{}': {}", path, e)))
+    };
+    let before = load(args.arg(0, "<before> snapshot file")?)?;
+    let after = match args.arg(1, "<after>") {
+        Ok(path) => load(path)?,
+        Err(_) => Snapshot::capture(),
+    };
+    let changes = before.diff(&after).changes();
+
+    let mut table = Table::new(&["Change", "PID", "Name", "Detail"]);
+    for c in &changes {
+        table.push(vec![
+            c.kind.to_string(),
+            c.pid.map(|pid| pid.to_string()).unwrap_or_default(),
+            c.name.clone(),
+            c.detail.clone(),
+        ]);
+    }
+    print_listing(format, &table, &changes)
+}
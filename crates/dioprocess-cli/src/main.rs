@@ -0,0 +1,281 @@
+//! Headless command-line front end to the dioprocess library crates
+//! Lists processes, threads, modules, handles, memory, connections and
+//! services, runs process actions and compares snapshots, without the
+//! desktop window
+
+mod commands;
+mod output;
+
+use std::fmt;
+use std::process::ExitCode;
+
+use output::Format;
+
+const USAGE: &str = "\
+Usage: dioprocess-cli [--format table|csv|tsv|json|ndjson] <command> [arguments]
+
+Commands:
+  ps [filter] [--sort pid|name|cpu|memory|threads] [--desc] [--tree] [--wide]
+                                 List processes, optionally as a parent/child tree
+  threads <pid>                  List the threads of a process
+  modules <pid>                  List the modules loaded in a process
+  handles <pid>                  List the open handles of a process
+  mem <pid>                      List the virtual memory regions of a process
+  net [--tcp | --udp] [--state <state>]
+                                 List TCP and UDP connections
+  services [--status running|stopped|paused] [--start-type auto|manual|disabled]
+                                 List services
+  kill <pid>                     Terminate a process
+  suspend <pid>                  Suspend every thread of a process
+  resume <pid>                   Resume a suspended process
+  inject <pid> <dll> [--method loadlibrary|hijack|manualmap]
+                                 Load a DLL into a process
+  service start|stop|delete <name>
+                                 Control a service
+  diff <before> [after]          Compare two snapshot files, or one with the live system
+
+Options:
+  -f, --format <format>          table (default), csv, tsv, json or ndjson
+  -h, --help                     Show this help
+  -V, --version                  Show the version
+";
+
+/// Options that take a value; every other `-x` / `--xyz` word is a flag
+const VALUE_OPTIONS: &[&str] = &[
+    "-f",
+    "--format",
+    "--sort",
+    "--state",
+    "--status",
+    "--start-type",
+    "--method",
+];
+
+/// Errors reported to the user, with the process exit code they map to
+#[derive(Debug)]
+pub enum CliError {
+    /// Bad command line; the usage text is printed after the message
+    Usage(String),
+    /// The operation was attempted and failed
+    Failed(String),
+}
+
+impl CliError {
+    fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Usage(_) => 2,
+            CliError::Failed(_) => 1,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(msg) => write!(f, "{}", msg),
+            CliError::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// Command line split into positional words and options
+pub struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+    fn parse(words: impl IntoIterator<Item = String>) -> Result<Self, CliError> {
+        let mut positional = Vec::new();
+        let mut options = Vec::new();
+        let mut words = words.into_iter();
+
+        while let Some(word) = words.next() {
+            if word.len() > 1 && word.starts_with('-') && word.parse::<i64>().is_err() {
+                // Accept both `--format json` and `--format=json`
+                let (name, inline) = match word.split_once('=') {
+                    Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                    None => (word, None),
+                };
+                let value = if VALUE_OPTIONS.contains(&name.as_str()) {
+                    match inline {
+                        Some(value) => Some(value),
+                        None => Some(words.next().ok_or_else(|| {
+                            CliError::Usage(format!("option '{}' needs a value", name))
+                        })?),
+                    }
+                } else if inline.is_some() {
+                    return Err(CliError::Usage(format!(
+                        "option '{}' does not take a value",
+                        name
+                    )));
+                } else {
+                    None
+                };
+                options.push((name, value));
+            } else {
+                positional.push(word);
+            }
+        }
+
+        Ok(Self {
+            positional,
+            options,
+        })
+    }
+
+    /// Whether a flag (or any of its spellings) was given
+    pub fn flag(&self, names: &[&str]) -> bool {
+        self.options
+            .iter()
+            .any(|(name, _)| names.contains(&name.as_str()))
+    }
+
+    /// Value of the last occurrence of an option
+    pub fn value(&self, names: &[&str]) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(name, _)| names.contains(&name.as_str()))
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    /// Positional argument after the command name
+    pub fn arg(&self, index: usize, what: &str) -> Result<&str, CliError> {
+        self.positional
+            .get(index + 1)
+            .map(String::as_str)
+            .ok_or_else(|| CliError::Usage(format!("missing {}", what)))
+    }
+
+    /// PID given as positional argument `index`
+    pub fn pid(&self, index: usize) -> Result<u32, CliError> {
+        let text = self.arg(index, "<pid>")?;
+        text.parse()
+            .map_err(|_| CliError::Usage(format!("'{}' is not a valid PID", text)))
+    }
+
+    /// Reject options and extra positionals the command does not understand
+    pub fn expect(&self, options: &[&str], max_positional: usize) -> Result<(), CliError> {
+        const GLOBAL: &[&str] = &["-f", "--format"];
+        if let Some((name, _)) = self
+            .options
+            .iter()
+            .find(|(name, _)| !options.contains(&name.as_str()) && !GLOBAL.contains(&name.as_str()))
+        {
+            return Err(CliError::Usage(format!("unknown option '{}'", name)));
+        }
+        if let Some(extra) = self.positional.get(max_positional + 1) {
+            return Err(CliError::Usage(format!("unexpected argument '{}'", extra)));
+        }
+        Ok(())
+    }
+}
+
+fn run(args: &Args) -> Result<(), CliError> {
+    let format = match args.value(&["-f", "--format"]) {
+        Some(name) => Format::parse(name)?,
+        None => Format::Table,
+    };
+
+    let Some(command) = args.positional.first() else {
+        return Err(CliError::Usage(String::from("missing command")));
+    };
+
+    match command.as_str() {
+        "ps" => commands::ps(args, format),
+        "threads" => commands::threads(args, format),
+        "modules" => commands::modules(args, format),
+        "handles" => commands::handles(args, format),
+        "mem" => commands::memory(args, format),
+        "net" => commands::net(args, format),
+        "services" => commands::services(args, format),
+        "kill" => commands::kill(args, format),
+        "suspend" => commands::suspend(args, format),
+        "resume" => commands::resume(args, format),
+        "inject" => commands::inject(args, format),
+        "service" => commands::service(args, format),
+        "diff" => commands::diff(args, format),
+        other => Err(CliError::Usage(format!("unknown command '{}'", other))),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(e.exit_code());
+        }
+    };
+
+    if args.flag(&["-h", "--help"]) || args.positional.first().is_some_and(|c| c == "help") {
+        print!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    if args.flag(&["-V", "--version"]) {
+        println!("dioprocess-cli {}", env!("CARGO_PKG_VERSION"));
+        return ExitCode::SUCCESS;
+    }
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e @ CliError::Usage(_)) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            ExitCode::from(e.exit_code())
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Args, CliError> {
+        Args::parse(line.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn splits_positionals_flags_and_values() {
+        let args = parse("ps chrome --sort=cpu --desc -f json").unwrap();
+        assert_eq!(args.positional, vec!["ps", "chrome"]);
+        assert_eq!(args.value(&["--sort"]), Some("cpu"));
+        assert_eq!(args.value(&["-f", "--format"]), Some("json"));
+        assert!(args.flag(&["--desc"]));
+        assert!(!args.flag(&["--tree"]));
+        assert!(args.expect(&["--sort", "--desc"], 1).is_ok());
+    }
+
+    #[test]
+    fn rejects_bad_options() {
+        assert!(matches!(parse("ps --sort"), Err(CliError::Usage(_))));
+        assert!(matches!(parse("ps --desc=yes"), Err(CliError::Usage(_))));
+
+        let args = parse("threads 12 34 --tree").unwrap();
+        assert!(matches!(
+            args.expect(&["--tree"], 1),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(args.expect(&[], 2), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn parses_pids() {
+        let args = parse("kill 4120").unwrap();
+        assert_eq!(args.pid(0).unwrap(), 4120);
+        assert!(matches!(
+            parse("kill x").unwrap().pid(0),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            parse("kill").unwrap().pid(0),
+            Err(CliError::Usage(_))
+        ));
+    }
+}
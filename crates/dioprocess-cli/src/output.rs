@@ -0,0 +1,186 @@
+//! Text table rendering of command results, and machine-readable output
+//! through the shared export writers
+
+use std::io::{self, Write};
+
+use export::ExportFormat;
+use serde::Serialize;
+
+use crate::CliError;
+
+/// Output format selected with `--format`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Export(ExportFormat),
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Result<Self, CliError> {
+        if name.eq_ignore_ascii_case("table") {
+            return Ok(Format::Table);
+        }
+        ExportFormat::from_name(name)
+            .map(Format::Export)
+            .ok_or_else(|| {
+                CliError::Usage(format!(
+                    "unknown format '{}' (expected table, csv, tsv, json or ndjson)",
+                    name
+                ))
+            })
+    }
+
+    /// CSV and TSV, which need flat records
+    pub fn is_delimited(&self) -> bool {
+        matches!(
+            self,
+            Format::Export(ExportFormat::Csv) | Format::Export(ExportFormat::Tsv)
+        )
+    }
+}
+
+/// Column headers and stringified cells of one listing
+pub struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&'static str]) -> Self {
+        Self {
+            headers: headers.to_vec(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<String>) {
+        debug_assert_eq!(row.len(), self.headers.len());
+        self.rows.push(row);
+    }
+
+    /// Space-padded columns under a dashed header line
+    fn render_text(&self) -> String {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let mut out = String::new();
+        let header: Vec<String> = self.headers.iter().map(|h| h.to_string()).collect();
+        push_padded(&mut out, &header, &widths);
+        let rule: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+        push_padded(&mut out, &rule, &widths);
+        for row in &self.rows {
+            push_padded(&mut out, row, &widths);
+        }
+        out
+    }
+}
+
+fn push_padded(out: &mut String, cells: &[String], widths: &[usize]) {
+    let last = cells.len().saturating_sub(1);
+    for (index, (cell, &width)) in cells.iter().zip(widths).enumerate() {
+        out.push_str(cell);
+        if index < last {
+            let pad = width - cell.chars().count();
+            out.push_str(&" ".repeat(pad + 2));
+        }
+    }
+    out.push('\n');
+}
+
+/// Print a listing: `table` for the text format, `items` for the others
+pub fn print_listing<T: Serialize>(
+    format: Format,
+    table: &Table,
+    items: &[T],
+) -> Result<(), CliError> {
+    let text = match format {
+        Format::Table => table.render_text(),
+        Format::Export(format) => {
+            export::to_string(format, items).map_err(|e| CliError::Failed(e.to_string()))?
+        }
+    };
+    write_stdout(&text)
+}
+
+/// Print the outcome of an action as a line of text or a JSON object
+pub fn print_status<T: Serialize>(
+    format: Format,
+    message: &str,
+    value: &T,
+) -> Result<(), CliError> {
+    match format {
+        Format::Export(ExportFormat::Json) => write_stdout(&json(value, true)?),
+        Format::Export(ExportFormat::Ndjson) => write_stdout(&json(value, false)?),
+        _ => write_stdout(&format!("{}\n", message)),
+    }
+}
+
+fn json<T: Serialize>(value: &T, pretty: bool) -> Result<String, CliError> {
+    let encoded = if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    };
+    let mut text =
+        encoded.map_err(|e| CliError::Failed(format!("failed to encode JSON: {}", e)))?;
+    text.push('\n');
+    Ok(text)
+}
+
+/// Write to stdout, treating a closed pipe (`| head`) as success
+fn write_stdout(text: &str) -> Result<(), CliError> {
+    let mut stdout = io::stdout().lock();
+    match stdout
+        .write_all(text.as_bytes())
+        .and_then(|_| stdout.flush())
+    {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => {
+            Err(CliError::Failed(format!("failed to write output: {}", e)))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Table {
+        let mut table = Table::new(&["PID", "Name", "Command Line"]);
+        table.push(vec!["4".into(), "System".into(), String::new()]);
+        table.push(vec![
+            "4120".into(),
+            "notepad.exe".into(),
+            "\"C:\\Windows\\notepad.exe\" a,b.txt".into(),
+        ]);
+        table
+    }
+
+    #[test]
+    fn text_columns_are_padded_to_the_widest_cell() {
+        let text = sample().render_text();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "PID   Name         Command Line");
+        assert_eq!(
+            lines[1],
+            "----  -----------  --------------------------------"
+        );
+        assert_eq!(lines[2], "4     System       ");
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!(
+            Format::parse("JSON").unwrap(),
+            Format::Export(ExportFormat::Json)
+        );
+        assert_eq!(Format::parse("table").unwrap(), Format::Table);
+        assert!(Format::parse("tsv").unwrap().is_delimited());
+        assert!(!Format::parse("ndjson").unwrap().is_delimited());
+        assert!(matches!(Format::parse("xml"), Err(CliError::Usage(_))));
+    }
+}
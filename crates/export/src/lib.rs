@@ -0,0 +1,394 @@
+//! Export of serializable records to CSV, TSV, JSON and NDJSON
+//!
+//! Delimited formats flatten each record (a struct, possibly with
+//! `#[serde(flatten)]` members, or a map) into one line whose header is the
+//! field names of the first record. JSON and NDJSON keep serde's usual
+//! representation.
+
+mod row;
+
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+
+use serde::Serialize;
+
+use row::{to_row, Row};
+
+/// Errors that can occur while exporting records.
+#[derive(Debug)]
+pub enum ExportError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    /// A record (or one of its fields) has a shape the format cannot hold
+    Unsupported(String),
+    /// A record's `Serialize` implementation failed
+    Serialize(String),
+    /// A record's fields differ from the header taken from the first record
+    FieldMismatch {
+        index: usize,
+    },
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Io(e) => write!(f, "Failed to write export: {}", e),
+            ExportError::Json(e) => write!(f, "Failed to encode JSON: {}", e),
+            ExportError::Unsupported(msg) => write!(f, "Unsupported record: {}", msg),
+            ExportError::Serialize(msg) => write!(f, "Failed to serialize record: {}", msg),
+            ExportError::FieldMismatch { index } => {
+                write!(f, "Record {} has different fields than the header", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl serde::ser::Error for ExportError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ExportError::Serialize(msg.to_string())
+    }
+}
+
+impl From<io::Error> for ExportError {
+    fn from(e: io::Error) -> Self {
+        ExportError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ExportError {
+    fn from(e: serde_json::Error) -> Self {
+        if e.is_io() {
+            ExportError::Io(e.into())
+        } else {
+            ExportError::Json(e)
+        }
+    }
+}
+
+/// Export file format
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+    /// Comma-separated values, quoted per RFC 4180
+    Csv,
+    /// Tab-separated values, with `\t`, `\n`, `\r` and `\\` escaped
+    Tsv,
+    /// One pretty-printed JSON array
+    Json,
+    /// One compact JSON object per line
+    Ndjson,
+}
+
+impl ExportFormat {
+    /// Every format, in the order offered to the user
+    pub const ALL: [ExportFormat; 4] = [
+        ExportFormat::Csv,
+        ExportFormat::Tsv,
+        ExportFormat::Json,
+        ExportFormat::Ndjson,
+    ];
+
+    /// File extension without the dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Tsv => "tsv",
+            ExportFormat::Json => "json",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+
+    /// Format for a file extension or format name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "tsv" | "tab" => Some(ExportFormat::Tsv),
+            "json" => Some(ExportFormat::Json),
+            "ndjson" | "jsonl" => Some(ExportFormat::Ndjson),
+            _ => None,
+        }
+    }
+
+    /// Format matching a file's extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::from_name(path.extension()?.to_str()?)
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportFormat::Csv => write!(f, "CSV"),
+            ExportFormat::Tsv => write!(f, "TSV"),
+            ExportFormat::Json => write!(f, "JSON"),
+            ExportFormat::Ndjson => write!(f, "NDJSON"),
+        }
+    }
+}
+
+/// Write `records` to `writer`.
+///
+/// An empty slice produces an empty delimited file (there is no record to
+/// take the header from), `[]` for JSON and nothing for NDJSON.
+pub fn write_records<T: Serialize, W: Write>(
+    format: ExportFormat,
+    records: &[T],
+    mut writer: W,
+) -> Result<(), ExportError> {
+    match format {
+        ExportFormat::Csv => write_delimited(records, &mut writer, ",", "\r\n", csv_field)?,
+        ExportFormat::Tsv => write_delimited(records, &mut writer, "\t", "\n", tsv_field)?,
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, records)?;
+            writer.write_all(b"\n")?;
+        }
+        ExportFormat::Ndjson => {
+            for record in records {
+                serde_json::to_writer(&mut writer, record)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Render `records` into a string
+pub fn to_string<T: Serialize>(format: ExportFormat, records: &[T]) -> Result<String, ExportError> {
+    let mut buffer = Vec::new();
+    write_records(format, records, &mut buffer)?;
+    // Every writer emits UTF-8 built from `String`s
+    Ok(String::from_utf8(buffer).expect("export output is UTF-8"))
+}
+
+/// Write `records` to a file in the format given by its extension (CSV when
+/// the extension is missing or unknown), returning the format used
+pub fn save<T: Serialize>(path: &Path, records: &[T]) -> Result<ExportFormat, ExportError> {
+    let format = ExportFormat::from_path(path).unwrap_or(ExportFormat::Csv);
+    let file = std::fs::File::create(path)?;
+    write_records(format, records, io::BufWriter::new(file))?;
+    Ok(format)
+}
+
+fn write_delimited<T: Serialize, W: Write>(
+    records: &[T],
+    writer: &mut W,
+    separator: &str,
+    line_end: &str,
+    escape: fn(&str) -> String,
+) -> Result<(), ExportError> {
+    let mut header: Option<Vec<String>> = None;
+    for (index, record) in records.iter().enumerate() {
+        let Row { names, values } = to_row(record)?;
+        match &header {
+            None => {
+                write_line(writer, &names, separator, line_end, escape)?;
+                header = Some(names);
+            }
+            Some(header) if *header != names => {
+                return Err(ExportError::FieldMismatch { index });
+            }
+            Some(_) => {}
+        }
+        write_line(writer, &values, separator, line_end, escape)?;
+    }
+    Ok(())
+}
+
+fn write_line<W: Write>(
+    writer: &mut W,
+    cells: &[String],
+    separator: &str,
+    line_end: &str,
+    escape: fn(&str) -> String,
+) -> io::Result<()> {
+    let escaped: Vec<String> = cells.iter().map(|cell| escape(cell)).collect();
+    writer.write_all(escaped.join(separator).as_bytes())?;
+    writer.write_all(line_end.as_bytes())
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break, or
+/// starts or ends with whitespace
+pub fn csv_field(field: &str) -> String {
+    let needs_quotes = field.contains([',', '"', '\n', '\r'])
+        || field.starts_with(char::is_whitespace)
+        || field.ends_with(char::is_whitespace);
+    if needs_quotes {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Escape a TSV field so it cannot split a line or a column
+pub fn tsv_field(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    enum Arch {
+        X64,
+        Other(u16),
+    }
+
+    #[derive(Serialize)]
+    struct Process {
+        pid: u32,
+        name: String,
+        memory_mb: f64,
+        user: Option<String>,
+        arch: Option<Arch>,
+        is_wow64: bool,
+    }
+
+    #[derive(Serialize)]
+    struct WithHashes {
+        #[serde(flatten)]
+        process: Process,
+        sha256: String,
+        tags: Vec<&'static str>,
+    }
+
+    fn processes() -> Vec<Process> {
+        vec![
+            Process {
+                pid: 4,
+                name: String::from("System"),
+                memory_mb: 0.5,
+                user: None,
+                arch: Some(Arch::X64),
+                is_wow64: false,
+            },
+            Process {
+                pid: 4120,
+                name: String::from("my \"tool\", v2.exe"),
+                memory_mb: 14.25,
+                user: Some(String::from("DESKTOP\\alice")),
+                arch: Some(Arch::Other(0x1c4)),
+                is_wow64: true,
+            },
+        ]
+    }
+
+    #[test]
+    fn csv_quotes_separators_quotes_and_line_breaks() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("C:\\a,b\\x.exe"), "\"C:\\a,b\\x.exe\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\r\nlines"), "\"two\r\nlines\"");
+        assert_eq!(csv_field(" padded"), "\" padded\"");
+        assert_eq!(csv_field(""), "");
+
+        assert_eq!(
+            to_string(ExportFormat::Csv, &processes()).unwrap(),
+            "pid,name,memory_mb,user,arch,is_wow64\r\n\
+             4,System,0.5,,X64,false\r\n\
+             4120,\"my \"\"tool\"\", v2.exe\",14.25,DESKTOP\\alice,Other(452),true\r\n"
+        );
+    }
+
+    #[test]
+    fn tsv_escapes_tabs_and_line_breaks() {
+        assert_eq!(tsv_field("a\tb\nc\\d"), "a\\tb\\nc\\\\d");
+
+        let tsv = to_string(ExportFormat::Tsv, &processes()).unwrap();
+        let lines: Vec<&str> = tsv.lines().collect();
+        assert_eq!(lines[0], "pid\tname\tmemory_mb\tuser\tarch\tis_wow64");
+        assert_eq!(
+            lines[2],
+            "4120\tmy \"tool\", v2.exe\t14.25\tDESKTOP\\\\alice\tOther(452)\ttrue"
+        );
+    }
+
+    #[test]
+    fn flattened_members_and_lists_become_columns() {
+        let records = vec![WithHashes {
+            process: processes().remove(0),
+            sha256: String::from("ab12"),
+            tags: vec!["critical", "system"],
+        }];
+        let csv = to_string(ExportFormat::Csv, &records).unwrap();
+        assert_eq!(
+            csv,
+            "pid,name,memory_mb,user,arch,is_wow64,sha256,tags\r\n\
+             4,System,0.5,,X64,false,ab12,critical; system\r\n"
+        );
+    }
+
+    #[test]
+    fn json_and_ndjson_keep_serde_shape() {
+        let json = to_string(ExportFormat::Json, &processes()).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[1]["user"], "DESKTOP\\alice");
+        assert_eq!(parsed[0]["user"], serde_json::Value::Null);
+        assert_eq!(parsed[1]["arch"]["Other"], 452);
+
+        let ndjson = to_string(ExportFormat::Ndjson, &processes()).unwrap();
+        let lines: Vec<&str> = ndjson.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let second: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(second["pid"], 4120);
+    }
+
+    #[test]
+    fn empty_exports() {
+        let none: Vec<Process> = Vec::new();
+        assert_eq!(to_string(ExportFormat::Csv, &none).unwrap(), "");
+        assert_eq!(to_string(ExportFormat::Json, &none).unwrap(), "[]\n");
+        assert_eq!(to_string(ExportFormat::Ndjson, &none).unwrap(), "");
+    }
+
+    #[test]
+    fn rejects_records_that_do_not_flatten() {
+        assert!(matches!(
+            to_string(ExportFormat::Csv, &[1, 2, 3]),
+            Err(ExportError::Unsupported(_))
+        ));
+
+        #[derive(Serialize)]
+        struct Nested {
+            inner: Process,
+        }
+        let nested = vec![Nested {
+            inner: processes().remove(0),
+        }];
+        assert!(matches!(
+            to_string(ExportFormat::Csv, &nested),
+            Err(ExportError::Unsupported(_))
+        ));
+        assert!(to_string(ExportFormat::Json, &nested).is_ok());
+    }
+
+    #[test]
+    fn formats_from_extensions() {
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out/processes.NDJSON")),
+            Some(ExportFormat::Ndjson)
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("a.jsonl")),
+            Some(ExportFormat::Ndjson)
+        );
+        assert_eq!(ExportFormat::from_path(Path::new("noext")), None);
+        for format in ExportFormat::ALL {
+            assert_eq!(ExportFormat::from_name(format.extension()), Some(format));
+        }
+    }
+}
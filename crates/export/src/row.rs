@@ -0,0 +1,464 @@
+//! Flattening of one serializable record into named text cells for the
+//! delimited (CSV / TSV) writers
+
+use serde::ser::{self, Impossible, Serialize};
+
+use crate::ExportError;
+
+/// Field names and their text values, in declaration order
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Row {
+    pub names: Vec<String>,
+    pub values: Vec<String>,
+}
+
+/// Flatten a struct or map record. Options become empty cells, unit enum
+/// variants their name, and sequences of scalars are joined with `"; "`.
+pub(crate) fn to_row<T: Serialize + ?Sized>(record: &T) -> Result<Row, ExportError> {
+    let mut row = Row::default();
+    record.serialize(RowSerializer { row: &mut row })?;
+    Ok(row)
+}
+
+fn not_a_record() -> ExportError {
+    ExportError::Unsupported(String::from("records must be structs or maps"))
+}
+
+/// Top-level serializer: accepts a struct or map and collects its fields
+struct RowSerializer<'a> {
+    row: &'a mut Row,
+}
+
+macro_rules! reject_scalars {
+    ($($method:ident($($ty:ty)?)),* $(,)?) => {
+        $(
+            fn $method(self $(, _value: $ty)?) -> Result<(), ExportError> {
+                Err(not_a_record())
+            }
+        )*
+    };
+}
+
+impl<'a> ser::Serializer for RowSerializer<'a> {
+    type Ok = ();
+    type Error = ExportError;
+    type SerializeSeq = Impossible<(), ExportError>;
+    type SerializeTuple = Impossible<(), ExportError>;
+    type SerializeTupleStruct = Impossible<(), ExportError>;
+    type SerializeTupleVariant = Impossible<(), ExportError>;
+    type SerializeMap = RowFields<'a>;
+    type SerializeStruct = RowFields<'a>;
+    type SerializeStructVariant = Impossible<(), ExportError>;
+
+    reject_scalars!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_none(),
+        serialize_unit(),
+    );
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), ExportError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), ExportError> {
+        Err(not_a_record())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+    ) -> Result<(), ExportError> {
+        Err(not_a_record())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), ExportError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), ExportError> {
+        Err(not_a_record())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, ExportError> {
+        Err(not_a_record())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, ExportError> {
+        Err(not_a_record())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, ExportError> {
+        Err(not_a_record())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, ExportError> {
+        Err(not_a_record())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, ExportError> {
+        Ok(RowFields {
+            row: self.row,
+            pending_key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, ExportError> {
+        Ok(RowFields {
+            row: self.row,
+            pending_key: None,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, ExportError> {
+        Err(not_a_record())
+    }
+}
+
+/// Collects the fields of the record being flattened
+struct RowFields<'a> {
+    row: &'a mut Row,
+    /// Map key waiting for its value
+    pending_key: Option<String>,
+}
+
+impl RowFields<'_> {
+    fn push<T: Serialize + ?Sized>(&mut self, name: String, value: &T) -> Result<(), ExportError> {
+        let text = value.serialize(CellSerializer)?;
+        self.row.names.push(name);
+        self.row.values.push(text);
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for RowFields<'_> {
+    type Ok = ();
+    type Error = ExportError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ExportError> {
+        self.push(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<(), ExportError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for RowFields<'_> {
+    type Ok = ();
+    type Error = ExportError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), ExportError> {
+        self.pending_key = Some(key.serialize(CellSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ExportError> {
+        let key = self
+            .pending_key
+            .take()
+            .ok_or_else(|| ExportError::Serialize(String::from("map value without a key")))?;
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<(), ExportError> {
+        Ok(())
+    }
+}
+
+/// Renders one field value as text
+struct CellSerializer;
+
+fn nested(what: &str) -> ExportError {
+    ExportError::Unsupported(format!(
+        "{} fields cannot be written to a delimited file; use JSON",
+        what
+    ))
+}
+
+impl ser::Serializer for CellSerializer {
+    type Ok = String;
+    type Error = ExportError;
+    type SerializeSeq = JoinedCell;
+    type SerializeTuple = JoinedCell;
+    type SerializeTupleStruct = JoinedCell;
+    type SerializeTupleVariant = JoinedCell;
+    type SerializeMap = Impossible<String, ExportError>;
+    type SerializeStruct = Impossible<String, ExportError>;
+    type SerializeStructVariant = Impossible<String, ExportError>;
+
+    fn serialize_bool(self, v: bool) -> Result<String, ExportError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String, ExportError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String, ExportError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String, ExportError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String, ExportError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String, ExportError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String, ExportError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String, ExportError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String, ExportError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<String, ExportError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<String, ExportError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, ExportError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String, ExportError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<String, ExportError> {
+        Ok(v.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    fn serialize_none(self) -> Result<String, ExportError> {
+        Ok(String::new())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String, ExportError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String, ExportError> {
+        Ok(String::new())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, ExportError> {
+        Ok(String::new())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<String, ExportError> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, ExportError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<String, ExportError> {
+        Ok(format!("{}({})", variant, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<JoinedCell, ExportError> {
+        Ok(JoinedCell::new(None, len.unwrap_or(0)))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<JoinedCell, ExportError> {
+        Ok(JoinedCell::new(None, len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<JoinedCell, ExportError> {
+        Ok(JoinedCell::new(None, len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<JoinedCell, ExportError> {
+        Ok(JoinedCell::new(Some(variant), len))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, ExportError> {
+        Err(nested("map"))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, ExportError> {
+        Err(nested(name))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, ExportError> {
+        Err(nested(variant))
+    }
+}
+
+/// A sequence of scalars written into a single cell
+struct JoinedCell {
+    variant: Option<&'static str>,
+    parts: Vec<String>,
+}
+
+impl JoinedCell {
+    fn new(variant: Option<&'static str>, len: usize) -> Self {
+        Self {
+            variant,
+            parts: Vec::with_capacity(len),
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ExportError> {
+        self.parts.push(value.serialize(CellSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> String {
+        let joined = self.parts.join("; ");
+        match self.variant {
+            Some(variant) => format!("{}({})", variant, joined),
+            None => joined,
+        }
+    }
+}
+
+impl ser::SerializeSeq for JoinedCell {
+    type Ok = String;
+    type Error = ExportError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ExportError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<String, ExportError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTuple for JoinedCell {
+    type Ok = String;
+    type Error = ExportError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ExportError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<String, ExportError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleStruct for JoinedCell {
+    type Ok = String;
+    type Error = ExportError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ExportError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<String, ExportError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleVariant for JoinedCell {
+    type Ok = String;
+    type Error = ExportError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ExportError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<String, ExportError> {
+        Ok(self.finish())
+    }
+}
@@ -0,0 +1,39 @@
+//! Linux stand-ins: injection and remote memory operations rely on Win32
+//! APIs, so every function fails with [`MiscError::Unsupported`]
+
+use crate::MiscError;
+
+/// Unsupported: inject a DLL with `CreateRemoteThread` + `LoadLibraryW`
+pub fn inject_dll(_pid: u32, _dll_path: &str) -> Result<(), MiscError> {
+    Err(MiscError::Unsupported("DLL injection"))
+}
+
+/// Unsupported: unload a module with `FreeLibrary`
+pub fn unload_module(_pid: u32, _base_address: usize) -> Result<(), MiscError> {
+    Err(MiscError::Unsupported("Module unloading"))
+}
+
+/// Unsupported: inject a DLL by hijacking an existing thread
+pub fn inject_dll_thread_hijack(_pid: u32, _dll_path: &str) -> Result<(), MiscError> {
+    Err(MiscError::Unsupported("Thread hijack injection"))
+}
+
+/// Unsupported: manually map a DLL into the target
+pub fn inject_dll_manual_map(_pid: u32, _dll_path: &str) -> Result<(), MiscError> {
+    Err(MiscError::Unsupported("Manual map injection"))
+}
+
+/// Unsupported: commit reserved memory in the target
+pub fn commit_memory(_pid: u32, _address: usize, _size: usize) -> Result<(), MiscError> {
+    Err(MiscError::Unsupported("Committing memory"))
+}
+
+/// Unsupported: decommit memory in the target
+pub fn decommit_memory(_pid: u32, _address: usize, _size: usize) -> Result<(), MiscError> {
+    Err(MiscError::Unsupported("Decommitting memory"))
+}
+
+/// Unsupported: release an allocation in the target
+pub fn free_memory(_pid: u32, _allocation_base: usize) -> Result<(), MiscError> {
+    Err(MiscError::Unsupported("Freeing memory"))
+}
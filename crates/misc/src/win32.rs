@@ -0,0 +1,802 @@
+//! DLL injection, unloading and remote memory operations via the Win32 API
+
+use std::ffi::CString;
+use std::path::Path;
+
+use pe::PeImage;
+use windows::core::PCSTR;
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::System::Diagnostics::Debug::{
+    GetThreadContext, SetThreadContext, WriteProcessMemory, CONTEXT, CONTEXT_FULL_AMD64,
+};
+use windows::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32,
+};
+use windows::Win32::System::LibraryLoader::{GetModuleHandleA, GetProcAddress};
+use windows::Win32::System::Memory::{
+    VirtualAllocEx, VirtualFreeEx, MEM_COMMIT, MEM_DECOMMIT, MEM_RELEASE, MEM_RESERVE,
+    PAGE_EXECUTE_READWRITE, PAGE_READWRITE,
+};
+use windows::Win32::System::Threading::{
+    CreateRemoteThread, OpenProcess, OpenThread, ResumeThread, SuspendThread, WaitForSingleObject,
+    PROCESS_ALL_ACCESS, PROCESS_CREATE_THREAD, PROCESS_QUERY_INFORMATION, PROCESS_VM_OPERATION,
+    PROCESS_VM_READ, PROCESS_VM_WRITE, THREAD_GET_CONTEXT, THREAD_SET_CONTEXT,
+    THREAD_SUSPEND_RESUME,
+};
+
+use crate::MiscError;
+
+/// Inject a DLL into a target process by PID.
+///
+/// Uses the classic `OpenProcess` -> `VirtualAllocEx` -> `WriteProcessMemory` ->
+/// `CreateRemoteThread` + `LoadLibraryW` technique.
+///
+/// # Safety
+/// This function uses unsafe Windows API calls to manipulate another process's memory.
+pub fn inject_dll(pid: u32, dll_path: &str) -> Result<(), MiscError> {
+    // Validate DLL exists
+    if !Path::new(dll_path).exists() {
+        return Err(MiscError::FileNotFound(dll_path.to_string()));
+    }
+
+    // Encode DLL path as wide string (UTF-16) with null terminator
+    let wide_path: Vec<u16> = dll_path.encode_utf16().chain(std::iter::once(0)).collect();
+    let wide_path_bytes = wide_path.len() * std::mem::size_of::<u16>();
+
+    unsafe {
+        // Open target process with required permissions
+        let process_handle = OpenProcess(
+            PROCESS_CREATE_THREAD
+                | PROCESS_QUERY_INFORMATION
+                | PROCESS_VM_OPERATION
+                | PROCESS_VM_READ
+                | PROCESS_VM_WRITE,
+            false,
+            pid,
+        )
+        .map_err(|_| MiscError::OpenProcessFailed(pid))?;
+
+        // Allocate memory in target process for the DLL path
+        let remote_mem = VirtualAllocEx(
+            process_handle,
+            Some(std::ptr::null()),
+            wide_path_bytes,
+            MEM_COMMIT | MEM_RESERVE,
+            PAGE_READWRITE,
+        );
+
+        if remote_mem.is_null() {
+            let _ = CloseHandle(process_handle);
+            return Err(MiscError::AllocFailed);
+        }
+
+        // Write the DLL path into the allocated memory
+        let write_result = WriteProcessMemory(
+            process_handle,
+            remote_mem,
+            wide_path.as_ptr() as *const _,
+            wide_path_bytes,
+            None,
+        );
+
+        if write_result.is_err() {
+            let _ = VirtualFreeEx(process_handle, remote_mem, 0, MEM_RELEASE);
+            let _ = CloseHandle(process_handle);
+            return Err(MiscError::WriteFailed);
+        }
+
+        // Resolve LoadLibraryW address from kernel32.dll
+        let kernel32_name = CString::new("kernel32.dll").unwrap();
+        let kernel32 =
+            GetModuleHandleA(PCSTR(kernel32_name.as_ptr() as *const u8)).map_err(|_| {
+                let _ = VirtualFreeEx(process_handle, remote_mem, 0, MEM_RELEASE);
+                let _ = CloseHandle(process_handle);
+                MiscError::GetModuleHandleFailed
+            })?;
+
+        let load_library_name = CString::new("LoadLibraryW").unwrap();
+        let load_library_addr =
+            GetProcAddress(kernel32, PCSTR(load_library_name.as_ptr() as *const u8));
+
+        let load_library_addr = match load_library_addr {
+            Some(addr) => addr,
+            None => {
+                let _ = VirtualFreeEx(process_handle, remote_mem, 0, MEM_RELEASE);
+                let _ = CloseHandle(process_handle);
+                return Err(MiscError::GetProcAddressFailed);
+            }
+        };
+
+        // Cast LoadLibraryW address to the thread start routine type
+        let thread_start: unsafe extern "system" fn(*mut std::ffi::c_void) -> u32 =
+            std::mem::transmute(load_library_addr);
+
+        // Create a remote thread in the target process that calls LoadLibraryW
+        let thread_handle = CreateRemoteThread(
+            process_handle,
+            None,
+            0,
+            Some(thread_start),
+            Some(remote_mem),
+            0,
+            None,
+        )
+        .map_err(|_| {
+            let _ = VirtualFreeEx(process_handle, remote_mem, 0, MEM_RELEASE);
+            let _ = CloseHandle(process_handle);
+            MiscError::CreateRemoteThreadFailed
+        })?;
+
+        // Wait for the remote thread to finish (10 second timeout)
+        let wait_result = WaitForSingleObject(thread_handle, 10_000);
+
+        let _ = CloseHandle(thread_handle);
+        let _ = VirtualFreeEx(process_handle, remote_mem, 0, MEM_RELEASE);
+        let _ = CloseHandle(process_handle);
+
+        // WAIT_OBJECT_0 = 0, WAIT_TIMEOUT = 258
+        if wait_result.0 != 0 {
+            return Err(MiscError::Timeout);
+        }
+
+        Ok(())
+    }
+}
+
+/// Unload a DLL from a target process by calling FreeLibrary remotely.
+///
+/// Uses `OpenProcess` -> `CreateRemoteThread` + `FreeLibrary` with the module
+/// base address as the HMODULE argument.
+///
+/// # Safety
+/// This function uses unsafe Windows API calls to manipulate another process.
+pub fn unload_module(pid: u32, base_address: usize) -> Result<(), MiscError> {
+    unsafe {
+        // Open target process with required permissions
+        let process_handle = OpenProcess(
+            PROCESS_CREATE_THREAD
+                | PROCESS_QUERY_INFORMATION
+                | PROCESS_VM_OPERATION
+                | PROCESS_VM_READ
+                | PROCESS_VM_WRITE,
+            false,
+            pid,
+        )
+        .map_err(|_| MiscError::OpenProcessFailed(pid))?;
+
+        // Resolve FreeLibrary address from kernel32.dll
+        let kernel32_name = CString::new("kernel32.dll").unwrap();
+        let kernel32 =
+            GetModuleHandleA(PCSTR(kernel32_name.as_ptr() as *const u8)).map_err(|_| {
+                let _ = CloseHandle(process_handle);
+                MiscError::GetModuleHandleFailed
+            })?;
+
+        let free_library_name = CString::new("FreeLibrary").unwrap();
+        let free_library_addr =
+            GetProcAddress(kernel32, PCSTR(free_library_name.as_ptr() as *const u8));
+
+        let free_library_addr = match free_library_addr {
+            Some(addr) => addr,
+            None => {
+                let _ = CloseHandle(process_handle);
+                return Err(MiscError::GetProcAddressFailed);
+            }
+        };
+
+        // Cast FreeLibrary address to the thread start routine type
+        let thread_start: unsafe extern "system" fn(*mut std::ffi::c_void) -> u32 =
+            std::mem::transmute(free_library_addr);
+
+        // Create a remote thread that calls FreeLibrary with the module base address
+        let thread_handle = CreateRemoteThread(
+            process_handle,
+            None,
+            0,
+            Some(thread_start),
+            Some(base_address as *const std::ffi::c_void),
+            0,
+            None,
+        )
+        .map_err(|_| {
+            let _ = CloseHandle(process_handle);
+            MiscError::CreateRemoteThreadFailed
+        })?;
+
+        // Wait for the remote thread to finish (10 second timeout)
+        let wait_result = WaitForSingleObject(thread_handle, 10_000);
+
+        let _ = CloseHandle(thread_handle);
+        let _ = CloseHandle(process_handle);
+
+        if wait_result.0 != 0 {
+            return Err(MiscError::Timeout);
+        }
+
+        Ok(())
+    }
+}
+
+/// Inject a DLL into a target process using thread hijacking.
+///
+/// Suspends an existing thread, saves its context, redirects execution to shellcode
+/// that calls `LoadLibraryW`, then restores original execution flow.
+///
+/// # Safety
+/// This function uses unsafe Windows API calls to manipulate another process's threads.
+pub fn inject_dll_thread_hijack(pid: u32, dll_path: &str) -> Result<(), MiscError> {
+    // Validate DLL exists
+    if !Path::new(dll_path).exists() {
+        return Err(MiscError::FileNotFound(dll_path.to_string()));
+    }
+
+    // Encode DLL path as wide string (UTF-16) with null terminator
+    let wide_path: Vec<u16> = dll_path.encode_utf16().chain(std::iter::once(0)).collect();
+    let wide_path_bytes = wide_path.len() * std::mem::size_of::<u16>();
+
+    unsafe {
+        // Open target process with full permissions
+        let process_handle = OpenProcess(PROCESS_ALL_ACCESS, false, pid)
+            .map_err(|_| MiscError::OpenProcessFailed(pid))?;
+
+        // Enumerate threads via CreateToolhelp32Snapshot to find a thread in the target process
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0).map_err(|_| {
+            let _ = CloseHandle(process_handle);
+            MiscError::ThreadEnumerationFailed
+        })?;
+
+        let mut thread_entry = THREADENTRY32 {
+            dwSize: std::mem::size_of::<THREADENTRY32>() as u32,
+            ..Default::default()
+        };
+
+        let mut target_tid: Option<u32> = None;
+
+        if Thread32First(snapshot, &mut thread_entry).is_ok() {
+            loop {
+                if thread_entry.th32OwnerProcessID == pid {
+                    target_tid = Some(thread_entry.th32ThreadID);
+                    break;
+                }
+                thread_entry.dwSize = std::mem::size_of::<THREADENTRY32>() as u32;
+                if Thread32Next(snapshot, &mut thread_entry).is_err() {
+                    break;
+                }
+            }
+        }
+
+        let _ = CloseHandle(snapshot);
+
+        let tid = match target_tid {
+            Some(t) => t,
+            None => {
+                let _ = CloseHandle(process_handle);
+                return Err(MiscError::NoThreadFound(pid));
+            }
+        };
+
+        // Open the target thread
+        let thread_handle = OpenThread(
+            THREAD_SUSPEND_RESUME | THREAD_GET_CONTEXT | THREAD_SET_CONTEXT,
+            false,
+            tid,
+        )
+        .map_err(|_| {
+            let _ = CloseHandle(process_handle);
+            MiscError::OpenThreadFailed(tid)
+        })?;
+
+        // Suspend the thread
+        let suspend_result = SuspendThread(thread_handle);
+        if suspend_result == u32::MAX {
+            let _ = CloseHandle(thread_handle);
+            let _ = CloseHandle(process_handle);
+            return Err(MiscError::SuspendThreadFailed(tid));
+        }
+
+        // Get thread context (save original RIP)
+        let mut context: CONTEXT = std::mem::zeroed();
+        context.ContextFlags = CONTEXT_FULL_AMD64;
+
+        if GetThreadContext(thread_handle, &mut context).is_err() {
+            let _ = ResumeThread(thread_handle);
+            let _ = CloseHandle(thread_handle);
+            let _ = CloseHandle(process_handle);
+            return Err(MiscError::GetContextFailed);
+        }
+
+        let original_rip = context.Rip;
+
+        // Allocate remote memory for DLL path + shellcode
+        // Layout: [DLL path (wide_path_bytes)] [shellcode (~80 bytes)]
+        let shellcode_offset = wide_path_bytes;
+        let total_size = shellcode_offset + 128; // generous buffer for shellcode
+
+        let remote_mem = VirtualAllocEx(
+            process_handle,
+            Some(std::ptr::null()),
+            total_size,
+            MEM_COMMIT | MEM_RESERVE,
+            PAGE_EXECUTE_READWRITE,
+        );
+
+        if remote_mem.is_null() {
+            let _ = ResumeThread(thread_handle);
+            let _ = CloseHandle(thread_handle);
+            let _ = CloseHandle(process_handle);
+            return Err(MiscError::AllocFailed);
+        }
+
+        let dll_path_ptr = remote_mem as u64;
+        let shellcode_ptr = (remote_mem as u64) + shellcode_offset as u64;
+
+        // Write UTF-16 DLL path to remote memory
+        if WriteProcessMemory(
+            process_handle,
+            remote_mem,
+            wide_path.as_ptr() as *const _,
+            wide_path_bytes,
+            None,
+        )
+        .is_err()
+        {
+            let _ = VirtualFreeEx(process_handle, remote_mem, 0, MEM_RELEASE);
+            let _ = ResumeThread(thread_handle);
+            let _ = CloseHandle(thread_handle);
+            let _ = CloseHandle(process_handle);
+            return Err(MiscError::WriteFailed);
+        }
+
+        // Resolve LoadLibraryW address
+        let kernel32_name = CString::new("kernel32.dll").unwrap();
+        let kernel32 =
+            GetModuleHandleA(PCSTR(kernel32_name.as_ptr() as *const u8)).map_err(|_| {
+                let _ = VirtualFreeEx(process_handle, remote_mem, 0, MEM_RELEASE);
+                let _ = ResumeThread(thread_handle);
+                let _ = CloseHandle(thread_handle);
+                let _ = CloseHandle(process_handle);
+                MiscError::GetModuleHandleFailed
+            })?;
+
+        let load_library_name = CString::new("LoadLibraryW").unwrap();
+        let load_library_addr =
+            GetProcAddress(kernel32, PCSTR(load_library_name.as_ptr() as *const u8));
+
+        let load_library_addr = match load_library_addr {
+            Some(addr) => addr as usize as u64,
+            None => {
+                let _ = VirtualFreeEx(process_handle, remote_mem, 0, MEM_RELEASE);
+                let _ = ResumeThread(thread_handle);
+                let _ = CloseHandle(thread_handle);
+                let _ = CloseHandle(process_handle);
+                return Err(MiscError::GetProcAddressFailed);
+            }
+        };
+
+        // Build x64 shellcode
+        // Fixes: proper RFLAGS save, stack alignment, shadow space placement,
+        // and register-clean return via xchg+ret.
+        let mut shellcode: Vec<u8> = Vec::new();
+
+        // pushfq - save RFLAGS (must be first, before any flag-modifying instructions)
+        shellcode.push(0x9C);
+
+        // Save volatile registers (7 pushes)
+        shellcode.push(0x50); // push rax
+        shellcode.push(0x51); // push rcx
+        shellcode.push(0x52); // push rdx
+        shellcode.extend_from_slice(&[0x41, 0x50]); // push r8
+        shellcode.extend_from_slice(&[0x41, 0x51]); // push r9
+        shellcode.extend_from_slice(&[0x41, 0x52]); // push r10
+        shellcode.extend_from_slice(&[0x41, 0x53]); // push r11
+
+        // Save rbp (non-volatile) so we can use it as frame pointer to restore RSP later
+        shellcode.push(0x55); // push rbp
+        shellcode.extend_from_slice(&[0x48, 0x89, 0xE5]); // mov rbp, rsp
+
+        // Align stack to 16 bytes, then allocate 0x20 shadow space
+        shellcode.extend_from_slice(&[0x48, 0x83, 0xE4, 0xF0]); // and rsp, -16
+        shellcode.extend_from_slice(&[0x48, 0x83, 0xEC, 0x20]); // sub rsp, 0x20
+
+        // mov rcx, <dll_path_ptr> (LoadLibraryW argument)
+        shellcode.extend_from_slice(&[0x48, 0xB9]);
+        shellcode.extend_from_slice(&dll_path_ptr.to_le_bytes());
+
+        // mov rax, <LoadLibraryW_addr>
+        shellcode.extend_from_slice(&[0x48, 0xB8]);
+        shellcode.extend_from_slice(&load_library_addr.to_le_bytes());
+
+        // call rax
+        shellcode.extend_from_slice(&[0xFF, 0xD0]);
+
+        // Restore RSP from frame pointer
+        shellcode.extend_from_slice(&[0x48, 0x89, 0xEC]); // mov rsp, rbp
+
+        // Restore rbp
+        shellcode.push(0x5D); // pop rbp
+
+        // Restore volatile registers (reverse order)
+        shellcode.extend_from_slice(&[0x41, 0x5B]); // pop r11
+        shellcode.extend_from_slice(&[0x41, 0x5A]); // pop r10
+        shellcode.extend_from_slice(&[0x41, 0x59]); // pop r9
+        shellcode.extend_from_slice(&[0x41, 0x58]); // pop r8
+        shellcode.push(0x5A); // pop rdx
+        shellcode.push(0x59); // pop rcx
+        shellcode.push(0x58); // pop rax
+
+        // popfq - restore RFLAGS
+        shellcode.push(0x9D);
+
+        // Jump to original RIP without clobbering any register:
+        // push rax (temp save), mov rax <original_rip>, xchg [rsp] rax (swap), ret
+        shellcode.push(0x50); // push rax
+        shellcode.extend_from_slice(&[0x48, 0xB8]); // mov rax, <original_rip>
+        shellcode.extend_from_slice(&original_rip.to_le_bytes());
+        shellcode.extend_from_slice(&[0x48, 0x87, 0x04, 0x24]); // xchg [rsp], rax
+        shellcode.push(0xC3); // ret
+
+        // Write shellcode to remote memory
+        if WriteProcessMemory(
+            process_handle,
+            (remote_mem as usize + shellcode_offset) as *const _,
+            shellcode.as_ptr() as *const _,
+            shellcode.len(),
+            None,
+        )
+        .is_err()
+        {
+            let _ = VirtualFreeEx(process_handle, remote_mem, 0, MEM_RELEASE);
+            let _ = ResumeThread(thread_handle);
+            let _ = CloseHandle(thread_handle);
+            let _ = CloseHandle(process_handle);
+            return Err(MiscError::WriteFailed);
+        }
+
+        // Redirect thread to our shellcode
+        context.Rip = shellcode_ptr;
+
+        if SetThreadContext(thread_handle, &context).is_err() {
+            let _ = VirtualFreeEx(process_handle, remote_mem, 0, MEM_RELEASE);
+            let _ = ResumeThread(thread_handle);
+            let _ = CloseHandle(thread_handle);
+            let _ = CloseHandle(process_handle);
+            return Err(MiscError::SetContextFailed);
+        }
+
+        // Resume the thread
+        let resume_result = ResumeThread(thread_handle);
+        if resume_result == u32::MAX {
+            let _ = CloseHandle(thread_handle);
+            let _ = CloseHandle(process_handle);
+            return Err(MiscError::ResumeThreadFailed(tid));
+        }
+
+        // NOTE: We intentionally do NOT free remote_mem here.
+        // The hijacked thread runs asynchronously - we have no way to know when the
+        // shellcode has finished executing. Freeing the memory while the thread is
+        // still running (or hasn't been scheduled yet) would cause a crash.
+        // The allocation is small (DLL path + ~80 bytes shellcode) and is an
+        // acceptable trade-off for stability.
+        let _ = CloseHandle(thread_handle);
+        let _ = CloseHandle(process_handle);
+
+        Ok(())
+    }
+}
+
+/// Inject a DLL into a target process using manual mapping.
+///
+/// Reads the DLL file, maps it into the target process manually (sections, relocations,
+/// imports), then runs its TLS callbacks and DllMain via a remote thread.
+///
+/// # Safety
+/// This function uses unsafe Windows API calls to manipulate another process's memory.
+pub fn inject_dll_manual_map(pid: u32, dll_path: &str) -> Result<(), MiscError> {
+    // Validate and read DLL file
+    if !Path::new(dll_path).exists() {
+        return Err(MiscError::FileNotFound(dll_path.to_string()));
+    }
+
+    let data =
+        std::fs::read(dll_path).map_err(|_| MiscError::FileReadFailed(dll_path.to_string()))?;
+
+    let pe_image = PeImage::parse(&data).map_err(|e| MiscError::InvalidPE(e.to_string()))?;
+    if !pe_image.is_64bit() {
+        return Err(MiscError::InvalidPE(
+            "Only PE32+ (64-bit) DLLs are supported".into(),
+        ));
+    }
+
+    let entry_point_rva = pe_image.optional_header.address_of_entry_point as usize;
+    let image_base = pe_image.optional_header.image_base;
+    let size_of_image = pe_image.optional_header.size_of_image as usize;
+    let import_dir = pe_image.data_directory(pe::DIRECTORY_IMPORT);
+    // TLS callbacks run before DllMain (implicit TLS slots are not allocated)
+    let tls_callbacks = pe_image.tls().map(|tls| tls.callbacks).unwrap_or_default();
+
+    // Build local image buffer (headers + sections at their virtual addresses)
+    let mut image = pe_image
+        .map_image()
+        .map_err(|e| MiscError::InvalidPE(e.to_string()))?;
+
+    unsafe {
+        // Open target process
+        let process_handle = OpenProcess(PROCESS_ALL_ACCESS, false, pid)
+            .map_err(|_| MiscError::OpenProcessFailed(pid))?;
+
+        // Allocate remote memory for the full image
+        let remote_base = VirtualAllocEx(
+            process_handle,
+            Some(std::ptr::null()),
+            size_of_image,
+            MEM_COMMIT | MEM_RESERVE,
+            PAGE_EXECUTE_READWRITE,
+        );
+
+        if remote_base.is_null() {
+            let _ = CloseHandle(process_handle);
+            return Err(MiscError::AllocFailed);
+        }
+
+        let actual_base = remote_base as u64;
+        let delta = actual_base.wrapping_sub(image_base) as i64;
+
+        // Process base relocations
+        if delta != 0 {
+            pe::apply_relocations(&mut image, &pe_image.relocations(), delta);
+        }
+
+        // Resolve imports
+        if let Some(import_dir) = import_dir {
+            let mut desc_offset = import_dir.virtual_address as usize;
+            while let (Some(ilt_rva), Some(name_rva), Some(iat_rva)) = (
+                pe::read_u32(&image, desc_offset),
+                pe::read_u32(&image, desc_offset + 12),
+                pe::read_u32(&image, desc_offset + 16),
+            ) {
+                let (ilt_rva, name_rva, iat_rva) =
+                    (ilt_rva as usize, name_rva as usize, iat_rva as usize);
+
+                // Null descriptor terminates the list
+                if name_rva == 0 && ilt_rva == 0 {
+                    break;
+                }
+
+                // Read DLL name from image buffer
+                let dll_name = pe::read_cstring(&image, name_rva).unwrap_or_default();
+                let dll_cname = CString::new(dll_name.as_str()).unwrap_or_default();
+
+                let module = GetModuleHandleA(PCSTR(dll_cname.as_ptr() as *const u8));
+                let module = match module {
+                    Ok(m) => m,
+                    Err(_) => {
+                        // Skip DLLs we can't resolve locally
+                        desc_offset += 20;
+                        continue;
+                    }
+                };
+
+                // Walk the ILT (or IAT if ILT is 0)
+                let thunk_rva = if ilt_rva != 0 { ilt_rva } else { iat_rva };
+                let mut thunk_off = thunk_rva;
+                let mut iat_off = iat_rva;
+
+                loop {
+                    if iat_off + 8 > size_of_image {
+                        break;
+                    }
+
+                    let thunk_value = match pe::read_u64(&image, thunk_off) {
+                        Some(0) | None => break,
+                        Some(v) => v,
+                    };
+
+                    let func_addr: u64;
+
+                    // Check ordinal flag (bit 63 for PE32+)
+                    if thunk_value & (1u64 << 63) != 0 {
+                        let ordinal = (thunk_value & 0xFFFF) as u16;
+                        let addr = GetProcAddress(module, PCSTR(ordinal as usize as *const u8));
+                        func_addr = match addr {
+                            Some(a) => a as usize as u64,
+                            None => 0,
+                        };
+                    } else {
+                        // Hint/Name: 2-byte hint + name string
+                        let hint_name_rva = (thunk_value & 0x7FFFFFFF) as usize;
+                        if let Some(func_name) = pe::read_cstring(&image, hint_name_rva + 2) {
+                            let func_cname = CString::new(func_name.as_str()).unwrap_or_default();
+                            let addr =
+                                GetProcAddress(module, PCSTR(func_cname.as_ptr() as *const u8));
+                            func_addr = match addr {
+                                Some(a) => a as usize as u64,
+                                None => 0,
+                            };
+                        } else {
+                            func_addr = 0;
+                        }
+                    }
+
+                    // Write resolved address to the IAT in our local buffer
+                    image[iat_off..iat_off + 8].copy_from_slice(&func_addr.to_le_bytes());
+
+                    thunk_off += 8;
+                    iat_off += 8;
+                }
+
+                desc_offset += 20;
+            }
+        }
+
+        // Write the processed image to remote memory
+        if WriteProcessMemory(
+            process_handle,
+            remote_base,
+            image.as_ptr() as *const _,
+            size_of_image,
+            None,
+        )
+        .is_err()
+        {
+            let _ = VirtualFreeEx(process_handle, remote_base, 0, MEM_RELEASE);
+            let _ = CloseHandle(process_handle);
+            return Err(MiscError::WriteFailed);
+        }
+
+        // Build loader shellcode: TLS callbacks first, then DllMain, each
+        // called as fn(hModule, DLL_PROCESS_ATTACH, NULL) like the real loader
+        let entry_addr = actual_base + entry_point_rva as u64;
+        let call_targets: Vec<u64> = tls_callbacks
+            .iter()
+            .map(|&rva| actual_base + rva as u64)
+            .chain(std::iter::once(entry_addr))
+            .collect();
+        let mut shellcode: Vec<u8> = Vec::new();
+
+        // sub rsp, 0x28 (shadow space + alignment)
+        shellcode.extend_from_slice(&[0x48, 0x83, 0xEC, 0x28]);
+
+        for target in call_targets {
+            // mov rcx, <remote_base> (hModule = DLL base)
+            shellcode.extend_from_slice(&[0x48, 0xB9]);
+            shellcode.extend_from_slice(&actual_base.to_le_bytes());
+
+            // mov rdx, 1 (DLL_PROCESS_ATTACH)
+            shellcode.extend_from_slice(&[0x48, 0xC7, 0xC2, 0x01, 0x00, 0x00, 0x00]);
+
+            // xor r8, r8 (lpvReserved = NULL)
+            shellcode.extend_from_slice(&[0x4D, 0x31, 0xC0]);
+
+            // mov rax, <callback / entry point>
+            shellcode.extend_from_slice(&[0x48, 0xB8]);
+            shellcode.extend_from_slice(&target.to_le_bytes());
+
+            // call rax
+            shellcode.extend_from_slice(&[0xFF, 0xD0]);
+        }
+
+        // add rsp, 0x28
+        shellcode.extend_from_slice(&[0x48, 0x83, 0xC4, 0x28]);
+
+        // ret
+        shellcode.push(0xC3);
+
+        // Allocate + write shellcode to separate remote memory
+        let shellcode_mem = VirtualAllocEx(
+            process_handle,
+            Some(std::ptr::null()),
+            shellcode.len(),
+            MEM_COMMIT | MEM_RESERVE,
+            PAGE_EXECUTE_READWRITE,
+        );
+
+        if shellcode_mem.is_null() {
+            // Don't free remote_base - image memory must stay for DLL
+            let _ = CloseHandle(process_handle);
+            return Err(MiscError::AllocFailed);
+        }
+
+        if WriteProcessMemory(
+            process_handle,
+            shellcode_mem,
+            shellcode.as_ptr() as *const _,
+            shellcode.len(),
+            None,
+        )
+        .is_err()
+        {
+            let _ = VirtualFreeEx(process_handle, shellcode_mem, 0, MEM_RELEASE);
+            let _ = CloseHandle(process_handle);
+            return Err(MiscError::WriteFailed);
+        }
+
+        // Create remote thread to execute shellcode (calls DllMain)
+        let thread_start: unsafe extern "system" fn(*mut std::ffi::c_void) -> u32 =
+            std::mem::transmute(shellcode_mem);
+
+        let thread_handle =
+            CreateRemoteThread(process_handle, None, 0, Some(thread_start), None, 0, None)
+                .map_err(|_| {
+                    let _ = VirtualFreeEx(process_handle, shellcode_mem, 0, MEM_RELEASE);
+                    let _ = CloseHandle(process_handle);
+                    MiscError::CreateRemoteThreadFailed
+                })?;
+
+        // Wait for DllMain to finish (10s timeout)
+        let wait_result = WaitForSingleObject(thread_handle, 10_000);
+
+        let _ = CloseHandle(thread_handle);
+        // Free shellcode memory (no longer needed after DllMain returns)
+        let _ = VirtualFreeEx(process_handle, shellcode_mem, 0, MEM_RELEASE);
+        // NOTE: remote_base (image memory) stays allocated - required for DLL to function
+        let _ = CloseHandle(process_handle);
+
+        if wait_result.0 != 0 {
+            return Err(MiscError::Timeout);
+        }
+
+        Ok(())
+    }
+}
+
+/// Commit a reserved memory region in a target process.
+pub fn commit_memory(pid: u32, address: usize, size: usize) -> Result<(), MiscError> {
+    unsafe {
+        let process_handle = OpenProcess(PROCESS_VM_OPERATION, false, pid)
+            .map_err(|_| MiscError::OpenProcessFailed(pid))?;
+
+        let result = VirtualAllocEx(
+            process_handle,
+            Some(address as *const _),
+            size,
+            MEM_COMMIT,
+            PAGE_READWRITE,
+        );
+
+        let _ = CloseHandle(process_handle);
+
+        if result.is_null() {
+            return Err(MiscError::CommitFailed(format!(
+                "VirtualAllocEx failed at 0x{:X}",
+                address
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Decommit a committed memory region in a target process.
+pub fn decommit_memory(pid: u32, address: usize, size: usize) -> Result<(), MiscError> {
+    unsafe {
+        let process_handle = OpenProcess(PROCESS_VM_OPERATION, false, pid)
+            .map_err(|_| MiscError::OpenProcessFailed(pid))?;
+
+        let result = VirtualFreeEx(process_handle, address as *mut _, size, MEM_DECOMMIT);
+
+        let _ = CloseHandle(process_handle);
+
+        result.map_err(|e| {
+            MiscError::DecommitFailed(format!("VirtualFreeEx failed at 0x{:X}: {}", address, e))
+        })
+    }
+}
+
+/// Free an entire allocation in a target process (uses allocation_base, size must be 0).
+pub fn free_memory(pid: u32, allocation_base: usize) -> Result<(), MiscError> {
+    unsafe {
+        let process_handle = OpenProcess(PROCESS_VM_OPERATION, false, pid)
+            .map_err(|_| MiscError::OpenProcessFailed(pid))?;
+
+        let result = VirtualFreeEx(process_handle, allocation_base as *mut _, 0, MEM_RELEASE);
+
+        let _ = CloseHandle(process_handle);
+
+        result.map_err(|e| {
+            MiscError::FreeFailed(format!(
+                "VirtualFreeEx failed at 0x{:X}: {}",
+                allocation_base, e
+            ))
+        })
+    }
+}
@@ -0,0 +1,200 @@
+//! Filtering and ordering of connection listings for the connections table
+
+use std::cmp::Ordering;
+
+use crate::{NetworkConnection, Protocol};
+
+/// Sort column for network table
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NetworkSortColumn {
+    Protocol,
+    LocalAddr,
+    LocalPort,
+    RemoteAddr,
+    RemotePort,
+    State,
+    Process,
+    Pid,
+}
+
+/// Sort order
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// Whether a connection passes the protocol and state dropdowns and the
+/// search box (`query` already lowercased)
+pub fn matches_connection(
+    c: &NetworkConnection,
+    protocol_filter: &str,
+    state_filter: &str,
+    query: &str,
+) -> bool {
+    let proto_match = match protocol_filter {
+        "tcp" => c.protocol == Protocol::Tcp,
+        "udp" => c.protocol == Protocol::Udp,
+        _ => true,
+    };
+
+    let state_match = if state_filter == "all" {
+        true
+    } else {
+        match &c.state {
+            Some(state) => state
+                .to_string()
+                .to_lowercase()
+                .contains(&state_filter.to_lowercase()),
+            None => state_filter.is_empty(),
+        }
+    };
+
+    let search_match = query.is_empty()
+        || c.local_addr.to_lowercase().contains(query)
+        || c.local_port.to_string().contains(query)
+        || c.remote_addr.to_lowercase().contains(query)
+        || c.remote_port.to_string().contains(query)
+        || c.process_name.to_lowercase().contains(query)
+        || c.pid.to_string().contains(query);
+
+    proto_match && state_match && search_match
+}
+
+/// Order two connections by the selected column
+pub fn compare_connections(
+    a: &NetworkConnection,
+    b: &NetworkConnection,
+    column: NetworkSortColumn,
+    order: SortOrder,
+) -> Ordering {
+    let cmp = match column {
+        NetworkSortColumn::Protocol => a.protocol.to_string().cmp(&b.protocol.to_string()),
+        NetworkSortColumn::LocalAddr => a.local_addr.cmp(&b.local_addr),
+        NetworkSortColumn::LocalPort => a.local_port.cmp(&b.local_port),
+        NetworkSortColumn::RemoteAddr => a.remote_addr.cmp(&b.remote_addr),
+        NetworkSortColumn::RemotePort => a.remote_port.cmp(&b.remote_port),
+        NetworkSortColumn::State => {
+            let a_state = a.state.map(|s| s.to_string()).unwrap_or_default();
+            let b_state = b.state.map(|s| s.to_string()).unwrap_or_default();
+            a_state.cmp(&b_state)
+        }
+        NetworkSortColumn::Process => a
+            .process_name
+            .to_lowercase()
+            .cmp(&b.process_name.to_lowercase()),
+        NetworkSortColumn::Pid => a.pid.cmp(&b.pid),
+    };
+    match order {
+        SortOrder::Ascending => cmp,
+        SortOrder::Descending => cmp.reverse(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockNetwork, NetworkBackend};
+
+    fn fixture() -> MockNetwork {
+        MockNetwork::from_json(include_str!("../fixtures/connections.json"))
+            .expect("fixture parses")
+    }
+
+    fn ports(connections: &[NetworkConnection]) -> Vec<u16> {
+        connections.iter().map(|c| c.local_port).collect()
+    }
+
+    fn filter(protocol: &str, state: &str, query: &str) -> Vec<u16> {
+        let connections: Vec<NetworkConnection> = fixture()
+            .connections()
+            .unwrap()
+            .into_iter()
+            .filter(|c| matches_connection(c, protocol, state, query))
+            .collect();
+        ports(&connections)
+    }
+
+    #[test]
+    fn filters_by_protocol() {
+        assert_eq!(filter("", "", "").len(), 8);
+        assert_eq!(filter("all", "", "").len(), 8);
+        assert_eq!(filter("tcp", "", ""), vec![135, 445, 51432, 51433, 9222]);
+        assert_eq!(filter("udp", "", ""), vec![5353, 137, 123]);
+    }
+
+    #[test]
+    fn filters_by_state() {
+        assert_eq!(filter("", "listen", ""), vec![135, 445, 9222]);
+        assert_eq!(filter("", "Established", ""), vec![51432]);
+        // UDP endpoints have no state, so any state filter drops them
+        assert_eq!(filter("udp", "listen", ""), Vec::<u16>::new());
+        assert_eq!(filter("udp", "all", "").len(), 3);
+    }
+
+    #[test]
+    fn searches_addresses_ports_and_processes() {
+        assert_eq!(filter("", "", "chrome"), vec![51432, 9222, 5353]);
+        assert_eq!(filter("", "", "142.250"), vec![51432, 51433]);
+        assert_eq!(filter("", "", "443"), vec![51432, 51433]);
+        assert_eq!(filter("", "", "1340"), vec![123]);
+        assert_eq!(filter("tcp", "listen", "chrome"), vec![9222]);
+    }
+
+    fn sorted(column: NetworkSortColumn, order: SortOrder) -> Vec<NetworkConnection> {
+        let mut connections = fixture().connections().unwrap();
+        connections.sort_by(|a, b| compare_connections(a, b, column, order));
+        connections
+    }
+
+    #[test]
+    fn sorts_by_each_column() {
+        assert_eq!(
+            ports(&sorted(NetworkSortColumn::LocalPort, SortOrder::Ascending)),
+            vec![123, 135, 137, 445, 5353, 9222, 51432, 51433]
+        );
+
+        let pids: Vec<u32> = sorted(NetworkSortColumn::Pid, SortOrder::Descending)
+            .iter()
+            .map(|c| c.pid)
+            .collect();
+        assert_eq!(pids, vec![5220, 5220, 5220, 1340, 1012, 4, 4, 0]);
+
+        // Stateless UDP rows sort before every TCP state
+        let states: Vec<String> = sorted(NetworkSortColumn::State, SortOrder::Ascending)
+            .iter()
+            .map(|c| c.state.map(|s| s.to_string()).unwrap_or_default())
+            .collect();
+        assert_eq!(states[..3], ["", "", ""]);
+        assert_eq!(
+            states[3..],
+            ["ESTABLISHED", "LISTEN", "LISTEN", "LISTEN", "TIME_WAIT"]
+        );
+
+        let names: Vec<String> = sorted(NetworkSortColumn::Process, SortOrder::Descending)
+            .into_iter()
+            .map(|c| c.process_name)
+            .collect();
+        assert_eq!(names.first().unwrap(), "System Idle Process");
+        assert_eq!(names.last().unwrap(), "chrome.exe");
+
+        let remote = sorted(NetworkSortColumn::RemotePort, SortOrder::Descending);
+        assert_eq!(ports(&remote)[..2], [51432, 51433]);
+    }
+
+    #[test]
+    fn replays_refreshes() {
+        let backend = fixture();
+        let first = backend.connections().unwrap();
+        let second = backend.connections().unwrap();
+        assert!(ports(&first).contains(&51433));
+        assert!(!ports(&second).contains(&51433));
+        let calc: Vec<&NetworkConnection> = second
+            .iter()
+            .filter(|c| matches_connection(c, "", "", "calc"))
+            .collect();
+        assert_eq!(calc.len(), 1);
+        assert_eq!(calc[0].remote_addr, "20.42.65.92");
+        assert_eq!(backend.connections().unwrap(), second);
+    }
+}
@@ -1,15 +1,28 @@
-//! Windows network connection enumeration module
-//! Contains Windows API calls for TCP/UDP connection listing
-
-use std::collections::HashMap;
-use windows::Win32::NetworkManagement::IpHelper::{
-    GetExtendedTcpTable, GetExtendedUdpTable, MIB_TCPROW_OWNER_PID, MIB_TCP_STATE,
-    MIB_UDPROW_OWNER_PID, TCP_TABLE_OWNER_PID_ALL, UDP_TABLE_OWNER_PID,
-};
-use windows::Win32::Networking::WinSock::AF_INET;
+//! Network connection enumeration module
+//! TCP/UDP connection listing behind a platform backend: the IP Helper API on
+//! Windows and `/proc/net` on Linux
+
+mod filter;
+#[cfg(target_os = "linux")]
+mod linux;
+mod mock;
+#[cfg(windows)]
+mod win32;
+
+pub use filter::{compare_connections, matches_connection, NetworkSortColumn, SortOrder};
+#[cfg(target_os = "linux")]
+pub use linux::LinuxBackend;
+pub use mock::MockNetwork;
+#[cfg(windows)]
+pub use win32::Win32Backend;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+use std::sync::OnceLock;
 
 /// Network connection protocol
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Protocol {
     Tcp,
     Udp,
@@ -25,7 +38,7 @@ impl std::fmt::Display for Protocol {
 }
 
 /// TCP connection state
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TcpState {
     Closed,
     Listen,
@@ -62,28 +75,8 @@ impl std::fmt::Display for TcpState {
     }
 }
 
-impl From<MIB_TCP_STATE> for TcpState {
-    fn from(state: MIB_TCP_STATE) -> Self {
-        match state {
-            MIB_TCP_STATE(1) => TcpState::Closed,
-            MIB_TCP_STATE(2) => TcpState::Listen,
-            MIB_TCP_STATE(3) => TcpState::SynSent,
-            MIB_TCP_STATE(4) => TcpState::SynReceived,
-            MIB_TCP_STATE(5) => TcpState::Established,
-            MIB_TCP_STATE(6) => TcpState::FinWait1,
-            MIB_TCP_STATE(7) => TcpState::FinWait2,
-            MIB_TCP_STATE(8) => TcpState::CloseWait,
-            MIB_TCP_STATE(9) => TcpState::Closing,
-            MIB_TCP_STATE(10) => TcpState::LastAck,
-            MIB_TCP_STATE(11) => TcpState::TimeWait,
-            MIB_TCP_STATE(12) => TcpState::DeleteTcb,
-            _ => TcpState::Unknown,
-        }
-    }
-}
-
 /// Network connection information
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct NetworkConnection {
     pub protocol: Protocol,
     pub local_addr: String,
@@ -96,178 +89,88 @@ pub struct NetworkConnection {
     pub exe_path: String,
 }
 
-/// Convert u32 IP address to string
-fn ip_to_string(ip: u32) -> String {
-    let bytes = ip.to_ne_bytes();
-    format!("{}.{}.{}.{}", bytes[0], bytes[1], bytes[2], bytes[3])
-}
-
-/// Convert network byte order port to host byte order
-fn port_from_network(port: u32) -> u16 {
-    ((port & 0xFF) << 8 | (port >> 8) & 0xFF) as u16
+/// Protocol, both endpoints and owning PID of a connection
+pub type ConnectionKey = (Protocol, String, u16, String, u16, u32);
+
+impl NetworkConnection {
+    /// Identity across refreshes; the state may change under the same key
+    pub fn key(&self) -> ConnectionKey {
+        (
+            self.protocol,
+            self.local_addr.clone(),
+            self.local_port,
+            self.remote_addr.clone(),
+            self.remote_port,
+            self.pid,
+        )
+    }
 }
 
-/// Get all network connections (TCP and UDP)
-pub fn get_network_connections() -> Vec<NetworkConnection> {
-    let mut connections = Vec::new();
-
-    // Get process info map for name/path lookup
-    let process_map: HashMap<u32, (String, String)> = process::get_processes()
-        .into_iter()
-        .map(|p| (p.pid, (p.name, p.exe_path)))
-        .collect();
-
-    // Get TCP connections
-    connections.extend(get_tcp_connections(&process_map));
-
-    // Get UDP connections
-    connections.extend(get_udp_connections(&process_map));
-
-    connections
+/// Why the connection table could not be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkError {
+    /// An IP Helper call failed with this Win32 error code
+    Win32 { operation: &'static str, code: u32 },
+    /// A `/proc/net` table could not be read
+    Io { path: String, kind: io::ErrorKind },
+    /// [`set_backend`] was called after a backend was already installed or used
+    BackendInUse,
 }
 
-/// Get TCP connections
-fn get_tcp_connections(process_map: &HashMap<u32, (String, String)>) -> Vec<NetworkConnection> {
-    let mut connections = Vec::new();
-
-    unsafe {
-        let mut size: u32 = 0;
-
-        // First call to get required buffer size
-        let _ = GetExtendedTcpTable(
-            None,
-            &mut size,
-            false,
-            AF_INET.0 as u32,
-            TCP_TABLE_OWNER_PID_ALL,
-            0,
-        );
-
-        if size == 0 {
-            return connections;
-        }
-
-        let mut buffer: Vec<u8> = vec![0; size as usize];
-
-        let result = GetExtendedTcpTable(
-            Some(buffer.as_mut_ptr() as *mut _),
-            &mut size,
-            false,
-            AF_INET.0 as u32,
-            TCP_TABLE_OWNER_PID_ALL,
-            0,
-        );
-
-        if result != 0 {
-            return connections;
-        }
-
-        // Parse the table
-        // Structure: DWORD dwNumEntries, MIB_TCPROW_OWNER_PID table[]
-        let num_entries = u32::from_ne_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as usize;
-        let entry_size = std::mem::size_of::<MIB_TCPROW_OWNER_PID>();
-
-        for i in 0..num_entries {
-            let offset = 4 + i * entry_size;
-            if offset + entry_size > buffer.len() {
-                break;
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkError::Win32 { operation, code } => write!(
+                f,
+                "{} failed: {}",
+                operation,
+                io::Error::from_raw_os_error(*code as i32)
+            ),
+            NetworkError::Io { path, kind } => {
+                write!(f, "Failed to read {}: {}", path, io::Error::from(*kind))
             }
-
-            let entry_ptr = buffer.as_ptr().add(offset) as *const MIB_TCPROW_OWNER_PID;
-            let entry = &*entry_ptr;
-
-            let pid = entry.dwOwningPid;
-            let (process_name, exe_path) = process_map
-                .get(&pid)
-                .cloned()
-                .unwrap_or_else(|| (format!("PID {}", pid), String::new()));
-
-            connections.push(NetworkConnection {
-                protocol: Protocol::Tcp,
-                local_addr: ip_to_string(entry.dwLocalAddr),
-                local_port: port_from_network(entry.dwLocalPort),
-                remote_addr: ip_to_string(entry.dwRemoteAddr),
-                remote_port: port_from_network(entry.dwRemotePort),
-                state: Some(TcpState::from(MIB_TCP_STATE(entry.dwState as i32))),
-                pid,
-                process_name,
-                exe_path,
-            });
+            NetworkError::BackendInUse => write!(f, "A network backend is already in use"),
         }
     }
-
-    connections
 }
 
-/// Get UDP connections
-fn get_udp_connections(process_map: &HashMap<u32, (String, String)>) -> Vec<NetworkConnection> {
-    let mut connections = Vec::new();
-
-    unsafe {
-        let mut size: u32 = 0;
-
-        // First call to get required buffer size
-        let _ = GetExtendedUdpTable(
-            None,
-            &mut size,
-            false,
-            AF_INET.0 as u32,
-            UDP_TABLE_OWNER_PID,
-            0,
-        );
-
-        if size == 0 {
-            return connections;
-        }
-
-        let mut buffer: Vec<u8> = vec![0; size as usize];
+impl std::error::Error for NetworkError {}
 
-        let result = GetExtendedUdpTable(
-            Some(buffer.as_mut_ptr() as *mut _),
-            &mut size,
-            false,
-            AF_INET.0 as u32,
-            UDP_TABLE_OWNER_PID,
-            0,
-        );
+/// Source of the TCP/UDP connection table
+pub trait NetworkBackend: Send + Sync {
+    /// Every TCP and UDP endpoint with its owning process
+    fn connections(&self) -> Result<Vec<NetworkConnection>, NetworkError>;
+}
 
-        if result != 0 {
-            return connections;
-        }
+static BACKEND: OnceLock<Box<dyn NetworkBackend>> = OnceLock::new();
 
-        // Parse the table
-        // Structure: DWORD dwNumEntries, MIB_UDPROW_OWNER_PID table[]
-        let num_entries = u32::from_ne_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as usize;
-        let entry_size = std::mem::size_of::<MIB_UDPROW_OWNER_PID>();
+/// Replace the platform backend (e.g. with a recorded or fake one).
+///
+/// Must be called before the first enumeration; fails with
+/// [`NetworkError::BackendInUse`] if a backend is already in use.
+pub fn set_backend(backend: Box<dyn NetworkBackend>) -> Result<(), NetworkError> {
+    BACKEND.set(backend).map_err(|_| NetworkError::BackendInUse)
+}
 
-        for i in 0..num_entries {
-            let offset = 4 + i * entry_size;
-            if offset + entry_size > buffer.len() {
-                break;
-            }
+/// The active backend, defaulting to the one for the current platform
+pub fn backend() -> &'static dyn NetworkBackend {
+    BACKEND.get_or_init(platform_backend).as_ref()
+}
 
-            let entry_ptr = buffer.as_ptr().add(offset) as *const MIB_UDPROW_OWNER_PID;
-            let entry = &*entry_ptr;
+#[cfg(windows)]
+fn platform_backend() -> Box<dyn NetworkBackend> {
+    Box::new(Win32Backend)
+}
 
-            let pid = entry.dwOwningPid;
-            let (process_name, exe_path) = process_map
-                .get(&pid)
-                .cloned()
-                .unwrap_or_else(|| (format!("PID {}", pid), String::new()));
+#[cfg(target_os = "linux")]
+fn platform_backend() -> Box<dyn NetworkBackend> {
+    Box::new(LinuxBackend)
+}
 
-            connections.push(NetworkConnection {
-                protocol: Protocol::Udp,
-                local_addr: ip_to_string(entry.dwLocalAddr),
-                local_port: port_from_network(entry.dwLocalPort),
-                remote_addr: String::new(),
-                remote_port: 0,
-                state: None,
-                pid,
-                process_name,
-                exe_path,
-            });
-        }
-    }
+#[cfg(not(any(windows, target_os = "linux")))]
+compile_error!("the network crate only has Windows and Linux backends");
 
-    connections
+/// Get all network connections (TCP and UDP)
+pub fn get_network_connections() -> Result<Vec<NetworkConnection>, NetworkError> {
+    backend().connections()
 }
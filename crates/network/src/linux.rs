@@ -0,0 +1,203 @@
+//! `/proc/net` backend for TCP/UDP connection listing
+
+use std::collections::HashMap;
+use std::fs;
+
+use crate::{NetworkBackend, NetworkConnection, NetworkError, Protocol, TcpState};
+
+/// Connection table read from `/proc/net/{tcp,udp}`.
+///
+/// Like the IP Helper backend this lists IPv4 endpoints only. Owners are
+/// found by matching socket inodes against `/proc/<pid>/fd`, so sockets of
+/// processes we may not inspect show up without a PID.
+pub struct LinuxBackend;
+
+impl NetworkBackend for LinuxBackend {
+    fn connections(&self) -> Result<Vec<NetworkConnection>, NetworkError> {
+        let tcp = read_table("/proc/net/tcp")?;
+        let udp = read_table("/proc/net/udp")?;
+
+        let owners = socket_owners();
+        let process_map: HashMap<u32, (String, String)> = process::get_processes()
+            .into_iter()
+            .map(|p| (p.pid, (p.name, p.exe_path)))
+            .collect();
+
+        let mut connections = parse_table(&tcp, Protocol::Tcp, &owners, &process_map);
+        connections.extend(parse_table(&udp, Protocol::Udp, &owners, &process_map));
+        Ok(connections)
+    }
+}
+
+fn read_table(path: &str) -> Result<String, NetworkError> {
+    fs::read_to_string(path).map_err(|e| NetworkError::Io {
+        path: path.to_string(),
+        kind: e.kind(),
+    })
+}
+
+/// Socket inode to owning PID, from the `socket:[<inode>]` links in every
+/// readable `/proc/<pid>/fd`
+fn socket_owners() -> HashMap<u64, u32> {
+    let mut owners = HashMap::new();
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return owners;
+    };
+    for entry in entries.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            let inode = fs::read_link(fd.path()).ok().and_then(|target| {
+                target
+                    .to_str()?
+                    .strip_prefix("socket:[")?
+                    .strip_suffix(']')?
+                    .parse::<u64>()
+                    .ok()
+            });
+            if let Some(inode) = inode {
+                owners.entry(inode).or_insert(pid);
+            }
+        }
+    }
+    owners
+}
+
+/// `/proc/net/tcp` state codes (`include/net/tcp_states.h`)
+fn tcp_state(code: u8) -> TcpState {
+    match code {
+        0x01 => TcpState::Established,
+        0x02 => TcpState::SynSent,
+        0x03 => TcpState::SynReceived,
+        0x04 => TcpState::FinWait1,
+        0x05 => TcpState::FinWait2,
+        0x06 => TcpState::TimeWait,
+        0x07 => TcpState::Closed,
+        0x08 => TcpState::CloseWait,
+        0x09 => TcpState::LastAck,
+        0x0A => TcpState::Listen,
+        0x0B => TcpState::Closing,
+        _ => TcpState::Unknown,
+    }
+}
+
+/// Parse `0100007F:0CEA`: the address is the raw `in_addr` printed as a
+/// native-endian word, the port is in host order
+fn parse_endpoint(text: &str) -> Option<(String, u16)> {
+    let (addr, port) = text.split_once(':')?;
+    let bytes = u32::from_str_radix(addr, 16).ok()?.to_ne_bytes();
+    let addr = format!("{}.{}.{}.{}", bytes[0], bytes[1], bytes[2], bytes[3]);
+    Some((addr, u16::from_str_radix(port, 16).ok()?))
+}
+
+/// Turn the rows of a `/proc/net/{tcp,udp}` table into connections, skipping
+/// the header and malformed lines
+fn parse_table(
+    text: &str,
+    protocol: Protocol,
+    owners: &HashMap<u64, u32>,
+    process_map: &HashMap<u32, (String, String)>,
+) -> Vec<NetworkConnection> {
+    text.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (local_addr, local_port) = parse_endpoint(fields.get(1)?)?;
+            let (remote_addr, remote_port) = parse_endpoint(fields.get(2)?)?;
+            let state = u8::from_str_radix(fields.get(3)?, 16).ok()?;
+            let inode: u64 = fields.get(9)?.parse().ok()?;
+
+            let pid = owners.get(&inode).copied().unwrap_or(0);
+            let (process_name, exe_path) = process_map
+                .get(&pid)
+                .cloned()
+                .unwrap_or_else(|| (format!("PID {}", pid), String::new()));
+
+            Some(match protocol {
+                Protocol::Tcp => NetworkConnection {
+                    protocol,
+                    local_addr,
+                    local_port,
+                    remote_addr,
+                    remote_port,
+                    state: Some(tcp_state(state)),
+                    pid,
+                    process_name,
+                    exe_path,
+                },
+                // UDP is connectionless; report it as the IP Helper API does
+                Protocol::Udp => NetworkConnection {
+                    protocol,
+                    local_addr,
+                    local_port,
+                    remote_addr: String::new(),
+                    remote_port: 0,
+                    state: None,
+                    pid,
+                    process_name,
+                    exe_path,
+                },
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TCP: &str = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0CEA 00000000:0000 0A 00000000:00000000 00:00000000 00000000   124        0 31337 1 0000000000000000 100 0 0 10 0
+   1: 0F02000A:C350 2E0D10AC:01BB 01 00000000:00000000 02:000A7E1A 00000000  1000        0 42424 2 0000000000000000 20 4 30 10 -1
+   2: garbage
+";
+
+    const UDP: &str = "   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  512: 00000000:0044 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 555 2 0000000000000000 0
+";
+
+    #[test]
+    fn parses_tcp_rows_and_resolves_owners() {
+        let owners = HashMap::from([(31337, 812)]);
+        let names = HashMap::from([(812, ("mysqld".to_string(), "/usr/sbin/mysqld".to_string()))]);
+        let connections = parse_table(TCP, Protocol::Tcp, &owners, &names);
+        assert_eq!(connections.len(), 2);
+
+        let listen = &connections[0];
+        assert_eq!(
+            (listen.local_addr.as_str(), listen.local_port),
+            ("127.0.0.1", 3306)
+        );
+        assert_eq!(listen.state, Some(TcpState::Listen));
+        assert_eq!((listen.pid, listen.process_name.as_str()), (812, "mysqld"));
+
+        let established = &connections[1];
+        assert_eq!(established.local_addr, "10.0.2.15");
+        assert_eq!(established.local_port, 50000);
+        assert_eq!(
+            (established.remote_addr.as_str(), established.remote_port),
+            ("172.16.13.46", 443)
+        );
+        assert_eq!(established.state, Some(TcpState::Established));
+        // Socket of a process we could not inspect
+        assert_eq!(established.pid, 0);
+        assert_eq!(established.process_name, "PID 0");
+    }
+
+    #[test]
+    fn udp_rows_have_no_remote_or_state() {
+        let connections = parse_table(UDP, Protocol::Udp, &HashMap::new(), &HashMap::new());
+        assert_eq!(connections.len(), 1);
+        assert_eq!(connections[0].local_port, 68);
+        assert_eq!(connections[0].remote_addr, "");
+        assert_eq!(connections[0].state, None);
+    }
+}
@@ -0,0 +1,43 @@
+//! In-memory backend that replays recorded connection tables
+
+use std::sync::Mutex;
+
+use crate::{NetworkBackend, NetworkConnection, NetworkError};
+
+/// Backend that serves a fixed sequence of connection tables.
+///
+/// Each call to [`NetworkBackend::connections`] moves to the next table and
+/// stays on the last one once the sequence is exhausted.
+pub struct MockNetwork {
+    snapshots: Vec<Vec<NetworkConnection>>,
+    /// Index of the table the next call returns
+    cursor: Mutex<usize>,
+}
+
+impl MockNetwork {
+    pub fn new(snapshots: Vec<Vec<NetworkConnection>>) -> Self {
+        Self {
+            snapshots,
+            cursor: Mutex::new(0),
+        }
+    }
+
+    /// Load a JSON array of connection tables
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+
+    /// Go back to the first table
+    pub fn rewind(&self) {
+        *self.cursor.lock().unwrap() = 0;
+    }
+}
+
+impl NetworkBackend for MockNetwork {
+    fn connections(&self) -> Result<Vec<NetworkConnection>, NetworkError> {
+        let mut cursor = self.cursor.lock().unwrap();
+        let index = (*cursor).min(self.snapshots.len().saturating_sub(1));
+        *cursor = index + 1;
+        Ok(self.snapshots.get(index).cloned().unwrap_or_default())
+    }
+}
@@ -0,0 +1,221 @@
+//! IP Helper backend for TCP/UDP connection listing
+
+use std::collections::HashMap;
+
+use windows::Win32::NetworkManagement::IpHelper::{
+    GetExtendedTcpTable, GetExtendedUdpTable, MIB_TCPROW_OWNER_PID, MIB_TCP_STATE,
+    MIB_UDPROW_OWNER_PID, TCP_TABLE_OWNER_PID_ALL, UDP_TABLE_OWNER_PID,
+};
+use windows::Win32::Networking::WinSock::AF_INET;
+
+use crate::{NetworkBackend, NetworkConnection, NetworkError, Protocol, TcpState};
+
+impl From<MIB_TCP_STATE> for TcpState {
+    fn from(state: MIB_TCP_STATE) -> Self {
+        match state {
+            MIB_TCP_STATE(1) => TcpState::Closed,
+            MIB_TCP_STATE(2) => TcpState::Listen,
+            MIB_TCP_STATE(3) => TcpState::SynSent,
+            MIB_TCP_STATE(4) => TcpState::SynReceived,
+            MIB_TCP_STATE(5) => TcpState::Established,
+            MIB_TCP_STATE(6) => TcpState::FinWait1,
+            MIB_TCP_STATE(7) => TcpState::FinWait2,
+            MIB_TCP_STATE(8) => TcpState::CloseWait,
+            MIB_TCP_STATE(9) => TcpState::Closing,
+            MIB_TCP_STATE(10) => TcpState::LastAck,
+            MIB_TCP_STATE(11) => TcpState::TimeWait,
+            MIB_TCP_STATE(12) => TcpState::DeleteTcb,
+            _ => TcpState::Unknown,
+        }
+    }
+}
+
+/// Convert u32 IP address to string
+fn ip_to_string(ip: u32) -> String {
+    let bytes = ip.to_ne_bytes();
+    format!("{}.{}.{}.{}", bytes[0], bytes[1], bytes[2], bytes[3])
+}
+
+/// Convert network byte order port to host byte order
+fn port_from_network(port: u32) -> u16 {
+    ((port & 0xFF) << 8 | (port >> 8) & 0xFF) as u16
+}
+
+/// Connection table read through the IP Helper API
+pub struct Win32Backend;
+
+impl NetworkBackend for Win32Backend {
+    fn connections(&self) -> Result<Vec<NetworkConnection>, NetworkError> {
+        let mut connections = Vec::new();
+
+        // Get process info map for name/path lookup
+        let process_map: HashMap<u32, (String, String)> = process::get_processes()
+            .into_iter()
+            .map(|p| (p.pid, (p.name, p.exe_path)))
+            .collect();
+
+        // Get TCP connections
+        connections.extend(get_tcp_connections(&process_map)?);
+
+        // Get UDP connections
+        connections.extend(get_udp_connections(&process_map)?);
+
+        Ok(connections)
+    }
+}
+
+/// Get TCP connections
+fn get_tcp_connections(
+    process_map: &HashMap<u32, (String, String)>,
+) -> Result<Vec<NetworkConnection>, NetworkError> {
+    let mut connections = Vec::new();
+
+    unsafe {
+        let mut size: u32 = 0;
+
+        // First call to get required buffer size
+        let _ = GetExtendedTcpTable(
+            None,
+            &mut size,
+            false,
+            AF_INET.0 as u32,
+            TCP_TABLE_OWNER_PID_ALL,
+            0,
+        );
+
+        if size == 0 {
+            return Ok(connections);
+        }
+
+        let mut buffer: Vec<u8> = vec![0; size as usize];
+
+        let result = GetExtendedTcpTable(
+            Some(buffer.as_mut_ptr() as *mut _),
+            &mut size,
+            false,
+            AF_INET.0 as u32,
+            TCP_TABLE_OWNER_PID_ALL,
+            0,
+        );
+
+        if result != 0 {
+            return Err(NetworkError::Win32 {
+                operation: "GetExtendedTcpTable",
+                code: result,
+            });
+        }
+
+        // Parse the table
+        // Structure: DWORD dwNumEntries, MIB_TCPROW_OWNER_PID table[]
+        let num_entries = u32::from_ne_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as usize;
+        let entry_size = std::mem::size_of::<MIB_TCPROW_OWNER_PID>();
+
+        for i in 0..num_entries {
+            let offset = 4 + i * entry_size;
+            if offset + entry_size > buffer.len() {
+                break;
+            }
+
+            let entry_ptr = buffer.as_ptr().add(offset) as *const MIB_TCPROW_OWNER_PID;
+            let entry = &*entry_ptr;
+
+            let pid = entry.dwOwningPid;
+            let (process_name, exe_path) = process_map
+                .get(&pid)
+                .cloned()
+                .unwrap_or_else(|| (format!("PID {}", pid), String::new()));
+
+            connections.push(NetworkConnection {
+                protocol: Protocol::Tcp,
+                local_addr: ip_to_string(entry.dwLocalAddr),
+                local_port: port_from_network(entry.dwLocalPort),
+                remote_addr: ip_to_string(entry.dwRemoteAddr),
+                remote_port: port_from_network(entry.dwRemotePort),
+                state: Some(TcpState::from(MIB_TCP_STATE(entry.dwState as i32))),
+                pid,
+                process_name,
+                exe_path,
+            });
+        }
+    }
+
+    Ok(connections)
+}
+
+/// Get UDP connections
+fn get_udp_connections(
+    process_map: &HashMap<u32, (String, String)>,
+) -> Result<Vec<NetworkConnection>, NetworkError> {
+    let mut connections = Vec::new();
+
+    unsafe {
+        let mut size: u32 = 0;
+
+        // First call to get required buffer size
+        let _ = GetExtendedUdpTable(
+            None,
+            &mut size,
+            false,
+            AF_INET.0 as u32,
+            UDP_TABLE_OWNER_PID,
+            0,
+        );
+
+        if size == 0 {
+            return Ok(connections);
+        }
+
+        let mut buffer: Vec<u8> = vec![0; size as usize];
+
+        let result = GetExtendedUdpTable(
+            Some(buffer.as_mut_ptr() as *mut _),
+            &mut size,
+            false,
+            AF_INET.0 as u32,
+            UDP_TABLE_OWNER_PID,
+            0,
+        );
+
+        if result != 0 {
+            return Err(NetworkError::Win32 {
+                operation: "GetExtendedUdpTable",
+                code: result,
+            });
+        }
+
+        // Parse the table
+        // Structure: DWORD dwNumEntries, MIB_UDPROW_OWNER_PID table[]
+        let num_entries = u32::from_ne_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as usize;
+        let entry_size = std::mem::size_of::<MIB_UDPROW_OWNER_PID>();
+
+        for i in 0..num_entries {
+            let offset = 4 + i * entry_size;
+            if offset + entry_size > buffer.len() {
+                break;
+            }
+
+            let entry_ptr = buffer.as_ptr().add(offset) as *const MIB_UDPROW_OWNER_PID;
+            let entry = &*entry_ptr;
+
+            let pid = entry.dwOwningPid;
+            let (process_name, exe_path) = process_map
+                .get(&pid)
+                .cloned()
+                .unwrap_or_else(|| (format!("PID {}", pid), String::new()));
+
+            connections.push(NetworkConnection {
+                protocol: Protocol::Udp,
+                local_addr: ip_to_string(entry.dwLocalAddr),
+                local_port: port_from_network(entry.dwLocalPort),
+                remote_addr: String::new(),
+                remote_port: 0,
+                state: None,
+                pid,
+                process_name,
+                exe_path,
+            });
+        }
+    }
+
+    Ok(connections)
+}
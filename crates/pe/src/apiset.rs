@@ -0,0 +1,354 @@
+//! API set schema resolution (`api-ms-win-*` / `ext-ms-*` contracts)
+
+use std::collections::HashMap;
+
+use crate::error::PeError;
+use crate::imports::ImportEntry;
+use crate::read::{read_u16, read_u32};
+use crate::PeImage;
+
+/// Size of API_SET_NAMESPACE_ENTRY (schema version 6)
+const NAMESPACE_ENTRY_SIZE: usize = 24;
+/// Size of API_SET_VALUE_ENTRY (schema version 6)
+const VALUE_ENTRY_SIZE: usize = 20;
+
+/// Mapping from API set contract names to the DLLs that implement them
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ApiSetSchema {
+    hosts: HashMap<String, String>,
+}
+
+impl ApiSetSchema {
+    /// Build a schema from `(contract, host)` pairs, e.g.
+    /// `("api-ms-win-core-file-l1-2-0.dll", "kernelbase.dll")`
+    pub fn from_entries<I, N, H>(entries: I) -> Self
+    where
+        I: IntoIterator<Item = (N, H)>,
+        N: AsRef<str>,
+        H: Into<String>,
+    {
+        let hosts = entries
+            .into_iter()
+            .map(|(name, host)| (contract_key(name.as_ref()), host.into()))
+            .collect();
+        ApiSetSchema { hosts }
+    }
+
+    /// Parse a version 6 (Windows 10+) `API_SET_NAMESPACE` blob
+    pub fn parse(namespace: &[u8]) -> Result<Self, PeError> {
+        let version = read_u32(namespace, 0).ok_or(PeError::TooSmall("API set namespace"))?;
+        if version != 6 {
+            return Err(PeError::UnsupportedApiSetVersion(version));
+        }
+        let count = read_u32(namespace, 12).ok_or(PeError::TooSmall("API set namespace"))?;
+        let entry_offset =
+            read_u32(namespace, 16).ok_or(PeError::TooSmall("API set namespace"))? as usize;
+
+        let mut hosts = HashMap::new();
+        for i in 0..count as usize {
+            let entry = entry_offset + i * NAMESPACE_ENTRY_SIZE;
+            let (name_offset, name_length, hashed_length, value_offset, value_count) = match (
+                read_u32(namespace, entry + 4),
+                read_u32(namespace, entry + 8),
+                read_u32(namespace, entry + 12),
+                read_u32(namespace, entry + 16),
+                read_u32(namespace, entry + 20),
+            ) {
+                (Some(a), Some(b), Some(c), Some(d), Some(e)) => (a, b, c, d, e),
+                _ => return Err(PeError::OutOfBounds("API set entry")),
+            };
+
+            // Only the hashed prefix (everything up to the last hyphen) is
+            // significant when matching import names
+            let name_length = hashed_length.min(name_length);
+            let name = match read_utf16(namespace, name_offset, name_length) {
+                Some(name) => name.to_lowercase(),
+                None => continue,
+            };
+
+            // Prefer the default host (the value without an importer name)
+            let mut host = None;
+            for j in 0..value_count as usize {
+                let value = value_offset as usize + j * VALUE_ENTRY_SIZE;
+                let importer_length = read_u32(namespace, value + 8).unwrap_or(0);
+                let host_offset = read_u32(namespace, value + 12).unwrap_or(0);
+                let host_length = read_u32(namespace, value + 16).unwrap_or(0);
+                if host_length == 0 {
+                    continue;
+                }
+                if let Some(value_host) = read_utf16(namespace, host_offset, host_length) {
+                    if importer_length == 0 || host.is_none() {
+                        host = Some(value_host);
+                    }
+                    if importer_length == 0 {
+                        break;
+                    }
+                }
+            }
+
+            if let Some(host) = host {
+                hosts.insert(name, host);
+            }
+        }
+
+        Ok(ApiSetSchema { hosts })
+    }
+
+    /// Load the schema from the `.apiset` section of `apisetschema.dll`
+    pub fn from_image(image: &PeImage) -> Result<Self, PeError> {
+        let section = image
+            .sections
+            .iter()
+            .find(|s| s.name == ".apiset")
+            .ok_or(PeError::MissingSection(".apiset"))?;
+        Self::parse(image.section_data(section))
+    }
+
+    /// Resolve an imported DLL name to its host DLL, if it is a known API set
+    pub fn resolve(&self, dll_name: &str) -> Option<&str> {
+        if !is_api_set_name(dll_name) {
+            return None;
+        }
+        self.hosts.get(&contract_key(dll_name)).map(String::as_str)
+    }
+
+    /// Fill in `host_dll` for every import whose DLL is a known API set
+    pub fn resolve_imports(&self, imports: &mut [ImportEntry]) {
+        for entry in imports.iter_mut() {
+            entry.host_dll = self.resolve(&entry.dll_name).map(str::to_string);
+        }
+    }
+
+    /// Number of contracts in the schema
+    pub fn len(&self) -> usize {
+        self.hosts.len()
+    }
+
+    /// Whether the schema holds no contracts
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty()
+    }
+}
+
+/// Whether a DLL name looks like an API set contract
+pub fn is_api_set_name(dll_name: &str) -> bool {
+    let lower = dll_name.to_ascii_lowercase();
+    lower.starts_with("api-") || lower.starts_with("ext-")
+}
+
+/// Normalize a contract name to its lookup key: lowercase, without the
+/// `.dll` extension and without the trailing revision number
+fn contract_key(name: &str) -> String {
+    let lower = name.to_ascii_lowercase();
+    let stem = lower.strip_suffix(".dll").unwrap_or(&lower);
+    match stem.rfind('-') {
+        Some(pos) => stem[..pos].to_string(),
+        None => stem.to_string(),
+    }
+}
+
+/// Read a UTF-16LE string of `length` bytes at `offset`
+fn read_utf16(data: &[u8], offset: u32, length: u32) -> Option<String> {
+    let units = (0..length as usize / 2)
+        .map(|i| read_u16(data, offset as usize + i * 2))
+        .collect::<Option<Vec<u16>>>()?;
+    Some(String::from_utf16_lossy(&units))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ImportKind;
+
+    /// Size of API_SET_NAMESPACE (schema version 6)
+    const HEADER_SIZE: usize = 28;
+
+    /// Lay out a version 6 namespace: header, namespace entries, value
+    /// entries, then the UTF-16 strings they point at. Each contract lists
+    /// `(importer, host)` values.
+    fn schema_blob(contracts: &[(&str, &[(&str, &str)])]) -> Vec<u8> {
+        let value_count: usize = contracts.iter().map(|(_, values)| values.len()).sum();
+        let values_start = HEADER_SIZE + contracts.len() * NAMESPACE_ENTRY_SIZE;
+        let strings_start = values_start + value_count * VALUE_ENTRY_SIZE;
+
+        let mut entries = Vec::new();
+        let mut value_entries = Vec::new();
+        let mut strings = Vec::new();
+        let mut push_string = |text: &str| -> [u32; 2] {
+            let offset = strings_start + strings.len();
+            strings.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+            [offset as u32, text.len() as u32 * 2]
+        };
+        for (name, values) in contracts {
+            let [name_offset, name_length] = push_string(name);
+            let hashed_length = name.rfind('-').unwrap_or(name.len()) as u32 * 2;
+            let value_offset = values_start + value_entries.len();
+            for (importer, host) in *values {
+                let [importer_offset, importer_length] = push_string(importer);
+                let [host_offset, host_length] = push_string(host);
+                for field in [
+                    0,
+                    importer_offset,
+                    importer_length,
+                    host_offset,
+                    host_length,
+                ] {
+                    value_entries.extend(field.to_le_bytes());
+                }
+            }
+            for field in [
+                0,
+                name_offset,
+                name_length,
+                hashed_length,
+                value_offset as u32,
+                values.len() as u32,
+            ] {
+                entries.extend(field.to_le_bytes());
+            }
+        }
+
+        let mut blob = Vec::new();
+        let size = (strings_start + strings.len()) as u32;
+        for field in [6, size, 0, contracts.len() as u32, HEADER_SIZE as u32, 0, 0] {
+            blob.extend(u32::to_le_bytes(field));
+        }
+        blob.extend(entries);
+        blob.extend(value_entries);
+        blob.extend(strings);
+        blob
+    }
+
+    fn sample() -> Vec<u8> {
+        schema_blob(&[
+            ("api-ms-win-core-file-l1-2-0", &[("", "kernelbase.dll")]),
+            // The default host wins over an importer-specific redirect
+            (
+                "api-ms-win-core-com-l1-1-1",
+                &[("kernel32.dll", "kernel32.dll"), ("", "combase.dll")],
+            ),
+            // With only a redirect, that host is still better than nothing
+            (
+                "ext-ms-win-ntuser-window-l1-1-0",
+                &[("shell32.dll", "user32.dll")],
+            ),
+            // Contracts with no host at all are left out
+            ("ext-ms-win-missing-l1-1-0", &[("", "")]),
+        ])
+    }
+
+    #[test]
+    fn parses_v6_schema_and_resolves_contracts() {
+        let schema = ApiSetSchema::parse(&sample()).unwrap();
+        assert_eq!(schema.len(), 3);
+
+        assert_eq!(
+            schema.resolve("api-ms-win-core-file-l1-2-0.dll"),
+            Some("kernelbase.dll")
+        );
+        // Revision and case do not matter
+        assert_eq!(
+            schema.resolve("API-MS-WIN-CORE-FILE-L1-2-4.DLL"),
+            Some("kernelbase.dll")
+        );
+        assert_eq!(
+            schema.resolve("api-ms-win-core-com-l1-1-1.dll"),
+            Some("combase.dll")
+        );
+        assert_eq!(
+            schema.resolve("ext-ms-win-ntuser-window-l1-1-0"),
+            Some("user32.dll")
+        );
+        assert_eq!(schema.resolve("ext-ms-win-missing-l1-1-0.dll"), None);
+        // Only the revision is stripped, not the minor version
+        assert_eq!(schema.resolve("api-ms-win-core-file-l1-3-0.dll"), None);
+        assert_eq!(schema.resolve("kernel32.dll"), None);
+
+        let mut imports = vec![ImportEntry {
+            dll_name: "api-ms-win-core-file-l1-2-1.dll".to_string(),
+            functions: vec!["CreateFile2".to_string()],
+            kind: ImportKind::Normal,
+            host_dll: None,
+        }];
+        schema.resolve_imports(&mut imports);
+        assert_eq!(imports[0].host_dll.as_deref(), Some("kernelbase.dll"));
+    }
+
+    #[test]
+    fn contract_key_strips_extension_and_revision() {
+        assert_eq!(
+            contract_key("api-ms-win-core-file-l1-2-0.dll"),
+            "api-ms-win-core-file-l1-2"
+        );
+        assert_eq!(
+            contract_key("API-MS-Win-Core-File-L1-2-3"),
+            "api-ms-win-core-file-l1-2"
+        );
+        assert_eq!(contract_key("nodash.DLL"), "nodash");
+        assert_eq!(contract_key("trailing-.dll"), "trailing");
+
+        let schema =
+            ApiSetSchema::from_entries([("api-ms-win-crt-runtime-l1-1-0.dll", "ucrtbase.dll")]);
+        assert_eq!(
+            schema.resolve("api-ms-win-crt-runtime-l1-1-7.dll"),
+            Some("ucrtbase.dll")
+        );
+    }
+
+    #[test]
+    fn bad_headers_are_rejected() {
+        assert_eq!(
+            ApiSetSchema::parse(&[]).unwrap_err(),
+            PeError::TooSmall("API set namespace")
+        );
+
+        let mut blob = sample();
+        blob[0..4].copy_from_slice(&2u32.to_le_bytes());
+        assert_eq!(
+            ApiSetSchema::parse(&blob).unwrap_err(),
+            PeError::UnsupportedApiSetVersion(2)
+        );
+
+        // More entries than the blob holds
+        let mut blob = sample();
+        blob[12..16].copy_from_slice(&1000u32.to_le_bytes());
+        assert_eq!(
+            ApiSetSchema::parse(&blob).unwrap_err(),
+            PeError::OutOfBounds("API set entry")
+        );
+    }
+
+    #[test]
+    fn out_of_range_offsets_skip_the_contract() {
+        let entry = |i: usize| HEADER_SIZE + i * NAMESPACE_ENTRY_SIZE;
+
+        // Name past the end of the blob
+        let mut blob = sample();
+        blob[entry(0) + 4..entry(0) + 8].copy_from_slice(&u32::MAX.to_le_bytes());
+        let schema = ApiSetSchema::parse(&blob).unwrap();
+        assert_eq!(schema.len(), 2);
+        assert_eq!(schema.resolve("api-ms-win-core-file-l1-2-0.dll"), None);
+
+        // Values past the end of the blob
+        let mut blob = sample();
+        blob[entry(1) + 16..entry(1) + 20].copy_from_slice(&u32::MAX.to_le_bytes());
+        let schema = ApiSetSchema::parse(&blob).unwrap();
+        assert_eq!(schema.len(), 2);
+        assert_eq!(schema.resolve("api-ms-win-core-com-l1-1-1.dll"), None);
+
+        // A name length running off the end
+        let mut blob = sample();
+        blob[entry(2) + 8..entry(2) + 16].copy_from_slice(&[0xFF; 8]);
+        assert_eq!(ApiSetSchema::parse(&blob).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn truncated_schemas_fail_or_parse_without_panicking() {
+        let blob = sample();
+        for len in 0..blob.len() {
+            if let Ok(schema) = ApiSetSchema::parse(&blob[..len]) {
+                assert!(schema.len() <= 3);
+            }
+        }
+    }
+}
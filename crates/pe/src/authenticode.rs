@@ -0,0 +1,486 @@
+//! Authenticode signature parsing (WIN_CERTIFICATE / PKCS#7 SignedData)
+//!
+//! Extracts the signer and timestamp from the embedded signature and checks
+//! the signed digest against a freshly computed image hash. Certificate chains
+//! and the RSA/ECDSA signatures themselves are not validated.
+
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::der::*;
+use crate::error::PeError;
+use crate::headers::{DATA_DIRECTORY_SIZE, DIRECTORY_SECURITY};
+use crate::read::{read_u16, read_u32};
+use crate::PeImage;
+
+/// WIN_CERT_TYPE_PKCS_SIGNED_DATA
+const WIN_CERT_TYPE_PKCS_SIGNED_DATA: u16 = 0x0002;
+
+const OID_SIGNED_DATA: &str = "1.2.840.113549.1.7.2";
+const OID_SPC_INDIRECT_DATA: &str = "1.3.6.1.4.1.311.2.1.4";
+const OID_SIGNING_TIME: &str = "1.2.840.113549.1.9.5";
+const OID_COUNTER_SIGNATURE: &str = "1.2.840.113549.1.9.6";
+const OID_RFC3161_TIMESTAMP: &str = "1.3.6.1.4.1.311.3.3.1";
+
+/// Hash algorithm used for the Authenticode digest
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    fn from_oid(oid: &str) -> Option<Self> {
+        match oid {
+            "1.2.840.113549.2.5" => Some(DigestAlgorithm::Md5),
+            "1.3.14.3.2.26" => Some(DigestAlgorithm::Sha1),
+            "2.16.840.1.101.3.4.2.1" => Some(DigestAlgorithm::Sha256),
+            "2.16.840.1.101.3.4.2.2" => Some(DigestAlgorithm::Sha384),
+            "2.16.840.1.101.3.4.2.3" => Some(DigestAlgorithm::Sha512),
+            _ => None,
+        }
+    }
+
+    /// Hash the concatenation of `parts`
+    pub fn digest(&self, parts: &[&[u8]]) -> Vec<u8> {
+        fn run<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+            let mut hasher = D::new();
+            for part in parts {
+                hasher.update(part);
+            }
+            hasher.finalize().to_vec()
+        }
+        match self {
+            DigestAlgorithm::Md5 => run::<Md5>(parts),
+            DigestAlgorithm::Sha1 => run::<Sha1>(parts),
+            DigestAlgorithm::Sha256 => run::<Sha256>(parts),
+            DigestAlgorithm::Sha384 => run::<Sha384>(parts),
+            DigestAlgorithm::Sha512 => run::<Sha512>(parts),
+        }
+    }
+}
+
+impl std::fmt::Display for DigestAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DigestAlgorithm::Md5 => write!(f, "MD5"),
+            DigestAlgorithm::Sha1 => write!(f, "SHA-1"),
+            DigestAlgorithm::Sha256 => write!(f, "SHA-256"),
+            DigestAlgorithm::Sha384 => write!(f, "SHA-384"),
+            DigestAlgorithm::Sha512 => write!(f, "SHA-512"),
+        }
+    }
+}
+
+/// Summary of an X.509 certificate embedded in a signature
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    /// Serial number as uppercase hex
+    pub serial_number: String,
+    pub not_before: Option<String>,
+    pub not_after: Option<String>,
+}
+
+/// Parsed Authenticode signature of a PE image
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuthenticodeSignature {
+    pub signer_subject: String,
+    /// commonName attribute of the signer's subject
+    pub signer_common_name: Option<String>,
+    pub signer_issuer: String,
+    /// Signer certificate serial number as uppercase hex
+    pub signer_serial: String,
+    /// Signing time from the counter-signature or RFC 3161 timestamp
+    pub timestamp: Option<String>,
+    pub digest_algorithm: DigestAlgorithm,
+    /// Digest recorded in the signature (SpcIndirectDataContent)
+    pub signed_digest: Vec<u8>,
+    /// Digest computed from the image
+    pub image_digest: Vec<u8>,
+    /// Every certificate carried in the signature
+    pub certificates: Vec<CertificateInfo>,
+}
+
+impl AuthenticodeSignature {
+    /// Whether the image hash matches the signed digest
+    pub fn hash_matches(&self) -> bool {
+        self.signed_digest == self.image_digest
+    }
+
+    /// Common name of the signer, falling back to the full subject.
+    /// Claimed by the signature only: the certificate chain and the
+    /// signature over it are not verified.
+    pub fn signer_name(&self) -> &str {
+        self.signer_common_name
+            .as_deref()
+            .unwrap_or(&self.signer_subject)
+    }
+}
+
+/// Hex string of a digest
+pub fn hex_digest(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl PeImage<'_> {
+    /// Parse the embedded Authenticode signature (data directory 4).
+    /// Returns `Ok(None)` for images without one.
+    pub fn authenticode(&self) -> Result<Option<AuthenticodeSignature>, PeError> {
+        let security_dir = match self.data_directory(DIRECTORY_SECURITY) {
+            Some(dir) => dir,
+            None => return Ok(None),
+        };
+
+        // The security directory address is a file offset, not an RVA
+        let table_start = security_dir.virtual_address as usize;
+        let table = self
+            .data()
+            .get(table_start..table_start.saturating_add(security_dir.size as usize))
+            .ok_or(PeError::OutOfBounds("certificate table"))?;
+
+        // WIN_CERTIFICATE entries are 8-byte aligned; use the first PKCS#7 one
+        let mut offset = 0;
+        let pkcs7 = loop {
+            let (length, cert_type) = match (read_u32(table, offset), read_u16(table, offset + 6)) {
+                (Some(length), Some(cert_type)) if length >= 8 => (length as usize, cert_type),
+                _ => return Ok(None),
+            };
+            let blob = table
+                .get(offset + 8..offset + length)
+                .ok_or(PeError::OutOfBounds("WIN_CERTIFICATE"))?;
+            if cert_type == WIN_CERT_TYPE_PKCS_SIGNED_DATA {
+                break blob;
+            }
+            offset += (length + 7) & !7;
+        };
+
+        let signed = SignedData::parse(pkcs7).ok_or(PeError::InvalidSignature)?;
+        let image_digest = signed
+            .digest_algorithm
+            .digest(&self.authenticode_ranges(table_start, table.len()));
+
+        let signer_cert = signed.certificates.iter().find(|cert| {
+            cert.issuer_raw == signed.signer_issuer_raw && cert.serial == signed.signer_serial
+        });
+
+        Ok(Some(AuthenticodeSignature {
+            signer_subject: signer_cert
+                .map(|c| c.info.subject.clone())
+                .unwrap_or_default(),
+            signer_common_name: signer_cert.and_then(|c| c.common_name.clone()),
+            signer_issuer: signed.signer_issuer,
+            signer_serial: format_serial(signed.signer_serial),
+            timestamp: signed.timestamp,
+            digest_algorithm: signed.digest_algorithm,
+            signed_digest: signed.digest,
+            image_digest,
+            certificates: signed.certificates.into_iter().map(|c| c.info).collect(),
+        }))
+    }
+
+    /// File ranges covered by the Authenticode hash: everything except the
+    /// checksum, the security directory entry and the certificate table
+    fn authenticode_ranges(&self, table_start: usize, table_len: usize) -> Vec<&[u8]> {
+        let data = self.data();
+        let opt_offset = self.optional_header_offset();
+        let checksum = opt_offset + 64;
+        let fixed_size = if self.is_64bit() { 112 } else { 96 };
+        let security_entry = opt_offset + fixed_size + DIRECTORY_SECURITY * DATA_DIRECTORY_SIZE;
+        let table_end = table_start.saturating_add(table_len);
+        let range = |start: usize, end: usize| data.get(start..end.min(data.len())).unwrap_or(&[]);
+
+        vec![
+            range(0, checksum),
+            range(checksum + 4, security_entry),
+            range(security_entry + DATA_DIRECTORY_SIZE, table_start),
+            range(table_end, data.len()),
+        ]
+    }
+}
+
+/// Certificate with the raw fields needed to match it to a signer
+struct ParsedCertificate<'a> {
+    issuer_raw: &'a [u8],
+    serial: &'a [u8],
+    common_name: Option<String>,
+    info: CertificateInfo,
+}
+
+impl<'a> ParsedCertificate<'a> {
+    fn parse(cert: &Tlv<'a>) -> Option<Self> {
+        let tbs = cert.children().next()?;
+        let mut fields = tbs.children().peekable();
+        // Optional explicit version
+        if fields.peek()?.tag == TAG_CONTEXT_0 {
+            fields.next();
+        }
+        let serial = fields.next().filter(|t| t.tag == TAG_INTEGER)?;
+        let _signature_algorithm = fields.next()?;
+        let issuer = fields.next()?;
+        let validity = fields.next()?;
+        let subject = fields.next()?;
+
+        let mut times = validity.children();
+        Some(ParsedCertificate {
+            issuer_raw: issuer.raw,
+            serial: serial.value,
+            common_name: common_name(&subject),
+            info: CertificateInfo {
+                subject: format_name(&subject),
+                issuer: format_name(&issuer),
+                serial_number: format_serial(serial.value),
+                not_before: times.next().and_then(|t| t.time()),
+                not_after: times.next().and_then(|t| t.time()),
+            },
+        })
+    }
+}
+
+/// The parts of a PKCS#7 SignedData we report on
+struct SignedData<'a> {
+    digest_algorithm: DigestAlgorithm,
+    digest: Vec<u8>,
+    certificates: Vec<ParsedCertificate<'a>>,
+    signer_issuer: String,
+    signer_issuer_raw: &'a [u8],
+    signer_serial: &'a [u8],
+    timestamp: Option<String>,
+}
+
+impl<'a> SignedData<'a> {
+    fn parse(blob: &'a [u8]) -> Option<Self> {
+        // ContentInfo ::= SEQUENCE { contentType, [0] EXPLICIT content }
+        let content_info = Tlv::expect(blob, TAG_SEQUENCE)?;
+        let mut fields = content_info.children();
+        if fields.next()?.oid()? != OID_SIGNED_DATA {
+            return None;
+        }
+        let signed_data = fields.next()?.children().next()?;
+
+        let mut fields = signed_data.children();
+        let _version = fields.next()?;
+        let _digest_algorithms = fields.next()?;
+        let (digest_algorithm, digest) = parse_indirect_data(&fields.next()?)?;
+
+        let mut certificates = Vec::new();
+        let mut signer_infos = None;
+        for field in fields {
+            match field.tag {
+                TAG_CONTEXT_0 => {
+                    certificates = field
+                        .children()
+                        .filter_map(|c| ParsedCertificate::parse(&c))
+                        .collect()
+                }
+                TAG_SET => signer_infos = Some(field),
+                _ => {}
+            }
+        }
+
+        // SignerInfo ::= SEQUENCE { version, issuerAndSerialNumber, digestAlgorithm,
+        //   [0] authenticatedAttributes OPTIONAL, digestEncryptionAlgorithm,
+        //   encryptedDigest, [1] unauthenticatedAttributes OPTIONAL }
+        let signer_info = signer_infos?.children().next()?;
+        let mut fields = signer_info.children();
+        let _version = fields.next()?;
+        let issuer_and_serial = fields.next()?;
+        let mut ids = issuer_and_serial.children();
+        let issuer = ids.next()?;
+        let serial = ids.next()?;
+
+        let mut timestamp = None;
+        for field in fields {
+            match field.tag {
+                TAG_CONTEXT_0 => timestamp = timestamp.or_else(|| signing_time(&field)),
+                TAG_CONTEXT_1 => timestamp = timestamp.or_else(|| countersignature_time(&field)),
+                _ => {}
+            }
+        }
+
+        Some(SignedData {
+            digest_algorithm,
+            digest,
+            certificates,
+            signer_issuer: format_name(&issuer),
+            signer_issuer_raw: issuer.raw,
+            signer_serial: serial.value,
+            timestamp,
+        })
+    }
+}
+
+/// Pull the digest out of `SpcIndirectDataContent`
+fn parse_indirect_data(content_info: &Tlv) -> Option<(DigestAlgorithm, Vec<u8>)> {
+    let mut fields = content_info.children();
+    if fields.next()?.oid()? != OID_SPC_INDIRECT_DATA {
+        return None;
+    }
+    // [0] EXPLICIT SpcIndirectDataContent ::= SEQUENCE { data, messageDigest DigestInfo }
+    let indirect = fields.next()?.children().next()?;
+    let digest_info = indirect.children().nth(1)?;
+    let mut fields = digest_info.children();
+    let algorithm = fields.next()?.children().next()?.oid()?;
+    let digest = fields.next().filter(|t| t.tag == TAG_OCTET_STRING)?;
+    Some((
+        DigestAlgorithm::from_oid(&algorithm)?,
+        digest.value.to_vec(),
+    ))
+}
+
+/// Iterate `(oid, first value)` pairs of an attribute set
+fn attributes<'a>(attrs: &Tlv<'a>) -> impl Iterator<Item = (String, Tlv<'a>)> {
+    attrs.children().filter_map(|attr| {
+        let mut fields = attr.children();
+        let oid = fields.next()?.oid()?;
+        let value = fields.next()?.children().next()?;
+        Some((oid, value))
+    })
+}
+
+/// `signingTime` from a set of authenticated attributes
+fn signing_time(attrs: &Tlv) -> Option<String> {
+    attributes(attrs)
+        .find(|(oid, _)| oid == OID_SIGNING_TIME)
+        .and_then(|(_, value)| value.time())
+}
+
+/// Timestamp from a legacy counter-signature or an RFC 3161 token
+fn countersignature_time(attrs: &Tlv) -> Option<String> {
+    for (oid, value) in attributes(attrs) {
+        match oid.as_str() {
+            OID_COUNTER_SIGNATURE => {
+                // Counter-signature is a SignerInfo; its signingTime lives in [0]
+                if let Some(time) = value
+                    .children()
+                    .filter(|f| f.tag == TAG_CONTEXT_0)
+                    .find_map(|f| signing_time(&f))
+                {
+                    return Some(time);
+                }
+            }
+            OID_RFC3161_TIMESTAMP => {
+                if let Some(time) = rfc3161_time(&value) {
+                    return Some(time);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `genTime` from an RFC 3161 timestamp token (ContentInfo wrapping TSTInfo)
+fn rfc3161_time(token: &Tlv) -> Option<String> {
+    let signed_data = token.children().nth(1)?.children().next()?;
+    // encapContentInfo ::= SEQUENCE { eContentType, [0] EXPLICIT OCTET STRING }
+    let encap = signed_data.children().nth(2)?;
+    let econtent = encap.children().nth(1)?.children().next()?;
+    let tst_info = Tlv::expect(econtent.value, TAG_SEQUENCE)?;
+    // TSTInfo ::= SEQUENCE { version, policy, messageImprint, serialNumber, genTime, ... }
+    tst_info
+        .children()
+        .find(|f| f.tag == TAG_GENERALIZED_TIME)?
+        .time()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `hello-world-x64.dll` signed with a throwaway test CA; the signer's
+    /// CN is "Contoso, Ltd" so a comma-splitting reader would cut it short
+    const SIGNED_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x64-signed.dll");
+    const X64_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x64.dll");
+
+    fn signature(data: &[u8]) -> AuthenticodeSignature {
+        PeImage::parse(data)
+            .unwrap()
+            .authenticode()
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn signed_digest_matches_image() {
+        let signature = signature(SIGNED_DLL);
+        assert_eq!(signature.digest_algorithm, DigestAlgorithm::Sha256);
+        assert_eq!(
+            hex_digest(&signature.signed_digest),
+            "6f34f6964d656b4ee4ba0c66cb5e37d0944ea523d4846903f9f2eac38cf33516"
+        );
+        assert!(signature.hash_matches());
+    }
+
+    #[test]
+    fn flipped_byte_is_a_mismatch() {
+        let mut data = SIGNED_DLL.to_vec();
+        // A byte inside .text, well away from the headers and the signature
+        data[0x500] ^= 0x01;
+        assert!(!signature(&data).hash_matches());
+
+        // The checksum is excluded from the hash, so patching it changes nothing
+        let mut data = SIGNED_DLL.to_vec();
+        let checksum = PeImage::parse(SIGNED_DLL).unwrap().optional_header_offset() + 64;
+        data[checksum] ^= 0xFF;
+        assert!(signature(&data).hash_matches());
+    }
+
+    #[test]
+    fn extracts_signer_issuer_and_serial() {
+        let signature = signature(SIGNED_DLL);
+        assert_eq!(signature.signer_subject, "C=US, O=Contoso, CN=Contoso, Ltd");
+        assert_eq!(signature.signer_name(), "Contoso, Ltd");
+        assert_eq!(
+            signature.signer_issuer,
+            "CN=DioProcess Test CA, O=DioProcess"
+        );
+        // 0x8F3A2B1C is encoded with a leading zero byte to keep it positive
+        assert_eq!(signature.signer_serial, "8F3A2B1C");
+        assert_eq!(
+            signature.timestamp.as_deref(),
+            Some("2026-03-01 12:00:00 UTC")
+        );
+
+        let subjects: Vec<&str> = signature
+            .certificates
+            .iter()
+            .map(|c| c.subject.as_str())
+            .collect();
+        assert_eq!(
+            subjects,
+            [
+                "C=US, O=Contoso, CN=Contoso, Ltd",
+                "CN=DioProcess Test CA, O=DioProcess"
+            ]
+        );
+        let leaf = &signature.certificates[0];
+        assert_eq!(leaf.not_before.as_deref(), Some("2026-01-01 00:00:00 UTC"));
+        assert_eq!(leaf.not_after.as_deref(), Some("2036-01-01 00:00:00 UTC"));
+    }
+
+    #[test]
+    fn unsigned_and_damaged_signatures() {
+        let image = PeImage::parse(X64_DLL).unwrap();
+        assert_eq!(image.authenticode().unwrap(), None);
+
+        // Corrupt the PKCS#7 ContentInfo tag right after the WIN_CERTIFICATE header
+        let mut data = SIGNED_DLL.to_vec();
+        data[X64_DLL.len() + 8] = 0x31;
+        let image = PeImage::parse(&data).unwrap();
+        assert!(matches!(
+            image.authenticode(),
+            Err(PeError::InvalidSignature)
+        ));
+
+        // A certificate table running past the end of the file
+        let truncated = &SIGNED_DLL[..SIGNED_DLL.len() - 16];
+        let image = PeImage::parse(truncated).unwrap();
+        assert!(matches!(image.authenticode(), Err(PeError::OutOfBounds(_))));
+    }
+}
@@ -0,0 +1,274 @@
+//! Minimal DER reader for the ASN.1 structures used by Authenticode
+
+pub(crate) const TAG_INTEGER: u8 = 0x02;
+pub(crate) const TAG_OCTET_STRING: u8 = 0x04;
+pub(crate) const TAG_OID: u8 = 0x06;
+pub(crate) const TAG_UTC_TIME: u8 = 0x17;
+pub(crate) const TAG_GENERALIZED_TIME: u8 = 0x18;
+pub(crate) const TAG_SEQUENCE: u8 = 0x30;
+pub(crate) const TAG_SET: u8 = 0x31;
+/// `[0]` constructed, context-specific
+pub(crate) const TAG_CONTEXT_0: u8 = 0xA0;
+/// `[1]` constructed, context-specific
+pub(crate) const TAG_CONTEXT_1: u8 = 0xA1;
+
+/// id-at-commonName
+const OID_COMMON_NAME: &str = "2.5.4.3";
+
+/// A single tag-length-value element
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Tlv<'a> {
+    pub tag: u8,
+    /// Contents octets
+    pub value: &'a [u8],
+    /// Full encoding including tag and length
+    pub raw: &'a [u8],
+}
+
+impl<'a> Tlv<'a> {
+    /// Parse the first element of `data`, returning it and the remaining bytes
+    pub fn parse(data: &'a [u8]) -> Option<(Self, &'a [u8])> {
+        let tag = *data.first()?;
+        let first = *data.get(1)? as usize;
+        let (len, header_len) = if first & 0x80 == 0 {
+            (first, 2)
+        } else {
+            // Long form; indefinite lengths (0x80) are not valid DER
+            let count = first & 0x7F;
+            if count == 0 || count > 4 {
+                return None;
+            }
+            let bytes = data.get(2..2 + count)?;
+            let len = bytes.iter().fold(0usize, |acc, &b| (acc << 8) | b as usize);
+            (len, 2 + count)
+        };
+        let end = header_len.checked_add(len)?;
+        let raw = data.get(..end)?;
+        Some((
+            Tlv {
+                tag,
+                value: &raw[header_len..],
+                raw,
+            },
+            &data[end..],
+        ))
+    }
+
+    /// Parse `data` as exactly one element with the expected tag
+    pub fn expect(data: &'a [u8], tag: u8) -> Option<Self> {
+        Tlv::parse(data)
+            .map(|(tlv, _)| tlv)
+            .filter(|tlv| tlv.tag == tag)
+    }
+
+    /// Iterate the children of a constructed element
+    pub fn children(&self) -> Children<'a> {
+        Children { rest: self.value }
+    }
+
+    /// Decode an OBJECT IDENTIFIER as dotted notation
+    pub fn oid(&self) -> Option<String> {
+        if self.tag != TAG_OID || self.value.is_empty() {
+            return None;
+        }
+        let mut arcs = vec![
+            (self.value[0] / 40).min(2) as u64,
+            self.value[0] as u64 - 40 * (self.value[0] / 40).min(2) as u64,
+        ];
+        let mut acc = 0u64;
+        for &byte in &self.value[1..] {
+            acc = (acc << 7) | (byte & 0x7F) as u64;
+            if byte & 0x80 == 0 {
+                arcs.push(acc);
+                acc = 0;
+            }
+        }
+        Some(
+            arcs.iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>()
+                .join("."),
+        )
+    }
+
+    /// Decode a string type (PrintableString, UTF8String, BMPString, ...)
+    pub fn string(&self) -> String {
+        match self.tag {
+            // BMPString is UTF-16BE
+            0x1E => {
+                let units: Vec<u16> = self
+                    .value
+                    .chunks_exact(2)
+                    .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                    .collect();
+                String::from_utf16_lossy(&units)
+            }
+            _ => String::from_utf8_lossy(self.value).to_string(),
+        }
+    }
+
+    /// Decode a UTCTime / GeneralizedTime as `YYYY-MM-DD HH:MM:SS UTC`
+    pub fn time(&self) -> Option<String> {
+        let text = std::str::from_utf8(self.value).ok()?;
+        let (year, rest) = match self.tag {
+            TAG_UTC_TIME => {
+                let yy: u32 = text.get(0..2)?.parse().ok()?;
+                // RFC 5280: years 50-99 are 19xx
+                (if yy >= 50 { 1900 + yy } else { 2000 + yy }, text.get(2..)?)
+            }
+            TAG_GENERALIZED_TIME => (text.get(0..4)?.parse().ok()?, text.get(4..)?),
+            _ => return None,
+        };
+        let field = |i: usize| rest.get(i..i + 2);
+        Some(format!(
+            "{:04}-{}-{} {}:{}:{} UTC",
+            year,
+            field(0)?,
+            field(2)?,
+            field(4)?,
+            field(6)?,
+            field(8).unwrap_or("00")
+        ))
+    }
+}
+
+/// Iterator over the children of a constructed element
+pub(crate) struct Children<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for Children<'a> {
+    type Item = Tlv<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (tlv, rest) = Tlv::parse(self.rest)?;
+        self.rest = rest;
+        Some(tlv)
+    }
+}
+
+/// `(OID, value)` of every attribute in an X.501 Name, in encoding order
+fn name_attributes<'a>(name: &Tlv<'a>) -> impl Iterator<Item = (String, String)> + 'a {
+    // Name ::= SEQUENCE OF RelativeDistinguishedName (SET OF AttributeTypeAndValue)
+    name.children()
+        .flat_map(|rdn| rdn.children())
+        .filter_map(|attribute| {
+            let mut fields = attribute.children();
+            let (oid, value) = (fields.next()?, fields.next()?);
+            Some((oid.oid().unwrap_or_default(), value.string()))
+        })
+}
+
+/// Render an X.501 Name as `CN=..., O=..., C=...`
+pub(crate) fn format_name(name: &Tlv) -> String {
+    name_attributes(name)
+        .map(|(oid, value)| {
+            let label = match oid.as_str() {
+                OID_COMMON_NAME => "CN",
+                "2.5.4.6" => "C",
+                "2.5.4.7" => "L",
+                "2.5.4.8" => "S",
+                "2.5.4.10" => "O",
+                "2.5.4.11" => "OU",
+                "1.2.840.113549.1.9.1" => "E",
+                _ => oid.as_str(),
+            };
+            format!("{}={}", label, value)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The commonName attribute of an X.501 Name, read as-is so a value
+/// containing ", " is not cut short
+pub(crate) fn common_name(name: &Tlv) -> Option<String> {
+    name_attributes(name)
+        .find(|(oid, _)| oid == OID_COMMON_NAME)
+        .map(|(_, value)| value)
+}
+
+/// Format an INTEGER's contents as uppercase hex, without the sign padding byte
+pub(crate) fn format_serial(value: &[u8]) -> String {
+    let bytes = match value {
+        [0, rest @ ..] if !rest.is_empty() => rest,
+        _ => value,
+    };
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_short_and_long_form_lengths() {
+        let (tlv, rest) = Tlv::parse(&[TAG_OCTET_STRING, 2, 0xAA, 0xBB, 0xCC]).unwrap();
+        assert_eq!(tlv.tag, TAG_OCTET_STRING);
+        assert_eq!(tlv.value, [0xAA, 0xBB]);
+        assert_eq!(tlv.raw.len(), 4);
+        assert_eq!(rest, [0xCC]);
+
+        // 0x82 0x01 0x00: two length bytes giving 256
+        let mut long = vec![TAG_SEQUENCE, 0x82, 0x01, 0x00];
+        long.resize(4 + 256, 0x55);
+        let (tlv, rest) = Tlv::parse(&long).unwrap();
+        assert_eq!(tlv.value.len(), 256);
+        assert_eq!(tlv.raw.len(), 260);
+        assert!(rest.is_empty());
+
+        // A long form may still encode a short length
+        let (tlv, _) = Tlv::parse(&[TAG_INTEGER, 0x81, 0x01, 0x07]).unwrap();
+        assert_eq!(tlv.value, [0x07]);
+    }
+
+    #[test]
+    fn rejects_truncated_and_indefinite_lengths() {
+        assert!(Tlv::parse(&[]).is_none());
+        assert!(Tlv::parse(&[TAG_SEQUENCE]).is_none());
+        // Contents shorter than the declared length
+        assert!(Tlv::parse(&[TAG_OCTET_STRING, 3, 0xAA, 0xBB]).is_none());
+        // Long form missing its length bytes, or its contents
+        assert!(Tlv::parse(&[TAG_SEQUENCE, 0x82, 0x01]).is_none());
+        assert!(Tlv::parse(&[TAG_SEQUENCE, 0x82, 0x01, 0x00, 0x00]).is_none());
+        // Indefinite length (BER only) and lengths wider than four bytes
+        assert!(Tlv::parse(&[TAG_SEQUENCE, 0x80, 0x00, 0x00]).is_none());
+        assert!(Tlv::parse(&[TAG_SEQUENCE, 0x85, 0, 0, 0, 0, 1, 0]).is_none());
+        // A length near usize::MAX must not overflow
+        assert!(Tlv::parse(&[TAG_SEQUENCE, 0x84, 0xFF, 0xFF, 0xFF, 0xFF]).is_none());
+
+        assert!(Tlv::expect(&[TAG_SET, 0], TAG_SEQUENCE).is_none());
+        assert_eq!(Tlv::expect(&[TAG_SET, 0], TAG_SET).unwrap().value, []);
+    }
+
+    #[test]
+    fn reads_common_name_containing_a_comma() {
+        // SEQUENCE { SET { SEQUENCE { 2.5.4.10, "Contoso" } },
+        //            SET { SEQUENCE { 2.5.4.3, "Contoso, Ltd" } } }
+        let attribute = |oid: u8, value: &str| {
+            let mut der = vec![0x31, 9 + value.len() as u8, 0x30, 7 + value.len() as u8];
+            der.extend([TAG_OID, 3, 0x55, 0x04, oid, 0x0C, value.len() as u8]);
+            der.extend(value.as_bytes());
+            der
+        };
+        let mut body = attribute(10, "Contoso");
+        body.extend(attribute(3, "Contoso, Ltd"));
+        let mut der = vec![TAG_SEQUENCE, body.len() as u8];
+        der.extend(body);
+
+        let name = Tlv::expect(&der, TAG_SEQUENCE).unwrap();
+        assert_eq!(format_name(&name), "O=Contoso, CN=Contoso, Ltd");
+        assert_eq!(common_name(&name).as_deref(), Some("Contoso, Ltd"));
+
+        let no_cn = attribute(10, "Contoso");
+        let mut der = vec![TAG_SEQUENCE, no_cn.len() as u8];
+        der.extend(no_cn);
+        assert_eq!(common_name(&Tlv::expect(&der, TAG_SEQUENCE).unwrap()), None);
+    }
+
+    #[test]
+    fn formats_serials_without_sign_padding() {
+        assert_eq!(format_serial(&[0x00, 0x8F, 0x3A]), "8F3A");
+        assert_eq!(format_serial(&[0x00]), "00");
+        assert_eq!(format_serial(&[0x10, 0x01]), "1001");
+    }
+}
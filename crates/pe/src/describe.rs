@@ -0,0 +1,181 @@
+//! Human-readable names for header fields and flag sets
+
+use crate::headers::*;
+
+/// COFF file header characteristics
+const FILE_FLAGS: &[(u16, &str)] = &[
+    (FILE_RELOCS_STRIPPED, "RELOCS_STRIPPED"),
+    (FILE_EXECUTABLE_IMAGE, "EXECUTABLE_IMAGE"),
+    (0x0004, "LINE_NUMS_STRIPPED"),
+    (0x0008, "LOCAL_SYMS_STRIPPED"),
+    (0x0010, "AGGRESSIVE_WS_TRIM"),
+    (FILE_LARGE_ADDRESS_AWARE, "LARGE_ADDRESS_AWARE"),
+    (0x0080, "BYTES_REVERSED_LO"),
+    (FILE_32BIT_MACHINE, "32BIT_MACHINE"),
+    (FILE_DEBUG_STRIPPED, "DEBUG_STRIPPED"),
+    (0x0400, "REMOVABLE_RUN_FROM_SWAP"),
+    (0x0800, "NET_RUN_FROM_SWAP"),
+    (FILE_SYSTEM, "SYSTEM"),
+    (FILE_DLL, "DLL"),
+    (0x4000, "UP_SYSTEM_ONLY"),
+    (0x8000, "BYTES_REVERSED_HI"),
+];
+
+/// Optional header DllCharacteristics, with the mitigation they enable
+const DLL_FLAGS: &[(u16, &str)] = &[
+    (DLL_HIGH_ENTROPY_VA, "HIGH_ENTROPY_VA (64-bit ASLR)"),
+    (DLL_DYNAMIC_BASE, "DYNAMIC_BASE (ASLR)"),
+    (DLL_FORCE_INTEGRITY, "FORCE_INTEGRITY"),
+    (DLL_NX_COMPAT, "NX_COMPAT (DEP)"),
+    (DLL_NO_ISOLATION, "NO_ISOLATION"),
+    (DLL_NO_SEH, "NO_SEH"),
+    (DLL_NO_BIND, "NO_BIND"),
+    (DLL_APPCONTAINER, "APPCONTAINER"),
+    (DLL_WDM_DRIVER, "WDM_DRIVER"),
+    (DLL_GUARD_CF, "GUARD_CF (CFG)"),
+    (DLL_TERMINAL_SERVER_AWARE, "TERMINAL_SERVER_AWARE"),
+];
+
+/// Section characteristics (alignment bits are reported separately)
+const SECTION_FLAGS: &[(u32, &str)] = &[
+    (SCN_CNT_CODE, "CODE"),
+    (SCN_CNT_INITIALIZED_DATA, "INITIALIZED_DATA"),
+    (SCN_CNT_UNINITIALIZED_DATA, "UNINITIALIZED_DATA"),
+    (0x0000_0200, "LNK_INFO"),
+    (0x0000_0800, "LNK_REMOVE"),
+    (0x0000_1000, "LNK_COMDAT"),
+    (0x0000_8000, "GPREL"),
+    (0x0100_0000, "LNK_NRELOC_OVFL"),
+    (SCN_MEM_DISCARDABLE, "DISCARDABLE"),
+    (SCN_MEM_NOT_CACHED, "NOT_CACHED"),
+    (SCN_MEM_NOT_PAGED, "NOT_PAGED"),
+    (SCN_MEM_SHARED, "SHARED"),
+    (SCN_MEM_EXECUTE, "EXECUTE"),
+    (SCN_MEM_READ, "READ"),
+    (SCN_MEM_WRITE, "WRITE"),
+];
+
+fn flag_names<T>(value: T, table: &[(T, &'static str)]) -> Vec<&'static str>
+where
+    T: Copy + PartialEq + std::ops::BitAnd<Output = T>,
+{
+    table
+        .iter()
+        .filter(|(flag, _)| value & *flag == *flag)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+/// Get the name of an IMAGE_FILE_MACHINE_* value
+pub fn machine_name(machine: u16) -> &'static str {
+    match machine {
+        0x0000 => "Unknown",
+        0x014c => "x86 (I386)",
+        0x0166 => "MIPS R4000",
+        0x01c0 => "ARM",
+        0x01c4 => "ARM Thumb-2 (ARMNT)",
+        0x0200 => "Itanium (IA64)",
+        0x8664 => "x64 (AMD64)",
+        0xaa64 => "ARM64",
+        0xa641 => "ARM64EC",
+        0xa64e => "ARM64X",
+        _ => "Other",
+    }
+}
+
+/// Get the name of an IMAGE_SUBSYSTEM_* value
+pub fn subsystem_name(subsystem: u16) -> &'static str {
+    match subsystem {
+        1 => "Native",
+        2 => "Windows GUI",
+        3 => "Windows Console",
+        5 => "OS/2 Console",
+        7 => "POSIX Console",
+        8 => "Native Win9x Driver",
+        9 => "Windows CE GUI",
+        10 => "EFI Application",
+        11 => "EFI Boot Service Driver",
+        12 => "EFI Runtime Driver",
+        13 => "EFI ROM",
+        14 => "Xbox",
+        16 => "Windows Boot Application",
+        _ => "Unknown",
+    }
+}
+
+/// Get the conventional name of a data directory index
+pub fn data_directory_name(index: usize) -> &'static str {
+    match index {
+        DIRECTORY_EXPORT => "Export",
+        DIRECTORY_IMPORT => "Import",
+        DIRECTORY_RESOURCE => "Resource",
+        DIRECTORY_EXCEPTION => "Exception",
+        DIRECTORY_SECURITY => "Security",
+        DIRECTORY_BASERELOC => "Base Relocation",
+        DIRECTORY_DEBUG => "Debug",
+        DIRECTORY_ARCHITECTURE => "Architecture",
+        DIRECTORY_GLOBALPTR => "Global Pointer",
+        DIRECTORY_TLS => "TLS",
+        DIRECTORY_LOAD_CONFIG => "Load Config",
+        DIRECTORY_BOUND_IMPORT => "Bound Import",
+        DIRECTORY_IAT => "IAT",
+        DIRECTORY_DELAY_IMPORT => "Delay Import",
+        DIRECTORY_COM_DESCRIPTOR => "CLR Runtime Header",
+        _ => "Reserved",
+    }
+}
+
+/// Names of the set COFF characteristics flags
+pub fn file_characteristics_names(characteristics: u16) -> Vec<&'static str> {
+    flag_names(characteristics, FILE_FLAGS)
+}
+
+/// Names of the set DllCharacteristics flags
+pub fn dll_characteristics_names(characteristics: u16) -> Vec<&'static str> {
+    flag_names(characteristics, DLL_FLAGS)
+}
+
+/// Names of the set section characteristics flags
+pub fn section_characteristics_names(characteristics: u32) -> Vec<&'static str> {
+    flag_names(characteristics, SECTION_FLAGS)
+}
+
+/// Short `RWX`-style protection string for a section
+pub fn section_protection(characteristics: u32) -> String {
+    let flag = |mask: u32, c: char| if characteristics & mask != 0 { c } else { '-' };
+    [
+        flag(SCN_MEM_READ, 'R'),
+        flag(SCN_MEM_WRITE, 'W'),
+        flag(SCN_MEM_EXECUTE, 'X'),
+    ]
+    .iter()
+    .collect()
+}
+
+/// Format a COFF `TimeDateStamp` (seconds since the Unix epoch) as UTC
+pub fn format_timestamp(timestamp: u32) -> String {
+    let secs = timestamp as i64;
+    let days = secs.div_euclid(86_400);
+    let rem = secs.rem_euclid(86_400);
+
+    // Civil-from-days (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
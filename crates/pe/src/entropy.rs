@@ -0,0 +1,51 @@
+//! Shannon entropy of raw section data
+
+use crate::headers::SectionHeader;
+use crate::PeImage;
+
+/// Shannon entropy of a byte buffer in bits per byte (0.0 - 8.0)
+pub fn shannon_entropy(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+
+    let mut counts = [0usize; 256];
+    for &byte in data {
+        counts[byte as usize] += 1;
+    }
+
+    let len = data.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// A section header together with the entropy of its raw data
+#[derive(Clone, Debug, PartialEq)]
+pub struct SectionInfo {
+    pub header: SectionHeader,
+    pub entropy: f64,
+}
+
+impl PeImage<'_> {
+    /// Entropy of a section's raw file data
+    pub fn section_entropy(&self, section: &SectionHeader) -> f64 {
+        shannon_entropy(self.section_data(section))
+    }
+
+    /// Every section header with the entropy of its raw data
+    pub fn section_infos(&self) -> Vec<SectionInfo> {
+        self.sections
+            .iter()
+            .map(|section| SectionInfo {
+                header: section.clone(),
+                entropy: self.section_entropy(section),
+            })
+            .collect()
+    }
+}
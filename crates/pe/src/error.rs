@@ -0,0 +1,40 @@
+//! PE parsing errors
+
+use std::fmt;
+
+/// Errors that can occur while parsing a PE image.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PeError {
+    TooSmall(&'static str),
+    InvalidDosSignature,
+    InvalidPeSignature,
+    UnsupportedOptionalHeader(u16),
+    OutOfBounds(&'static str),
+    MissingSection(&'static str),
+    UnsupportedApiSetVersion(u32),
+    InvalidSignature,
+    /// `SizeOfImage` is zero or implausibly large for the file
+    InvalidImageSize(u32),
+}
+
+impl fmt::Display for PeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PeError::TooSmall(what) => write!(f, "File too small for {}", what),
+            PeError::InvalidDosSignature => write!(f, "Invalid DOS magic (not MZ)"),
+            PeError::InvalidPeSignature => write!(f, "Invalid PE signature"),
+            PeError::UnsupportedOptionalHeader(magic) => {
+                write!(f, "Unsupported optional header magic 0x{:X}", magic)
+            }
+            PeError::OutOfBounds(what) => write!(f, "{} lies outside the file", what),
+            PeError::MissingSection(name) => write!(f, "Section {} not found", name),
+            PeError::UnsupportedApiSetVersion(version) => {
+                write!(f, "Unsupported API set schema version {}", version)
+            }
+            PeError::InvalidSignature => write!(f, "Malformed Authenticode signature"),
+            PeError::InvalidImageSize(size) => write!(f, "Invalid SizeOfImage 0x{:X}", size),
+        }
+    }
+}
+
+impl std::error::Error for PeError {}
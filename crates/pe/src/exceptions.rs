@@ -0,0 +1,228 @@
+//! x64 exception directory (RUNTIME_FUNCTION / UNWIND_INFO) parsing
+
+use crate::headers::{DIRECTORY_EXCEPTION, MACHINE_AMD64};
+use crate::PeImage;
+
+/// Size of an x64 RUNTIME_FUNCTION entry
+const RUNTIME_FUNCTION_SIZE: usize = 12;
+/// Guard against corrupt directory sizes
+const MAX_RUNTIME_FUNCTIONS: usize = 0x100000;
+
+/// The function has an exception handler
+pub const UNW_FLAG_EHANDLER: u8 = 0x1;
+/// The function has a termination handler
+pub const UNW_FLAG_UHANDLER: u8 = 0x2;
+/// The unwind info continues a previous RUNTIME_FUNCTION
+pub const UNW_FLAG_CHAININFO: u8 = 0x4;
+
+/// Decoded UNWIND_INFO header
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UnwindInfo {
+    pub version: u8,
+    /// UNW_FLAG_* bits
+    pub flags: u8,
+    pub prolog_size: u8,
+    pub code_count: u8,
+    pub frame_register: u8,
+    pub frame_offset: u8,
+    /// RVA of the language-specific handler (EHANDLER / UHANDLER)
+    pub handler: Option<u32>,
+    /// RVA of the parent function for chained unwind info
+    pub chained_to: Option<u32>,
+}
+
+/// A single x64 RUNTIME_FUNCTION entry
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RuntimeFunction {
+    pub begin_address: u32,
+    pub end_address: u32,
+    pub unwind_info_address: u32,
+    /// `None` if the unwind info lies outside the file
+    pub unwind_info: Option<UnwindInfo>,
+}
+
+impl RuntimeFunction {
+    /// Length of the function in bytes
+    pub fn len(&self) -> u32 {
+        self.end_address.saturating_sub(self.begin_address)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl PeImage<'_> {
+    /// Enumerate the exception directory (data directory 3).
+    /// Only the x64 format is decoded; other machines return an empty list.
+    pub fn runtime_functions(&self) -> Vec<RuntimeFunction> {
+        let mut functions = Vec::new();
+        if self.file_header.machine != MACHINE_AMD64 {
+            return functions;
+        }
+        let dir = match self.data_directory(DIRECTORY_EXCEPTION) {
+            Some(dir) => dir,
+            None => return functions,
+        };
+
+        let count = (dir.size as usize / RUNTIME_FUNCTION_SIZE).min(MAX_RUNTIME_FUNCTIONS);
+        for i in 0..count as u32 {
+            let entry = dir
+                .virtual_address
+                .wrapping_add(i * RUNTIME_FUNCTION_SIZE as u32);
+            let (Some(begin_address), Some(end_address), Some(unwind_info_address)) = (
+                self.read_u32_at_rva(entry),
+                self.read_u32_at_rva(entry.wrapping_add(4)),
+                self.read_u32_at_rva(entry.wrapping_add(8)),
+            ) else {
+                break;
+            };
+            if begin_address == 0 && end_address == 0 {
+                continue;
+            }
+            functions.push(RuntimeFunction {
+                begin_address,
+                end_address,
+                unwind_info_address,
+                unwind_info: self.unwind_info(unwind_info_address),
+            });
+        }
+
+        functions
+    }
+
+    /// Decode the UNWIND_INFO header at an RVA
+    fn unwind_info(&self, rva: u32) -> Option<UnwindInfo> {
+        let header = self.slice_at_rva(rva, 4)?;
+
+        let mut info = UnwindInfo {
+            version: header[0] & 0x7,
+            flags: header[0] >> 3,
+            prolog_size: header[1],
+            code_count: header[2],
+            frame_register: header[3] & 0xF,
+            frame_offset: header[3] >> 4,
+            handler: None,
+            chained_to: None,
+        };
+
+        // Unwind codes are 2 bytes each, padded to an even count
+        let trailer = rva.wrapping_add(4 + ((info.code_count as u32 + 1) & !1) * 2);
+        if info.flags & UNW_FLAG_CHAININFO != 0 {
+            info.chained_to = self.read_u32_at_rva(trailer);
+        } else if info.flags & (UNW_FLAG_EHANDLER | UNW_FLAG_UHANDLER) != 0 {
+            info.handler = self.read_u32_at_rva(trailer);
+        }
+
+        Some(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const X64_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x64.dll");
+    const X86_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x86.dll");
+
+    /// File offset of the exception directory
+    fn pdata_offset(data: &[u8]) -> usize {
+        let image = PeImage::parse(data).unwrap();
+        let dir = image.data_directory(DIRECTORY_EXCEPTION).unwrap();
+        image.rva_to_offset(dir.virtual_address).unwrap()
+    }
+
+    #[test]
+    fn parses_sample_runtime_functions() {
+        let functions = PeImage::parse(X64_DLL).unwrap().runtime_functions();
+        assert_eq!(functions.len(), 38);
+        assert!(functions
+            .windows(2)
+            .all(|w| w[0].end_address <= w[1].begin_address));
+
+        let first = &functions[0];
+        assert_eq!(
+            (first.begin_address, first.end_address, first.len()),
+            (0x1000, 0x1024, 0x24)
+        );
+        assert_eq!(
+            first.unwind_info,
+            Some(UnwindInfo {
+                version: 1,
+                prolog_size: 4,
+                code_count: 1,
+                ..Default::default()
+            })
+        );
+
+        // Functions with SEH handlers point at the shared language handler
+        let handled: Vec<(u32, u8, Option<u32>)> = functions
+            .iter()
+            .filter_map(|f| {
+                let info = f.unwind_info.as_ref()?;
+                (info.flags != 0).then_some((f.begin_address, info.flags, info.handler))
+            })
+            .collect();
+        assert_eq!(
+            handled,
+            [
+                (0x10F4, UNW_FLAG_UHANDLER, Some(0x1E50)),
+                (0x1220, UNW_FLAG_UHANDLER, Some(0x1E50)),
+                (0x12A4, UNW_FLAG_EHANDLER, Some(0x1E50)),
+                (0x1834, UNW_FLAG_EHANDLER, Some(0x1E50)),
+            ]
+        );
+    }
+
+    #[test]
+    fn other_machines_are_not_decoded() {
+        assert!(PeImage::parse(X86_DLL)
+            .unwrap()
+            .runtime_functions()
+            .is_empty());
+    }
+
+    #[test]
+    fn chained_unwind_info_is_followed() {
+        let mut data = X64_DLL.to_vec();
+        let image = PeImage::parse(X64_DLL).unwrap();
+        let unwind = image.rva_to_offset(0x2580).unwrap();
+        // Mark the first function's unwind info as chained; its single
+        // unwind code is padded to two, so the parent entry follows at +8
+        data[unwind] = 1 | (UNW_FLAG_CHAININFO << 3);
+        data[unwind + 8..unwind + 12].copy_from_slice(&0x1234u32.to_le_bytes());
+        let info = PeImage::parse(&data).unwrap().runtime_functions()[0]
+            .unwind_info
+            .clone()
+            .unwrap();
+        assert_eq!(info.flags, UNW_FLAG_CHAININFO);
+        assert_eq!(info.chained_to, Some(0x1234));
+        assert_eq!(info.handler, None);
+    }
+
+    #[test]
+    fn truncated_or_out_of_range_entries() {
+        let offset = pdata_offset(X64_DLL);
+
+        // Entry 10 is cut short, so only the first ten survive
+        let image = PeImage::parse(&X64_DLL[..offset + 10 * RUNTIME_FUNCTION_SIZE + 4]).unwrap();
+        let functions = image.runtime_functions();
+        assert_eq!(functions.len(), 10);
+        assert!(functions.iter().all(|f| f.unwind_info.is_some()));
+
+        // Unwind info outside the file
+        let mut data = X64_DLL.to_vec();
+        data[offset + 8..offset + 12].copy_from_slice(&0x7FFF_0000u32.to_le_bytes());
+        let functions = PeImage::parse(&data).unwrap().runtime_functions();
+        assert_eq!(functions[0].unwind_info, None);
+        assert_eq!(functions.len(), 38);
+
+        // A directory size far beyond the section
+        let mut data = X64_DLL.to_vec();
+        let image = PeImage::parse(X64_DLL).unwrap();
+        let entry = image.optional_header_offset() + 112 + DIRECTORY_EXCEPTION * 8;
+        data[entry + 4..entry + 8].copy_from_slice(&u32::MAX.to_le_bytes());
+        let functions = PeImage::parse(&data).unwrap().runtime_functions();
+        assert!(functions.len() >= 38 && functions.len() <= MAX_RUNTIME_FUNCTIONS);
+    }
+}
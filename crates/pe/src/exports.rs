@@ -0,0 +1,84 @@
+//! Export directory parsing
+
+use crate::headers::DIRECTORY_EXPORT;
+use crate::read::read_u32;
+use crate::PeImage;
+
+/// Ordinals are 16-bit, so no sane export table has more functions than this
+const MAX_EXPORTS: u32 = 0x10000;
+
+/// A single exported function
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExportEntry {
+    /// Biased ordinal (`Base` + index into the function table)
+    pub ordinal: u32,
+    /// `None` for exports only reachable by ordinal
+    pub name: Option<String>,
+    pub rva: u32,
+    /// Target of a forwarded export, e.g. `NTDLL.RtlAllocateHeap`
+    pub forwarder: Option<String>,
+}
+
+impl PeImage<'_> {
+    /// Enumerate the export directory (data directory 0)
+    pub fn exports(&self) -> Vec<ExportEntry> {
+        let mut exports = Vec::new();
+
+        let dir = match self.data_directory(DIRECTORY_EXPORT) {
+            Some(dir) => dir,
+            None => return exports,
+        };
+        // IMAGE_EXPORT_DIRECTORY is 40 bytes
+        let header = match self.slice_at_rva(dir.virtual_address, 40) {
+            Some(h) => h,
+            None => return exports,
+        };
+
+        let ordinal_base = read_u32(header, 16).unwrap_or(0);
+        let number_of_functions = read_u32(header, 20).unwrap_or(0).min(MAX_EXPORTS);
+        let number_of_names = read_u32(header, 24).unwrap_or(0).min(MAX_EXPORTS);
+        let functions_rva = read_u32(header, 28).unwrap_or(0);
+        let names_rva = read_u32(header, 32).unwrap_or(0);
+        let name_ordinals_rva = read_u32(header, 36).unwrap_or(0);
+
+        // Map function index -> name via the parallel name/ordinal tables
+        let mut names: Vec<Option<String>> = vec![None; number_of_functions as usize];
+        for i in 0..number_of_names {
+            let name_rva = self.read_u32_at_rva(names_rva.wrapping_add(i * 4));
+            let index = self.read_u16_at_rva(name_ordinals_rva.wrapping_add(i * 2));
+            if let (Some(name_rva), Some(index)) = (name_rva, index) {
+                if let Some(slot) = names.get_mut(index as usize) {
+                    *slot = self.read_cstring_at_rva(name_rva);
+                }
+            }
+        }
+
+        let dir_end = dir.virtual_address as u64 + dir.size as u64;
+        for (i, name) in names.into_iter().enumerate() {
+            let rva = match self.read_u32_at_rva(functions_rva.wrapping_add(i as u32 * 4)) {
+                Some(rva) => rva,
+                None => break,
+            };
+            // Unused slots in the function table
+            if rva == 0 {
+                continue;
+            }
+
+            // An RVA pointing back inside the export directory is a forwarder string
+            let forwarder = if rva >= dir.virtual_address && (rva as u64) < dir_end {
+                self.read_cstring_at_rva(rva)
+            } else {
+                None
+            };
+
+            exports.push(ExportEntry {
+                ordinal: ordinal_base.wrapping_add(i as u32),
+                name,
+                rva,
+                forwarder,
+            });
+        }
+
+        exports
+    }
+}
@@ -0,0 +1,175 @@
+//! File hashes and import hash (imphash) fingerprints
+
+use crate::authenticode::{hex_digest, DigestAlgorithm};
+use crate::imports::{ImportEntry, ImportKind};
+use crate::ordinals::ordinal_name;
+use crate::PeImage;
+
+/// Cryptographic hashes and imphash of a file, as lowercase hex
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileHashes {
+    pub md5: String,
+    pub sha1: String,
+    pub sha256: String,
+    /// `None` for non-PE files and images without an import directory
+    pub imphash: Option<String>,
+}
+
+impl FileHashes {
+    /// Hash raw file contents, adding the imphash if they parse as a PE image
+    pub fn compute(data: &[u8]) -> Self {
+        FileHashes {
+            md5: hex_digest(&DigestAlgorithm::Md5.digest(&[data])),
+            sha1: hex_digest(&DigestAlgorithm::Sha1.digest(&[data])),
+            sha256: hex_digest(&DigestAlgorithm::Sha256.digest(&[data])),
+            imphash: PeImage::parse(data)
+                .ok()
+                .and_then(|image| imphash(&image.normal_imports())),
+        }
+    }
+}
+
+/// Compute the imphash (MD5 of the ordered `dll.function` list) of the
+/// classic import directory, in the format popularised by pefile.
+///
+/// Delay-load and bound entries are ignored. Imports by ordinal from
+/// ws2_32/wsock32 hash under their function names as pefile resolves them;
+/// other ordinals, including oleaut32's, hash as `ordN`.
+pub fn imphash(imports: &[ImportEntry]) -> Option<String> {
+    let mut parts = Vec::new();
+    for entry in imports.iter().filter(|e| e.kind == ImportKind::Normal) {
+        let dll = entry.dll_name.to_lowercase();
+        let library = match dll.rsplit_once('.') {
+            Some((stem, "dll" | "ocx" | "sys")) => stem.to_string(),
+            _ => dll.clone(),
+        };
+        for function in &entry.functions {
+            let function = match function.strip_prefix("Ordinal #") {
+                Some(ordinal) => ordinal
+                    .parse()
+                    .ok()
+                    .and_then(|ordinal| ordinal_name(&dll, ordinal))
+                    .map_or_else(|| format!("ord{}", ordinal), str::to_lowercase),
+                None => function.to_lowercase(),
+            };
+            parts.push(format!("{}.{}", library, function));
+        }
+    }
+
+    if parts.is_empty() {
+        return None;
+    }
+    let joined = parts.join(",");
+    Some(hex_digest(
+        &DigestAlgorithm::Md5.digest(&[joined.as_bytes()]),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headers::DIRECTORY_IMPORT;
+
+    const X64_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x64.dll");
+    const X86_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x86.dll");
+
+    fn import(dll_name: &str, functions: &[&str], kind: ImportKind) -> ImportEntry {
+        ImportEntry {
+            dll_name: dll_name.to_string(),
+            functions: functions.iter().map(|f| f.to_string()).collect(),
+            kind,
+            host_dll: None,
+        }
+    }
+
+    #[test]
+    fn hashes_the_samples() {
+        let hashes = FileHashes::compute(X64_DLL);
+        assert_eq!(hashes.md5, "7f8a2b842948eb70133fa34f0cfe772b");
+        assert_eq!(hashes.sha1, "7ce46211a5a8d7fe4a767e12bd80769673fdaee5");
+        assert_eq!(
+            hashes.sha256,
+            "078ca38607f24fd21a563fa5189843734677b98d5017d5ebb03b2960053b25b5"
+        );
+        // Reference values computed outside this crate with pefile's algorithm
+        assert_eq!(
+            hashes.imphash.as_deref(),
+            Some("14e2b78ee82ad03fac47525feddca7e6")
+        );
+        assert_eq!(
+            FileHashes::compute(X86_DLL).imphash.as_deref(),
+            Some("10d8cbcc4d9e244d697f1c09224856dc")
+        );
+    }
+
+    #[test]
+    fn imphash_normalizes_names_and_ordinals() {
+        let imports = [
+            import("KERNEL32.dll", &["CreateThread"], ImportKind::Normal),
+            import(
+                "WS2_32.DLL",
+                &["Ordinal #115", "Ordinal #100"],
+                ImportKind::Normal,
+            ),
+            import("driver.SYS", &["IoCallDriver"], ImportKind::Normal),
+            import("library.drv", &["Init"], ImportKind::Normal),
+            // Only the classic import directory counts
+            import("dbghelp.dll", &["MiniDumpWriteDump"], ImportKind::Delay),
+        ];
+        let joined = "kernel32.createthread,ws2_32.wsastartup,ws2_32.ord100,driver.iocalldriver,\
+                      library.drv.init";
+        assert_eq!(
+            imphash(&imports),
+            Some(hex_digest(
+                &DigestAlgorithm::Md5.digest(&[joined.as_bytes()])
+            ))
+        );
+
+        assert_eq!(imphash(&[]), None);
+        assert_eq!(
+            imphash(&[import("dbghelp.dll", &["X"], ImportKind::Delay)]),
+            None
+        );
+    }
+
+    #[test]
+    fn winsock_ordinals_hash_under_their_names() {
+        // A typical downloader importing winsock only by ordinal; pefile
+        // hashes "wsock32.wsastartup,wsock32.socket,wsock32.connect,..."
+        let imports = [import(
+            "WSOCK32.dll",
+            &[
+                "Ordinal #115",
+                "Ordinal #23",
+                "Ordinal #4",
+                "Ordinal #19",
+                "Ordinal #3",
+            ],
+            ImportKind::Normal,
+        )];
+        assert_eq!(
+            imphash(&imports).as_deref(),
+            Some("0ee538ecd0e72cf15fe05ce6259cf14d")
+        );
+    }
+
+    #[test]
+    fn non_pe_and_truncated_files_still_hash() {
+        let hashes = FileHashes::compute(b"");
+        assert_eq!(hashes.md5, "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(hashes.imphash, None);
+        assert_eq!(FileHashes::compute(b"not a PE file").imphash, None);
+
+        // Cutting the file before the import directory drops only the imphash
+        let image = PeImage::parse(X64_DLL).unwrap();
+        let imports = image.data_directory(DIRECTORY_IMPORT).unwrap();
+        let offset = image.rva_to_offset(imports.virtual_address).unwrap();
+        let truncated = FileHashes::compute(&X64_DLL[..offset]);
+        assert_eq!(truncated.sha256.len(), 64);
+        assert_eq!(truncated.imphash, None);
+
+        for len in (offset..offset + 0x200).step_by(3) {
+            let _ = FileHashes::compute(&X64_DLL[..len]);
+        }
+    }
+}
@@ -0,0 +1,368 @@
+//! PE header structures (DOS, COFF, optional header, sections)
+
+use crate::error::PeError;
+use crate::read::{read_u16, read_u32, read_u64, read_u8};
+
+/// "MZ"
+pub const DOS_SIGNATURE: u16 = 0x5A4D;
+/// "PE\0\0"
+pub const PE_SIGNATURE: u32 = 0x0000_4550;
+/// Optional header magic for 32-bit images
+pub const PE32_MAGIC: u16 = 0x10b;
+/// Optional header magic for 64-bit images
+pub const PE32_PLUS_MAGIC: u16 = 0x20b;
+
+pub const DOS_HEADER_SIZE: usize = 64;
+pub const FILE_HEADER_SIZE: usize = 20;
+pub const SECTION_HEADER_SIZE: usize = 40;
+pub const DATA_DIRECTORY_SIZE: usize = 8;
+
+// IMAGE_FILE_MACHINE_* values
+pub const MACHINE_I386: u16 = 0x014c;
+pub const MACHINE_AMD64: u16 = 0x8664;
+pub const MACHINE_ARM64: u16 = 0xaa64;
+
+// Data directory indices
+pub const DIRECTORY_EXPORT: usize = 0;
+pub const DIRECTORY_IMPORT: usize = 1;
+pub const DIRECTORY_RESOURCE: usize = 2;
+pub const DIRECTORY_EXCEPTION: usize = 3;
+pub const DIRECTORY_SECURITY: usize = 4;
+pub const DIRECTORY_BASERELOC: usize = 5;
+pub const DIRECTORY_DEBUG: usize = 6;
+pub const DIRECTORY_ARCHITECTURE: usize = 7;
+pub const DIRECTORY_GLOBALPTR: usize = 8;
+pub const DIRECTORY_TLS: usize = 9;
+pub const DIRECTORY_LOAD_CONFIG: usize = 10;
+pub const DIRECTORY_BOUND_IMPORT: usize = 11;
+pub const DIRECTORY_IAT: usize = 12;
+pub const DIRECTORY_DELAY_IMPORT: usize = 13;
+pub const DIRECTORY_COM_DESCRIPTOR: usize = 14;
+
+// IMAGE_FILE_* characteristics (COFF header)
+pub const FILE_RELOCS_STRIPPED: u16 = 0x0001;
+pub const FILE_EXECUTABLE_IMAGE: u16 = 0x0002;
+pub const FILE_LARGE_ADDRESS_AWARE: u16 = 0x0020;
+pub const FILE_32BIT_MACHINE: u16 = 0x0100;
+pub const FILE_DEBUG_STRIPPED: u16 = 0x0200;
+pub const FILE_SYSTEM: u16 = 0x1000;
+pub const FILE_DLL: u16 = 0x2000;
+
+// IMAGE_DLLCHARACTERISTICS_* (optional header)
+pub const DLL_HIGH_ENTROPY_VA: u16 = 0x0020;
+pub const DLL_DYNAMIC_BASE: u16 = 0x0040;
+pub const DLL_FORCE_INTEGRITY: u16 = 0x0080;
+pub const DLL_NX_COMPAT: u16 = 0x0100;
+pub const DLL_NO_ISOLATION: u16 = 0x0200;
+pub const DLL_NO_SEH: u16 = 0x0400;
+pub const DLL_NO_BIND: u16 = 0x0800;
+pub const DLL_APPCONTAINER: u16 = 0x1000;
+pub const DLL_WDM_DRIVER: u16 = 0x2000;
+pub const DLL_GUARD_CF: u16 = 0x4000;
+pub const DLL_TERMINAL_SERVER_AWARE: u16 = 0x8000;
+
+// IMAGE_SCN_* section characteristics
+pub const SCN_CNT_CODE: u32 = 0x0000_0020;
+pub const SCN_CNT_INITIALIZED_DATA: u32 = 0x0000_0040;
+pub const SCN_CNT_UNINITIALIZED_DATA: u32 = 0x0000_0080;
+pub const SCN_MEM_DISCARDABLE: u32 = 0x0200_0000;
+pub const SCN_MEM_NOT_CACHED: u32 = 0x0400_0000;
+pub const SCN_MEM_NOT_PAGED: u32 = 0x0800_0000;
+pub const SCN_MEM_SHARED: u32 = 0x1000_0000;
+pub const SCN_MEM_EXECUTE: u32 = 0x2000_0000;
+pub const SCN_MEM_READ: u32 = 0x4000_0000;
+pub const SCN_MEM_WRITE: u32 = 0x8000_0000;
+
+/// IMAGE_DOS_HEADER
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DosHeader {
+    pub magic: u16,
+    pub bytes_on_last_page: u16,
+    pub pages_in_file: u16,
+    pub relocations: u16,
+    pub header_paragraphs: u16,
+    pub min_extra_paragraphs: u16,
+    pub max_extra_paragraphs: u16,
+    pub initial_ss: u16,
+    pub initial_sp: u16,
+    pub checksum: u16,
+    pub initial_ip: u16,
+    pub initial_cs: u16,
+    pub relocation_table_offset: u16,
+    pub overlay_number: u16,
+    pub oem_id: u16,
+    pub oem_info: u16,
+    /// `e_lfanew` - file offset of the PE signature
+    pub pe_offset: u32,
+}
+
+impl DosHeader {
+    pub(crate) fn parse(data: &[u8]) -> Result<Self, PeError> {
+        if data.len() < DOS_HEADER_SIZE {
+            return Err(PeError::TooSmall("DOS header"));
+        }
+        let word = |offset: usize| read_u16(data, offset).unwrap_or(0);
+        let header = DosHeader {
+            magic: word(0),
+            bytes_on_last_page: word(2),
+            pages_in_file: word(4),
+            relocations: word(6),
+            header_paragraphs: word(8),
+            min_extra_paragraphs: word(10),
+            max_extra_paragraphs: word(12),
+            initial_ss: word(14),
+            initial_sp: word(16),
+            checksum: word(18),
+            initial_ip: word(20),
+            initial_cs: word(22),
+            relocation_table_offset: word(24),
+            overlay_number: word(26),
+            oem_id: word(36),
+            oem_info: word(38),
+            pe_offset: read_u32(data, 60).unwrap_or(0),
+        };
+        if header.magic != DOS_SIGNATURE {
+            return Err(PeError::InvalidDosSignature);
+        }
+        Ok(header)
+    }
+}
+
+/// IMAGE_FILE_HEADER (COFF header)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileHeader {
+    pub machine: u16,
+    pub number_of_sections: u16,
+    pub time_date_stamp: u32,
+    pub pointer_to_symbol_table: u32,
+    pub number_of_symbols: u32,
+    pub size_of_optional_header: u16,
+    pub characteristics: u16,
+}
+
+impl FileHeader {
+    pub(crate) fn parse(data: &[u8], offset: usize) -> Result<Self, PeError> {
+        let bytes = data
+            .get(offset..offset.saturating_add(FILE_HEADER_SIZE))
+            .ok_or(PeError::TooSmall("COFF header"))?;
+        Ok(FileHeader {
+            machine: read_u16(bytes, 0).unwrap_or(0),
+            number_of_sections: read_u16(bytes, 2).unwrap_or(0),
+            time_date_stamp: read_u32(bytes, 4).unwrap_or(0),
+            pointer_to_symbol_table: read_u32(bytes, 8).unwrap_or(0),
+            number_of_symbols: read_u32(bytes, 12).unwrap_or(0),
+            size_of_optional_header: read_u16(bytes, 16).unwrap_or(0),
+            characteristics: read_u16(bytes, 18).unwrap_or(0),
+        })
+    }
+}
+
+/// IMAGE_DATA_DIRECTORY
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DataDirectory {
+    pub virtual_address: u32,
+    pub size: u32,
+}
+
+impl DataDirectory {
+    /// A directory is present when both its address and size are non-zero
+    pub fn is_present(&self) -> bool {
+        self.virtual_address != 0 && self.size != 0
+    }
+}
+
+/// IMAGE_OPTIONAL_HEADER32 / IMAGE_OPTIONAL_HEADER64, widened to a single shape
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OptionalHeader {
+    pub magic: u16,
+    pub major_linker_version: u8,
+    pub minor_linker_version: u8,
+    pub size_of_code: u32,
+    pub size_of_initialized_data: u32,
+    pub size_of_uninitialized_data: u32,
+    pub address_of_entry_point: u32,
+    pub base_of_code: u32,
+    /// Only present in PE32 images
+    pub base_of_data: Option<u32>,
+    pub image_base: u64,
+    pub section_alignment: u32,
+    pub file_alignment: u32,
+    pub major_operating_system_version: u16,
+    pub minor_operating_system_version: u16,
+    pub major_image_version: u16,
+    pub minor_image_version: u16,
+    pub major_subsystem_version: u16,
+    pub minor_subsystem_version: u16,
+    pub win32_version_value: u32,
+    pub size_of_image: u32,
+    pub size_of_headers: u32,
+    pub checksum: u32,
+    pub subsystem: u16,
+    pub dll_characteristics: u16,
+    pub size_of_stack_reserve: u64,
+    pub size_of_stack_commit: u64,
+    pub size_of_heap_reserve: u64,
+    pub size_of_heap_commit: u64,
+    pub loader_flags: u32,
+    pub number_of_rva_and_sizes: u32,
+    pub data_directories: Vec<DataDirectory>,
+}
+
+impl OptionalHeader {
+    pub(crate) fn parse(data: &[u8], offset: usize, size: usize) -> Result<Self, PeError> {
+        let magic = read_u16(data, offset).ok_or(PeError::TooSmall("optional header"))?;
+        let is_64 = match magic {
+            PE32_MAGIC => false,
+            PE32_PLUS_MAGIC => true,
+            other => return Err(PeError::UnsupportedOptionalHeader(other)),
+        };
+
+        // Fixed part is 96 bytes for PE32 and 112 bytes for PE32+
+        let fixed_size = if is_64 { 112 } else { 96 };
+        let bytes = data
+            .get(offset..offset.saturating_add(fixed_size))
+            .ok_or(PeError::TooSmall("optional header"))?;
+
+        let word = |off: usize| read_u16(bytes, off).unwrap_or(0);
+        let dword = |off: usize| read_u32(bytes, off).unwrap_or(0);
+        // Fields that are 4 bytes in PE32 and 8 bytes in PE32+
+        let (image_base, base_of_data) = if is_64 {
+            (read_u64(bytes, 24).unwrap_or(0), None)
+        } else {
+            (dword(28) as u64, Some(dword(24)))
+        };
+        let (stack_reserve, stack_commit, heap_reserve, heap_commit, tail) = if is_64 {
+            (
+                read_u64(bytes, 72).unwrap_or(0),
+                read_u64(bytes, 80).unwrap_or(0),
+                read_u64(bytes, 88).unwrap_or(0),
+                read_u64(bytes, 96).unwrap_or(0),
+                104,
+            )
+        } else {
+            (
+                dword(72) as u64,
+                dword(76) as u64,
+                dword(80) as u64,
+                dword(84) as u64,
+                88,
+            )
+        };
+        let number_of_rva_and_sizes = dword(tail + 4);
+
+        // Data directories follow the fixed part, limited by both the declared
+        // count and the size of the optional header in the COFF header
+        let available = size.saturating_sub(fixed_size) / DATA_DIRECTORY_SIZE;
+        let count = (number_of_rva_and_sizes as usize).min(available).min(16);
+        let dir_base = offset + fixed_size;
+        let data_directories = (0..count)
+            .map_while(|i| {
+                let off = dir_base + i * DATA_DIRECTORY_SIZE;
+                Some(DataDirectory {
+                    virtual_address: read_u32(data, off)?,
+                    size: read_u32(data, off + 4)?,
+                })
+            })
+            .collect();
+
+        Ok(OptionalHeader {
+            magic,
+            major_linker_version: read_u8(bytes, 2).unwrap_or(0),
+            minor_linker_version: read_u8(bytes, 3).unwrap_or(0),
+            size_of_code: dword(4),
+            size_of_initialized_data: dword(8),
+            size_of_uninitialized_data: dword(12),
+            address_of_entry_point: dword(16),
+            base_of_code: dword(20),
+            base_of_data,
+            image_base,
+            section_alignment: dword(32),
+            file_alignment: dword(36),
+            major_operating_system_version: word(40),
+            minor_operating_system_version: word(42),
+            major_image_version: word(44),
+            minor_image_version: word(46),
+            major_subsystem_version: word(48),
+            minor_subsystem_version: word(50),
+            win32_version_value: dword(52),
+            size_of_image: dword(56),
+            size_of_headers: dword(60),
+            checksum: dword(64),
+            subsystem: word(68),
+            dll_characteristics: word(70),
+            size_of_stack_reserve: stack_reserve,
+            size_of_stack_commit: stack_commit,
+            size_of_heap_reserve: heap_reserve,
+            size_of_heap_commit: heap_commit,
+            loader_flags: dword(tail),
+            number_of_rva_and_sizes,
+            data_directories,
+        })
+    }
+
+    /// Whether this is a PE32+ (64-bit) optional header
+    pub fn is_pe32_plus(&self) -> bool {
+        self.magic == PE32_PLUS_MAGIC
+    }
+}
+
+/// IMAGE_SECTION_HEADER
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SectionHeader {
+    pub name: String,
+    pub virtual_size: u32,
+    pub virtual_address: u32,
+    pub size_of_raw_data: u32,
+    pub pointer_to_raw_data: u32,
+    pub pointer_to_relocations: u32,
+    pub pointer_to_linenumbers: u32,
+    pub number_of_relocations: u16,
+    pub number_of_linenumbers: u16,
+    pub characteristics: u32,
+}
+
+impl SectionHeader {
+    pub(crate) fn parse(data: &[u8], offset: usize) -> Option<Self> {
+        let bytes = data.get(offset..offset.checked_add(SECTION_HEADER_SIZE)?)?;
+        let raw_name = &bytes[..8];
+        let name_len = raw_name.iter().position(|&b| b == 0).unwrap_or(8);
+        Some(SectionHeader {
+            name: String::from_utf8_lossy(&raw_name[..name_len]).to_string(),
+            virtual_size: read_u32(bytes, 8)?,
+            virtual_address: read_u32(bytes, 12)?,
+            size_of_raw_data: read_u32(bytes, 16)?,
+            pointer_to_raw_data: read_u32(bytes, 20)?,
+            pointer_to_relocations: read_u32(bytes, 24)?,
+            pointer_to_linenumbers: read_u32(bytes, 28)?,
+            number_of_relocations: read_u16(bytes, 32)?,
+            number_of_linenumbers: read_u16(bytes, 34)?,
+            characteristics: read_u32(bytes, 36)?,
+        })
+    }
+
+    /// Size the section occupies once mapped (falls back to raw size when
+    /// the linker left `VirtualSize` as zero)
+    pub fn mapped_size(&self) -> u32 {
+        if self.virtual_size == 0 {
+            self.size_of_raw_data
+        } else {
+            self.virtual_size
+        }
+    }
+
+    /// Whether the section is mapped executable
+    pub fn is_executable(&self) -> bool {
+        self.characteristics & SCN_MEM_EXECUTE != 0
+    }
+
+    /// Whether the section is mapped writable
+    pub fn is_writable(&self) -> bool {
+        self.characteristics & SCN_MEM_WRITE != 0
+    }
+
+    /// Whether `rva` falls inside this section once mapped
+    pub fn contains_rva(&self, rva: u32) -> bool {
+        rva >= self.virtual_address
+            && (rva as u64) < self.virtual_address as u64 + self.mapped_size() as u64
+    }
+}
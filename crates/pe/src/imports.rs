@@ -0,0 +1,228 @@
+//! Import directory parsing (classic, delay-load and bound imports)
+
+use crate::headers::{DIRECTORY_BOUND_IMPORT, DIRECTORY_DELAY_IMPORT, DIRECTORY_IMPORT};
+use crate::read::{read_cstring, read_u16, read_u32};
+use crate::PeImage;
+
+/// Size of IMAGE_IMPORT_DESCRIPTOR
+const IMPORT_DESCRIPTOR_SIZE: u32 = 20;
+/// Size of IMAGE_DELAYLOAD_DESCRIPTOR
+const DELAY_DESCRIPTOR_SIZE: u32 = 32;
+/// Size of IMAGE_BOUND_IMPORT_DESCRIPTOR / IMAGE_BOUND_FORWARDER_REF
+const BOUND_DESCRIPTOR_SIZE: usize = 8;
+
+/// Which import directory an entry came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportKind {
+    /// Import directory (data directory 1)
+    Normal,
+    /// Delay-load import directory (data directory 13)
+    Delay,
+    /// Bound import directory (data directory 11)
+    Bound,
+}
+
+impl std::fmt::Display for ImportKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportKind::Normal => write!(f, "Normal"),
+            ImportKind::Delay => write!(f, "Delay"),
+            ImportKind::Bound => write!(f, "Bound"),
+        }
+    }
+}
+
+/// Import entry for a PE module
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportEntry {
+    pub dll_name: String,
+    pub functions: Vec<String>,
+    pub kind: ImportKind,
+    /// Real host DLL when `dll_name` is an API set resolved through an
+    /// [`ApiSetSchema`](crate::ApiSetSchema)
+    pub host_dll: Option<String>,
+}
+
+impl PeImage<'_> {
+    /// Enumerate classic, delay-load and bound imports, in that order
+    pub fn imports(&self) -> Vec<ImportEntry> {
+        let mut imports = self.normal_imports();
+        imports.extend(self.delay_imports());
+        imports.extend(self.bound_imports());
+        imports
+    }
+
+    /// Walk the classic import directory (data directory 1)
+    pub fn normal_imports(&self) -> Vec<ImportEntry> {
+        let mut imports = Vec::new();
+        let import_dir = match self.data_directory(DIRECTORY_IMPORT) {
+            Some(dir) => dir,
+            None => return imports,
+        };
+
+        let mut desc_rva = import_dir.virtual_address;
+        while let Some(desc) = self.slice_at_rva(desc_rva, IMPORT_DESCRIPTOR_SIZE as usize) {
+            let ilt_rva = read_u32(desc, 0).unwrap_or(0);
+            let name_rva = read_u32(desc, 12).unwrap_or(0);
+            let iat_rva = read_u32(desc, 16).unwrap_or(0);
+
+            // Null descriptor terminates the list
+            if name_rva == 0 && ilt_rva == 0 {
+                break;
+            }
+
+            // Read imported functions from ILT (or IAT if ILT is 0)
+            let thunk_rva = if ilt_rva != 0 { ilt_rva } else { iat_rva };
+            imports.push(ImportEntry {
+                dll_name: self.import_dll_name(name_rva),
+                functions: self.thunk_names(thunk_rva),
+                kind: ImportKind::Normal,
+                host_dll: None,
+            });
+            desc_rva = desc_rva.wrapping_add(IMPORT_DESCRIPTOR_SIZE);
+        }
+
+        imports
+    }
+
+    /// Walk the delay-load import directory (data directory 13)
+    pub fn delay_imports(&self) -> Vec<ImportEntry> {
+        let mut imports = Vec::new();
+        let delay_dir = match self.data_directory(DIRECTORY_DELAY_IMPORT) {
+            Some(dir) => dir,
+            None => return imports,
+        };
+
+        let mut desc_rva = delay_dir.virtual_address;
+        while let Some(desc) = self.slice_at_rva(desc_rva, DELAY_DESCRIPTOR_SIZE as usize) {
+            let attributes = read_u32(desc, 0).unwrap_or(0);
+            let name = read_u32(desc, 4).unwrap_or(0);
+            let int = read_u32(desc, 16).unwrap_or(0);
+
+            if name == 0 {
+                break;
+            }
+
+            // Descriptors without the "RVA based" attribute (pre-VC7 linkers)
+            // hold virtual addresses instead of RVAs
+            let to_rva = |value: u32| -> u32 {
+                if attributes & 1 == 0 {
+                    (value as u64).wrapping_sub(self.optional_header.image_base) as u32
+                } else {
+                    value
+                }
+            };
+
+            imports.push(ImportEntry {
+                dll_name: self.import_dll_name(to_rva(name)),
+                functions: self.thunk_names(to_rva(int)),
+                kind: ImportKind::Delay,
+                host_dll: None,
+            });
+            desc_rva = desc_rva.wrapping_add(DELAY_DESCRIPTOR_SIZE);
+        }
+
+        imports
+    }
+
+    /// Walk the bound import directory (data directory 11).
+    ///
+    /// Bound imports only record module names and timestamps; forwarder
+    /// references are reported as their own bound entries.
+    pub fn bound_imports(&self) -> Vec<ImportEntry> {
+        let mut imports = Vec::new();
+        let bound_dir = match self.data_directory(DIRECTORY_BOUND_IMPORT) {
+            Some(dir) => dir,
+            None => return imports,
+        };
+        // Unlike other directories this one lives in the headers and its
+        // "RVA" is really a file offset; names are relative to its start
+        let table = match self
+            .rva_to_offset(bound_dir.virtual_address)
+            .and_then(|off| {
+                self.data()
+                    .get(off..off.saturating_add(bound_dir.size as usize))
+            }) {
+            Some(table) => table,
+            None => return imports,
+        };
+
+        let name_at = |offset: u16| {
+            read_cstring(table, offset as usize).unwrap_or_else(|| String::from("(unknown)"))
+        };
+
+        let mut offset = 0;
+        while let (Some(timestamp), Some(name_offset), Some(forwarders)) = (
+            read_u32(table, offset),
+            read_u16(table, offset + 4),
+            read_u16(table, offset + 6),
+        ) {
+            if timestamp == 0 && name_offset == 0 && forwarders == 0 {
+                break;
+            }
+            imports.push(ImportEntry {
+                dll_name: name_at(name_offset),
+                functions: Vec::new(),
+                kind: ImportKind::Bound,
+                host_dll: None,
+            });
+            offset += BOUND_DESCRIPTOR_SIZE;
+
+            for _ in 0..forwarders {
+                if let Some(name_offset) = read_u16(table, offset + 4) {
+                    imports.push(ImportEntry {
+                        dll_name: name_at(name_offset),
+                        functions: Vec::new(),
+                        kind: ImportKind::Bound,
+                        host_dll: None,
+                    });
+                }
+                offset += BOUND_DESCRIPTOR_SIZE;
+            }
+        }
+
+        imports
+    }
+
+    fn import_dll_name(&self, name_rva: u32) -> String {
+        self.read_cstring_at_rva(name_rva)
+            .unwrap_or_else(|| String::from("(unknown)"))
+    }
+
+    /// Resolve a null-terminated thunk array (ILT/INT) to function names
+    fn thunk_names(&self, mut thunk_rva: u32) -> Vec<String> {
+        let is_pe32plus = self.is_64bit();
+        let entry_size = if is_pe32plus { 8 } else { 4 };
+        // Ordinal flag is bit 63 for PE32+, bit 31 for PE32
+        let ordinal_flag = if is_pe32plus { 1u64 << 63 } else { 1u64 << 31 };
+
+        let mut functions = Vec::new();
+        if thunk_rva == 0 {
+            return functions;
+        }
+        loop {
+            let thunk_value = if is_pe32plus {
+                self.read_u64_at_rva(thunk_rva)
+            } else {
+                self.read_u32_at_rva(thunk_rva).map(u64::from)
+            };
+            let thunk_value = match thunk_value {
+                Some(0) | None => break,
+                Some(v) => v,
+            };
+
+            if thunk_value & ordinal_flag != 0 {
+                functions.push(format!("Ordinal #{}", thunk_value & 0xFFFF));
+            } else {
+                // Hint/Name table entry: 2-byte hint + null-terminated name
+                let hint_name_rva = (thunk_value & 0x7FFFFFFF) as u32;
+                if let Some(name) = self.read_cstring_at_rva(hint_name_rva.wrapping_add(2)) {
+                    functions.push(name);
+                }
+            }
+
+            thunk_rva = thunk_rva.wrapping_add(entry_size);
+        }
+        functions
+    }
+}
@@ -0,0 +1,304 @@
+//! In-memory vs on-disk image comparison
+
+use crate::headers::DIRECTORY_IAT;
+use crate::{PeError, PeImage};
+
+/// Differences separated by fewer identical bytes than this are reported as
+/// one range, so a hook with an unchanged byte in the middle stays one patch
+const MERGE_GAP: usize = 4;
+
+/// A run of bytes that differs between the file and memory
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatchedRange {
+    pub rva: u32,
+    /// Bytes expected from the relocated file image
+    pub expected: Vec<u8>,
+    /// Bytes found in memory
+    pub actual: Vec<u8>,
+}
+
+impl PatchedRange {
+    pub fn len(&self) -> usize {
+        self.actual.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actual.is_empty()
+    }
+}
+
+/// Comparison result for a single section
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SectionIntegrity {
+    pub name: String,
+    pub rva: u32,
+    pub size: u32,
+    /// `false` when the section could not be read from memory
+    pub readable: bool,
+    /// Bytes actually compared; less than `size` after a short read
+    pub compared: u32,
+    pub patches: Vec<PatchedRange>,
+}
+
+impl SectionIntegrity {
+    /// Whether the whole section was read and compared
+    pub fn is_complete(&self) -> bool {
+        self.readable && self.compared >= self.size
+    }
+}
+
+/// Comparison result for a whole module
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModuleIntegrity {
+    pub sections: Vec<SectionIntegrity>,
+}
+
+impl ModuleIntegrity {
+    /// Whether any compared section differs from the file
+    pub fn is_modified(&self) -> bool {
+        self.sections.iter().any(|s| !s.patches.is_empty())
+    }
+
+    /// Whether every section was read and compared in full, so an unmodified
+    /// result means the module really matches the file
+    pub fn is_complete(&self) -> bool {
+        self.sections.iter().all(SectionIntegrity::is_complete)
+    }
+
+    /// Total number of patched ranges
+    pub fn patch_count(&self) -> usize {
+        self.sections.iter().map(|s| s.patches.len()).sum()
+    }
+
+    /// Total number of patched bytes
+    pub fn patched_bytes(&self) -> usize {
+        self.sections
+            .iter()
+            .flat_map(|s| s.patches.iter())
+            .map(PatchedRange::len)
+            .sum()
+    }
+}
+
+/// Compare two buffers and return the differing ranges. `base_rva` is the RVA
+/// of the first byte; bytes inside `ignored` (RVA, length) ranges never count
+/// as differences. Only the common prefix of both buffers is compared.
+pub fn diff_bytes(
+    expected: &[u8],
+    actual: &[u8],
+    base_rva: u32,
+    ignored: &[(u32, u32)],
+) -> Vec<PatchedRange> {
+    let is_ignored = |index: usize| {
+        let rva = base_rva as u64 + index as u64;
+        ignored
+            .iter()
+            .any(|&(start, len)| rva >= start as u64 && rva < start as u64 + len as u64)
+    };
+
+    // Collect [start, end) runs of differing bytes
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for (i, (e, a)) in expected.iter().zip(actual).enumerate() {
+        if e == a || is_ignored(i) {
+            continue;
+        }
+        match runs.last_mut() {
+            Some((_, end)) if i - *end < MERGE_GAP => *end = i + 1,
+            _ => runs.push((i, i + 1)),
+        }
+    }
+
+    runs.into_iter()
+        .map(|(start, end)| PatchedRange {
+            rva: base_rva.wrapping_add(start as u32),
+            expected: expected[start..end].to_vec(),
+            actual: actual[start..end].to_vec(),
+        })
+        .collect()
+}
+
+impl PeImage<'_> {
+    /// Ranges of read-only sections the loader legitimately writes to: the
+    /// IAT and the CFG check/dispatch function pointers
+    pub fn loader_written_ranges(&self) -> Vec<(u32, u32)> {
+        let mut ranges = Vec::new();
+        if let Some(iat) = self.data_directory(DIRECTORY_IAT) {
+            ranges.push((iat.virtual_address, iat.size));
+        }
+
+        // GuardCFCheckFunctionPointer / GuardCFDispatchFunctionPointer
+        if let Some(load_config) = self.load_config() {
+            let width = if self.is_64bit() { 8 } else { 4 };
+            for va in [
+                load_config.guard_cf_check_function_pointer,
+                load_config.guard_cf_dispatch_function_pointer,
+            ]
+            .into_iter()
+            .flatten()
+            .filter(|&va| va != 0)
+            {
+                let rva = va.wrapping_sub(self.optional_header.image_base) as u32;
+                ranges.push((rva, width));
+            }
+        }
+
+        ranges
+    }
+
+    /// Compare the read-only sections of this file, rebased to `load_base`,
+    /// against memory. `read_memory(rva, len)` returns the bytes currently
+    /// mapped at `load_base + rva`, or `None` if they cannot be read.
+    pub fn compare_with_memory<F>(
+        &self,
+        load_base: u64,
+        mut read_memory: F,
+    ) -> Result<ModuleIntegrity, PeError>
+    where
+        F: FnMut(u32, usize) -> Option<Vec<u8>>,
+    {
+        let expected = self.relocated_image(load_base)?;
+        let ignored = self.loader_written_ranges();
+
+        let sections = self
+            .sections
+            .iter()
+            // Writable sections change legitimately at runtime
+            .filter(|s| !s.is_writable() && s.size_of_raw_data != 0)
+            .map(|section| {
+                let rva = section.virtual_address;
+                // Only bytes backed by the file can be compared
+                let size = section.mapped_size().min(section.size_of_raw_data);
+                let start = (rva as usize).min(expected.len());
+                let end = (start + size as usize).min(expected.len());
+
+                let (readable, compared, patches) = match read_memory(rva, end - start) {
+                    Some(actual) => (
+                        true,
+                        actual.len().min(end - start) as u32,
+                        diff_bytes(&expected[start..end], &actual, rva, &ignored),
+                    ),
+                    None => (false, 0, Vec::new()),
+                };
+
+                SectionIntegrity {
+                    name: section.name.clone(),
+                    rva,
+                    size,
+                    readable,
+                    compared,
+                    patches,
+                }
+            })
+            .collect();
+
+        Ok(ModuleIntegrity { sections })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_buffers_have_no_patches() {
+        let bytes = [0x48, 0x89, 0x5C, 0x24, 0x08, 0x57];
+        assert!(diff_bytes(&bytes, &bytes, 0x1000, &[]).is_empty());
+        assert!(diff_bytes(&[], &[], 0x1000, &[]).is_empty());
+    }
+
+    #[test]
+    fn nearby_differences_merge_into_one_range() {
+        let expected = [0u8; 16];
+        let mut actual = expected;
+        // A 5-byte jmp hook with an unchanged byte in the middle
+        actual[2..7].copy_from_slice(&[0xE9, 0x10, 0x00, 0x20, 0x30]);
+        // Far enough away to be its own range
+        actual[14] = 0xCC;
+
+        let patches = diff_bytes(&expected, &actual, 0x1000, &[]);
+        assert_eq!(patches.len(), 2);
+        assert_eq!(patches[0].rva, 0x1002);
+        assert_eq!(patches[0].actual, [0xE9, 0x10, 0x00, 0x20, 0x30]);
+        assert_eq!(patches[0].expected, [0; 5]);
+        assert_eq!(patches[1].rva, 0x100E);
+        assert_eq!(patches[1].len(), 1);
+    }
+
+    #[test]
+    fn ignored_ranges_do_not_count() {
+        let expected = [0u8; 32];
+        let mut actual = expected;
+        // IAT slot the loader filled in, and a relocated pointer
+        actual[8..16].copy_from_slice(&0x7FFE_1234_5678u64.to_le_bytes());
+        actual[24..28].copy_from_slice(&[1, 2, 3, 4]);
+
+        let ignored = [(0x2008, 8), (0x2018, 4)];
+        assert!(diff_bytes(&expected, &actual, 0x2000, &ignored).is_empty());
+
+        // Only the byte just past an ignored range is reported
+        actual[28] = 0x90;
+        let patches = diff_bytes(&expected, &actual, 0x2000, &ignored);
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].rva, 0x201C);
+        assert_eq!(patches[0].actual, [0x90]);
+    }
+
+    #[test]
+    fn only_the_common_prefix_is_compared() {
+        let expected = [1u8, 2, 3, 4, 5, 6, 7, 8];
+        let short = [1u8, 2, 0xFF];
+        let patches = diff_bytes(&expected, &short, 0, &[]);
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].rva, 2);
+        assert_eq!(patches[0].expected, [3]);
+
+        // A longer memory read doesn't report the extra bytes
+        let mut long = expected.to_vec();
+        long.extend_from_slice(&[0xAA; 8]);
+        assert!(diff_bytes(&expected, &long, 0, &[]).is_empty());
+    }
+
+    const X64_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x64.dll");
+    const LOAD_BASE: u64 = 0x7FF8_0000_0000;
+
+    #[test]
+    fn memory_matching_the_file_is_complete_and_clean() {
+        let image = PeImage::parse(X64_DLL).unwrap();
+        let mapped = image.relocated_image(LOAD_BASE).unwrap();
+        let integrity = image
+            .compare_with_memory(LOAD_BASE, |rva, len| {
+                Some(mapped[rva as usize..rva as usize + len].to_vec())
+            })
+            .unwrap();
+        assert!(!integrity.sections.is_empty());
+        assert!(integrity.is_complete());
+        assert!(!integrity.is_modified());
+    }
+
+    #[test]
+    fn unreadable_or_short_reads_are_incomplete() {
+        let image = PeImage::parse(X64_DLL).unwrap();
+        let mapped = image.relocated_image(LOAD_BASE).unwrap();
+
+        let unreadable = image.compare_with_memory(LOAD_BASE, |_, _| None).unwrap();
+        assert!(!unreadable.is_modified());
+        assert!(!unreadable.is_complete());
+        assert!(unreadable
+            .sections
+            .iter()
+            .all(|s| !s.readable && s.compared == 0));
+
+        // Only the first 16 bytes of each section come back
+        let truncated = image
+            .compare_with_memory(LOAD_BASE, |rva, len| {
+                Some(mapped[rva as usize..rva as usize + len.min(16)].to_vec())
+            })
+            .unwrap();
+        assert!(!truncated.is_modified());
+        assert!(!truncated.is_complete());
+        let text = &truncated.sections[0];
+        assert!(text.readable);
+        assert_eq!(text.compared, 16);
+        assert!(!text.is_complete());
+    }
+}
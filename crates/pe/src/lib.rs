@@ -0,0 +1,426 @@
+//! Portable Executable (PE) parsing module
+//! Pure Rust, bounds-checked parser for PE32 / PE32+ images read from disk or memory
+
+mod apiset;
+mod authenticode;
+mod der;
+mod describe;
+mod entropy;
+mod error;
+mod exceptions;
+mod exports;
+mod hashes;
+mod headers;
+mod imports;
+mod integrity;
+mod load_config;
+mod ordinals;
+mod packer;
+mod read;
+mod relocations;
+mod resources;
+mod rich;
+mod tls;
+mod version;
+
+pub use apiset::{is_api_set_name, ApiSetSchema};
+pub use authenticode::{hex_digest, AuthenticodeSignature, CertificateInfo, DigestAlgorithm};
+pub use describe::*;
+pub use entropy::{shannon_entropy, SectionInfo};
+pub use error::PeError;
+pub use exceptions::{
+    RuntimeFunction, UnwindInfo, UNW_FLAG_CHAININFO, UNW_FLAG_EHANDLER, UNW_FLAG_UHANDLER,
+};
+pub use exports::ExportEntry;
+pub use hashes::{imphash, FileHashes};
+pub use headers::*;
+pub use imports::{ImportEntry, ImportKind};
+pub use integrity::{diff_bytes, ModuleIntegrity, PatchedRange, SectionIntegrity};
+pub use load_config::{
+    LoadConfig, GUARD_CF_EXPORT_SUPPRESSION_INFO_PRESENT, GUARD_CF_FUNCTION_TABLE_PRESENT,
+    GUARD_CF_FUNCTION_TABLE_SIZE_MASK, GUARD_CF_INSTRUMENTED,
+};
+pub use packer::{
+    assess_packing, PackerAssessment, PackerFinding, HIGH_ENTROPY, PACKED_SCORE, SUSPICIOUS_SCORE,
+};
+pub use read::{read_cstring, read_u16, read_u32, read_u64, read_u8};
+pub use relocations::{
+    apply_relocations, Relocation, REL_BASED_ABSOLUTE, REL_BASED_DIR64, REL_BASED_HIGHLOW,
+};
+pub use resources::*;
+pub use rich::{rich_product_name, RichEntry, RichHeader};
+pub use tls::TlsDirectory;
+pub use version::{parse_version_info, VersionInfo};
+
+/// Largest `SizeOfImage` [`PeImage::map_image`] will allocate
+pub const MAX_IMAGE_SIZE: usize = 1 << 30;
+
+/// `SizeOfImage` may exceed the file size this many times over (zero-filled
+/// sections), or [`MIN_IMAGE_ALLOWANCE`] for small files
+const IMAGE_GROWTH: usize = 64;
+const MIN_IMAGE_ALLOWANCE: usize = 16 << 20;
+
+/// A parsed PE image borrowing the raw file bytes
+#[derive(Clone, Debug)]
+pub struct PeImage<'a> {
+    data: &'a [u8],
+    pub dos_header: DosHeader,
+    pub file_header: FileHeader,
+    pub optional_header: OptionalHeader,
+    pub sections: Vec<SectionHeader>,
+}
+
+impl<'a> PeImage<'a> {
+    /// Parse the headers and section table of a PE file
+    pub fn parse(data: &'a [u8]) -> Result<Self, PeError> {
+        let dos_header = DosHeader::parse(data)?;
+
+        let pe_offset = dos_header.pe_offset as usize;
+        match read_u32(data, pe_offset) {
+            Some(PE_SIGNATURE) => {}
+            Some(_) => return Err(PeError::InvalidPeSignature),
+            None => return Err(PeError::TooSmall("PE signature")),
+        }
+
+        // COFF header starts right after the signature
+        let coff_offset = pe_offset + 4;
+        let file_header = FileHeader::parse(data, coff_offset)?;
+
+        let opt_offset = coff_offset + FILE_HEADER_SIZE;
+        let opt_size = file_header.size_of_optional_header as usize;
+        let optional_header = OptionalHeader::parse(data, opt_offset, opt_size)?;
+
+        // Section table follows the optional header; stop at the first
+        // header that does not fit instead of failing the whole parse
+        let sections_offset = opt_offset + opt_size;
+        let sections = (0..file_header.number_of_sections as usize)
+            .map_while(|i| SectionHeader::parse(data, sections_offset + i * SECTION_HEADER_SIZE))
+            .collect();
+
+        Ok(PeImage {
+            data,
+            dos_header,
+            file_header,
+            optional_header,
+            sections,
+        })
+    }
+
+    /// Raw bytes the image was parsed from
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Whether this is a 64-bit (PE32+) image
+    pub fn is_64bit(&self) -> bool {
+        self.optional_header.is_pe32_plus()
+    }
+
+    /// File offset of the optional header
+    pub fn optional_header_offset(&self) -> usize {
+        self.dos_header.pe_offset as usize + 4 + FILE_HEADER_SIZE
+    }
+
+    /// Get a data directory by index (see the `DIRECTORY_*` constants),
+    /// or `None` if it is absent or empty
+    pub fn data_directory(&self, index: usize) -> Option<DataDirectory> {
+        self.optional_header
+            .data_directories
+            .get(index)
+            .copied()
+            .filter(DataDirectory::is_present)
+    }
+
+    /// Find the section containing an RVA
+    pub fn section_for_rva(&self, rva: u32) -> Option<&SectionHeader> {
+        self.sections.iter().find(|s| s.contains_rva(rva))
+    }
+
+    /// Convert an RVA to a file offset
+    pub fn rva_to_offset(&self, rva: u32) -> Option<usize> {
+        // Headers are mapped 1:1 at the start of the image
+        if rva < self.optional_header.size_of_headers && (rva as usize) < self.data.len() {
+            return Some(rva as usize);
+        }
+
+        let section = self.section_for_rva(rva)?;
+        let delta = rva - section.virtual_address;
+        // RVAs in the zero-filled tail of a section have no file backing
+        if delta >= section.size_of_raw_data {
+            return None;
+        }
+        let offset = section.pointer_to_raw_data as usize + delta as usize;
+        (offset < self.data.len()).then_some(offset)
+    }
+
+    /// Borrow `len` bytes of file data starting at an RVA
+    pub fn slice_at_rva(&self, rva: u32, len: usize) -> Option<&'a [u8]> {
+        let offset = self.rva_to_offset(rva)?;
+        self.data.get(offset..offset.checked_add(len)?)
+    }
+
+    /// Read a `u16` at an RVA
+    pub fn read_u16_at_rva(&self, rva: u32) -> Option<u16> {
+        read_u16(self.data, self.rva_to_offset(rva)?)
+    }
+
+    /// Read a `u32` at an RVA
+    pub fn read_u32_at_rva(&self, rva: u32) -> Option<u32> {
+        read_u32(self.data, self.rva_to_offset(rva)?)
+    }
+
+    /// Read a `u64` at an RVA
+    pub fn read_u64_at_rva(&self, rva: u32) -> Option<u64> {
+        read_u64(self.data, self.rva_to_offset(rva)?)
+    }
+
+    /// Read a pointer-sized value (`u64` for PE32+, `u32` for PE32) at an RVA
+    pub fn read_pointer_at_rva(&self, rva: u32) -> Option<u64> {
+        if self.is_64bit() {
+            self.read_u64_at_rva(rva)
+        } else {
+            self.read_u32_at_rva(rva).map(u64::from)
+        }
+    }
+
+    /// Convert a virtual address based at the preferred image base to an RVA
+    pub fn va_to_rva(&self, va: u64) -> Option<u32> {
+        let rva = va.checked_sub(self.optional_header.image_base)?;
+        (rva < self.optional_header.size_of_image as u64).then_some(rva as u32)
+    }
+
+    /// Read a null-terminated string at an RVA
+    pub fn read_cstring_at_rva(&self, rva: u32) -> Option<String> {
+        read_cstring(self.data, self.rva_to_offset(rva)?)
+    }
+
+    /// Raw file data backing a section (clamped to the end of the file)
+    pub fn section_data(&self, section: &SectionHeader) -> &'a [u8] {
+        let start = (section.pointer_to_raw_data as usize).min(self.data.len());
+        let end = start
+            .saturating_add(section.size_of_raw_data as usize)
+            .min(self.data.len());
+        &self.data[start..end]
+    }
+
+    /// Lay the file out as the loader would in memory: headers followed by
+    /// each section copied to its virtual address, zero-filled elsewhere.
+    ///
+    /// `SizeOfImage` comes from the untrusted header, so it must be non-zero
+    /// and at most 64 times the file size. Small files may still map up to
+    /// 16 MiB, and no image may exceed [`MAX_IMAGE_SIZE`].
+    pub fn map_image(&self) -> Result<Vec<u8>, PeError> {
+        let size_of_image = self.optional_header.size_of_image as usize;
+        let allowance = self
+            .data
+            .len()
+            .saturating_mul(IMAGE_GROWTH)
+            .clamp(MIN_IMAGE_ALLOWANCE, MAX_IMAGE_SIZE);
+        if size_of_image == 0 || size_of_image > allowance {
+            return Err(PeError::InvalidImageSize(
+                self.optional_header.size_of_image,
+            ));
+        }
+        let mut image = vec![0u8; size_of_image];
+
+        let header_len = (self.optional_header.size_of_headers as usize)
+            .min(self.data.len())
+            .min(size_of_image);
+        image[..header_len].copy_from_slice(&self.data[..header_len]);
+
+        for section in &self.sections {
+            if section.size_of_raw_data == 0 || section.pointer_to_raw_data == 0 {
+                continue;
+            }
+            let src = self.section_data(section);
+            let dst_start = section.virtual_address as usize;
+            if dst_start >= size_of_image {
+                continue;
+            }
+            let copy_len = src.len().min(size_of_image - dst_start);
+            image[dst_start..dst_start + copy_len].copy_from_slice(&src[..copy_len]);
+        }
+
+        Ok(image)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const X64_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x64.dll");
+    const X86_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x86.dll");
+
+    /// Run every parser over an image; none of them may panic
+    fn exercise(data: &[u8]) {
+        let Ok(image) = PeImage::parse(data) else {
+            return;
+        };
+        let imports = image.imports();
+        let _ = imphash(&imports);
+        let _ = image.exports();
+        let _ = image.relocations();
+        let _ = image.resources();
+        let _ = image.version_info();
+        let _ = image.tls();
+        let _ = image.load_config();
+        let _ = image.runtime_functions();
+        let _ = image.rich_header();
+        let _ = image.authenticode();
+        let _ = image.packer_assessment();
+        let _ = image.loader_written_ranges();
+        let _ = image.compare_with_memory(0x1_4000_0000, |_, len| Some(vec![0xCC; len]));
+    }
+
+    fn pe_offset(data: &[u8]) -> usize {
+        read_u32(data, 0x3C).unwrap() as usize
+    }
+
+    #[test]
+    fn parses_x64_headers_and_sections() {
+        let image = PeImage::parse(X64_DLL).unwrap();
+        assert!(image.is_64bit());
+        assert_eq!(image.file_header.machine, 0x8664);
+        assert_eq!(image.optional_header.image_base, 0x1_8000_0000);
+        assert_eq!(image.optional_header.address_of_entry_point, 0x13F0);
+        assert_eq!(image.optional_header.size_of_image, 0x8000);
+
+        let names: Vec<&str> = image.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [".text", ".rdata", ".data", ".pdata", ".gfids", ".rsrc", ".reloc"]
+        );
+        let text = &image.sections[0];
+        assert!(text.is_executable() && !text.is_writable());
+        assert!(image.sections[2].is_writable());
+        assert_eq!(image.section_for_rva(0x13F0).unwrap().name, ".text");
+        assert_eq!(image.rva_to_offset(0x1000), Some(0x400));
+        assert_eq!(image.map_image().unwrap().len(), 0x8000);
+    }
+
+    #[test]
+    fn parses_x86_headers_and_sections() {
+        let image = PeImage::parse(X86_DLL).unwrap();
+        assert!(!image.is_64bit());
+        assert_eq!(image.file_header.machine, 0x14C);
+        assert_eq!(image.optional_header.image_base, 0x1000_0000);
+        assert_eq!(image.sections.len(), 6);
+        assert_eq!(image.sections[0].name, ".text");
+        assert_eq!(image.sections[5].name, ".reloc");
+        assert!(!image.relocations().is_empty());
+    }
+
+    #[test]
+    fn parses_imports_and_exports() {
+        for (data, exports) in [
+            (X64_DLL, ["DllMain", "MessageBoxThread"]),
+            (X86_DLL, ["_DllMain@12", "_MessageBoxThread@4"]),
+        ] {
+            let image = PeImage::parse(data).unwrap();
+            let imports = image.imports();
+            let dlls: Vec<&str> = imports.iter().map(|i| i.dll_name.as_str()).collect();
+            assert_eq!(
+                dlls,
+                [
+                    "KERNEL32.dll",
+                    "USER32.dll",
+                    "VCRUNTIME140.dll",
+                    "api-ms-win-crt-runtime-l1-1-0.dll"
+                ]
+            );
+            assert_eq!(imports[1].functions, ["MessageBoxA"]);
+            assert!(imports[0].functions.iter().any(|f| f == "CreateThread"));
+            assert!(imports.iter().all(|i| i.kind == ImportKind::Normal));
+
+            let names: Vec<_> = image.exports().into_iter().map(|e| e.name).collect();
+            assert_eq!(names, exports.map(|name| Some(name.to_string())));
+        }
+    }
+
+    #[test]
+    fn truncated_files_fail_or_parse_without_panicking() {
+        assert_eq!(
+            PeImage::parse(&[]).unwrap_err(),
+            PeError::TooSmall("DOS header")
+        );
+        assert!(PeImage::parse(&X64_DLL[..0x40]).is_err());
+
+        for data in [X64_DLL, X86_DLL] {
+            for len in (0..data.len()).step_by(7) {
+                exercise(&data[..len]);
+            }
+        }
+    }
+
+    #[test]
+    fn corrupted_headers_are_rejected() {
+        let mut data = X64_DLL.to_vec();
+        data[0] = b'X';
+        assert_eq!(
+            PeImage::parse(&data).unwrap_err(),
+            PeError::InvalidDosSignature
+        );
+
+        let mut data = X64_DLL.to_vec();
+        let pe = pe_offset(&data);
+        data[pe] = b'X';
+        assert_eq!(
+            PeImage::parse(&data).unwrap_err(),
+            PeError::InvalidPeSignature
+        );
+
+        // e_lfanew pointing past the end of the file
+        let mut data = X64_DLL.to_vec();
+        data[0x3C..0x40].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(PeImage::parse(&data).is_err());
+
+        // Unknown optional header magic
+        let mut data = X64_DLL.to_vec();
+        let opt = pe_offset(&data) + 4 + FILE_HEADER_SIZE;
+        data[opt..opt + 2].copy_from_slice(&0x1234u16.to_le_bytes());
+        assert_eq!(
+            PeImage::parse(&data).unwrap_err(),
+            PeError::UnsupportedOptionalHeader(0x1234)
+        );
+    }
+
+    #[test]
+    fn implausible_size_of_image_is_rejected() {
+        let opt = pe_offset(X64_DLL) + 4 + FILE_HEADER_SIZE;
+        for size in [0u32, u32::MAX] {
+            let mut data = X64_DLL.to_vec();
+            data[opt + 56..opt + 60].copy_from_slice(&size.to_le_bytes());
+            let image = PeImage::parse(&data).unwrap();
+            assert_eq!(image.map_image(), Err(PeError::InvalidImageSize(size)));
+            assert!(image
+                .compare_with_memory(0, |_, len| Some(vec![0; len]))
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn corrupted_bytes_never_panic() {
+        // Deterministic xorshift so failures reproduce
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for original in [X64_DLL, X86_DLL] {
+            for _ in 0..300 {
+                let mut data = original.to_vec();
+                for _ in 0..8 {
+                    // Aim most corruption at the headers, where the offsets live
+                    let limit = if next() % 4 == 0 { data.len() } else { 0x400 };
+                    let index = next() as usize % limit;
+                    data[index] = next() as u8;
+                }
+                exercise(&data);
+            }
+        }
+    }
+}
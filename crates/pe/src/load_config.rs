@@ -0,0 +1,226 @@
+//! Load configuration directory parsing
+
+use crate::headers::DIRECTORY_LOAD_CONFIG;
+use crate::PeImage;
+
+/// Guard against corrupt handler / function counts
+const MAX_TABLE_ENTRIES: u64 = 0x100000;
+
+/// Module was built with Control Flow Guard instrumentation
+pub const GUARD_CF_INSTRUMENTED: u32 = 0x0000_0100;
+/// Module has a CFG function table
+pub const GUARD_CF_FUNCTION_TABLE_PRESENT: u32 = 0x0000_0400;
+/// Module has export suppression information
+pub const GUARD_CF_EXPORT_SUPPRESSION_INFO_PRESENT: u32 = 0x0000_4000;
+/// Number of extra bytes after each CFG function table RVA (bits 28-31)
+pub const GUARD_CF_FUNCTION_TABLE_SIZE_MASK: u32 = 0xF000_0000;
+
+/// IMAGE_LOAD_CONFIG_DIRECTORY32/64 (pointer fields are VAs at the preferred
+/// image base; fields beyond the declared `size` are `None`)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LoadConfig {
+    /// Declared structure size, which grows with each linker version
+    pub size: u32,
+    pub time_date_stamp: u32,
+    pub security_cookie: Option<u64>,
+    pub se_handler_table: Option<u64>,
+    /// RVAs of the registered SafeSEH handlers (x86 only)
+    pub se_handlers: Vec<u32>,
+    pub guard_cf_check_function_pointer: Option<u64>,
+    pub guard_cf_dispatch_function_pointer: Option<u64>,
+    pub guard_cf_function_table: Option<u64>,
+    /// RVAs of the valid indirect call targets
+    pub guard_cf_functions: Vec<u32>,
+    /// IMAGE_GUARD_* flags
+    pub guard_flags: Option<u32>,
+}
+
+impl LoadConfig {
+    /// Whether the module was built with Control Flow Guard
+    pub fn is_cfg_instrumented(&self) -> bool {
+        self.guard_flags
+            .is_some_and(|flags| flags & GUARD_CF_INSTRUMENTED != 0)
+    }
+}
+
+/// Offsets of the fields read from the 32-bit and 64-bit layouts
+struct Layout {
+    security_cookie: u32,
+    se_handler_table: u32,
+    se_handler_count: u32,
+    guard_cf_check: u32,
+    guard_cf_dispatch: u32,
+    guard_cf_table: u32,
+    guard_cf_count: u32,
+    guard_flags: u32,
+}
+
+const LAYOUT_32: Layout = Layout {
+    security_cookie: 0x3C,
+    se_handler_table: 0x40,
+    se_handler_count: 0x44,
+    guard_cf_check: 0x48,
+    guard_cf_dispatch: 0x4C,
+    guard_cf_table: 0x50,
+    guard_cf_count: 0x54,
+    guard_flags: 0x58,
+};
+
+const LAYOUT_64: Layout = Layout {
+    security_cookie: 0x58,
+    se_handler_table: 0x60,
+    se_handler_count: 0x68,
+    guard_cf_check: 0x70,
+    guard_cf_dispatch: 0x78,
+    guard_cf_table: 0x80,
+    guard_cf_count: 0x88,
+    guard_flags: 0x90,
+};
+
+impl PeImage<'_> {
+    /// Parse the load configuration directory (data directory 10)
+    pub fn load_config(&self) -> Option<LoadConfig> {
+        let dir = self.data_directory(DIRECTORY_LOAD_CONFIG)?;
+        let base = dir.virtual_address;
+        let size = self.read_u32_at_rva(base)?;
+        let (layout, width) = if self.is_64bit() {
+            (&LAYOUT_64, 8)
+        } else {
+            (&LAYOUT_32, 4)
+        };
+
+        // Only read fields the declared size covers
+        let pointer = |offset: u32| {
+            (offset + width <= size)
+                .then(|| self.read_pointer_at_rva(base.wrapping_add(offset)))
+                .flatten()
+        };
+        let dword = |offset: u32| {
+            (offset + 4 <= size)
+                .then(|| self.read_u32_at_rva(base.wrapping_add(offset)))
+                .flatten()
+        };
+
+        let mut config = LoadConfig {
+            size,
+            time_date_stamp: dword(4).unwrap_or(0),
+            security_cookie: pointer(layout.security_cookie),
+            se_handler_table: pointer(layout.se_handler_table),
+            se_handlers: Vec::new(),
+            guard_cf_check_function_pointer: pointer(layout.guard_cf_check),
+            guard_cf_dispatch_function_pointer: pointer(layout.guard_cf_dispatch),
+            guard_cf_function_table: pointer(layout.guard_cf_table),
+            guard_cf_functions: Vec::new(),
+            guard_flags: dword(layout.guard_flags),
+        };
+
+        // SafeSEH: array of handler RVAs
+        if let (Some(table), Some(count)) =
+            (config.se_handler_table, pointer(layout.se_handler_count))
+        {
+            config.se_handlers = self.rva_table(table, count, 4);
+        }
+
+        // CFG: array of RVAs, each followed by n bytes of metadata
+        if let (Some(table), Some(count)) = (
+            config.guard_cf_function_table,
+            pointer(layout.guard_cf_count),
+        ) {
+            let flags = config.guard_flags.unwrap_or(0);
+            let stride = 4 + ((flags & GUARD_CF_FUNCTION_TABLE_SIZE_MASK) >> 28);
+            config.guard_cf_functions = self.rva_table(table, count, stride);
+        }
+
+        Some(config)
+    }
+
+    /// Read `count` RVAs spaced `stride` bytes apart from the table at `table_va`
+    fn rva_table(&self, table_va: u64, count: u64, stride: u32) -> Vec<u32> {
+        let Some(table) = self.va_to_rva(table_va) else {
+            return Vec::new();
+        };
+        (0..count.min(MAX_TABLE_ENTRIES) as u32)
+            .map_while(|i| self.read_u32_at_rva(table.checked_add(i.checked_mul(stride)?)?))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const X64_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x64.dll");
+    const X86_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x86.dll");
+
+    /// File offset of the load config directory
+    fn config_offset(data: &[u8]) -> usize {
+        let image = PeImage::parse(data).unwrap();
+        let dir = image.data_directory(DIRECTORY_LOAD_CONFIG).unwrap();
+        image.rva_to_offset(dir.virtual_address).unwrap()
+    }
+
+    #[test]
+    fn parses_sample_load_configs() {
+        let config = PeImage::parse(X64_DLL).unwrap().load_config().unwrap();
+        assert_eq!(config.size, 148);
+        assert_eq!(config.security_cookie, Some(0x1_8000_3000));
+        assert_eq!(config.se_handler_table, Some(0));
+        assert!(config.se_handlers.is_empty());
+        assert_eq!(config.guard_cf_check_function_pointer, Some(0x1_8000_2108));
+        assert_eq!(
+            config.guard_cf_dispatch_function_pointer,
+            Some(0x1_8000_2110)
+        );
+        assert!(config.guard_cf_functions.is_empty());
+        assert_eq!(config.guard_flags, Some(GUARD_CF_INSTRUMENTED));
+        assert!(config.is_cfg_instrumented());
+
+        // The x86 build registers one SafeSEH handler
+        let config = PeImage::parse(X86_DLL).unwrap().load_config().unwrap();
+        assert_eq!(config.size, 92);
+        assert_eq!(config.security_cookie, Some(0x1000_3004));
+        assert_eq!(config.se_handler_table, Some(0x1000_21A0));
+        assert_eq!(config.se_handlers, [0x1A8B]);
+        assert_eq!(config.guard_cf_check_function_pointer, Some(0x1000_2078));
+        assert!(config.is_cfg_instrumented());
+    }
+
+    #[test]
+    fn fields_beyond_declared_size_are_none() {
+        let mut data = X64_DLL.to_vec();
+        let offset = config_offset(&data);
+        data[offset..offset + 4].copy_from_slice(&0x60u32.to_le_bytes());
+        let config = PeImage::parse(&data).unwrap().load_config().unwrap();
+        assert_eq!(config.size, 0x60);
+        assert_eq!(config.security_cookie, Some(0x1_8000_3000));
+        assert_eq!(config.se_handler_table, None);
+        assert_eq!(config.guard_flags, None);
+        assert!(!config.is_cfg_instrumented());
+    }
+
+    #[test]
+    fn huge_table_counts_stop_at_unreadable_data() {
+        let mut data = X86_DLL.to_vec();
+        let count = config_offset(&data) + LAYOUT_32.se_handler_count as usize;
+        data[count..count + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let config = PeImage::parse(&data).unwrap().load_config().unwrap();
+        assert_eq!(config.se_handlers[0], 0x1A8B);
+        assert!((config.se_handlers.len() as u64) < MAX_TABLE_ENTRIES);
+    }
+
+    #[test]
+    fn truncated_directory_does_not_panic() {
+        for data in [X64_DLL, X86_DLL] {
+            let offset = config_offset(data);
+            // Cut before the size field is complete
+            let image = PeImage::parse(&data[..offset + 2]).unwrap();
+            assert_eq!(image.load_config(), None);
+
+            for len in offset..offset + 0x100 {
+                if let Some(config) = PeImage::parse(&data[..len]).unwrap().load_config() {
+                    assert!(config.guard_cf_functions.is_empty());
+                }
+            }
+        }
+    }
+}
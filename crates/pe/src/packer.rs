@@ -0,0 +1,326 @@
+//! Packer / obfuscation heuristics over parsed section data
+
+use crate::entropy::SectionInfo;
+use crate::PeImage;
+
+/// Entropy above which section data is most likely compressed or encrypted
+pub const HIGH_ENTROPY: f64 = 7.2;
+/// Score from which a module is reported as suspicious
+pub const SUSPICIOUS_SCORE: u32 = 20;
+/// Score from which a module is reported as likely packed
+pub const PACKED_SCORE: u32 = 50;
+
+/// Section names emitted by common compilers and linkers
+const STANDARD_SECTIONS: &[&str] = &[
+    ".text", ".rdata", ".data", ".pdata", ".rsrc", ".reloc", ".idata", ".edata", ".tls", ".bss",
+    ".CRT", ".gfids", ".giats", ".gljmp", ".00cfg", ".didat", ".xdata", ".sdata", ".orpc",
+    ".textbss", ".retplne", ".voltbl", ".fptable", "_RDATA", ".msvcjmc", ".gehcont", ".rodata",
+    ".eh_fram", ".gxfg", ".buildid", ".ndata", ".apiset", ".wpp_sf", ".mrdata", "fothk", "PAGE",
+    "INIT", "CODE", "DATA", "BSS", ".itext", ".didata", ".debug",
+];
+
+/// Section names left behind by well-known packers and protectors
+const PACKER_SECTIONS: &[(&str, &str)] = &[
+    ("UPX", "UPX"),
+    (".aspack", "ASPack"),
+    (".adata", "ASPack"),
+    (".MPRESS", "MPRESS"),
+    (".themida", "Themida"),
+    (".winlice", "WinLicense"),
+    (".vmp", "VMProtect"),
+    (".enigma", "Enigma"),
+    (".petite", "Petite"),
+    (".nsp", "NsPack"),
+    ("PEC2", "PECompact"),
+    ("pec", "PECompact"),
+    (".packed", "generic packer"),
+    (".perplex", "Perplex"),
+    (".yP", "Y0da Protector"),
+    ("MEW", "MEW"),
+    ("FSG!", "FSG"),
+];
+
+/// A single heuristic that contributed to the score
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackerFinding {
+    pub reason: String,
+    pub weight: u32,
+}
+
+/// Result of [`assess_packing`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PackerAssessment {
+    /// Sum of finding weights, capped at 100
+    pub score: u32,
+    pub findings: Vec<PackerFinding>,
+}
+
+impl PackerAssessment {
+    /// "Clean", "Suspicious" or "Likely packed"
+    pub fn verdict(&self) -> &'static str {
+        if self.score >= PACKED_SCORE {
+            "Likely packed"
+        } else if self.score >= SUSPICIOUS_SCORE {
+            "Suspicious"
+        } else {
+            "Clean"
+        }
+    }
+
+    pub fn is_suspicious(&self) -> bool {
+        self.score >= SUSPICIOUS_SCORE
+    }
+
+    fn add(&mut self, weight: u32, reason: String) {
+        self.findings.push(PackerFinding { reason, weight });
+    }
+}
+
+fn is_standard_name(name: &str) -> bool {
+    // Grouped sections (".text$mn") and long MinGW names ("/4") are normal
+    let base = name.split('$').next().unwrap_or(name);
+    name.starts_with('/')
+        || STANDARD_SECTIONS
+            .iter()
+            .any(|standard| base.eq_ignore_ascii_case(standard))
+        || base.starts_with("PAGE")
+}
+
+fn packer_for_name(name: &str) -> Option<&'static str> {
+    PACKER_SECTIONS
+        .iter()
+        .find(|(prefix, _)| name.starts_with(prefix))
+        .map(|(_, packer)| *packer)
+}
+
+/// Score how likely an image is packed or obfuscated from its sections and
+/// entry point RVA (0 for images without an entry point).
+///
+/// Checks, each adding to the score: writable+executable sections, high
+/// entropy data, virtual sizes far larger than the raw data, non-standard or
+/// packer section names, and an entry point outside `.text`.
+pub fn assess_packing(sections: &[SectionInfo], entry_point: u32) -> PackerAssessment {
+    let mut assessment = PackerAssessment::default();
+    let mut unusual_names = Vec::new();
+
+    for section in sections {
+        let header = &section.header;
+        let name = header.name.as_str();
+
+        if header.is_writable() && header.is_executable() {
+            assessment.add(25, format!("{} is writable and executable", name));
+        }
+
+        if header.size_of_raw_data > 0 && section.entropy >= HIGH_ENTROPY {
+            let weight = if header.is_executable() { 25 } else { 15 };
+            assessment.add(
+                weight,
+                format!("{} has high entropy ({:.2})", name, section.entropy),
+            );
+        }
+
+        // Memory that only gets its contents once unpacked at run time;
+        // packers also unpack into data sections and reprotect them later
+        if header.virtual_size > 0 {
+            let executable = header.is_executable();
+            if header.size_of_raw_data == 0 {
+                if executable {
+                    assessment.add(20, format!("{} is executable but has no raw data", name));
+                } else {
+                    assessment.add(
+                        10,
+                        format!(
+                            "{} has no raw data but virtual size 0x{:X}",
+                            name, header.virtual_size
+                        ),
+                    );
+                }
+            } else if header.virtual_size / header.size_of_raw_data >= 4 {
+                let weight = if executable { 15 } else { 5 };
+                assessment.add(
+                    weight,
+                    format!(
+                        "{} virtual size 0x{:X} is far larger than raw size 0x{:X}",
+                        name, header.virtual_size, header.size_of_raw_data
+                    ),
+                );
+            }
+        }
+
+        if let Some(packer) = packer_for_name(name) {
+            assessment.add(30, format!("{} is a {} section name", name, packer));
+        } else if !is_standard_name(name) {
+            unusual_names.push(name.to_string());
+        }
+    }
+
+    if !unusual_names.is_empty() {
+        let weight = (unusual_names.len() as u32 * 5).min(15);
+        assessment.add(
+            weight,
+            format!("Non-standard section names: {}", unusual_names.join(", ")),
+        );
+    }
+
+    if entry_point != 0 {
+        let entry_section = sections
+            .iter()
+            .position(|s| s.header.contains_rva(entry_point));
+        match entry_section {
+            None => assessment.add(
+                30,
+                format!("Entry point 0x{:X} is outside every section", entry_point),
+            ),
+            Some(index) => {
+                let header = &sections[index].header;
+                if !header.is_executable() {
+                    assessment.add(
+                        25,
+                        format!("Entry point is in non-executable section {}", header.name),
+                    );
+                } else if !header.name.starts_with(".text") && header.name != "CODE" {
+                    assessment.add(10, format!("Entry point is in {}, not .text", header.name));
+                }
+                if index + 1 == sections.len() && sections.len() > 1 {
+                    assessment.add(10, "Entry point is in the last section".to_string());
+                }
+            }
+        }
+    }
+
+    let total: u32 = assessment.findings.iter().map(|f| f.weight).sum();
+    assessment.score = total.min(100);
+    assessment
+}
+
+impl PeImage<'_> {
+    /// Run [`assess_packing`] over this image
+    pub fn packer_assessment(&self) -> PackerAssessment {
+        assess_packing(
+            &self.section_infos(),
+            self.optional_header.address_of_entry_point,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headers::{
+        SectionHeader, SCN_CNT_CODE, SCN_CNT_INITIALIZED_DATA, SCN_MEM_EXECUTE, SCN_MEM_READ,
+        SCN_MEM_WRITE,
+    };
+
+    const CODE: u32 = SCN_CNT_CODE | SCN_MEM_EXECUTE | SCN_MEM_READ;
+    const RDATA: u32 = SCN_CNT_INITIALIZED_DATA | SCN_MEM_READ;
+    const DATA: u32 = RDATA | SCN_MEM_WRITE;
+
+    fn section(
+        name: &str,
+        virtual_address: u32,
+        virtual_size: u32,
+        size_of_raw_data: u32,
+        characteristics: u32,
+        entropy: f64,
+    ) -> SectionInfo {
+        SectionInfo {
+            header: SectionHeader {
+                name: name.to_string(),
+                virtual_address,
+                virtual_size,
+                size_of_raw_data,
+                characteristics,
+                ..SectionHeader::default()
+            },
+            entropy,
+        }
+    }
+
+    fn reasons(assessment: &PackerAssessment) -> Vec<&str> {
+        assessment
+            .findings
+            .iter()
+            .map(|f| f.reason.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn compiler_layout_is_clean() {
+        let sections = [
+            section(".text", 0x1000, 0xF43, 0x1000, CODE, 5.98),
+            section(".rdata", 0x2000, 0xB70, 0xC00, RDATA, 4.21),
+            section(".data", 0x3000, 0x640, 0x200, DATA, 0.43),
+            section(".reloc", 0x4000, 0x18, 0x200, RDATA, 0.30),
+        ];
+        let assessment = assess_packing(&sections, 0x13F0);
+        assert_eq!(assessment, PackerAssessment::default());
+        assert_eq!(assessment.verdict(), "Clean");
+    }
+
+    #[test]
+    fn upx_layout_is_likely_packed() {
+        let sections = [
+            section("UPX0", 0x1000, 0x10000, 0, CODE | SCN_MEM_WRITE, 0.0),
+            section("UPX1", 0x11000, 0x6000, 0x5E00, CODE | SCN_MEM_WRITE, 7.9),
+            section(".rsrc", 0x17000, 0x1000, 0x1000, DATA, 4.5),
+        ];
+        let assessment = assess_packing(&sections, 0x16A30);
+
+        assert_eq!(
+            reasons(&assessment),
+            [
+                "UPX0 is writable and executable",
+                "UPX0 is executable but has no raw data",
+                "UPX0 is a UPX section name",
+                "UPX1 is writable and executable",
+                "UPX1 has high entropy (7.90)",
+                "UPX1 is a UPX section name",
+                "Entry point is in UPX1, not .text",
+            ]
+        );
+        assert_eq!(assessment.score, 100);
+        assert_eq!(assessment.verdict(), "Likely packed");
+    }
+
+    #[test]
+    fn entry_point_in_data_section_is_flagged() {
+        let sections = [
+            section(".text", 0x1000, 0x800, 0x800, CODE, 6.0),
+            section(".data", 0x2000, 0x400, 0x400, DATA, 3.0),
+        ];
+        let assessment = assess_packing(&sections, 0x2010);
+        assert_eq!(
+            reasons(&assessment),
+            [
+                "Entry point is in non-executable section .data",
+                "Entry point is in the last section",
+            ]
+        );
+        assert_eq!(assessment.score, 35);
+        assert_eq!(assessment.verdict(), "Suspicious");
+
+        let outside = assess_packing(&sections, 0x9000);
+        assert_eq!(outside.score, 30);
+    }
+
+    #[test]
+    fn oversized_data_sections_weigh_less_than_code() {
+        let sections = [
+            section(".text", 0x1000, 0x800, 0x800, CODE, 6.0),
+            // Room for a payload to be unpacked into and reprotected
+            section(".data", 0x2000, 0x40000, 0x200, DATA, 1.0),
+            section(".bss", 0x43000, 0x8000, 0, DATA, 0.0),
+        ];
+        let assessment = assess_packing(&sections, 0x1000);
+        let weights: Vec<u32> = assessment.findings.iter().map(|f| f.weight).collect();
+        assert_eq!(weights, [5, 10]);
+        assert_eq!(
+            reasons(&assessment),
+            [
+                ".data virtual size 0x40000 is far larger than raw size 0x200",
+                ".bss has no raw data but virtual size 0x8000",
+            ]
+        );
+        assert_eq!(assessment.verdict(), "Clean");
+    }
+}
@@ -0,0 +1,36 @@
+//! Bounds-checked little-endian readers over byte buffers
+//!
+//! Every reader returns `None` instead of panicking when the requested range
+//! does not fit in the buffer, so malformed files can never index out of bounds.
+
+/// Read a `u8` at `offset`
+pub fn read_u8(data: &[u8], offset: usize) -> Option<u8> {
+    data.get(offset).copied()
+}
+
+/// Read a little-endian `u16` at `offset`
+pub fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+/// Read a little-endian `u32` at `offset`
+pub fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Read a little-endian `u64` at `offset`
+pub fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset.checked_add(8)?)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Read a null-terminated C string at `offset`.
+///
+/// If no terminator is found the string runs to the end of the buffer.
+pub fn read_cstring(data: &[u8], offset: usize) -> Option<String> {
+    let tail = data.get(offset..)?;
+    let end = tail.iter().position(|&b| b == 0).unwrap_or(tail.len());
+    Some(String::from_utf8_lossy(&tail[..end]).to_string())
+}
@@ -0,0 +1,109 @@
+//! Base relocation directory parsing and application
+
+use crate::headers::DIRECTORY_BASERELOC;
+use crate::read::{read_u16, read_u32, read_u64};
+use crate::{PeError, PeImage};
+
+/// IMAGE_REL_BASED_ABSOLUTE - padding entry, no fixup
+pub const REL_BASED_ABSOLUTE: u8 = 0;
+/// IMAGE_REL_BASED_HIGHLOW - 32-bit fixup
+pub const REL_BASED_HIGHLOW: u8 = 3;
+/// IMAGE_REL_BASED_DIR64 - 64-bit fixup
+pub const REL_BASED_DIR64: u8 = 10;
+
+/// A single base relocation fixup
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Relocation {
+    /// RVA of the patched value
+    pub rva: u32,
+    /// IMAGE_REL_BASED_* type
+    pub kind: u8,
+}
+
+impl Relocation {
+    /// Number of bytes the fixup rewrites
+    pub fn width(&self) -> usize {
+        match self.kind {
+            REL_BASED_DIR64 => 8,
+            REL_BASED_HIGHLOW => 4,
+            _ => 0,
+        }
+    }
+}
+
+impl PeImage<'_> {
+    /// Enumerate base relocations (data directory 5), skipping padding entries
+    pub fn relocations(&self) -> Vec<Relocation> {
+        let mut relocations = Vec::new();
+        let reloc_dir = match self.data_directory(DIRECTORY_BASERELOC) {
+            Some(dir) => dir,
+            None => return relocations,
+        };
+        let table = match self.slice_at_rva(reloc_dir.virtual_address, reloc_dir.size as usize) {
+            Some(table) => table,
+            None => return relocations,
+        };
+
+        let mut offset = 0;
+        while let (Some(block_rva), Some(block_size)) =
+            (read_u32(table, offset), read_u32(table, offset + 4))
+        {
+            let block_size = block_size as usize;
+            if block_size < 8 {
+                break;
+            }
+
+            let num_entries = (block_size - 8) / 2;
+            for i in 0..num_entries {
+                let entry = match read_u16(table, offset + 8 + i * 2) {
+                    Some(e) => e,
+                    None => break,
+                };
+                let kind = (entry >> 12) as u8;
+                if kind == REL_BASED_ABSOLUTE {
+                    continue;
+                }
+                relocations.push(Relocation {
+                    rva: block_rva.wrapping_add((entry & 0x0FFF) as u32),
+                    kind,
+                });
+            }
+
+            offset += block_size;
+        }
+
+        relocations
+    }
+
+    /// Map the image and rebase it as if the loader placed it at `load_base`
+    pub fn relocated_image(&self, load_base: u64) -> Result<Vec<u8>, PeError> {
+        let mut image = self.map_image()?;
+        let delta = load_base.wrapping_sub(self.optional_header.image_base) as i64;
+        if delta != 0 {
+            apply_relocations(&mut image, &self.relocations(), delta);
+        }
+        Ok(image)
+    }
+}
+
+/// Apply base relocations to a mapped image (indexed by RVA)
+pub fn apply_relocations(image: &mut [u8], relocations: &[Relocation], delta: i64) {
+    for reloc in relocations {
+        let target = reloc.rva as usize;
+        match reloc.kind {
+            REL_BASED_DIR64 => {
+                if let Some(val) = read_u64(image, target) {
+                    let new_val = (val as i64).wrapping_add(delta) as u64;
+                    image[target..target + 8].copy_from_slice(&new_val.to_le_bytes());
+                }
+            }
+            REL_BASED_HIGHLOW => {
+                if let Some(val) = read_u32(image, target) {
+                    let new_val = (val as i32).wrapping_add(delta as i32) as u32;
+                    image[target..target + 4].copy_from_slice(&new_val.to_le_bytes());
+                }
+            }
+            _ => {}
+        }
+    }
+}
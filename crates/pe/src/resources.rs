@@ -0,0 +1,333 @@
+//! Resource directory parsing (type / name / language tree)
+
+use crate::headers::DIRECTORY_RESOURCE;
+use crate::read::{read_u16, read_u32};
+use crate::PeImage;
+
+/// Size of IMAGE_RESOURCE_DIRECTORY
+const DIRECTORY_SIZE: usize = 16;
+/// Size of IMAGE_RESOURCE_DIRECTORY_ENTRY
+const ENTRY_SIZE: usize = 8;
+/// Guard against corrupt or hostile trees
+const MAX_RESOURCES: usize = 0x10000;
+
+pub const RT_CURSOR: u16 = 1;
+pub const RT_BITMAP: u16 = 2;
+pub const RT_ICON: u16 = 3;
+pub const RT_MENU: u16 = 4;
+pub const RT_DIALOG: u16 = 5;
+pub const RT_STRING: u16 = 6;
+pub const RT_FONTDIR: u16 = 7;
+pub const RT_FONT: u16 = 8;
+pub const RT_ACCELERATOR: u16 = 9;
+pub const RT_RCDATA: u16 = 10;
+pub const RT_MESSAGETABLE: u16 = 11;
+pub const RT_GROUP_CURSOR: u16 = 12;
+pub const RT_GROUP_ICON: u16 = 14;
+pub const RT_VERSION: u16 = 16;
+pub const RT_DLGINCLUDE: u16 = 17;
+pub const RT_PLUGPLAY: u16 = 19;
+pub const RT_VXD: u16 = 20;
+pub const RT_ANICURSOR: u16 = 21;
+pub const RT_ANIICON: u16 = 22;
+pub const RT_HTML: u16 = 23;
+pub const RT_MANIFEST: u16 = 24;
+
+/// A resource directory entry identifier: numeric ID or UTF-16 name
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ResourceId {
+    Id(u16),
+    Name(String),
+}
+
+impl std::fmt::Display for ResourceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResourceId::Id(id) => write!(f, "#{}", id),
+            ResourceId::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// A single resource (leaf of the type / name / language tree)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResourceEntry {
+    pub type_id: ResourceId,
+    pub name: ResourceId,
+    pub language: u16,
+    /// RVA of the resource data
+    pub rva: u32,
+    pub size: u32,
+    pub code_page: u32,
+}
+
+impl ResourceEntry {
+    /// Display name of the resource type (`RT_*` name or the custom type name)
+    pub fn type_name(&self) -> String {
+        match &self.type_id {
+            ResourceId::Id(id) => resource_type_name(*id)
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("#{}", id)),
+            ResourceId::Name(name) => name.clone(),
+        }
+    }
+}
+
+/// Get the name of a predefined resource type
+pub fn resource_type_name(type_id: u16) -> Option<&'static str> {
+    Some(match type_id {
+        RT_CURSOR => "CURSOR",
+        RT_BITMAP => "BITMAP",
+        RT_ICON => "ICON",
+        RT_MENU => "MENU",
+        RT_DIALOG => "DIALOG",
+        RT_STRING => "STRING",
+        RT_FONTDIR => "FONTDIR",
+        RT_FONT => "FONT",
+        RT_ACCELERATOR => "ACCELERATOR",
+        RT_RCDATA => "RCDATA",
+        RT_MESSAGETABLE => "MESSAGETABLE",
+        RT_GROUP_CURSOR => "GROUP_CURSOR",
+        RT_GROUP_ICON => "GROUP_ICON",
+        RT_VERSION => "VERSION",
+        RT_DLGINCLUDE => "DLGINCLUDE",
+        RT_PLUGPLAY => "PLUGPLAY",
+        RT_VXD => "VXD",
+        RT_ANICURSOR => "ANICURSOR",
+        RT_ANIICON => "ANIICON",
+        RT_HTML => "HTML",
+        RT_MANIFEST => "MANIFEST",
+        _ => return None,
+    })
+}
+
+impl<'a> PeImage<'a> {
+    /// Flatten the resource directory (data directory 2) into its leaves
+    pub fn resources(&self) -> Vec<ResourceEntry> {
+        let mut resources = Vec::new();
+        let dir = match self.data_directory(DIRECTORY_RESOURCE) {
+            Some(dir) => dir,
+            None => return resources,
+        };
+        let root = match self.slice_at_rva(dir.virtual_address, dir.size as usize) {
+            Some(root) => root,
+            // Tolerate a directory size running past the raw data
+            None => match self.rva_to_offset(dir.virtual_address) {
+                Some(offset) => &self.data()[offset..],
+                None => return resources,
+            },
+        };
+
+        // Level 1: type, level 2: name, level 3: language
+        for (type_id, type_dir) in directory_entries(root, 0) {
+            let Some(type_dir) = subdirectory(type_dir) else {
+                continue;
+            };
+            for (name, name_dir) in directory_entries(root, type_dir) {
+                let Some(name_dir) = subdirectory(name_dir) else {
+                    continue;
+                };
+                for (language, data_entry) in directory_entries(root, name_dir) {
+                    // Leaves must not point at further directories
+                    if subdirectory(data_entry).is_some() {
+                        continue;
+                    }
+                    let leaf = data_entry as usize;
+                    let (Some(rva), Some(size)) = (read_u32(root, leaf), read_u32(root, leaf + 4))
+                    else {
+                        continue;
+                    };
+                    let language = match language {
+                        ResourceId::Id(id) => id,
+                        ResourceId::Name(_) => 0,
+                    };
+                    resources.push(ResourceEntry {
+                        type_id: type_id.clone(),
+                        name: name.clone(),
+                        language,
+                        rva,
+                        size,
+                        code_page: read_u32(root, leaf + 8).unwrap_or(0),
+                    });
+                    if resources.len() >= MAX_RESOURCES {
+                        return resources;
+                    }
+                }
+            }
+        }
+
+        resources
+    }
+
+    /// Raw bytes of a resource
+    pub fn resource_data(&self, entry: &ResourceEntry) -> Option<&'a [u8]> {
+        self.slice_at_rva(entry.rva, entry.size as usize)
+    }
+
+    /// Data of the first resource of the given predefined type
+    pub fn find_resource(&self, type_id: u16) -> Option<&'a [u8]> {
+        self.resources()
+            .iter()
+            .filter(|r| r.type_id == ResourceId::Id(type_id))
+            .find_map(|r| self.resource_data(r))
+    }
+}
+
+/// Subdirectory offset if the high bit of `OffsetToData` is set
+fn subdirectory(offset_to_data: u32) -> Option<usize> {
+    (offset_to_data & 0x8000_0000 != 0).then_some((offset_to_data & 0x7FFF_FFFF) as usize)
+}
+
+/// Entries of the directory at `offset` as `(id, OffsetToData)` pairs
+fn directory_entries(root: &[u8], offset: usize) -> Vec<(ResourceId, u32)> {
+    let named = read_u16(root, offset + 12).unwrap_or(0) as usize;
+    let ids = read_u16(root, offset + 14).unwrap_or(0) as usize;
+
+    (0..named + ids)
+        .map_while(|i| {
+            let entry = offset + DIRECTORY_SIZE + i * ENTRY_SIZE;
+            let name = read_u32(root, entry)?;
+            let offset_to_data = read_u32(root, entry + 4)?;
+            let id = if name & 0x8000_0000 != 0 {
+                ResourceId::Name(resource_name(root, (name & 0x7FFF_FFFF) as usize)?)
+            } else {
+                ResourceId::Id(name as u16)
+            };
+            Some((id, offset_to_data))
+        })
+        .collect()
+}
+
+/// IMAGE_RESOURCE_DIR_STRING_U: length-prefixed UTF-16 string
+fn resource_name(root: &[u8], offset: usize) -> Option<String> {
+    let len = read_u16(root, offset)? as usize;
+    let units = (0..len)
+        .map(|i| read_u16(root, offset + 2 + i * 2))
+        .collect::<Option<Vec<u16>>>()?;
+    Some(String::from_utf16_lossy(&units))
+}
+
+/// Guess the format of a resource blob from its leading bytes
+pub fn detect_resource_format(data: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"MZ", "PE image"),
+        (b"\x89PNG\r\n\x1a\n", "PNG"),
+        (b"GIF8", "GIF"),
+        (b"\xFF\xD8\xFF", "JPEG"),
+        (b"BM", "BMP"),
+        (b"PK\x03\x04", "ZIP"),
+        (b"MSCF", "CAB"),
+        (b"7z\xBC\xAF\x27\x1C", "7z"),
+        (b"Rar!", "RAR"),
+        (b"\x1F\x8B", "GZIP"),
+        (b"%PDF", "PDF"),
+        (b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1", "OLE"),
+        (b"\x7FELF", "ELF"),
+    ];
+
+    if let Some((_, name)) = SIGNATURES.iter().find(|(magic, _)| data.starts_with(magic)) {
+        return Some(name);
+    }
+
+    // Text resources (manifests, HTML, scripts), optionally behind a UTF-8 BOM
+    let text = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    let head = &text[..text.len().min(64)];
+    let trimmed = head.trim_ascii_start();
+    if trimmed.starts_with(b"<?xml") || trimmed.starts_with(b"<assembly") {
+        Some("XML")
+    } else if trimmed.len() >= 5 && trimmed[..5].eq_ignore_ascii_case(b"<html") {
+        Some("HTML")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const X64_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x64.dll");
+    const X86_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x86.dll");
+
+    #[test]
+    fn lists_the_sample_manifest() {
+        for (data, rva) in [(X64_DLL, 0x6060), (X86_DLL, 0x5060)] {
+            let image = PeImage::parse(data).unwrap();
+            let resources = image.resources();
+            assert_eq!(
+                resources,
+                [ResourceEntry {
+                    type_id: ResourceId::Id(RT_MANIFEST),
+                    name: ResourceId::Id(2),
+                    language: 1033,
+                    rva,
+                    size: 381,
+                    code_page: 0,
+                }]
+            );
+            assert_eq!(resources[0].type_name(), "MANIFEST");
+
+            let manifest = image.resource_data(&resources[0]).unwrap();
+            assert_eq!(manifest.len(), 381);
+            assert!(manifest.starts_with(b"<?xml"));
+            assert_eq!(detect_resource_format(manifest), Some("XML"));
+            assert_eq!(image.find_resource(RT_MANIFEST), Some(manifest));
+            assert_eq!(image.find_resource(RT_VERSION), None);
+        }
+    }
+
+    #[test]
+    fn truncated_resource_data_is_not_returned() {
+        let image = PeImage::parse(X64_DLL).unwrap();
+        let entry = image.resources().remove(0);
+        let offset = image.rva_to_offset(entry.rva).unwrap();
+
+        // The directory survives but the manifest is cut short
+        let truncated = PeImage::parse(&X64_DLL[..offset + 100]).unwrap();
+        assert_eq!(truncated.resources(), std::slice::from_ref(&entry));
+        assert_eq!(truncated.resource_data(&entry), None);
+        assert_eq!(truncated.find_resource(RT_MANIFEST), None);
+
+        let rsrc = image.rva_to_offset(0x6000).unwrap();
+        for len in rsrc..offset {
+            let image = PeImage::parse(&X64_DLL[..len]).unwrap();
+            for entry in image.resources() {
+                assert!(image.resource_data(&entry).is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn corrupted_directories_do_not_panic() {
+        let image = PeImage::parse(X64_DLL).unwrap();
+        let rsrc = image.rva_to_offset(0x6000).unwrap();
+        // Every byte of the three directory levels and the data entry
+        for offset in rsrc..rsrc + 0x60 {
+            for value in [0x00, 0x7F, 0x80, 0xFF] {
+                let mut data = X64_DLL.to_vec();
+                data[offset] = value;
+                let image = PeImage::parse(&data).unwrap();
+                for entry in image.resources() {
+                    let _ = image.resource_data(&entry);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn detects_formats_from_leading_bytes() {
+        assert_eq!(detect_resource_format(b"MZ\x90\x00"), Some("PE image"));
+        assert_eq!(
+            detect_resource_format(b"\x89PNG\r\n\x1a\n...."),
+            Some("PNG")
+        );
+        assert_eq!(
+            detect_resource_format(b"\xEF\xBB\xBF  <assembly xmlns"),
+            Some("XML")
+        );
+        assert_eq!(detect_resource_format(b"\r\n<HTML><body>"), Some("HTML"));
+        assert_eq!(detect_resource_format(b"<ht"), None);
+        assert_eq!(detect_resource_format(b""), None);
+        assert_eq!(detect_resource_format(&[0x01, 0x02, 0x03]), None);
+    }
+}
@@ -0,0 +1,267 @@
+//! "Rich" header decoding (linker tool-chain stamp between the DOS stub and PE header)
+
+use crate::read::read_u32;
+use crate::PeImage;
+
+/// "Rich" marker following the encoded entries
+const RICH_MARKER: u32 = 0x6863_6952;
+/// "DanS" marker at the start of the decoded block
+const DANS_MARKER: u32 = 0x536E_6144;
+/// The header never starts before the end of the DOS header
+const MIN_OFFSET: usize = 0x40;
+
+/// One `@comp.id` entry: a tool (product ID + build) and how many objects it produced
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RichEntry {
+    pub product_id: u16,
+    pub build: u16,
+    pub count: u32,
+}
+
+impl RichEntry {
+    /// Packed `@comp.id` value (`product_id << 16 | build`)
+    pub fn comp_id(&self) -> u32 {
+        (self.product_id as u32) << 16 | self.build as u32
+    }
+
+    /// Best-effort description of the tool, e.g. "C++ compiler (VS2019)"
+    pub fn product_name(&self) -> Option<String> {
+        rich_product_name(self.product_id, self.build)
+    }
+}
+
+/// Decoded Rich header
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RichHeader {
+    /// File offset of the "DanS" marker
+    pub offset: usize,
+    /// XOR key, which is also the checksum of the DOS header and entries
+    pub key: u32,
+    pub entries: Vec<RichEntry>,
+    /// Whether `key` matches the recomputed checksum (a mismatch means the
+    /// header or DOS stub was edited after linking)
+    pub checksum_valid: bool,
+}
+
+/// Tool names for product IDs 0x00FD-0x010E (Visual Studio 2015 and later),
+/// which follow the same order as the earlier tool sets
+const TOOLS_14: [&str; 18] = [
+    "AliasObj",
+    "Cvtpgd",
+    "Cvtres",
+    "Export",
+    "Implib",
+    "Linker",
+    "MASM",
+    "C compiler",
+    "C++ compiler",
+    "C compiler (CIL)",
+    "C++ compiler (CIL)",
+    "C compiler (LTCG)",
+    "C++ compiler (LTCG)",
+    "MSIL (LTCG)",
+    "C compiler (PGO instrument)",
+    "C++ compiler (PGO instrument)",
+    "C compiler (PGO optimize)",
+    "C++ compiler (PGO optimize)",
+];
+
+/// Describe a Rich header product ID.
+///
+/// Visual Studio 2015+ tools are named individually and dated by build number;
+/// older product IDs are only mapped to their approximate Visual Studio release.
+pub fn rich_product_name(product_id: u16, build: u16) -> Option<String> {
+    let name = match product_id {
+        0x0000 => "Unmarked objects".to_string(),
+        0x0001 => "Imported functions".to_string(),
+        0x0002..=0x005F => "VS 6.0 / .NET 2002 era tool".to_string(),
+        0x0060..=0x006C => "VS .NET 2003 tool".to_string(),
+        0x006D..=0x0082 => "VS2005 tool".to_string(),
+        0x0083..=0x0096 => "VS2008 tool".to_string(),
+        0x0097..=0x00C6 => "VS2010 tool".to_string(),
+        0x00C7..=0x00FC => "VS2012 / VS2013 tool".to_string(),
+        0x00FD..=0x010E => {
+            let tool = TOOLS_14[(product_id - 0x00FD) as usize];
+            // All 14.x tool sets share product IDs; the build tells them apart
+            let release = match build {
+                0..=24999 => "VS2015",
+                25000..=27499 => "VS2017",
+                27500..=29999 => "VS2019",
+                _ => "VS2022",
+            };
+            format!("{} ({})", tool, release)
+        }
+        _ => return None,
+    };
+    Some(name)
+}
+
+impl PeImage<'_> {
+    /// Decode the Rich header, if the linker emitted one
+    pub fn rich_header(&self) -> Option<RichHeader> {
+        let data = self.data();
+        let end = (self.dos_header.pe_offset as usize).min(data.len());
+
+        // "Rich" is followed by the key; search backwards from the PE header
+        let rich_offset = (MIN_OFFSET..end.saturating_sub(7))
+            .rev()
+            .filter(|offset| offset % 4 == 0)
+            .find(|&offset| read_u32(data, offset) == Some(RICH_MARKER))?;
+        let key = read_u32(data, rich_offset + 4)?;
+
+        // Walk back until the decoded "DanS" marker
+        let dans_offset = (MIN_OFFSET..rich_offset)
+            .rev()
+            .filter(|offset| offset % 4 == 0)
+            .find(|&offset| read_u32(data, offset).map(|v| v ^ key) == Some(DANS_MARKER))?;
+
+        // "DanS" is followed by three zero padding DWORDs, then comp.id/count pairs
+        let entries: Vec<RichEntry> = (dans_offset + 16..rich_offset)
+            .step_by(8)
+            .filter_map(|offset| {
+                let comp_id = read_u32(data, offset)? ^ key;
+                let count = read_u32(data, offset + 4)? ^ key;
+                Some(RichEntry {
+                    product_id: (comp_id >> 16) as u16,
+                    build: comp_id as u16,
+                    count,
+                })
+            })
+            .collect();
+
+        // Checksum over the DOS header/stub (without e_lfanew) and the entries
+        let mut checksum = dans_offset as u32;
+        for (i, &byte) in data[..dans_offset].iter().enumerate() {
+            if (0x3C..0x40).contains(&i) {
+                continue;
+            }
+            checksum = checksum.wrapping_add((byte as u32).rotate_left(i as u32));
+        }
+        for entry in &entries {
+            checksum = checksum.wrapping_add(entry.comp_id().rotate_left(entry.count));
+        }
+
+        Some(RichHeader {
+            offset: dans_offset,
+            key,
+            entries,
+            checksum_valid: checksum == key,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const X64_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x64.dll");
+    const X86_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x86.dll");
+
+    fn entry(product_id: u16, build: u16, count: u32) -> RichEntry {
+        RichEntry {
+            product_id,
+            build,
+            count,
+        }
+    }
+
+    #[test]
+    fn decodes_sample_header() {
+        let rich = PeImage::parse(X64_DLL).unwrap().rich_header().unwrap();
+        assert_eq!(rich.offset, 0x80);
+        assert_eq!(rich.key, 0xC7C1_E26F);
+        assert!(rich.checksum_valid);
+        assert_eq!(
+            rich.entries,
+            [
+                entry(0x93, 30729, 2),
+                entry(0x101, 24123, 2),
+                entry(0x103, 24123, 2),
+                entry(0x105, 24123, 9),
+                entry(0x104, 24123, 9),
+                entry(0xCB, 65501, 5),
+                entry(0x01, 0, 33),
+                entry(0x109, 24215, 1),
+                entry(0x100, 24215, 1),
+                entry(0xFF, 24210, 1),
+                entry(0x102, 24215, 1),
+            ]
+        );
+
+        let names: Vec<Option<String>> = rich.entries[..4]
+            .iter()
+            .map(RichEntry::product_name)
+            .collect();
+        assert_eq!(
+            names,
+            [
+                Some("VS2008 tool".to_string()),
+                Some("Implib (VS2015)".to_string()),
+                Some("MASM (VS2015)".to_string()),
+                Some("C++ compiler (VS2015)".to_string()),
+            ]
+        );
+        assert_eq!(rich.entries[6].comp_id(), 0x0001_0000);
+
+        let rich = PeImage::parse(X86_DLL).unwrap().rich_header().unwrap();
+        assert_eq!(rich.key, 0xDFEE_C7FF);
+        assert!(rich.checksum_valid);
+        assert_eq!(rich.entries[6], entry(0x01, 0, 30));
+    }
+
+    #[test]
+    fn edited_stub_fails_the_checksum() {
+        let mut data = X64_DLL.to_vec();
+        // "This program cannot be run in DOS mode" lives in the stub
+        data[0x4E] ^= 0x20;
+        let rich = PeImage::parse(&data).unwrap().rich_header().unwrap();
+        assert!(!rich.checksum_valid);
+        assert_eq!(rich.entries.len(), 11);
+    }
+
+    #[test]
+    fn missing_markers_mean_no_header() {
+        let rich = PeImage::parse(X64_DLL).unwrap().rich_header().unwrap();
+        let pe_offset = PeImage::parse(X64_DLL).unwrap().dos_header.pe_offset as usize;
+        let rich_marker = (rich.offset..pe_offset)
+            .step_by(4)
+            .find(|&offset| read_u32(X64_DLL, offset) == Some(RICH_MARKER))
+            .unwrap();
+
+        let mut data = X64_DLL.to_vec();
+        data[rich_marker] = b'X';
+        assert_eq!(PeImage::parse(&data).unwrap().rich_header(), None);
+
+        let mut data = X64_DLL.to_vec();
+        data[rich.offset] ^= 0xFF;
+        assert_eq!(PeImage::parse(&data).unwrap().rich_header(), None);
+
+        // A marker in the last stub dword has no key before the PE header
+        let mut data = X64_DLL.to_vec();
+        data[rich_marker] = b'X';
+        data[pe_offset - 4..pe_offset].copy_from_slice(&RICH_MARKER.to_le_bytes());
+        assert_eq!(PeImage::parse(&data).unwrap().rich_header(), None);
+
+        // Every byte between the DOS header and the PE header, corrupted
+        for offset in MIN_OFFSET..pe_offset {
+            let mut data = X64_DLL.to_vec();
+            data[offset] ^= 0xA5;
+            let _ = PeImage::parse(&data).unwrap().rich_header();
+        }
+    }
+
+    #[test]
+    fn names_products_by_release() {
+        assert_eq!(rich_product_name(0x0000, 0).unwrap(), "Unmarked objects");
+        assert_eq!(
+            rich_product_name(0x0105, 27508).unwrap(),
+            "C++ compiler (VS2019)"
+        );
+        assert_eq!(rich_product_name(0x0102, 30133).unwrap(), "Linker (VS2022)");
+        assert_eq!(
+            rich_product_name(0x010E, 25017).unwrap(),
+            "C++ compiler (PGO optimize) (VS2017)"
+        );
+        assert_eq!(rich_product_name(0x010F, 0), None);
+    }
+}
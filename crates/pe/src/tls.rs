@@ -0,0 +1,168 @@
+//! TLS directory parsing
+
+use crate::headers::DIRECTORY_TLS;
+use crate::PeImage;
+
+/// Guard against unterminated callback arrays
+const MAX_TLS_CALLBACKS: usize = 0x100;
+
+/// IMAGE_TLS_DIRECTORY32/64 (addresses are VAs at the preferred image base)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TlsDirectory {
+    pub start_address_of_raw_data: u64,
+    pub end_address_of_raw_data: u64,
+    pub address_of_index: u64,
+    pub address_of_callbacks: u64,
+    pub size_of_zero_fill: u32,
+    pub characteristics: u32,
+    /// RVAs of the TLS callbacks, which the loader runs before the entry point
+    pub callbacks: Vec<u32>,
+}
+
+impl TlsDirectory {
+    /// Size of the TLS template (initialized data plus zero fill)
+    pub fn template_size(&self) -> u64 {
+        self.end_address_of_raw_data
+            .saturating_sub(self.start_address_of_raw_data)
+            + self.size_of_zero_fill as u64
+    }
+}
+
+impl PeImage<'_> {
+    /// Parse the TLS directory (data directory 9) and its callback array
+    pub fn tls(&self) -> Option<TlsDirectory> {
+        let dir = self.data_directory(DIRECTORY_TLS)?;
+        let rva = dir.virtual_address;
+        let width = if self.is_64bit() { 8 } else { 4 };
+
+        let mut tls = TlsDirectory {
+            start_address_of_raw_data: self.read_pointer_at_rva(rva)?,
+            end_address_of_raw_data: self.read_pointer_at_rva(rva.wrapping_add(width))?,
+            address_of_index: self.read_pointer_at_rva(rva.wrapping_add(width * 2))?,
+            address_of_callbacks: self.read_pointer_at_rva(rva.wrapping_add(width * 3))?,
+            size_of_zero_fill: self.read_u32_at_rva(rva.wrapping_add(width * 4))?,
+            characteristics: self.read_u32_at_rva(rva.wrapping_add(width * 4 + 4))?,
+            callbacks: Vec::new(),
+        };
+
+        // Null-terminated array of callback VAs
+        if let Some(array_rva) = self.va_to_rva(tls.address_of_callbacks) {
+            for i in 0..MAX_TLS_CALLBACKS as u32 {
+                match self.read_pointer_at_rva(array_rva.wrapping_add(i * width)) {
+                    Some(0) | None => break,
+                    Some(va) => {
+                        if let Some(callback) = self.va_to_rva(va) {
+                            tls.callbacks.push(callback);
+                        }
+                    }
+                }
+            }
+        }
+
+        Some(tls)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const X64_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x64.dll");
+    const X86_DLL: &[u8] = include_bytes!("../../../assets/dll/hello-world-x86.dll");
+
+    /// Plant a TLS directory and callback array in a zeroed, file-backed run
+    /// of `.data` at `rva`, pointing data directory 9 at it
+    fn with_tls(original: &[u8], rva: u32) -> Vec<u8> {
+        let image = PeImage::parse(original).unwrap();
+        let base = image.optional_header.image_base;
+        let (width, directories) = if image.is_64bit() { (8, 112) } else { (4, 96) };
+        let offset = image.rva_to_offset(rva).unwrap();
+        let entry = image.optional_header_offset() + directories + DIRECTORY_TLS * 8;
+
+        let callbacks = rva + 0x30;
+        let mut directory = Vec::new();
+        let push_pointer = |bytes: &mut Vec<u8>, value: u64| {
+            bytes.extend(&value.to_le_bytes()[..width]);
+        };
+        for va in [
+            base + 0x3000,
+            base + 0x3010,
+            base + 0x3020,
+            base + callbacks as u64,
+        ] {
+            push_pointer(&mut directory, va);
+        }
+        directory.extend(0x20u32.to_le_bytes());
+        directory.extend(0u32.to_le_bytes());
+        // Two callbacks, one outside the image (skipped), then the terminator
+        let mut array = Vec::new();
+        for va in [base + 0x1000, base + 0x1010, 0x10, 0] {
+            push_pointer(&mut array, va);
+        }
+
+        let mut data = original.to_vec();
+        assert!(data[offset..offset + 0x30 + array.len()]
+            .iter()
+            .all(|&b| b == 0));
+        data[offset..offset + directory.len()].copy_from_slice(&directory);
+        data[offset + 0x30..offset + 0x30 + array.len()].copy_from_slice(&array);
+        data[entry..entry + 4].copy_from_slice(&rva.to_le_bytes());
+        data[entry + 4..entry + 8].copy_from_slice(&(width as u32 * 4 + 8).to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn samples_have_no_tls() {
+        assert_eq!(PeImage::parse(X64_DLL).unwrap().tls(), None);
+        assert_eq!(PeImage::parse(X86_DLL).unwrap().tls(), None);
+    }
+
+    #[test]
+    fn parses_directory_and_callbacks() {
+        for (original, rva) in [(X64_DLL, 0x3040), (X86_DLL, 0x3020)] {
+            let data = with_tls(original, rva);
+            let image = PeImage::parse(&data).unwrap();
+            let base = image.optional_header.image_base;
+            let tls = image.tls().unwrap();
+            assert_eq!(tls.start_address_of_raw_data, base + 0x3000);
+            assert_eq!(tls.end_address_of_raw_data, base + 0x3010);
+            assert_eq!(tls.address_of_index, base + 0x3020);
+            assert_eq!(tls.address_of_callbacks, base + rva as u64 + 0x30);
+            assert_eq!(tls.size_of_zero_fill, 0x20);
+            assert_eq!(tls.template_size(), 0x30);
+            assert_eq!(tls.callbacks, [0x1000, 0x1010]);
+        }
+    }
+
+    #[test]
+    fn truncated_directory_is_not_returned() {
+        let data = with_tls(X64_DLL, 0x3040);
+        let offset = PeImage::parse(&data)
+            .unwrap()
+            .rva_to_offset(0x3040)
+            .unwrap();
+
+        // Cut inside the directory itself
+        let image = PeImage::parse(&data[..offset + 20]).unwrap();
+        assert_eq!(image.tls(), None);
+
+        // Cut inside the callback array: the callbacks read so far are kept
+        let image = PeImage::parse(&data[..offset + 0x30 + 12]).unwrap();
+        assert_eq!(image.tls().unwrap().callbacks, [0x1000]);
+
+        for len in offset..offset + 0x50 {
+            let _ = PeImage::parse(&data[..len]).unwrap().tls();
+        }
+    }
+
+    #[test]
+    fn inverted_template_range_does_not_underflow() {
+        let tls = TlsDirectory {
+            start_address_of_raw_data: 0x2000,
+            end_address_of_raw_data: 0x1000,
+            size_of_zero_fill: 8,
+            ..Default::default()
+        };
+        assert_eq!(tls.template_size(), 8);
+    }
+}
@@ -0,0 +1,317 @@
+//! VS_VERSIONINFO resource parsing
+
+use crate::read::{read_u16, read_u32};
+use crate::resources::RT_VERSION;
+use crate::PeImage;
+
+/// VS_FIXEDFILEINFO signature
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xFEEF_04BD;
+
+/// Version information of a PE image
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VersionInfo {
+    /// `dwFileVersionMS/LS` from VS_FIXEDFILEINFO as `a.b.c.d`
+    pub fixed_file_version: Option<String>,
+    /// `dwProductVersionMS/LS` from VS_FIXEDFILEINFO as `a.b.c.d`
+    pub fixed_product_version: Option<String>,
+    /// Key/value pairs of the first StringFileInfo string table
+    pub strings: Vec<(String, String)>,
+}
+
+impl VersionInfo {
+    /// Look up a string table value (e.g. `CompanyName`), ignoring empty values
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.strings
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+            .filter(|v| !v.is_empty())
+    }
+
+    pub fn company_name(&self) -> Option<&str> {
+        self.get("CompanyName")
+    }
+
+    pub fn file_description(&self) -> Option<&str> {
+        self.get("FileDescription")
+    }
+
+    pub fn original_filename(&self) -> Option<&str> {
+        self.get("OriginalFilename")
+    }
+
+    /// `ProductVersion` string, falling back to the fixed product version
+    pub fn product_version(&self) -> Option<&str> {
+        self.get("ProductVersion")
+            .or(self.fixed_product_version.as_deref())
+    }
+
+    /// `FileVersion` string, falling back to the fixed file version
+    pub fn file_version(&self) -> Option<&str> {
+        self.get("FileVersion")
+            .or(self.fixed_file_version.as_deref())
+    }
+}
+
+/// Header shared by every node of the version tree
+struct Block<'a> {
+    key: String,
+    /// `wType`: 1 for text values, 0 for binary
+    is_text: bool,
+    value_length: usize,
+    value: &'a [u8],
+    children: &'a [u8],
+}
+
+impl<'a> Block<'a> {
+    /// Parse the block at the start of `data`, returning it and its total length
+    fn parse(data: &'a [u8]) -> Option<(Self, usize)> {
+        let length = (read_u16(data, 0)? as usize).min(data.len());
+        let value_length = read_u16(data, 2)? as usize;
+        let is_text = read_u16(data, 4)? == 1;
+        if length < 6 {
+            return None;
+        }
+        let block = &data[..length];
+
+        // szKey: null-terminated UTF-16, then padding to a 32-bit boundary
+        let mut units = Vec::new();
+        let mut offset = 6;
+        while let Some(unit) = read_u16(block, offset) {
+            offset += 2;
+            if unit == 0 {
+                break;
+            }
+            units.push(unit);
+        }
+        let value_start = align4(offset);
+
+        // wValueLength counts WCHARs for text values and bytes otherwise
+        let value_bytes = if is_text {
+            value_length * 2
+        } else {
+            value_length
+        };
+        let value_end = (value_start + value_bytes).min(length);
+        let value = block.get(value_start..value_end).unwrap_or(&[]);
+        let children = block.get(align4(value_end)..).unwrap_or(&[]);
+
+        Some((
+            Block {
+                key: String::from_utf16_lossy(&units),
+                is_text,
+                value_length,
+                value,
+                children,
+            },
+            align4(length),
+        ))
+    }
+
+    /// Iterate child blocks
+    fn children(&self) -> impl Iterator<Item = Block<'a>> {
+        let mut rest = self.children;
+        std::iter::from_fn(move || {
+            let (block, length) = Block::parse(rest)?;
+            rest = rest.get(length..).unwrap_or(&[]);
+            Some(block)
+        })
+    }
+
+    /// Decode a text value, dropping the terminating null
+    fn text(&self) -> String {
+        let units: Vec<u16> = self
+            .value
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|&u| u != 0)
+            .collect();
+        String::from_utf16_lossy(&units).trim().to_string()
+    }
+}
+
+fn align4(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+/// Format a pair of version DWORDs as `a.b.c.d`
+fn format_version(ms: u32, ls: u32) -> String {
+    format!("{}.{}.{}.{}", ms >> 16, ms & 0xFFFF, ls >> 16, ls & 0xFFFF)
+}
+
+/// Parse a raw VS_VERSIONINFO resource
+pub fn parse_version_info(data: &[u8]) -> Option<VersionInfo> {
+    let (root, _) = Block::parse(data)?;
+    if root.key != "VS_VERSION_INFO" {
+        return None;
+    }
+
+    let mut info = VersionInfo::default();
+    if root.value_length >= 52 && read_u32(root.value, 0) == Some(FIXED_FILE_INFO_SIGNATURE) {
+        let dword = |offset| read_u32(root.value, offset).unwrap_or(0);
+        info.fixed_file_version = Some(format_version(dword(8), dword(12)));
+        info.fixed_product_version = Some(format_version(dword(16), dword(20)));
+    }
+
+    // StringFileInfo -> StringTable (one per language/code page) -> String
+    if let Some(string_file_info) = root.children().find(|b| b.key == "StringFileInfo") {
+        if let Some(table) = string_file_info.children().next() {
+            info.strings = table
+                .children()
+                .filter(|s| s.is_text || !s.value.is_empty())
+                .map(|s| (s.key.clone(), s.text()))
+                .collect();
+        }
+    }
+
+    Some(info)
+}
+
+impl PeImage<'_> {
+    /// Parse the first RT_VERSION resource
+    pub fn version_info(&self) -> Option<VersionInfo> {
+        parse_version_info(self.find_resource(RT_VERSION)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encode a version block; `value_length` is in WCHARs for text values
+    fn block(key: &str, value: &[u8], is_text: bool, children: &[Vec<u8>]) -> Vec<u8> {
+        let value_length = if is_text {
+            value.len() / 2
+        } else {
+            value.len()
+        };
+        let mut data = vec![0, 0];
+        data.extend((value_length as u16).to_le_bytes());
+        data.extend((is_text as u16).to_le_bytes());
+        data.extend(key.encode_utf16().chain([0]).flat_map(u16::to_le_bytes));
+        data.resize(align4(data.len()), 0);
+        data.extend(value);
+        for child in children {
+            data.resize(align4(data.len()), 0);
+            data.extend(child);
+        }
+        let length = data.len() as u16;
+        data[0..2].copy_from_slice(&length.to_le_bytes());
+        data
+    }
+
+    fn text(key: &str, value: &str) -> Vec<u8> {
+        let value: Vec<u8> = value
+            .encode_utf16()
+            .chain([0])
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        block(key, &value, true, &[])
+    }
+
+    fn fixed_file_info(file: (u32, u32), product: (u32, u32)) -> Vec<u8> {
+        let mut value = Vec::new();
+        for dword in [
+            FIXED_FILE_INFO_SIGNATURE,
+            0x1_0000,
+            file.0,
+            file.1,
+            product.0,
+            product.1,
+        ] {
+            value.extend(dword.to_le_bytes());
+        }
+        value.resize(52, 0);
+        value
+    }
+
+    fn sample() -> Vec<u8> {
+        let strings = block(
+            "StringFileInfo",
+            &[],
+            true,
+            &[block(
+                "040904B0",
+                &[],
+                true,
+                &[
+                    text("CompanyName", "Contoso"),
+                    text("FileDescription", " Test library "),
+                    text("FileVersion", ""),
+                    text("ProductVersion", "1.2.3"),
+                ],
+            )],
+        );
+        let translation = block("Translation", &[0x09, 0x04, 0xB0, 0x04], false, &[]);
+        block(
+            "VS_VERSION_INFO",
+            &fixed_file_info((0x000A_0000, 0x4A61_0001), (0x000A_0000, 0)),
+            false,
+            &[block("VarFileInfo", &[], true, &[translation]), strings],
+        )
+    }
+
+    #[test]
+    fn parses_fixed_info_and_string_table() {
+        let info = parse_version_info(&sample()).unwrap();
+        assert_eq!(info.fixed_file_version.as_deref(), Some("10.0.19041.1"));
+        assert_eq!(info.fixed_product_version.as_deref(), Some("10.0.0.0"));
+        assert_eq!(info.strings.len(), 4);
+
+        assert_eq!(info.company_name(), Some("Contoso"));
+        assert_eq!(info.get("companyname"), Some("Contoso"));
+        assert_eq!(info.file_description(), Some("Test library"));
+        assert_eq!(info.original_filename(), None);
+        // An empty FileVersion string falls back to the fixed version
+        assert_eq!(info.file_version(), Some("10.0.19041.1"));
+        assert_eq!(info.product_version(), Some("1.2.3"));
+    }
+
+    #[test]
+    fn samples_have_no_version_resource() {
+        for data in [
+            &include_bytes!("../../../assets/dll/hello-world-x64.dll")[..],
+            &include_bytes!("../../../assets/dll/hello-world-x86.dll")[..],
+        ] {
+            assert_eq!(PeImage::parse(data).unwrap().version_info(), None);
+        }
+    }
+
+    #[test]
+    fn rejects_other_roots_and_bad_fixed_info() {
+        let other = block("StringFileInfo", &[], true, &[]);
+        assert_eq!(parse_version_info(&other), None);
+        assert_eq!(parse_version_info(&[]), None);
+        // wLength below the 6-byte header
+        assert_eq!(parse_version_info(&[4, 0, 0, 0, 0, 0, 0, 0]), None);
+
+        let mut fixed = fixed_file_info((1, 2), (3, 4));
+        fixed[0] ^= 0xFF;
+        let info = parse_version_info(&block("VS_VERSION_INFO", &fixed, false, &[])).unwrap();
+        assert_eq!(info.fixed_file_version, None);
+        assert!(info.strings.is_empty());
+    }
+
+    #[test]
+    fn truncated_or_oversized_blocks_do_not_panic() {
+        let data = sample();
+        for len in 0..data.len() {
+            if let Some(info) = parse_version_info(&data[..len]) {
+                assert!(info.strings.len() <= 4);
+            }
+        }
+
+        // Every wLength claiming far more than is there
+        let mut data = sample();
+        for offset in (0..data.len()).step_by(4) {
+            let mut patched = data.clone();
+            patched[offset..offset + 2].copy_from_slice(&u16::MAX.to_le_bytes());
+            let _ = parse_version_info(&patched);
+        }
+        data[2..4].copy_from_slice(&u16::MAX.to_le_bytes());
+        assert_eq!(
+            parse_version_info(&data).unwrap().fixed_file_version,
+            Some("10.0.19041.1".to_string())
+        );
+    }
+}
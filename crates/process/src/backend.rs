@@ -0,0 +1,59 @@
+//! Platform abstraction for process, thread, module and memory enumeration
+
+use std::sync::OnceLock;
+
+use crate::{MemoryRegionInfo, ModuleInfo, ProcessCounters, ProcessError, ProcessInfo, ThreadInfo};
+
+/// Source of process information for one platform.
+///
+/// Memory regions use the Windows `MEM_*` / `PAGE_*` values for state, type
+/// and protection on every platform, so the UI can render them uniformly.
+pub trait ProcessBackend: Send + Sync {
+    /// Every running process, with CPU usage since the previous call
+    fn processes(&self) -> Vec<ProcessInfo>;
+
+    /// Resource counters of every running process, cheap enough to read
+    /// every couple of seconds
+    fn counters(&self) -> Vec<ProcessCounters>;
+
+    /// Threads of one process
+    fn threads(&self, pid: u32) -> Result<Vec<ThreadInfo>, ProcessError>;
+
+    /// Executable images mapped into one process
+    fn modules(&self, pid: u32) -> Result<Vec<ModuleInfo>, ProcessError>;
+
+    /// Virtual memory regions of one process, in address order
+    fn memory_regions(&self, pid: u32) -> Result<Vec<MemoryRegionInfo>, ProcessError>;
+
+    /// Read up to `size` bytes at `address`; a short read is truncated and
+    /// reading nothing is an error
+    fn read_memory(&self, pid: u32, address: usize, size: usize) -> Result<Vec<u8>, ProcessError>;
+}
+
+static BACKEND: OnceLock<Box<dyn ProcessBackend>> = OnceLock::new();
+
+/// Replace the platform backend (e.g. with a recorded or fake one).
+///
+/// Must be called before the first enumeration; fails with
+/// [`ProcessError::BackendInUse`] if a backend is already in use.
+pub fn set_backend(backend: Box<dyn ProcessBackend>) -> Result<(), ProcessError> {
+    BACKEND.set(backend).map_err(|_| ProcessError::BackendInUse)
+}
+
+/// The active backend, defaulting to the one for the current platform
+pub fn backend() -> &'static dyn ProcessBackend {
+    BACKEND.get_or_init(platform_backend).as_ref()
+}
+
+#[cfg(windows)]
+fn platform_backend() -> Box<dyn ProcessBackend> {
+    Box::new(crate::Win32Backend)
+}
+
+#[cfg(target_os = "linux")]
+fn platform_backend() -> Box<dyn ProcessBackend> {
+    Box::new(crate::LinuxBackend)
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+compile_error!("the process crate only has Windows and Linux backends");
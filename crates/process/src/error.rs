@@ -0,0 +1,173 @@
+//! Process enumeration and control errors
+
+use std::fmt;
+use std::io;
+
+use pe::PeError;
+
+/// `ERROR_ACCESS_DENIED`
+const ERROR_ACCESS_DENIED: u32 = 5;
+/// `STATUS_ACCESS_DENIED`
+const STATUS_ACCESS_DENIED: u32 = 0xC000_0022;
+
+/// Why a process, thread or memory operation failed.
+///
+/// Win32 codes are the `GetLastError` values, so "access denied" (5) can be
+/// told apart from a process that has exited (87, invalid parameter).
+#[derive(Debug)]
+pub enum ProcessError {
+    /// `OpenProcess` failed for this PID
+    OpenProcess { pid: u32, code: u32 },
+    /// `OpenThread` failed for this thread ID
+    OpenThread { thread_id: u32, code: u32 },
+    /// A Win32 call failed with this `GetLastError` code
+    Win32 { operation: &'static str, code: u32 },
+    /// A native API call returned this NTSTATUS
+    NtStatus {
+        operation: &'static str,
+        status: u32,
+    },
+    /// A `/proc` file could not be read
+    Io { path: String, error: io::Error },
+    /// A system call failed with this OS error
+    Os {
+        operation: &'static str,
+        error: io::Error,
+    },
+    /// The operation has no implementation on this platform
+    Unsupported(&'static str),
+    /// The process is not running (or not in the recorded snapshot)
+    NotFound(u32),
+    /// The recorded snapshot has the process but not this detail list
+    /// (`what`, e.g. "Threads"), because reading it failed at capture time
+    NotCaptured { pid: u32, what: &'static str },
+    /// Nothing could be read at this address
+    Unreadable { pid: u32, address: usize },
+    /// A module file was read but could not be parsed as a PE image
+    UnreadableImage { path: String, error: PeError },
+    /// [`set_backend`](crate::set_backend) was called after a backend was
+    /// already installed or used
+    BackendInUse,
+}
+
+impl ProcessError {
+    /// Whether the OS refused access, as opposed to the target being gone
+    pub fn is_access_denied(&self) -> bool {
+        match self {
+            ProcessError::OpenProcess { code, .. }
+            | ProcessError::OpenThread { code, .. }
+            | ProcessError::Win32 { code, .. } => *code == ERROR_ACCESS_DENIED,
+            ProcessError::NtStatus { status, .. } => *status == STATUS_ACCESS_DENIED,
+            ProcessError::Io { error, .. } | ProcessError::Os { error, .. } => {
+                error.kind() == io::ErrorKind::PermissionDenied
+            }
+            ProcessError::NotFound(_)
+            | ProcessError::NotCaptured { .. }
+            | ProcessError::Unreadable { .. }
+            | ProcessError::UnreadableImage { .. }
+            | ProcessError::BackendInUse
+            | ProcessError::Unsupported(_) => false,
+        }
+    }
+}
+
+/// System message for a Win32 error code, e.g. "Access is denied. (os error 5)"
+fn os_message(code: u32) -> io::Error {
+    io::Error::from_raw_os_error(code as i32)
+}
+
+impl fmt::Display for ProcessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProcessError::OpenProcess { pid, code } => {
+                write!(f, "Failed to open process {}: {}", pid, os_message(*code))
+            }
+            ProcessError::OpenThread { thread_id, code } => {
+                write!(
+                    f,
+                    "Failed to open thread {}: {}",
+                    thread_id,
+                    os_message(*code)
+                )
+            }
+            ProcessError::Win32 { operation, code } => {
+                write!(f, "{} failed: {}", operation, os_message(*code))
+            }
+            ProcessError::NtStatus { operation, status } => {
+                write!(f, "{} failed with NTSTATUS 0x{:08X}", operation, status)
+            }
+            ProcessError::Io { path, error } => write!(f, "Failed to read {}: {}", path, error),
+            ProcessError::Os { operation, error } => write!(f, "{} failed: {}", operation, error),
+            ProcessError::Unsupported(operation) => {
+                write!(f, "{} is not supported on this platform", operation)
+            }
+            ProcessError::NotFound(pid) => write!(f, "Process {} not found", pid),
+            ProcessError::NotCaptured { pid, what } => {
+                write!(
+                    f,
+                    "{} of process {} were not captured in this snapshot",
+                    what, pid
+                )
+            }
+            ProcessError::Unreadable { pid, address } => {
+                write!(
+                    f,
+                    "No readable memory at 0x{:X} in process {}",
+                    address, pid
+                )
+            }
+            ProcessError::UnreadableImage { path, error } => {
+                write!(f, "Failed to parse {}: {}", path, error)
+            }
+            ProcessError::BackendInUse => write!(f, "A process backend is already in use"),
+        }
+    }
+}
+
+impl std::error::Error for ProcessError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn access_denied_is_recognised_across_sources() {
+        let open = ProcessError::OpenProcess { pid: 4, code: 5 };
+        assert!(open.is_access_denied());
+        assert!(open.to_string().starts_with("Failed to open process 4: "));
+
+        let status = ProcessError::NtStatus {
+            operation: "NtSuspendProcess",
+            status: 0xC000_0022,
+        };
+        assert!(status.is_access_denied());
+        assert_eq!(
+            status.to_string(),
+            "NtSuspendProcess failed with NTSTATUS 0xC0000022"
+        );
+
+        let io = ProcessError::Io {
+            path: String::from("/proc/1/maps"),
+            error: io::Error::from(io::ErrorKind::PermissionDenied),
+        };
+        assert!(io.is_access_denied());
+        assert!(!ProcessError::NotFound(9999).is_access_denied());
+
+        let missing = ProcessError::NotCaptured {
+            pid: 4,
+            what: "Handles",
+        };
+        assert!(!missing.is_access_denied());
+        assert_eq!(
+            missing.to_string(),
+            "Handles of process 4 were not captured in this snapshot"
+        );
+
+        let unsupported = ProcessError::Unsupported("Handle enumeration");
+        assert!(!unsupported.is_access_denied());
+        assert_eq!(
+            unsupported.to_string(),
+            "Handle enumeration is not supported on this platform"
+        );
+    }
+}
@@ -0,0 +1,425 @@
+//! Filtering, ordering and tree flattening of process listings for the
+//! process table
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use crate::{ProcessInfo, ProcessStats, ProcessTreeNode};
+
+/// Sort column for the process table
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ProcessSortColumn {
+    Pid,
+    Name,
+    Memory,
+    Threads,
+    Cpu,
+    /// One of the optional counter columns
+    Stat(StatColumn),
+}
+
+/// Optional process table columns backed by [`ProcessStats`]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum StatColumn {
+    PrivateBytes,
+    PeakWorkingSet,
+    Pagefile,
+    PageFaults,
+    IoReadBytes,
+    IoWriteBytes,
+    IoOtherBytes,
+    IoReadOps,
+    IoWriteOps,
+    IoOtherOps,
+    Handles,
+    GdiObjects,
+    UserObjects,
+}
+
+impl StatColumn {
+    pub const ALL: [StatColumn; 13] = [
+        StatColumn::PrivateBytes,
+        StatColumn::PeakWorkingSet,
+        StatColumn::Pagefile,
+        StatColumn::PageFaults,
+        StatColumn::IoReadBytes,
+        StatColumn::IoWriteBytes,
+        StatColumn::IoOtherBytes,
+        StatColumn::IoReadOps,
+        StatColumn::IoWriteOps,
+        StatColumn::IoOtherOps,
+        StatColumn::Handles,
+        StatColumn::GdiObjects,
+        StatColumn::UserObjects,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StatColumn::PrivateBytes => "Private Bytes",
+            StatColumn::PeakWorkingSet => "Peak Working Set",
+            StatColumn::Pagefile => "Pagefile",
+            StatColumn::PageFaults => "Page Faults",
+            StatColumn::IoReadBytes => "I/O Read",
+            StatColumn::IoWriteBytes => "I/O Write",
+            StatColumn::IoOtherBytes => "I/O Other",
+            StatColumn::IoReadOps => "Read Ops",
+            StatColumn::IoWriteOps => "Write Ops",
+            StatColumn::IoOtherOps => "Other Ops",
+            StatColumn::Handles => "Handles",
+            StatColumn::GdiObjects => "GDI Objects",
+            StatColumn::UserObjects => "USER Objects",
+        }
+    }
+
+    pub fn value(self, stats: &ProcessStats) -> u64 {
+        match self {
+            StatColumn::PrivateBytes => stats.private_bytes,
+            StatColumn::PeakWorkingSet => stats.peak_working_set_bytes,
+            StatColumn::Pagefile => stats.pagefile_bytes,
+            StatColumn::PageFaults => stats.page_fault_count as u64,
+            StatColumn::IoReadBytes => stats.io_read_bytes,
+            StatColumn::IoWriteBytes => stats.io_write_bytes,
+            StatColumn::IoOtherBytes => stats.io_other_bytes,
+            StatColumn::IoReadOps => stats.io_read_ops,
+            StatColumn::IoWriteOps => stats.io_write_ops,
+            StatColumn::IoOtherOps => stats.io_other_ops,
+            StatColumn::Handles => stats.handle_count as u64,
+            StatColumn::GdiObjects => stats.gdi_objects as u64,
+            StatColumn::UserObjects => stats.user_objects as u64,
+        }
+    }
+
+    /// Cell text: byte counts in MB, the rest as plain counts
+    pub fn format(self, stats: &ProcessStats) -> String {
+        let value = self.value(stats);
+        match self {
+            StatColumn::PrivateBytes
+            | StatColumn::PeakWorkingSet
+            | StatColumn::Pagefile
+            | StatColumn::IoReadBytes
+            | StatColumn::IoWriteBytes
+            | StatColumn::IoOtherBytes => format!("{:.1} MB", value as f64 / (1024.0 * 1024.0)),
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// Sort order
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// Tree placement of a process row in the process tab's tree mode
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TreeRowState {
+    pub depth: usize,
+    pub has_children: bool,
+    pub expanded: bool,
+    /// Parent exited or its PID was reused
+    pub orphan: bool,
+    /// Number of processes hidden under a collapsed node
+    pub hidden_descendants: usize,
+}
+
+/// Whether a process matches the search box (`query` already lowercased)
+pub fn matches_process(p: &ProcessInfo, query: &str) -> bool {
+    query.is_empty()
+        || p.name.to_lowercase().contains(query)
+        || p.pid.to_string().contains(query)
+        || p.exe_path.to_lowercase().contains(query)
+        || p.command_line
+            .as_ref()
+            .is_some_and(|c| c.to_lowercase().contains(query))
+        || p.user
+            .as_ref()
+            .is_some_and(|u| u.to_lowercase().contains(query))
+}
+
+/// Order two processes by the selected column
+pub fn compare_processes(
+    a: &ProcessInfo,
+    b: &ProcessInfo,
+    column: ProcessSortColumn,
+    order: SortOrder,
+) -> Ordering {
+    let cmp = match column {
+        ProcessSortColumn::Pid => a.pid.cmp(&b.pid),
+        ProcessSortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        ProcessSortColumn::Memory => a
+            .memory_mb
+            .partial_cmp(&b.memory_mb)
+            .unwrap_or(Ordering::Equal),
+        ProcessSortColumn::Threads => a.thread_count.cmp(&b.thread_count),
+        ProcessSortColumn::Cpu => a
+            .cpu_usage
+            .partial_cmp(&b.cpu_usage)
+            .unwrap_or(Ordering::Equal),
+        ProcessSortColumn::Stat(stat) => stat.value(&a.stats).cmp(&stat.value(&b.stats)),
+    };
+    match order {
+        SortOrder::Ascending => cmp,
+        SortOrder::Descending => cmp.reverse(),
+    }
+}
+
+/// Flatten a process tree into table rows, sorting siblings and skipping the
+/// children of collapsed nodes. With a search query, only matching processes
+/// and their ancestors are kept and every node is shown expanded.
+pub fn flatten_tree(
+    nodes: &[ProcessTreeNode],
+    depth: usize,
+    collapsed: &HashSet<u32>,
+    query: &str,
+    column: ProcessSortColumn,
+    order: SortOrder,
+    rows: &mut Vec<(ProcessInfo, TreeRowState)>,
+) {
+    let mut nodes: Vec<&ProcessTreeNode> = nodes.iter().collect();
+    nodes.sort_by(|a, b| compare_processes(&a.process, &b.process, column, order));
+
+    for node in nodes {
+        if !query.is_empty() && !subtree_matches(node, query) {
+            continue;
+        }
+        let expanded = !query.is_empty() || !collapsed.contains(&node.process.pid);
+        rows.push((
+            node.process.clone(),
+            TreeRowState {
+                depth,
+                has_children: !node.children.is_empty(),
+                expanded,
+                orphan: node.orphan,
+                hidden_descendants: if expanded { 0 } else { node.descendant_count() },
+            },
+        ));
+        if expanded {
+            flatten_tree(
+                &node.children,
+                depth + 1,
+                collapsed,
+                query,
+                column,
+                order,
+                rows,
+            );
+        }
+    }
+}
+
+/// Drop every node whose subtree has no match and sort siblings, for output
+/// that keeps the tree nested
+pub fn prune_tree(
+    nodes: Vec<ProcessTreeNode>,
+    query: &str,
+    column: ProcessSortColumn,
+    order: SortOrder,
+) -> Vec<ProcessTreeNode> {
+    let mut kept: Vec<ProcessTreeNode> = nodes
+        .into_iter()
+        .filter_map(|mut node| {
+            node.children = prune_tree(node.children, query, column, order);
+            (matches_process(&node.process, query) || !node.children.is_empty()).then_some(node)
+        })
+        .collect();
+    kept.sort_by(|a, b| compare_processes(&a.process, &b.process, column, order));
+    kept
+}
+
+fn subtree_matches(node: &ProcessTreeNode, query: &str) -> bool {
+    matches_process(&node.process, query)
+        || node
+            .children
+            .iter()
+            .any(|child| subtree_matches(child, query))
+}
+
+/// PIDs of every node with children, for "Collapse All"
+pub fn collect_parent_pids(node: &ProcessTreeNode) -> Vec<u32> {
+    let mut pids = Vec::new();
+    if !node.children.is_empty() {
+        pids.push(node.process.pid);
+        pids.extend(node.children.iter().flat_map(collect_parent_pids));
+    }
+    pids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{build_process_tree, MockBackend, ProcessBackend};
+
+    fn fixture() -> Vec<ProcessInfo> {
+        MockBackend::from_json(include_str!("../fixtures/processes.json"))
+            .expect("fixture parses")
+            .processes()
+    }
+
+    fn flatten(collapsed: &HashSet<u32>, query: &str) -> Vec<(ProcessInfo, TreeRowState)> {
+        let tree = build_process_tree(fixture());
+        let mut rows = Vec::new();
+        flatten_tree(
+            &tree,
+            0,
+            collapsed,
+            query,
+            ProcessSortColumn::Pid,
+            SortOrder::Ascending,
+            &mut rows,
+        );
+        rows
+    }
+
+    fn pids<T>(rows: &[(ProcessInfo, T)]) -> Vec<u32> {
+        rows.iter().map(|(p, _)| p.pid).collect()
+    }
+
+    #[test]
+    fn query_matches_name_pid_path_command_line_and_user() {
+        let processes = fixture();
+        let matching = |query: &str| -> Vec<u32> {
+            processes
+                .iter()
+                .filter(|p| matches_process(p, query))
+                .map(|p| p.pid)
+                .collect()
+        };
+
+        assert_eq!(matching("").len(), processes.len());
+        assert_eq!(matching("notepad"), vec![4120]);
+        assert_eq!(matching("5300"), vec![5300]);
+        assert_eq!(matching("todo.txt"), vec![4120]);
+        assert_eq!(matching("--type=renderer"), vec![5300]);
+        assert_eq!(matching("alice"), vec![3760, 4120, 5220, 5300]);
+        assert_eq!(matching("google\\chrome"), vec![5220, 5300]);
+        assert!(matching("no-such-process").is_empty());
+    }
+
+    #[test]
+    fn sorts_by_each_column() {
+        let mut processes = fixture();
+        let order_by = |processes: &mut Vec<ProcessInfo>, column, order| {
+            processes.sort_by(|a, b| compare_processes(a, b, column, order));
+            processes.iter().map(|p| p.pid).take(3).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            order_by(
+                &mut processes,
+                ProcessSortColumn::Memory,
+                SortOrder::Descending
+            ),
+            vec![5220, 3760, 5300]
+        );
+        assert_eq!(
+            order_by(
+                &mut processes,
+                ProcessSortColumn::Cpu,
+                SortOrder::Descending
+            ),
+            vec![5220, 5300, 3760]
+        );
+        assert_eq!(
+            order_by(
+                &mut processes,
+                ProcessSortColumn::Threads,
+                SortOrder::Ascending
+            ),
+            vec![560, 120, 4120]
+        );
+        assert_eq!(
+            order_by(
+                &mut processes,
+                ProcessSortColumn::Pid,
+                SortOrder::Descending
+            ),
+            vec![5300, 5220, 4120]
+        );
+        // Names compare case-insensitively; equal names keep snapshot order
+        assert_eq!(
+            order_by(
+                &mut processes,
+                ProcessSortColumn::Name,
+                SortOrder::Ascending
+            ),
+            vec![5300, 5220, 700]
+        );
+        assert_eq!(
+            order_by(
+                &mut processes,
+                ProcessSortColumn::Stat(StatColumn::Handles),
+                SortOrder::Descending
+            ),
+            vec![3760, 5220, 4120]
+        );
+    }
+
+    #[test]
+    fn flattens_tree_depth_first() {
+        let rows = flatten(&HashSet::new(), "");
+        assert_eq!(
+            pids(&rows),
+            vec![0, 4, 120, 560, 700, 3760, 4120, 5220, 5300]
+        );
+
+        let depths: Vec<usize> = rows.iter().map(|(_, tree)| tree.depth).collect();
+        assert_eq!(depths, vec![0, 0, 1, 1, 2, 0, 1, 1, 2]);
+
+        let (_, explorer) = &rows[5];
+        assert!(explorer.orphan && explorer.has_children && explorer.expanded);
+        assert_eq!(explorer.hidden_descendants, 0);
+    }
+
+    #[test]
+    fn collapsed_nodes_hide_their_subtree() {
+        let rows = flatten(&HashSet::from([3760, 560]), "");
+        assert_eq!(pids(&rows), vec![0, 4, 120, 560, 3760]);
+
+        let (_, smss) = &rows[3];
+        assert!(!smss.expanded);
+        assert_eq!(smss.hidden_descendants, 1);
+        let (_, explorer) = &rows[4];
+        assert_eq!(explorer.hidden_descendants, 3);
+    }
+
+    #[test]
+    fn search_keeps_ancestors_and_expands_them() {
+        let rows = flatten(&HashSet::from([3760, 5220]), "renderer");
+        assert_eq!(pids(&rows), vec![3760, 5220, 5300]);
+        assert!(rows.iter().all(|(_, tree)| tree.expanded));
+    }
+
+    #[test]
+    fn pruning_keeps_matches_and_their_ancestors_nested() {
+        let tree = prune_tree(
+            build_process_tree(fixture()),
+            "renderer",
+            ProcessSortColumn::Pid,
+            SortOrder::Ascending,
+        );
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].process.pid, 3760);
+        let chrome = &tree[0].children;
+        assert_eq!(chrome.len(), 1);
+        assert_eq!(chrome[0].process.pid, 5220);
+        assert_eq!(chrome[0].children[0].process.pid, 5300);
+
+        let all = prune_tree(
+            build_process_tree(fixture()),
+            "",
+            ProcessSortColumn::Pid,
+            SortOrder::Descending,
+        );
+        let roots: Vec<u32> = all.iter().map(|node| node.process.pid).collect();
+        assert_eq!(roots, vec![3760, 4, 0]);
+    }
+
+    #[test]
+    fn collapse_all_collects_every_parent() {
+        let tree = build_process_tree(fixture());
+        let mut parents: Vec<u32> = tree.iter().flat_map(collect_parent_pids).collect();
+        parents.sort_unstable();
+        assert_eq!(parents, vec![4, 560, 3760, 5220]);
+    }
+}
@@ -0,0 +1,293 @@
+//! Background sampler keeping the last hour of resource usage of every process
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, Once};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{backend, logical_cpu_count, ProcessCounters};
+
+/// Time between two samples of every process
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long samples are kept, for running and exited processes alike
+pub const HISTORY_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+static HISTORY: Mutex<Option<ProcessHistory>> = Mutex::new(None);
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Resource usage of one process at one point in time
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ProcessSample {
+    /// Milliseconds since the Unix epoch
+    pub time: u64,
+    /// Share of the whole machine's CPU time since the previous sample, in
+    /// percent, as in [`ProcessInfo::cpu_usage`](crate::ProcessInfo::cpu_usage)
+    pub cpu_usage: f32,
+    pub working_set_bytes: u64,
+    pub private_bytes: u64,
+    pub handle_count: u32,
+    pub thread_count: u32,
+    /// Bytes read, written and transferred otherwise per second since the
+    /// previous sample
+    pub io_bytes_per_sec: u64,
+}
+
+/// A series of [`ProcessSample`]s that can be graphed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProcessMetric {
+    Cpu,
+    WorkingSet,
+    PrivateBytes,
+    Handles,
+    Threads,
+    IoBytes,
+}
+
+impl ProcessMetric {
+    pub const ALL: [ProcessMetric; 6] = [
+        ProcessMetric::Cpu,
+        ProcessMetric::WorkingSet,
+        ProcessMetric::PrivateBytes,
+        ProcessMetric::Handles,
+        ProcessMetric::Threads,
+        ProcessMetric::IoBytes,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ProcessMetric::Cpu => "CPU Usage",
+            ProcessMetric::WorkingSet => "Working Set",
+            ProcessMetric::PrivateBytes => "Private Bytes",
+            ProcessMetric::Handles => "Handles",
+            ProcessMetric::Threads => "Threads",
+            ProcessMetric::IoBytes => "I/O Bytes",
+        }
+    }
+
+    pub fn value(self, sample: &ProcessSample) -> f64 {
+        match self {
+            ProcessMetric::Cpu => sample.cpu_usage as f64,
+            ProcessMetric::WorkingSet => sample.working_set_bytes as f64,
+            ProcessMetric::PrivateBytes => sample.private_bytes as f64,
+            ProcessMetric::Handles => sample.handle_count as f64,
+            ProcessMetric::Threads => sample.thread_count as f64,
+            ProcessMetric::IoBytes => sample.io_bytes_per_sec as f64,
+        }
+    }
+}
+
+/// Samples of one process, oldest first
+struct History {
+    start_time: Option<u64>,
+    /// Counters behind the newest sample, for the CPU and I/O rates
+    last: ProcessCounters,
+    samples: VecDeque<ProcessSample>,
+}
+
+/// Per-PID ring buffers of [`ProcessSample`]s covering a fixed retention.
+///
+/// Each [`record`](Self::record) turns one reading of every process's
+/// counters into a sample per process. A PID seen with a different start time
+/// starts over, and an exited process's history is dropped once its newest
+/// sample is older than the retention.
+pub struct ProcessHistory {
+    retention_ms: u64,
+    cpu_count: u32,
+    processes: HashMap<u32, History>,
+}
+
+impl ProcessHistory {
+    /// `cpu_count` logical processors share 100% CPU between them
+    pub fn new(retention: Duration, cpu_count: u32) -> Self {
+        Self {
+            retention_ms: retention.as_millis() as u64,
+            cpu_count: cpu_count.max(1),
+            processes: HashMap::new(),
+        }
+    }
+
+    /// Add the samples for counters read at `time` (milliseconds since the
+    /// Unix epoch) and forget those older than the retention
+    pub fn record(&mut self, counters: Vec<ProcessCounters>, time: u64) {
+        for current in counters {
+            let history = self
+                .processes
+                .entry(current.pid)
+                .or_insert_with(|| History {
+                    start_time: current.start_time,
+                    last: ProcessCounters::default(),
+                    samples: VecDeque::new(),
+                });
+            if history.start_time != current.start_time {
+                history.start_time = current.start_time;
+                history.samples.clear();
+            }
+
+            // Rates need a previous sample; the first one reads as idle
+            let (cpu_usage, io_bytes_per_sec) = match history.samples.back() {
+                Some(previous) if time > previous.time => {
+                    let elapsed_ms = time - previous.time;
+                    let cpu_ms = current.cpu_time_ms.saturating_sub(history.last.cpu_time_ms);
+                    let io_bytes = current
+                        .stats
+                        .io_bytes()
+                        .saturating_sub(history.last.stats.io_bytes());
+                    let capacity_ms = elapsed_ms as f64 * self.cpu_count as f64;
+                    (
+                        (cpu_ms as f64 / capacity_ms * 100.0).min(100.0) as f32,
+                        io_bytes * 1000 / elapsed_ms,
+                    )
+                }
+                _ => (0.0, 0),
+            };
+
+            history.samples.push_back(ProcessSample {
+                time,
+                cpu_usage,
+                working_set_bytes: current.working_set_bytes,
+                private_bytes: current.stats.private_bytes,
+                handle_count: current.stats.handle_count,
+                thread_count: current.thread_count,
+                io_bytes_per_sec,
+            });
+            history.last = current;
+        }
+
+        let cutoff = time.saturating_sub(self.retention_ms);
+        self.processes.retain(|_, history| {
+            while history
+                .samples
+                .front()
+                .is_some_and(|sample| sample.time < cutoff)
+            {
+                history.samples.pop_front();
+            }
+            !history.samples.is_empty()
+        });
+    }
+
+    /// Samples of `pid` taken at or after `since`, oldest first
+    pub fn samples(&self, pid: u32, since: u64) -> Vec<ProcessSample> {
+        self.processes
+            .get(&pid)
+            .map(|history| {
+                history
+                    .samples
+                    .iter()
+                    .filter(|sample| sample.time >= since)
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+/// Sample every process every [`SAMPLE_INTERVAL`] on a background thread,
+/// keeping [`HISTORY_RETENTION`] of history. Only the first call starts it.
+pub fn start_history_sampler() {
+    static STARTED: Once = Once::new();
+    STARTED.call_once(|| {
+        let cpu_count = logical_cpu_count();
+        let _ = std::thread::Builder::new()
+            .name("process-history".into())
+            .spawn(move || loop {
+                if !PAUSED.load(Ordering::Relaxed) {
+                    let counters = backend().counters();
+                    HISTORY
+                        .lock()
+                        .unwrap()
+                        .get_or_insert_with(|| ProcessHistory::new(HISTORY_RETENTION, cpu_count))
+                        .record(counters, now_ms());
+                }
+                std::thread::sleep(SAMPLE_INTERVAL);
+            });
+    });
+}
+
+/// Stop polling the live system (e.g. while a saved snapshot is being
+/// viewed) or start again. History recorded so far is kept.
+pub fn pause_history_sampler(paused: bool) {
+    PAUSED.store(paused, Ordering::Relaxed);
+}
+
+/// Samples recorded for `pid` over the last `range`, oldest first; empty
+/// unless [`start_history_sampler`] is running
+pub fn get_process_history(pid: u32, range: Duration) -> Vec<ProcessSample> {
+    let since = now_ms().saturating_sub(range.as_millis() as u64);
+    HISTORY
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|history| history.samples(pid, since))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProcessStats;
+
+    fn counters(pid: u32, start_time: u64, cpu_time_ms: u64, io_bytes: u64) -> ProcessCounters {
+        ProcessCounters {
+            pid,
+            start_time: Some(start_time),
+            cpu_time_ms,
+            working_set_bytes: 64 << 20,
+            thread_count: 8,
+            stats: ProcessStats {
+                private_bytes: 32 << 20,
+                handle_count: 120,
+                io_read_bytes: io_bytes / 2,
+                io_write_bytes: io_bytes - io_bytes / 2,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn rates_come_from_the_previous_sample() {
+        let mut history = ProcessHistory::new(HISTORY_RETENTION, 4);
+        history.record(vec![counters(42, 1, 5_000, 10_000)], 1_000);
+        // 2s on 4 CPUs is 8000 ms of capacity; 2000 ms used is 25%
+        history.record(vec![counters(42, 1, 7_000, 30_000)], 3_000);
+
+        let samples = history.samples(42, 0);
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].cpu_usage, 0.0);
+        assert_eq!(samples[1].cpu_usage, 25.0);
+        assert_eq!(samples[1].io_bytes_per_sec, 10_000);
+        assert_eq!(ProcessMetric::Handles.value(&samples[1]), 120.0);
+        assert_eq!(history.samples(42, 2_000), samples[1..]);
+    }
+
+    #[test]
+    fn reused_pid_starts_a_new_history() {
+        let mut history = ProcessHistory::new(HISTORY_RETENTION, 1);
+        history.record(vec![counters(42, 1, 5_000, 0)], 1_000);
+        history.record(vec![counters(42, 2, 100, 0)], 3_000);
+
+        let samples = history.samples(42, 0);
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].cpu_usage, 0.0);
+    }
+
+    #[test]
+    fn samples_older_than_the_retention_are_dropped() {
+        let mut history = ProcessHistory::new(Duration::from_secs(10), 1);
+        history.record(vec![counters(1, 1, 0, 0), counters(2, 1, 0, 0)], 0);
+        history.record(vec![counters(1, 1, 0, 0)], 6_000);
+        history.record(vec![counters(1, 1, 0, 0)], 12_000);
+
+        assert_eq!(history.samples(1, 0).len(), 2);
+        // PID 2 exited and its only sample has expired
+        assert!(history.samples(2, 0).is_empty());
+        assert!(!history.processes.contains_key(&2));
+    }
+}
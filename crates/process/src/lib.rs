@@ -1,49 +1,293 @@
-//! Windows process management module
-//! Contains Windows API calls for process enumeration and management
+//! Process management module
+//! Process, thread, module and memory enumeration behind a platform backend,
+//! plus PE file inspection helpers shared by every platform
+
+mod backend;
+mod error;
+mod filter;
+mod history;
+#[cfg(target_os = "linux")]
+mod linux;
+mod mock;
+mod recent;
+mod refresh;
+mod tree;
+#[cfg(windows)]
+mod win32;
+
+pub use backend::{backend, set_backend, ProcessBackend};
+pub use error::ProcessError;
+pub use filter::{
+    collect_parent_pids, compare_processes, flatten_tree, matches_process, prune_tree,
+    ProcessSortColumn, SortOrder, StatColumn, TreeRowState,
+};
+pub use history::{
+    get_process_history, pause_history_sampler, start_history_sampler, ProcessHistory,
+    ProcessMetric, ProcessSample, HISTORY_RETENTION, SAMPLE_INTERVAL,
+};
+#[cfg(target_os = "linux")]
+pub use linux::{
+    close_process_handle, get_all_process_handles, get_process_handles, kill_process, kill_thread,
+    resume_process, resume_thread, suspend_process, suspend_thread, LinuxBackend,
+};
+pub use mock::{MemoryBlock, MockBackend, ProcessSnapshot};
+pub use recent::{RecentChanges, RowHighlight};
+pub use refresh::{RefreshEngine, RefreshEvent};
+pub use tree::{build_process_tree, ProcessTreeNode};
+#[cfg(windows)]
+pub use win32::{
+    close_process_handle, get_all_process_handles, get_process_handles, kill_process, kill_thread,
+    resume_process, resume_thread, suspend_process, suspend_thread, Win32Backend,
+};
 
-use ntapi::ntexapi::{NtQuerySystemInformation, SystemHandleInformation};
-use ntapi::ntpsapi::{NtResumeProcess, NtSuspendProcess};
+use pe::PeImage;
+pub use pe::{
+    ApiSetSchema, AuthenticodeSignature, CertificateInfo, DataDirectory, DigestAlgorithm,
+    DosHeader, ExportEntry, FileHashes, FileHeader, ImportEntry, ImportKind, LoadConfig,
+    ModuleIntegrity, OptionalHeader, PackerAssessment, PackerFinding, PatchedRange, PeError,
+    ResourceEntry, ResourceId, RichEntry, RichHeader, RuntimeFunction, SectionHeader, SectionInfo,
+    SectionIntegrity, TlsDirectory, UnwindInfo, VersionInfo,
+};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::mem::zeroed;
 use std::process::Command;
-use std::sync::Mutex;
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
 use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, RefreshKind, System};
-use windows::core::PWSTR;
-use windows::Win32::Foundation::DuplicateHandle;
-use windows::Win32::Foundation::{CloseHandle, BOOL, HANDLE, MAX_PATH};
-use windows::Win32::System::Diagnostics::ToolHelp::{
-    CreateToolhelp32Snapshot, Module32FirstW, Module32NextW, Process32FirstW, Process32NextW,
-    Thread32First, Thread32Next, MODULEENTRY32W, PROCESSENTRY32W, TH32CS_SNAPMODULE,
-    TH32CS_SNAPMODULE32, TH32CS_SNAPPROCESS, TH32CS_SNAPTHREAD, THREADENTRY32,
-};
-use windows::Win32::System::Diagnostics::Debug::ReadProcessMemory;
-use windows::Win32::System::Memory::{
-    VirtualQueryEx, MEMORY_BASIC_INFORMATION, MEM_COMMIT, MEM_FREE, MEM_IMAGE, MEM_MAPPED,
-    MEM_PRIVATE, MEM_RESERVE, PAGE_EXECUTE, PAGE_EXECUTE_READ, PAGE_EXECUTE_READWRITE,
-    PAGE_EXECUTE_WRITECOPY, PAGE_GUARD, PAGE_NOACCESS, PAGE_NOCACHE, PAGE_READONLY,
-    PAGE_READWRITE, PAGE_WRITECOMBINE, PAGE_WRITECOPY,
-};
-use windows::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
-use windows::Win32::System::Threading::GetCurrentProcess;
-use windows::Win32::System::Threading::{
-    GetThreadPriority, OpenProcess, OpenThread, QueryFullProcessImageNameW, ResumeThread,
-    SuspendThread, TerminateProcess, TerminateThread, PROCESS_DUP_HANDLE, PROCESS_NAME_WIN32,
-    PROCESS_QUERY_INFORMATION, PROCESS_SUSPEND_RESUME, PROCESS_TERMINATE, PROCESS_VM_READ,
-    THREAD_QUERY_INFORMATION, THREAD_SUSPEND_RESUME, THREAD_TERMINATE,
-};
+
+// `MEMORY_BASIC_INFORMATION` values; every backend reports regions in these terms
+pub const MEM_COMMIT: u32 = 0x1000;
+pub const MEM_RESERVE: u32 = 0x2000;
+pub const MEM_FREE: u32 = 0x10000;
+pub const MEM_PRIVATE: u32 = 0x20000;
+pub const MEM_MAPPED: u32 = 0x40000;
+pub const MEM_IMAGE: u32 = 0x1000000;
+pub const PAGE_NOACCESS: u32 = 0x01;
+pub const PAGE_READONLY: u32 = 0x02;
+pub const PAGE_READWRITE: u32 = 0x04;
+pub const PAGE_WRITECOPY: u32 = 0x08;
+pub const PAGE_EXECUTE: u32 = 0x10;
+pub const PAGE_EXECUTE_READ: u32 = 0x20;
+pub const PAGE_EXECUTE_READWRITE: u32 = 0x40;
+pub const PAGE_EXECUTE_WRITECOPY: u32 = 0x80;
+pub const PAGE_GUARD: u32 = 0x100;
+pub const PAGE_NOCACHE: u32 = 0x200;
+pub const PAGE_WRITECOMBINE: u32 = 0x400;
 
 /// Global system info for CPU tracking (needs to persist between calls)
 static SYSTEM_INFO: Mutex<Option<System>> = Mutex::new(None);
 
+/// Authenticode results per file (hashing large images is expensive)
+static SIGNATURE_CACHE: FileCache<Option<SignatureStatus>> = FileCache::new();
+
+/// Version resources per file, so listings don't re-read every image on refresh
+static VERSION_CACHE: FileCache<Option<VersionInfo>> = FileCache::new();
+
+/// MD5 / SHA-1 / SHA-256 / imphash per file
+static HASH_CACHE: FileCache<Option<FileHashes>> = FileCache::new();
+
+/// Packer heuristics per file (entropy over every section is not free)
+static PACKER_CACHE: FileCache<Option<PackerAssessment>> = FileCache::new();
+
+/// Cache for per-file results keyed by path, invalidated when the file's
+/// size or modification time changes
+struct FileCache<T> {
+    entries: Mutex<Option<HashMap<String, FileCacheEntry<T>>>>,
+}
+
+/// File size, modification time and the cached value
+type FileCacheEntry<T> = (u64, Option<SystemTime>, T);
+
+impl<T: Clone> FileCache<T> {
+    const fn new() -> Self {
+        FileCache {
+            entries: Mutex::new(None),
+        }
+    }
+
+    /// Return the cached value for `path`, computing it if the file changed.
+    /// Returns `None` if the file does not exist.
+    fn get_or_insert_with(&self, path: &str, compute: impl FnOnce() -> T) -> Option<T> {
+        let metadata = std::fs::metadata(path).ok()?;
+        let (len, modified) = (metadata.len(), metadata.modified().ok());
+
+        if let Some((cached_len, cached_modified, value)) = self
+            .entries
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashMap::new)
+            .get(path)
+        {
+            if *cached_len == len && *cached_modified == modified {
+                return Some(value.clone());
+            }
+        }
+
+        // Compute without holding the lock
+        let value = compute();
+        self.entries
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashMap::new)
+            .insert(path.to_string(), (len, modified, value.clone()));
+        Some(value)
+    }
+}
+
 /// Process information structure
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ProcessInfo {
     pub pid: u32,
     pub name: String,
     pub memory_mb: f64,
     pub thread_count: u32,
     pub exe_path: String,
+    /// Share of the whole machine's CPU time since the previous refresh, in percent
     pub cpu_usage: f32,
+    /// `CompanyName` from the executable's version resource
+    pub company: Option<String>,
+    /// `FileDescription` from the executable's version resource
+    pub description: Option<String>,
+    /// `ProductVersion` from the executable's version resource
+    pub version: Option<String>,
+    /// PID of the creating process (may since have exited or been reused)
+    pub parent_pid: u32,
+    /// Terminal Services session the process runs in
+    pub session_id: Option<u32>,
+    /// Owning account as `DOMAIN\user`
+    pub user: Option<String>,
+    /// Owning account SID in `S-1-5-...` form
+    pub user_sid: Option<String>,
+    /// Mandatory integrity level of the primary token
+    pub integrity_level: Option<IntegrityLevel>,
+    /// Command line from the PEB's process parameters
+    pub command_line: Option<String>,
+    /// Creation time in milliseconds since the Unix epoch
+    pub start_time: Option<u64>,
+    /// Instruction set the process runs as
+    pub arch: Option<ProcessArch>,
+    /// Whether the process is a 32-bit process running under WoW64
+    pub is_wow64: bool,
+    /// Memory, I/O and object counters
+    #[serde(flatten)]
+    pub stats: ProcessStats,
+}
+
+/// Memory, I/O and object counters of a process, read on every refresh.
+///
+/// Counters the platform does not have, or that cannot be read for a
+/// process, are zero.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcessStats {
+    /// Committed memory only this process can use
+    pub private_bytes: u64,
+    pub peak_working_set_bytes: u64,
+    /// Commit charge against the page file
+    pub pagefile_bytes: u64,
+    pub page_fault_count: u32,
+    pub io_read_bytes: u64,
+    pub io_write_bytes: u64,
+    /// Bytes of I/O that is neither a read nor a write, e.g. device control
+    pub io_other_bytes: u64,
+    pub io_read_ops: u64,
+    pub io_write_ops: u64,
+    pub io_other_ops: u64,
+    pub handle_count: u32,
+    pub gdi_objects: u32,
+    pub user_objects: u32,
+}
+
+impl ProcessStats {
+    /// Bytes read, written and transferred otherwise since the process started
+    pub fn io_bytes(&self) -> u64 {
+        self.io_read_bytes + self.io_write_bytes + self.io_other_bytes
+    }
+}
+
+impl ProcessInfo {
+    /// Identity across refreshes: PID plus start time, so a reused PID is a
+    /// different process
+    pub fn key(&self) -> (u32, Option<u64>) {
+        (self.pid, self.start_time)
+    }
+}
+
+/// Mandatory integrity level, bucketed from the label SID's RID
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum IntegrityLevel {
+    Untrusted,
+    Low,
+    Medium,
+    MediumPlus,
+    High,
+    System,
+    Protected,
+}
+
+impl IntegrityLevel {
+    /// Map a `SECURITY_MANDATORY_*_RID` value to its level
+    pub fn from_rid(rid: u32) -> Self {
+        match rid {
+            0..=0x0FFF => IntegrityLevel::Untrusted,
+            0x1000..=0x1FFF => IntegrityLevel::Low,
+            0x2000..=0x20FF => IntegrityLevel::Medium,
+            0x2100..=0x2FFF => IntegrityLevel::MediumPlus,
+            0x3000..=0x3FFF => IntegrityLevel::High,
+            0x4000..=0x4FFF => IntegrityLevel::System,
+            _ => IntegrityLevel::Protected,
+        }
+    }
+}
+
+impl std::fmt::Display for IntegrityLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntegrityLevel::Untrusted => write!(f, "Untrusted"),
+            IntegrityLevel::Low => write!(f, "Low"),
+            IntegrityLevel::Medium => write!(f, "Medium"),
+            IntegrityLevel::MediumPlus => write!(f, "Medium+"),
+            IntegrityLevel::High => write!(f, "High"),
+            IntegrityLevel::System => write!(f, "System"),
+            IntegrityLevel::Protected => write!(f, "Protected"),
+        }
+    }
+}
+
+/// Processor architecture of a process
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProcessArch {
+    X86,
+    X64,
+    Arm,
+    Arm64,
+    /// Any other `IMAGE_FILE_MACHINE_*` value
+    Other(u16),
+}
+
+impl ProcessArch {
+    /// Map an `IMAGE_FILE_MACHINE_*` value to an architecture
+    pub fn from_machine(machine: u16) -> Self {
+        match machine {
+            0x014C => ProcessArch::X86,
+            0x8664 => ProcessArch::X64,
+            0x01C0 | 0x01C4 => ProcessArch::Arm,
+            0xAA64 => ProcessArch::Arm64,
+            other => ProcessArch::Other(other),
+        }
+    }
+}
+
+impl std::fmt::Display for ProcessArch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProcessArch::X86 => write!(f, "x86"),
+            ProcessArch::X64 => write!(f, "x64"),
+            ProcessArch::Arm => write!(f, "ARM"),
+            ProcessArch::Arm64 => write!(f, "ARM64"),
+            ProcessArch::Other(machine) => write!(f, "0x{:04X}", machine),
+        }
+    }
 }
 
 /// System statistics
@@ -57,61 +301,19 @@ pub struct SystemStats {
     pub uptime_seconds: u64,
 }
 
-/// Get list of running processes using Windows API with CPU usage from sysinfo
+/// Get list of running processes from the active backend
 pub fn get_processes() -> Vec<ProcessInfo> {
-    let mut processes = Vec::new();
-
-    // Get CPU usage from sysinfo
-    let cpu_map = get_cpu_usage_map();
-
-    unsafe {
-        // Create a snapshot of all processes
-        let snapshot = match CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) {
-            Ok(handle) => handle,
-            Err(_) => return processes,
-        };
-
-        let mut entry: PROCESSENTRY32W = zeroed();
-        entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
-
-        // Get the first process
-        if Process32FirstW(snapshot, &mut entry).is_ok() {
-            loop {
-                let name = String::from_utf16_lossy(
-                    &entry.szExeFile[..entry
-                        .szExeFile
-                        .iter()
-                        .position(|&c| c == 0)
-                        .unwrap_or(entry.szExeFile.len())],
-                );
-
-                let (memory_mb, exe_path) = get_process_details(entry.th32ProcessID);
-                let cpu_usage = cpu_map.get(&entry.th32ProcessID).copied().unwrap_or(0.0);
-
-                processes.push(ProcessInfo {
-                    pid: entry.th32ProcessID,
-                    name,
-                    memory_mb,
-                    thread_count: entry.cntThreads,
-                    exe_path,
-                    cpu_usage,
-                });
-
-                // Get the next process
-                if Process32NextW(snapshot, &mut entry).is_err() {
-                    break;
-                }
-            }
-        }
-
-        let _ = CloseHandle(snapshot);
-    }
+    backend().processes()
+}
 
-    processes
+/// Logical processors sharing the machine's CPU time
+pub(crate) fn logical_cpu_count() -> u32 {
+    std::thread::available_parallelism().map_or(1, |n| n.get() as u32)
 }
 
-/// Get CPU usage map using sysinfo
-fn get_cpu_usage_map() -> HashMap<u32, f32> {
+/// CPU usage of every process as a share of the whole machine. sysinfo
+/// reports a percent of one core, so it is divided by the processor count.
+pub(crate) fn get_cpu_usage_map() -> HashMap<u32, f32> {
     let mut map = HashMap::new();
 
     let mut sys_guard = SYSTEM_INFO.lock().unwrap();
@@ -124,43 +326,41 @@ fn get_cpu_usage_map() -> HashMap<u32, f32> {
     // Refresh processes to get CPU usage
     sys.refresh_processes_specifics(ProcessesToUpdate::All, ProcessRefreshKind::new().with_cpu());
 
+    let cpu_count = logical_cpu_count() as f32;
     for (pid, process) in sys.processes() {
-        map.insert(pid.as_u32(), process.cpu_usage());
+        map.insert(pid.as_u32(), process.cpu_usage() / cpu_count);
     }
 
     map
 }
 
-/// Process stats for graph display
-#[derive(Clone, Debug, Default)]
-pub struct ProcessStats {
-    pub cpu_usage: f32,
-    pub memory_mb: f64,
-}
-
-/// Get CPU and memory usage for a specific process
-pub fn get_process_stats(pid: u32) -> ProcessStats {
-    let mut sys_guard = SYSTEM_INFO.lock().unwrap();
-    let sys = sys_guard.get_or_insert_with(|| {
-        System::new_with_specifics(
-            RefreshKind::new()
-                .with_processes(ProcessRefreshKind::new().with_cpu().with_memory()),
-        )
-    });
-
-    // Refresh the specific process
-    sys.refresh_processes_specifics(
-        ProcessesToUpdate::Some(&[sysinfo::Pid::from_u32(pid)]),
-        ProcessRefreshKind::new().with_cpu().with_memory(),
-    );
-
-    if let Some(process) = sys.process(sysinfo::Pid::from_u32(pid)) {
-        ProcessStats {
-            cpu_usage: process.cpu_usage(),
-            memory_mb: process.memory() as f64 / (1024.0 * 1024.0),
+/// Cumulative resource counters of one process, read for every process in
+/// a single pass by the history sampler: the [`ProcessStats`] of a refresh
+/// plus what the sampler needs to turn them into rates
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProcessCounters {
+    pub pid: u32,
+    /// Milliseconds since the Unix epoch, to tell a reused PID apart
+    pub start_time: Option<u64>,
+    /// User plus kernel CPU time in milliseconds
+    pub cpu_time_ms: u64,
+    pub working_set_bytes: u64,
+    pub thread_count: u32,
+    pub stats: ProcessStats,
+}
+
+impl ProcessCounters {
+    /// Counters of a refreshed process; its CPU time is not part of
+    /// [`ProcessInfo`] and reads as zero
+    pub fn from_info(info: &ProcessInfo) -> Self {
+        ProcessCounters {
+            pid: info.pid,
+            start_time: info.start_time,
+            working_set_bytes: (info.memory_mb * 1024.0 * 1024.0) as u64,
+            thread_count: info.thread_count,
+            stats: info.stats.clone(),
+            ..Default::default()
         }
-    } else {
-        ProcessStats::default()
     }
 }
 
@@ -197,106 +397,19 @@ pub fn get_system_stats() -> SystemStats {
     }
 }
 
-/// Get memory usage and executable path for a specific process
-fn get_process_details(pid: u32) -> (f64, String) {
-    unsafe {
-        let handle: HANDLE =
-            match OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, false, pid) {
-                Ok(h) => h,
-                Err(_) => return (0.0, String::new()),
-            };
-
-        // Get memory info
-        let mut mem_counters: PROCESS_MEMORY_COUNTERS = zeroed();
-        mem_counters.cb = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
-
-        let memory = if GetProcessMemoryInfo(
-            handle,
-            &mut mem_counters,
-            std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
-        )
-        .is_ok()
-        {
-            mem_counters.WorkingSetSize as f64 / (1024.0 * 1024.0)
-        } else {
-            0.0
-        };
-
-        // Get executable path
-        let mut path_buf = [0u16; MAX_PATH as usize];
-        let mut size = path_buf.len() as u32;
-        let exe_path = if QueryFullProcessImageNameW(
-            handle,
-            PROCESS_NAME_WIN32,
-            PWSTR(path_buf.as_mut_ptr()),
-            &mut size,
-        )
-        .is_ok()
-        {
-            String::from_utf16_lossy(&path_buf[..size as usize])
-        } else {
-            String::new()
-        };
-
-        let _ = CloseHandle(handle);
-        (memory, exe_path)
-    }
-}
-
-/// Kill a process by PID
-/// Returns true if successful, false otherwise
-pub fn kill_process(pid: u32) -> bool {
-    unsafe {
-        let handle = match OpenProcess(PROCESS_TERMINATE, false, pid) {
-            Ok(h) => h,
-            Err(_) => return false,
-        };
-
-        let result = TerminateProcess(handle, 1).is_ok();
-        let _ = CloseHandle(handle);
-        result
-    }
-}
-
-/// Suspend a process by PID (pause all threads)
-/// Returns true if successful, false otherwise
-pub fn suspend_process(pid: u32) -> bool {
-    unsafe {
-        let handle = match OpenProcess(PROCESS_SUSPEND_RESUME, false, pid) {
-            Ok(h) => h,
-            Err(_) => return false,
-        };
-
-        let status = NtSuspendProcess(handle.0 as *mut _);
-        let _ = CloseHandle(handle);
-        status == 0 // NTSTATUS 0 = STATUS_SUCCESS
-    }
-}
-
-/// Resume a suspended process by PID
-/// Returns true if successful, false otherwise
-pub fn resume_process(pid: u32) -> bool {
-    unsafe {
-        let handle = match OpenProcess(PROCESS_SUSPEND_RESUME, false, pid) {
-            Ok(h) => h,
-            Err(_) => return false,
-        };
-
-        let status = NtResumeProcess(handle.0 as *mut _);
-        let _ = CloseHandle(handle);
-        status == 0 // NTSTATUS 0 = STATUS_SUCCESS
-    }
-}
-
-/// Open file location in Windows Explorer
+/// Open file location in Windows Explorer (the containing folder elsewhere)
 pub fn open_file_location(path: &str) {
     if path.is_empty() {
         return;
     }
-    // Use explorer.exe with /select to highlight the file
-    let _ = Command::new("explorer.exe")
-        .args(["/select,", path])
-        .spawn();
+    if cfg!(windows) {
+        // Use explorer.exe with /select to highlight the file
+        let _ = Command::new("explorer.exe")
+            .args(["/select,", path])
+            .spawn();
+    } else if let Some(folder) = std::path::Path::new(path).parent() {
+        let _ = Command::new("xdg-open").arg(folder).spawn();
+    }
 }
 
 /// Format uptime in human readable format
@@ -314,8 +427,25 @@ pub fn format_uptime(seconds: u64) -> String {
     }
 }
 
+/// Format a process start time (milliseconds since the Unix epoch) as UTC
+pub fn format_start_time(start_time: Option<u64>) -> String {
+    match start_time {
+        Some(ms) => pe::format_timestamp((ms / 1000) as u32),
+        None => String::new(),
+    }
+}
+
+/// Architecture label such as "x64" or "x86 (WoW64)"
+pub fn arch_label(process: &ProcessInfo) -> String {
+    match process.arch {
+        Some(arch) if process.is_wow64 => format!("{} (WoW64)", arch),
+        Some(arch) => arch.to_string(),
+        None => String::new(),
+    }
+}
+
 /// Thread information structure
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ThreadInfo {
     pub thread_id: u32,
     pub owner_pid: u32,
@@ -324,100 +454,8 @@ pub struct ThreadInfo {
 }
 
 /// Get list of threads for a specific process
-pub fn get_process_threads(pid: u32) -> Vec<ThreadInfo> {
-    let mut threads = Vec::new();
-
-    unsafe {
-        let snapshot = match CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0) {
-            Ok(handle) => handle,
-            Err(_) => return threads,
-        };
-
-        let mut entry: THREADENTRY32 = zeroed();
-        entry.dwSize = std::mem::size_of::<THREADENTRY32>() as u32;
-
-        if Thread32First(snapshot, &mut entry).is_ok() {
-            loop {
-                if entry.th32OwnerProcessID == pid {
-                    let priority = get_thread_priority(entry.th32ThreadID);
-
-                    threads.push(ThreadInfo {
-                        thread_id: entry.th32ThreadID,
-                        owner_pid: entry.th32OwnerProcessID,
-                        base_priority: entry.tpBasePri,
-                        priority,
-                    });
-                }
-
-                if Thread32Next(snapshot, &mut entry).is_err() {
-                    break;
-                }
-            }
-        }
-
-        let _ = CloseHandle(snapshot);
-    }
-
-    threads
-}
-
-/// Get thread priority
-fn get_thread_priority(thread_id: u32) -> i32 {
-    unsafe {
-        let handle = match OpenThread(THREAD_QUERY_INFORMATION, false, thread_id) {
-            Ok(h) => h,
-            Err(_) => return 0,
-        };
-
-        let priority = GetThreadPriority(handle);
-        let _ = CloseHandle(handle);
-        priority
-    }
-}
-
-/// Suspend a thread by ID
-/// Returns true if successful, false otherwise
-pub fn suspend_thread(thread_id: u32) -> bool {
-    unsafe {
-        let handle = match OpenThread(THREAD_SUSPEND_RESUME, false, thread_id) {
-            Ok(h) => h,
-            Err(_) => return false,
-        };
-
-        let result = SuspendThread(handle);
-        let _ = CloseHandle(handle);
-        result != u32::MAX // Returns previous suspend count, or -1 on error
-    }
-}
-
-/// Resume a suspended thread by ID
-/// Returns true if successful, false otherwise
-pub fn resume_thread(thread_id: u32) -> bool {
-    unsafe {
-        let handle = match OpenThread(THREAD_SUSPEND_RESUME, false, thread_id) {
-            Ok(h) => h,
-            Err(_) => return false,
-        };
-
-        let result = ResumeThread(handle);
-        let _ = CloseHandle(handle);
-        result != u32::MAX // Returns previous suspend count, or -1 on error
-    }
-}
-
-/// Terminate a thread by ID (DANGEROUS - may cause process instability)
-/// Returns true if successful, false otherwise
-pub fn kill_thread(thread_id: u32) -> bool {
-    unsafe {
-        let handle = match OpenThread(THREAD_TERMINATE, false, thread_id) {
-            Ok(h) => h,
-            Err(_) => return false,
-        };
-
-        let result = TerminateThread(handle, 1).is_ok();
-        let _ = CloseHandle(handle);
-        result
-    }
+pub fn get_process_threads(pid: u32) -> Result<Vec<ThreadInfo>, ProcessError> {
+    backend().threads(pid)
 }
 
 /// Get thread priority name
@@ -435,7 +473,7 @@ pub fn get_priority_name(priority: i32) -> &'static str {
 }
 
 /// Handle information structure
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct HandleInfo {
     pub handle_value: u16,
     pub object_type_index: u8,
@@ -443,227 +481,6 @@ pub struct HandleInfo {
     pub granted_access: u32,
 }
 
-/// Get list of handles for a specific process
-pub fn get_process_handles(pid: u32) -> Vec<HandleInfo> {
-    let mut handles = Vec::new();
-
-    unsafe {
-        // Start with a reasonable buffer size
-        let mut buffer_size: usize = 0x10000; // 64KB initial
-        let mut buffer: Vec<u8>;
-        let mut return_length: u32 = 0;
-
-        // Loop until we have enough buffer
-        loop {
-            buffer = vec![0u8; buffer_size];
-
-            let status = NtQuerySystemInformation(
-                SystemHandleInformation,
-                buffer.as_mut_ptr() as *mut _,
-                buffer_size as u32,
-                &mut return_length,
-            );
-
-            // STATUS_INFO_LENGTH_MISMATCH = 0xC0000004
-            if status == 0xC0000004u32 as i32 {
-                buffer_size *= 2;
-                if buffer_size > 0x4000000 {
-                    // 64MB max
-                    return handles;
-                }
-                continue;
-            }
-
-            if status != 0 {
-                return handles;
-            }
-
-            break;
-        }
-
-        // Parse the buffer manually
-        // SYSTEM_HANDLE_INFORMATION structure:
-        // ULONG NumberOfHandles
-        // SYSTEM_HANDLE_TABLE_ENTRY_INFO Handles[1]
-
-        if buffer.len() < 4 {
-            return handles;
-        }
-
-        let number_of_handles =
-            u32::from_ne_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as usize;
-
-        // Each SYSTEM_HANDLE_TABLE_ENTRY_INFO is 16 bytes on x86, 24 bytes on x64
-        #[cfg(target_pointer_width = "64")]
-        const ENTRY_SIZE: usize = 24;
-        #[cfg(target_pointer_width = "32")]
-        const ENTRY_SIZE: usize = 16;
-
-        let entries_start = if cfg!(target_pointer_width = "64") {
-            8
-        } else {
-            4
-        }; // alignment
-
-        for i in 0..number_of_handles {
-            let offset = entries_start + i * ENTRY_SIZE;
-            if offset + ENTRY_SIZE > buffer.len() {
-                break;
-            }
-
-            // Parse entry based on architecture
-            #[cfg(target_pointer_width = "64")]
-            let (entry_pid, handle_value, object_type, granted_access) = {
-                // x64: UniqueProcessId (USHORT at 0), reserved (USHORT at 2), ObjectTypeIndex (UCHAR at 4),
-                // HandleAttributes (UCHAR at 5), HandleValue (USHORT at 6), Object (PVOID at 8), GrantedAccess (ULONG at 16)
-                let unique_pid = u16::from_ne_bytes([buffer[offset], buffer[offset + 1]]) as u32;
-                let obj_type = buffer[offset + 4];
-                let handle_val = u16::from_ne_bytes([buffer[offset + 6], buffer[offset + 7]]);
-                let access = u32::from_ne_bytes([
-                    buffer[offset + 16],
-                    buffer[offset + 17],
-                    buffer[offset + 18],
-                    buffer[offset + 19],
-                ]);
-                (unique_pid, handle_val, obj_type, access)
-            };
-
-            #[cfg(target_pointer_width = "32")]
-            let (entry_pid, handle_value, object_type, granted_access) = {
-                let unique_pid = u16::from_ne_bytes([buffer[offset], buffer[offset + 1]]) as u32;
-                let obj_type = buffer[offset + 4];
-                let handle_val = u16::from_ne_bytes([buffer[offset + 6], buffer[offset + 7]]);
-                let access = u32::from_ne_bytes([
-                    buffer[offset + 12],
-                    buffer[offset + 13],
-                    buffer[offset + 14],
-                    buffer[offset + 15],
-                ]);
-                (unique_pid, handle_val, obj_type, access)
-            };
-
-            if entry_pid == pid {
-                let type_name = get_object_type_name(object_type);
-
-                handles.push(HandleInfo {
-                    handle_value,
-                    object_type_index: object_type,
-                    object_type_name: type_name,
-                    granted_access,
-                });
-            }
-        }
-    }
-
-    handles
-}
-
-/// Get object type name from type index (common Windows object types)
-fn get_object_type_name(type_index: u8) -> String {
-    // Common object type indices on Windows 10/11
-    // Note: These can vary by Windows version
-    match type_index {
-        0 => "Reserved",
-        1 => "Reserved",
-        2 => "Type",
-        3 => "Directory",
-        4 => "SymbolicLink",
-        5 => "Token",
-        6 => "Job",
-        7 => "Process",
-        8 => "Thread",
-        9 => "UserApcReserve",
-        10 => "IoCompletionReserve",
-        11 => "ActivityReference",
-        12 => "PsSiloContextPaged",
-        13 => "PsSiloContextNonPaged",
-        14 => "DebugObject",
-        15 => "Event",
-        16 => "Mutant",
-        17 => "Callback",
-        18 => "Semaphore",
-        19 => "Timer",
-        20 => "IRTimer",
-        21 => "Profile",
-        22 => "KeyedEvent",
-        23 => "WindowStation",
-        24 => "Desktop",
-        25 => "Composition",
-        26 => "RawInputManager",
-        27 => "CoreMessaging",
-        28 => "TpWorkerFactory",
-        29 => "Adapter",
-        30 => "Controller",
-        31 => "Device",
-        32 => "Driver",
-        33 => "IoCompletion",
-        34 => "WaitCompletionPacket",
-        35 => "File",
-        36 => "TmTm",
-        37 => "TmTx",
-        38 => "TmRm",
-        39 => "TmEn",
-        40 => "Section",
-        41 => "Session",
-        42 => "Partition",
-        43 => "Key",
-        44 => "RegistryTransaction",
-        45 => "ALPC Port",
-        46 => "EnergyTracker",
-        47 => "PowerRequest",
-        48 => "WmiGuid",
-        49 => "EtwRegistration",
-        50 => "EtwSessionDemuxEntry",
-        51 => "EtwConsumer",
-        52 => "DmaAdapter",
-        53 => "DmaDomain",
-        54 => "PcwObject",
-        55 => "FilterConnectionPort",
-        56 => "FilterCommunicationPort",
-        57 => "NdisCmState",
-        58 => "DxgkSharedResource",
-        59 => "DxgkSharedSyncObject",
-        60 => "DxgkSharedSwapChainObject",
-        _ => "Unknown",
-    }
-    .to_string()
-}
-
-/// Close a handle in another process
-/// Returns true if successful, false otherwise
-/// WARNING: Closing handles can cause process instability!
-pub fn close_process_handle(pid: u32, handle_value: u16) -> bool {
-    use windows::Win32::Foundation::DUPLICATE_CLOSE_SOURCE;
-
-    unsafe {
-        // Open the target process with DUP_HANDLE permission
-        let process_handle = match OpenProcess(PROCESS_DUP_HANDLE, false, pid) {
-            Ok(h) => h,
-            Err(_) => return false,
-        };
-
-        // Duplicate the handle with DUPLICATE_CLOSE_SOURCE to close it in the target process
-        let mut dup_handle: HANDLE = HANDLE::default();
-        let result = DuplicateHandle(
-            process_handle,
-            HANDLE(handle_value as isize as *mut _),
-            GetCurrentProcess(),
-            &mut dup_handle,
-            0,
-            BOOL(0),
-            DUPLICATE_CLOSE_SOURCE,
-        );
-
-        // Close our copy if we got one
-        if !dup_handle.is_invalid() {
-            let _ = CloseHandle(dup_handle);
-        }
-
-        let _ = CloseHandle(process_handle);
-        result.is_ok()
-    }
-}
-
 /// Get handle type category for display coloring
 pub fn get_handle_type_category(type_name: &str) -> &'static str {
     match type_name {
@@ -680,342 +497,262 @@ pub fn get_handle_type_category(type_name: &str) -> &'static str {
 }
 
 /// Module information structure
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ModuleInfo {
     pub name: String,
     pub base_address: usize,
     pub size: u32,
     pub path: String,
-}
-
-/// Import entry for a PE module
-#[derive(Clone, Debug, PartialEq)]
-pub struct ImportEntry {
-    pub dll_name: String,
-    pub functions: Vec<String>,
+    /// `CompanyName` from the module's version resource
+    pub company: Option<String>,
+    /// `FileDescription` from the module's version resource
+    pub description: Option<String>,
+    /// `ProductVersion` from the module's version resource
+    pub version: Option<String>,
 }
 
 /// Get list of loaded modules for a specific process
-pub fn get_process_modules(pid: u32) -> Vec<ModuleInfo> {
-    let mut modules = Vec::new();
-
-    unsafe {
-        let snapshot = match CreateToolhelp32Snapshot(TH32CS_SNAPMODULE | TH32CS_SNAPMODULE32, pid)
-        {
-            Ok(handle) => handle,
-            Err(_) => return modules,
-        };
+pub fn get_process_modules(pid: u32) -> Result<Vec<ModuleInfo>, ProcessError> {
+    backend().modules(pid)
+}
 
-        let mut entry: MODULEENTRY32W = zeroed();
-        entry.dwSize = std::mem::size_of::<MODULEENTRY32W>() as u32;
-
-        if Module32FirstW(snapshot, &mut entry).is_ok() {
-            loop {
-                let name = String::from_utf16_lossy(
-                    &entry.szModule[..entry
-                        .szModule
-                        .iter()
-                        .position(|&c| c == 0)
-                        .unwrap_or(entry.szModule.len())],
-                );
-                let path = String::from_utf16_lossy(
-                    &entry.szExePath[..entry
-                        .szExePath
-                        .iter()
-                        .position(|&c| c == 0)
-                        .unwrap_or(entry.szExePath.len())],
-                );
-
-                modules.push(ModuleInfo {
-                    name,
-                    base_address: entry.modBaseAddr as usize,
-                    size: entry.modBaseSize,
-                    path,
-                });
-
-                if Module32NextW(snapshot, &mut entry).is_err() {
-                    break;
-                }
-            }
-        }
+/// Get imported DLLs and functions (classic, delay-load and bound) from a PE file on disk
+pub fn get_module_imports(module_path: &str) -> Result<Vec<ImportEntry>, ProcessError> {
+    get_module_imports_with_api_sets(module_path, None)
+}
 
-        let _ = CloseHandle(snapshot);
+/// Get imports from a PE file on disk, resolving API set contracts
+/// (`api-ms-win-*` / `ext-ms-*`) to their host DLLs when a schema is given
+pub fn get_module_imports_with_api_sets(
+    module_path: &str,
+    schema: Option<&ApiSetSchema>,
+) -> Result<Vec<ImportEntry>, ProcessError> {
+    let data = read_module_file(module_path)?;
+    let mut imports = parse_module_file(module_path, &data)?.imports();
+    if let Some(schema) = schema {
+        schema.resolve_imports(&mut imports);
     }
-
-    modules
+    Ok(imports)
 }
 
-/// Get imported DLLs and functions from a PE file on disk
-pub fn get_module_imports(module_path: &str) -> Vec<ImportEntry> {
-    let mut imports = Vec::new();
-    let data = match std::fs::read(module_path) {
-        Ok(d) => d,
-        Err(_) => return imports,
-    };
-
-    // Parse DOS header
-    if data.len() < 64 {
-        return imports;
-    }
-    let dos_magic = u16::from_le_bytes([data[0], data[1]]);
-    if dos_magic != 0x5A4D {
-        // Not a valid PE ("MZ")
-        return imports;
-    }
-    let pe_offset = u32::from_le_bytes([data[60], data[61], data[62], data[63]]) as usize;
+fn read_module_file(module_path: &str) -> Result<Vec<u8>, ProcessError> {
+    std::fs::read(module_path).map_err(|error| ProcessError::Io {
+        path: module_path.to_string(),
+        error,
+    })
+}
 
-    // Parse PE signature
-    if data.len() < pe_offset + 4 {
-        return imports;
-    }
-    let pe_sig = u32::from_le_bytes([
-        data[pe_offset],
-        data[pe_offset + 1],
-        data[pe_offset + 2],
-        data[pe_offset + 3],
-    ]);
-    if pe_sig != 0x00004550 {
-        // Not "PE\0\0"
-        return imports;
-    }
+fn parse_module_file<'a>(module_path: &str, data: &'a [u8]) -> Result<PeImage<'a>, ProcessError> {
+    PeImage::parse(data).map_err(|error| ProcessError::UnreadableImage {
+        path: module_path.to_string(),
+        error,
+    })
+}
 
-    // COFF header starts at pe_offset + 4
-    let coff_offset = pe_offset + 4;
-    if data.len() < coff_offset + 20 {
-        return imports;
-    }
-    let optional_header_size =
-        u16::from_le_bytes([data[coff_offset + 16], data[coff_offset + 17]]) as usize;
+/// API set schema of the running system, loaded once from `apisetschema.dll`
+pub fn system_api_set_schema() -> Option<&'static ApiSetSchema> {
+    static SCHEMA: OnceLock<Option<ApiSetSchema>> = OnceLock::new();
+    SCHEMA
+        .get_or_init(|| {
+            let system_root =
+                std::env::var("SystemRoot").unwrap_or_else(|_| String::from("C:\\Windows"));
+            let path = format!("{}\\System32\\apisetschema.dll", system_root);
+            let data = std::fs::read(path).ok()?;
+            let image = PeImage::parse(&data).ok()?;
+            ApiSetSchema::from_image(&image).ok()
+        })
+        .as_ref()
+}
 
-    // Optional header starts after COFF header
-    let opt_offset = coff_offset + 20;
-    if data.len() < opt_offset + 2 {
-        return imports;
-    }
-    let opt_magic = u16::from_le_bytes([data[opt_offset], data[opt_offset + 1]]);
-
-    // Determine import directory RVA based on PE32 vs PE32+
-    let import_dir_rva;
-    let import_dir_size;
-    match opt_magic {
-        0x10b => {
-            // PE32
-            if data.len() < opt_offset + 104 + 8 {
-                return imports;
-            }
-            import_dir_rva = u32::from_le_bytes([
-                data[opt_offset + 104],
-                data[opt_offset + 105],
-                data[opt_offset + 106],
-                data[opt_offset + 107],
-            ]) as usize;
-            import_dir_size = u32::from_le_bytes([
-                data[opt_offset + 108],
-                data[opt_offset + 109],
-                data[opt_offset + 110],
-                data[opt_offset + 111],
-            ]) as usize;
-        }
-        0x20b => {
-            // PE32+ (64-bit)
-            if data.len() < opt_offset + 120 + 8 {
-                return imports;
+/// Authenticode status of a file on disk
+#[derive(Clone, Debug, PartialEq)]
+pub enum SignatureStatus {
+    /// No embedded signature (catalog signatures are not checked)
+    Unsigned,
+    /// Embedded signature; check `hash_matches()` before trusting it
+    Signed(AuthenticodeSignature),
+    /// Security directory present but not a parsable PKCS#7 signature
+    Malformed,
+}
+
+impl SignatureStatus {
+    /// Whether the file carries a signature whose digest matches the image
+    pub fn is_valid(&self) -> bool {
+        matches!(self, SignatureStatus::Signed(sig) if sig.hash_matches())
+    }
+
+    /// Short text for the "Signer" column. The signer is named only when the
+    /// digest matches, and marked unverified since the signature itself is not
+    /// checked.
+    pub fn signer_label(&self) -> String {
+        match self {
+            SignatureStatus::Signed(sig) if sig.hash_matches() => {
+                format!("{} (unverified)", sig.signer_name())
             }
-            import_dir_rva = u32::from_le_bytes([
-                data[opt_offset + 120],
-                data[opt_offset + 121],
-                data[opt_offset + 122],
-                data[opt_offset + 123],
-            ]) as usize;
-            import_dir_size = u32::from_le_bytes([
-                data[opt_offset + 124],
-                data[opt_offset + 125],
-                data[opt_offset + 126],
-                data[opt_offset + 127],
-            ]) as usize;
+            SignatureStatus::Signed(_) => "Hash mismatch".to_string(),
+            SignatureStatus::Unsigned => "Unsigned".to_string(),
+            SignatureStatus::Malformed => "Invalid signature".to_string(),
         }
-        _ => return imports,
-    }
-
-    if import_dir_rva == 0 || import_dir_size == 0 {
-        return imports;
-    }
-
-    // Parse section headers to build RVA-to-file-offset mapping
-    let num_sections = u16::from_le_bytes([data[coff_offset + 2], data[coff_offset + 3]]) as usize;
-    let sections_offset = opt_offset + optional_header_size;
-
-    struct SectionInfo {
-        virtual_address: usize,
-        virtual_size: usize,
-        raw_data_offset: usize,
     }
+}
 
-    let mut sections = Vec::new();
-    for i in 0..num_sections {
-        let s_off = sections_offset + i * 40;
-        if data.len() < s_off + 40 {
-            break;
-        }
-        let virtual_size = u32::from_le_bytes([
-            data[s_off + 8],
-            data[s_off + 9],
-            data[s_off + 10],
-            data[s_off + 11],
-        ]) as usize;
-        let virtual_address = u32::from_le_bytes([
-            data[s_off + 12],
-            data[s_off + 13],
-            data[s_off + 14],
-            data[s_off + 15],
-        ]) as usize;
-        let raw_data_offset = u32::from_le_bytes([
-            data[s_off + 20],
-            data[s_off + 21],
-            data[s_off + 22],
-            data[s_off + 23],
-        ]) as usize;
-        sections.push(SectionInfo {
-            virtual_address,
-            virtual_size,
-            raw_data_offset,
-        });
+/// Parse and verify the embedded Authenticode signature of a PE file on disk.
+/// Results are cached until the file changes; returns `None` if the file
+/// cannot be read or is not a PE image.
+pub fn get_file_signature(path: &str) -> Option<SignatureStatus> {
+    if path.is_empty() {
+        return None;
+    }
+    SIGNATURE_CACHE
+        .get_or_insert_with(path, || {
+            let data = std::fs::read(path).ok()?;
+            let image = PeImage::parse(&data).ok()?;
+            Some(match image.authenticode() {
+                Ok(Some(signature)) => SignatureStatus::Signed(signature),
+                Ok(None) => SignatureStatus::Unsigned,
+                Err(_) => SignatureStatus::Malformed,
+            })
+        })
+        .flatten()
+}
+
+/// Get the VS_VERSIONINFO resource (company, description, versions, ...) of a
+/// PE file on disk. Results are cached until the file changes.
+pub fn get_file_version_info(path: &str) -> Option<VersionInfo> {
+    if path.is_empty() {
+        return None;
     }
+    VERSION_CACHE
+        .get_or_insert_with(path, || {
+            let data = std::fs::read(path).ok()?;
+            PeImage::parse(&data).ok()?.version_info()
+        })
+        .flatten()
+}
 
-    let rva_to_offset = |rva: usize| -> Option<usize> {
-        for s in &sections {
-            if rva >= s.virtual_address && rva < s.virtual_address + s.virtual_size {
-                return Some(rva - s.virtual_address + s.raw_data_offset);
-            }
-        }
-        None
-    };
-
-    // Parse import directory table
-    let import_dir_file_offset = match rva_to_offset(import_dir_rva) {
-        Some(off) => off,
-        None => return imports,
-    };
-
-    // Each IMAGE_IMPORT_DESCRIPTOR is 20 bytes
-    let mut desc_offset = import_dir_file_offset;
-    loop {
-        if data.len() < desc_offset + 20 {
-            break;
-        }
-
-        let ilt_rva = u32::from_le_bytes([
-            data[desc_offset],
-            data[desc_offset + 1],
-            data[desc_offset + 2],
-            data[desc_offset + 3],
-        ]) as usize;
-        let name_rva = u32::from_le_bytes([
-            data[desc_offset + 12],
-            data[desc_offset + 13],
-            data[desc_offset + 14],
-            data[desc_offset + 15],
-        ]) as usize;
-
-        // Null descriptor terminates the list
-        if name_rva == 0 && ilt_rva == 0 {
-            break;
-        }
-
-        // Read DLL name
-        let dll_name = if let Some(name_off) = rva_to_offset(name_rva) {
-            read_cstring(&data, name_off)
-        } else {
-            String::from("(unknown)")
-        };
-
-        // Read imported functions from ILT (or IAT if ILT is 0)
-        let thunk_rva = if ilt_rva != 0 {
-            ilt_rva
-        } else {
-            // Fallback to IAT (FirstThunk)
-            u32::from_le_bytes([
-                data[desc_offset + 16],
-                data[desc_offset + 17],
-                data[desc_offset + 18],
-                data[desc_offset + 19],
-            ]) as usize
-        };
-
-        let mut functions = Vec::new();
-        if let Some(mut thunk_off) = rva_to_offset(thunk_rva) {
-            let is_pe32plus = opt_magic == 0x20b;
-            let entry_size = if is_pe32plus { 8 } else { 4 };
-
-            loop {
-                if data.len() < thunk_off + entry_size {
-                    break;
-                }
-
-                let thunk_value = if is_pe32plus {
-                    u64::from_le_bytes([
-                        data[thunk_off],
-                        data[thunk_off + 1],
-                        data[thunk_off + 2],
-                        data[thunk_off + 3],
-                        data[thunk_off + 4],
-                        data[thunk_off + 5],
-                        data[thunk_off + 6],
-                        data[thunk_off + 7],
-                    ])
-                } else {
-                    u32::from_le_bytes([
-                        data[thunk_off],
-                        data[thunk_off + 1],
-                        data[thunk_off + 2],
-                        data[thunk_off + 3],
-                    ]) as u64
-                };
-
-                if thunk_value == 0 {
-                    break;
-                }
-
-                // Check ordinal flag (bit 63 for PE32+, bit 31 for PE32)
-                let ordinal_flag = if is_pe32plus { 1u64 << 63 } else { 1u64 << 31 };
-                if thunk_value & ordinal_flag != 0 {
-                    let ordinal = thunk_value & 0xFFFF;
-                    functions.push(format!("Ordinal #{}", ordinal));
-                } else {
-                    // Hint/Name table entry: 2-byte hint + null-terminated name
-                    let hint_name_rva = (thunk_value & 0x7FFFFFFF) as usize;
-                    if let Some(hn_off) = rva_to_offset(hint_name_rva) {
-                        if data.len() > hn_off + 2 {
-                            functions.push(read_cstring(&data, hn_off + 2));
-                        }
-                    }
-                }
-
-                thunk_off += entry_size;
-            }
-        }
-
-        imports.push(ImportEntry {
-            dll_name,
-            functions,
-        });
-        desc_offset += 20;
+/// Get the MD5, SHA-1, SHA-256 and imphash of a file on disk.
+/// Results are cached until the file changes.
+pub fn get_file_hashes(path: &str) -> Option<FileHashes> {
+    if path.is_empty() {
+        return None;
     }
-
-    imports
+    HASH_CACHE
+        .get_or_insert_with(path, || {
+            let data = std::fs::read(path).ok()?;
+            Some(FileHashes::compute(&data))
+        })
+        .flatten()
 }
 
-/// Read a null-terminated C string from a byte buffer
-fn read_cstring(data: &[u8], offset: usize) -> String {
-    let mut end = offset;
-    while end < data.len() && data[end] != 0 {
-        end += 1;
-    }
-    String::from_utf8_lossy(&data[offset..end]).to_string()
+/// Score how likely a PE file on disk is packed or obfuscated (section
+/// entropy, W+X sections, size mismatches, section names, entry point).
+/// Results are cached until the file changes.
+pub fn get_file_packer_assessment(path: &str) -> Option<PackerAssessment> {
+    if path.is_empty() {
+        return None;
+    }
+    PACKER_CACHE
+        .get_or_insert_with(path, || {
+            let data = std::fs::read(path).ok()?;
+            Some(PeImage::parse(&data).ok()?.packer_assessment())
+        })
+        .flatten()
+}
+
+/// Company, description and product version for process/module listings
+pub(crate) fn version_fields(path: &str) -> (Option<String>, Option<String>, Option<String>) {
+    match get_file_version_info(path) {
+        Some(info) => (
+            info.company_name().map(str::to_string),
+            info.file_description().map(str::to_string),
+            info.product_version().map(str::to_string),
+        ),
+        None => (None, None, None),
+    }
+}
+
+/// Get exported functions (names, ordinals, RVAs and forwarders) from a PE file on disk
+pub fn get_module_exports(module_path: &str) -> Result<Vec<ExportEntry>, ProcessError> {
+    let data = read_module_file(module_path)?;
+    Ok(parse_module_file(module_path, &data)?.exports())
+}
+
+/// A resource of a module file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModuleResource {
+    pub entry: ResourceEntry,
+    /// Format guessed from the leading bytes (e.g. "PE image", "PNG", "XML")
+    pub format: Option<&'static str>,
+}
+
+/// Get the full resource tree (type / name / language) of a module file on disk
+pub fn get_module_resources(module_path: &str) -> Result<Vec<ModuleResource>, ProcessError> {
+    let data = read_module_file(module_path)?;
+    let image = parse_module_file(module_path, &data)?;
+    Ok(image
+        .resources()
+        .into_iter()
+        .map(|entry| {
+            let format = image
+                .resource_data(&entry)
+                .and_then(pe::detect_resource_format);
+            ModuleResource { entry, format }
+        })
+        .collect())
+}
+
+/// Read the raw bytes of a single resource from a module file on disk
+pub fn get_module_resource_data(
+    module_path: &str,
+    entry: &ResourceEntry,
+) -> Result<Vec<u8>, ProcessError> {
+    let data = read_module_file(module_path)?;
+    let image = parse_module_file(module_path, &data)?;
+    image
+        .resource_data(entry)
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| ProcessError::UnreadableImage {
+            path: module_path.to_string(),
+            error: PeError::OutOfBounds("Resource data"),
+        })
+}
+
+/// Parsed PE headers of a module file
+#[derive(Clone, Debug, PartialEq)]
+pub struct PeHeaders {
+    pub dos_header: DosHeader,
+    pub file_header: FileHeader,
+    pub optional_header: OptionalHeader,
+    pub sections: Vec<SectionInfo>,
+    /// Linker tool-chain stamp between the DOS stub and PE header
+    pub rich_header: Option<RichHeader>,
+    /// TLS directory, including the callbacks that run before the entry point
+    pub tls: Option<TlsDirectory>,
+    pub load_config: Option<LoadConfig>,
+    /// x64 exception table (empty for other machines)
+    pub runtime_functions: Vec<RuntimeFunction>,
+}
+
+/// Get the DOS/COFF/optional headers, Rich header, data directories, section
+/// table, TLS, load configuration and exception directories of a PE file on disk
+pub fn get_module_pe_headers(module_path: &str) -> Result<PeHeaders, ProcessError> {
+    let data = read_module_file(module_path)?;
+    let image = parse_module_file(module_path, &data)?;
+
+    Ok(PeHeaders {
+        dos_header: image.dos_header.clone(),
+        file_header: image.file_header.clone(),
+        optional_header: image.optional_header.clone(),
+        sections: image.section_infos(),
+        rich_header: image.rich_header(),
+        tls: image.tls(),
+        load_config: image.load_config(),
+        runtime_functions: image.runtime_functions(),
+    })
 }
 
 /// Memory region information structure
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct MemoryRegionInfo {
     pub base_address: usize,
     pub allocation_base: usize,
@@ -1027,94 +764,85 @@ pub struct MemoryRegionInfo {
 }
 
 /// Get all virtual memory regions for a process
-pub fn get_process_memory_regions(pid: u32) -> Vec<MemoryRegionInfo> {
-    let mut regions = Vec::new();
-
-    unsafe {
-        let handle = match OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, false, pid) {
-            Ok(h) => h,
-            Err(_) => return regions,
+pub fn get_process_memory_regions(pid: u32) -> Result<Vec<MemoryRegionInfo>, ProcessError> {
+    backend().memory_regions(pid)
+}
+
+/// Read memory from a process at a given address (at most 1MB per call).
+/// A short read is truncated; reading nothing is an error.
+pub fn read_process_memory(pid: u32, address: usize, size: usize) -> Result<Vec<u8>, ProcessError> {
+    backend().read_memory(pid, address, size.min(1024 * 1024))
+}
+
+/// Read a range of memory of any size, in chunks of at most 1MB.
+/// Fails if the first chunk cannot be read; a later failure or short read
+/// truncates the result.
+fn read_process_memory_range(
+    pid: u32,
+    address: usize,
+    size: usize,
+) -> Result<Vec<u8>, ProcessError> {
+    const CHUNK: usize = 1024 * 1024;
+    let mut buffer = Vec::with_capacity(size);
+    while buffer.len() < size {
+        let want = (size - buffer.len()).min(CHUNK);
+        let chunk = match read_process_memory(pid, address + buffer.len(), want) {
+            Ok(chunk) => chunk,
+            Err(error) if buffer.is_empty() => return Err(error),
+            Err(_) => break,
         };
-
-        let mut address: usize = 0;
-        let mbi_size = std::mem::size_of::<MEMORY_BASIC_INFORMATION>();
-
-        loop {
-            let mut mbi: MEMORY_BASIC_INFORMATION = zeroed();
-            let result = VirtualQueryEx(
-                handle,
-                Some(address as *const _),
-                &mut mbi,
-                mbi_size,
-            );
-
-            if result == 0 {
-                break;
-            }
-
-            regions.push(MemoryRegionInfo {
-                base_address: mbi.BaseAddress as usize,
-                allocation_base: mbi.AllocationBase as usize,
-                region_size: mbi.RegionSize,
-                state: mbi.State.0,
-                mem_type: mbi.Type.0,
-                protect: mbi.Protect.0,
-                allocation_protect: mbi.AllocationProtect.0,
-            });
-
-            // Advance to next region
-            let next = address.checked_add(mbi.RegionSize);
-            match next {
-                Some(n) if n > address => address = n,
-                _ => break,
-            }
+        let short = chunk.len() < want;
+        buffer.extend_from_slice(&chunk);
+        if short {
+            break;
         }
-
-        let _ = CloseHandle(handle);
     }
+    Ok(buffer)
+}
+
+/// Compare a loaded module's read-only sections (`.text`, `.rdata`, ...) in
+/// the target process against the file on disk, rebased to the module's
+/// actual base address, and report patched byte ranges.
+///
+/// Fails with the memory read error when no section could be read at all,
+/// e.g. when access to the process is denied.
+pub fn check_module_integrity(
+    pid: u32,
+    module: &ModuleInfo,
+) -> Result<ModuleIntegrity, ProcessError> {
+    let data = read_module_file(&module.path)?;
+    let image = parse_module_file(&module.path, &data)?;
+    let base = module.base_address;
+
+    let mut memory_error = None;
+    let integrity = image
+        .compare_with_memory(base as u64, |rva, len| {
+            match read_process_memory_range(pid, base + rva as usize, len) {
+                Ok(bytes) => Some(bytes),
+                Err(error) => {
+                    memory_error.get_or_insert(error);
+                    None
+                }
+            }
+        })
+        .map_err(|error| ProcessError::UnreadableImage {
+            path: module.path.clone(),
+            error,
+        })?;
 
-    regions
-}
-
-/// Read memory from a process at a given address
-pub fn read_process_memory(pid: u32, address: usize, size: usize) -> Vec<u8> {
-    let capped_size = size.min(1024 * 1024); // Cap at 1MB
-    let mut buffer = vec![0u8; capped_size];
-
-    unsafe {
-        let handle = match OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, false, pid) {
-            Ok(h) => h,
-            Err(_) => return Vec::new(),
-        };
-
-        let mut bytes_read: usize = 0;
-        let result = ReadProcessMemory(
-            handle,
-            address as *const _,
-            buffer.as_mut_ptr() as *mut _,
-            capped_size,
-            Some(&mut bytes_read),
-        );
-
-        let _ = CloseHandle(handle);
-
-        if result.is_err() || bytes_read == 0 {
-            return Vec::new();
-        }
-
-        buffer.truncate(bytes_read);
+    match memory_error {
+        Some(error) if integrity.sections.iter().all(|s| !s.readable) => Err(error),
+        _ => Ok(integrity),
     }
-
-    buffer
 }
 
 /// Get human-readable state name
 pub fn get_memory_state_name(state: u32) -> &'static str {
-    if state == MEM_COMMIT.0 {
+    if state == MEM_COMMIT {
         "Commit"
-    } else if state == MEM_RESERVE.0 {
+    } else if state == MEM_RESERVE {
         "Reserve"
-    } else if state == MEM_FREE.0 {
+    } else if state == MEM_FREE {
         "Free"
     } else {
         "Unknown"
@@ -1123,11 +851,11 @@ pub fn get_memory_state_name(state: u32) -> &'static str {
 
 /// Get human-readable memory type name
 pub fn get_memory_type_name(mem_type: u32) -> &'static str {
-    if mem_type == MEM_PRIVATE.0 {
+    if mem_type == MEM_PRIVATE {
         "Private"
-    } else if mem_type == MEM_MAPPED.0 {
+    } else if mem_type == MEM_MAPPED {
         "Mapped"
-    } else if mem_type == MEM_IMAGE.0 {
+    } else if mem_type == MEM_IMAGE {
         "Image"
     } else if mem_type == 0 {
         "-"
@@ -1143,34 +871,34 @@ pub fn get_memory_protect_name(protect: u32) -> String {
     }
 
     let base = protect & 0xFF;
-    let base_name = if base == PAGE_NOACCESS.0 {
+    let base_name = if base == PAGE_NOACCESS {
         "NoAccess"
-    } else if base == PAGE_READONLY.0 {
+    } else if base == PAGE_READONLY {
         "Read"
-    } else if base == PAGE_READWRITE.0 {
+    } else if base == PAGE_READWRITE {
         "ReadWrite"
-    } else if base == PAGE_WRITECOPY.0 {
+    } else if base == PAGE_WRITECOPY {
         "WriteCopy"
-    } else if base == PAGE_EXECUTE.0 {
+    } else if base == PAGE_EXECUTE {
         "Execute"
-    } else if base == PAGE_EXECUTE_READ.0 {
+    } else if base == PAGE_EXECUTE_READ {
         "ExecuteRead"
-    } else if base == PAGE_EXECUTE_READWRITE.0 {
+    } else if base == PAGE_EXECUTE_READWRITE {
         "ExecuteReadWrite"
-    } else if base == PAGE_EXECUTE_WRITECOPY.0 {
+    } else if base == PAGE_EXECUTE_WRITECOPY {
         "ExecuteWriteCopy"
     } else {
         "Unknown"
     };
 
     let mut modifiers = Vec::new();
-    if protect & PAGE_GUARD.0 != 0 {
+    if protect & PAGE_GUARD != 0 {
         modifiers.push("Guard");
     }
-    if protect & PAGE_NOCACHE.0 != 0 {
+    if protect & PAGE_NOCACHE != 0 {
         modifiers.push("NoCache");
     }
-    if protect & PAGE_WRITECOMBINE.0 != 0 {
+    if protect & PAGE_WRITECOMBINE != 0 {
         modifiers.push("WriteCombine");
     }
 
@@ -1180,3 +908,96 @@ pub fn get_memory_protect_name(protect: u32) -> String {
         format!("{} + {}", base_name, modifiers.join(" + "))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str) -> String {
+        format!("{}/../../assets/dll/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    #[test]
+    fn module_imports_and_exports_report_why_they_failed() {
+        let dll = asset("hello-world-x64.dll");
+        assert!(!get_module_imports(&dll).unwrap().is_empty());
+        assert!(get_module_exports(&dll).is_ok());
+
+        let missing = get_module_exports(&asset("missing.dll")).unwrap_err();
+        assert!(matches!(missing, ProcessError::Io { ref error, .. }
+            if error.kind() == std::io::ErrorKind::NotFound));
+
+        let manifest = format!("{}/Cargo.toml", env!("CARGO_MANIFEST_DIR"));
+        let not_pe = get_module_imports(&manifest).unwrap_err();
+        assert!(matches!(not_pe, ProcessError::UnreadableImage { .. }));
+        assert!(not_pe.to_string().starts_with("Failed to parse "));
+    }
+
+    #[test]
+    fn module_resources_and_headers_report_why_they_failed() {
+        let dll = asset("hello-world-x64.dll");
+        let resources = get_module_resources(&dll).unwrap();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].format, Some("XML"));
+        let data = get_module_resource_data(&dll, &resources[0].entry).unwrap();
+        assert_eq!(data.len(), 381);
+        assert!(get_module_pe_headers(&dll).is_ok());
+
+        let missing = asset("missing.dll");
+        assert!(matches!(
+            get_module_resources(&missing),
+            Err(ProcessError::Io { .. })
+        ));
+        assert!(matches!(
+            get_module_pe_headers(&missing),
+            Err(ProcessError::Io { .. })
+        ));
+
+        let manifest = format!("{}/Cargo.toml", env!("CARGO_MANIFEST_DIR"));
+        assert!(matches!(
+            get_module_resources(&manifest),
+            Err(ProcessError::UnreadableImage { .. })
+        ));
+        assert!(matches!(
+            get_module_pe_headers(&manifest),
+            Err(ProcessError::UnreadableImage { .. })
+        ));
+
+        // An entry pointing past the end of the file has no data to read
+        let mut entry = resources[0].entry.clone();
+        entry.rva = u32::MAX;
+        let error = get_module_resource_data(&dll, &entry).unwrap_err();
+        assert!(matches!(
+            error,
+            ProcessError::UnreadableImage {
+                error: PeError::OutOfBounds(_),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn module_integrity_reports_why_it_failed() {
+        let module = |path: String| ModuleInfo {
+            name: String::from("hello-world-x64.dll"),
+            // Never mapped, so every memory read fails
+            base_address: 0x1000,
+            size: 0x8000,
+            path,
+            company: None,
+            description: None,
+            version: None,
+        };
+        let pid = std::process::id();
+
+        let missing = check_module_integrity(pid, &module(asset("missing.dll"))).unwrap_err();
+        assert!(matches!(missing, ProcessError::Io { .. }));
+
+        let manifest = format!("{}/Cargo.toml", env!("CARGO_MANIFEST_DIR"));
+        let not_pe = check_module_integrity(pid, &module(manifest)).unwrap_err();
+        assert!(matches!(not_pe, ProcessError::UnreadableImage { .. }));
+
+        let unmapped = check_module_integrity(pid, &module(asset("hello-world-x64.dll")));
+        assert!(unmapped.is_err());
+    }
+}
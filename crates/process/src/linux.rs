@@ -0,0 +1,584 @@
+//! Linux backend over `/proc/<pid>/{stat,status,cmdline,exe,maps,task,mem,fd,io}`
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::os::unix::fs::FileExt;
+
+use crate::{
+    get_cpu_usage_map, version_fields, FileCache, HandleInfo, MemoryRegionInfo, ModuleInfo,
+    ProcessArch, ProcessBackend, ProcessCounters, ProcessError, ProcessInfo, ProcessStats,
+    ThreadInfo, MEM_COMMIT, MEM_FREE, MEM_IMAGE, MEM_MAPPED, MEM_PRIVATE, PAGE_EXECUTE,
+    PAGE_EXECUTE_READ, PAGE_EXECUTE_READWRITE, PAGE_NOACCESS, PAGE_READONLY, PAGE_READWRITE,
+};
+
+/// `USER_HZ`, the unit of `starttime` in `/proc/<pid>/stat` (100 on every
+/// mainstream architecture)
+const CLOCK_TICKS_PER_SECOND: u64 = 100;
+
+/// Executable architectures, so a refresh doesn't re-read every ELF header
+static ARCH_CACHE: FileCache<Option<ProcessArch>> = FileCache::new();
+
+/// `/proc` implementation of [`ProcessBackend`]
+pub struct LinuxBackend;
+
+/// Fields of `/proc/<pid>/stat` (or `/proc/<pid>/task/<tid>/stat`)
+struct Stat {
+    comm: String,
+    ppid: u32,
+    session: u32,
+    priority: i32,
+    nice: i32,
+    num_threads: u32,
+    start_ticks: u64,
+    /// User plus system time in clock ticks
+    cpu_ticks: u64,
+    /// Minor plus major faults
+    page_faults: u64,
+}
+
+fn read_stat(path: &str) -> Option<Stat> {
+    let text = fs::read_to_string(path).ok()?;
+    // comm is parenthesised and may itself contain spaces and ')'
+    let open = text.find('(')?;
+    let close = text.rfind(')')?;
+    let comm = text.get(open + 1..close)?.to_string();
+    let fields: Vec<&str> = text.get(close + 1..)?.split_whitespace().collect();
+    // Indices relative to the state field (field 3 in proc(5))
+    let field = |index: usize| fields.get(index).copied();
+
+    Some(Stat {
+        comm,
+        ppid: field(1)?.parse().ok()?,
+        session: field(3)?.parse().ok()?,
+        priority: field(15)?.parse().ok()?,
+        nice: field(16)?.parse().ok()?,
+        num_threads: field(17)?.parse().ok()?,
+        start_ticks: field(19)?.parse().ok()?,
+        cpu_ticks: field(11)?.parse::<u64>().ok()? + field(12)?.parse::<u64>().ok()?,
+        page_faults: field(7)?.parse::<u64>().ok()? + field(9)?.parse::<u64>().ok()?,
+    })
+}
+
+/// Value of a `Key:\tvalue` line in `/proc/<pid>/status`
+fn status_field<'a>(status: &'a str, key: &str) -> Option<&'a str> {
+    status.lines().find_map(|line| {
+        line.strip_prefix(key)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .map(str::trim)
+    })
+}
+
+/// A `/proc/<pid>/status` size such as `VmRSS:  1234 kB`, in bytes
+fn status_bytes(status: &str, key: &str) -> Option<u64> {
+    let kb: u64 = status_field(status, key)?
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+/// Memory, I/O and descriptor counters of a process. Open file descriptors
+/// stand in for handles, and `rchar` / `wchar` / `syscr` / `syscw` for I/O;
+/// both read as zero for other users' processes.
+fn process_stats(pid: u32, stat: &Stat, status: &str) -> ProcessStats {
+    let io = fs::read_to_string(format!("/proc/{}/io", pid)).unwrap_or_default();
+    let io_field = |key: &str| -> u64 {
+        status_field(&io, key)
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0)
+    };
+    let handle_count = fs::read_dir(format!("/proc/{}/fd", pid))
+        .map(|entries| entries.count() as u32)
+        .unwrap_or(0);
+
+    ProcessStats {
+        private_bytes: status_bytes(status, "RssAnon").unwrap_or(0),
+        peak_working_set_bytes: status_bytes(status, "VmHWM").unwrap_or(0),
+        pagefile_bytes: status_bytes(status, "VmSwap").unwrap_or(0),
+        page_fault_count: stat.page_faults.min(u32::MAX as u64) as u32,
+        io_read_bytes: io_field("rchar"),
+        io_write_bytes: io_field("wchar"),
+        io_read_ops: io_field("syscr"),
+        io_write_ops: io_field("syscw"),
+        handle_count,
+        ..Default::default()
+    }
+}
+
+/// System boot time in seconds since the Unix epoch
+fn boot_time() -> Option<u64> {
+    fs::read_to_string("/proc/stat")
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("btime "))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// User names by UID from `/etc/passwd`
+fn user_names() -> HashMap<u32, String> {
+    fs::read_to_string("/etc/passwd")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split(':');
+            let name = parts.next()?;
+            let uid = parts.nth(1)?.parse().ok()?;
+            Some((uid, name.to_string()))
+        })
+        .collect()
+}
+
+/// Architecture from the ELF header of the executable, cached until the
+/// file changes
+fn elf_arch(path: &str) -> Option<ProcessArch> {
+    if path.is_empty() {
+        return None;
+    }
+    ARCH_CACHE
+        .get_or_insert_with(path, || read_elf_arch(path))
+        .flatten()
+}
+
+fn read_elf_arch(path: &str) -> Option<ProcessArch> {
+    let mut header = [0u8; 20];
+    fs::File::open(path)
+        .ok()?
+        .read_exact_at(&mut header, 0)
+        .ok()?;
+    if &header[..4] != b"\x7FELF" {
+        return None;
+    }
+    // EI_DATA: 2 means e_machine and every other field is big-endian
+    let machine = if header[5] == 2 {
+        u16::from_be_bytes([header[18], header[19]])
+    } else {
+        u16::from_le_bytes([header[18], header[19]])
+    };
+    // Map ELF e_machine to the IMAGE_FILE_MACHINE_* value ProcessArch uses
+    let machine = match machine {
+        3 => 0x014C,
+        62 => 0x8664,
+        40 => 0x01C0,
+        183 => 0xAA64,
+        other => other,
+    };
+    Some(ProcessArch::from_machine(machine))
+}
+
+/// Map a nice value (-20..19) onto the Win32 relative thread priority levels
+/// understood by `get_priority_name`
+fn priority_from_nice(nice: i32) -> i32 {
+    match nice {
+        i32::MIN..=-11 => 2,
+        -10..=-1 => 1,
+        0 => 0,
+        1..=10 => -1,
+        11..=18 => -2,
+        _ => -15,
+    }
+}
+
+/// Numeric `/proc` entries (process or thread IDs)
+fn numeric_entries(path: &str) -> io::Result<Vec<u32>> {
+    let mut ids: Vec<u32> = fs::read_dir(path)?
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+        .collect();
+    ids.sort_unstable();
+    Ok(ids)
+}
+
+/// A missing `/proc/<pid>` entry means the process is gone
+fn proc_error(pid: u32, path: String, error: io::Error) -> ProcessError {
+    if error.kind() == io::ErrorKind::NotFound {
+        ProcessError::NotFound(pid)
+    } else {
+        ProcessError::Io { path, error }
+    }
+}
+
+/// One line of `/proc/<pid>/maps`
+struct Mapping {
+    start: usize,
+    end: usize,
+    readable: bool,
+    writable: bool,
+    executable: bool,
+    /// Backing file, if the mapping is file-backed
+    path: Option<String>,
+}
+
+fn read_maps(pid: u32) -> Result<Vec<Mapping>, ProcessError> {
+    let path = format!("/proc/{}/maps", pid);
+    let text = fs::read_to_string(&path).map_err(|error| proc_error(pid, path, error))?;
+    Ok(text
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(6, char::is_whitespace);
+            let (start, end) = parts.next()?.split_once('-')?;
+            let perms = parts.next()?.as_bytes();
+            // offset, device and inode
+            parts.nth(2)?;
+            let path = parts.next().map(str::trim).unwrap_or("");
+            Some(Mapping {
+                start: usize::from_str_radix(start, 16).ok()?,
+                end: usize::from_str_radix(end, 16).ok()?,
+                readable: perms.first() == Some(&b'r'),
+                writable: perms.get(1) == Some(&b'w'),
+                executable: perms.get(2) == Some(&b'x'),
+                path: path.starts_with('/').then(|| path.to_string()),
+            })
+        })
+        .collect())
+}
+
+/// Lowest address and end of every file that has an executable mapping
+fn image_ranges(maps: &[Mapping]) -> BTreeMap<String, (usize, usize)> {
+    let mut files: BTreeMap<String, (usize, usize, bool)> = BTreeMap::new();
+    for mapping in maps {
+        if let Some(path) = &mapping.path {
+            let entry = files
+                .entry(path.clone())
+                .or_insert((mapping.start, mapping.end, false));
+            entry.0 = entry.0.min(mapping.start);
+            entry.1 = entry.1.max(mapping.end);
+            entry.2 |= mapping.executable;
+        }
+    }
+    files
+        .into_iter()
+        .filter(|(_, (_, _, executable))| *executable)
+        .map(|(path, (start, end, _))| (path, (start, end)))
+        .collect()
+}
+
+fn page_protection(mapping: &Mapping) -> u32 {
+    match (mapping.readable, mapping.writable, mapping.executable) {
+        (_, true, true) => PAGE_EXECUTE_READWRITE,
+        (true, false, true) => PAGE_EXECUTE_READ,
+        (false, false, true) => PAGE_EXECUTE,
+        (_, true, false) => PAGE_READWRITE,
+        (true, false, false) => PAGE_READONLY,
+        (false, false, false) => PAGE_NOACCESS,
+    }
+}
+
+impl ProcessBackend for LinuxBackend {
+    fn processes(&self) -> Vec<ProcessInfo> {
+        let cpu_map = get_cpu_usage_map();
+        let boot_time = boot_time();
+        let users = user_names();
+
+        numeric_entries("/proc")
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|pid| {
+                let stat = read_stat(&format!("/proc/{}/stat", pid))?;
+                let status =
+                    fs::read_to_string(format!("/proc/{}/status", pid)).unwrap_or_default();
+                let exe_path = fs::read_link(format!("/proc/{}/exe", pid))
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+
+                let memory_mb = status_field(&status, "VmRSS")
+                    .and_then(|value| value.trim_end_matches("kB").trim().parse::<f64>().ok())
+                    .map(|kb| kb / 1024.0)
+                    .unwrap_or(0.0);
+                // Effective UID (the second of real, effective, saved, filesystem)
+                let uid: Option<u32> = status_field(&status, "Uid")
+                    .and_then(|value| value.split_whitespace().nth(1)?.parse().ok());
+                let command_line = fs::read(format!("/proc/{}/cmdline", pid))
+                    .ok()
+                    .filter(|args| !args.is_empty())
+                    .map(|args| {
+                        String::from_utf8_lossy(args.strip_suffix(b"\0").unwrap_or(&args))
+                            .replace('\0', " ")
+                    });
+                let start_time = boot_time
+                    .map(|boot| boot * 1000 + stat.start_ticks * 1000 / CLOCK_TICKS_PER_SECOND);
+                let (company, description, version) = version_fields(&exe_path);
+                let stats = process_stats(pid, &stat, &status);
+
+                Some(ProcessInfo {
+                    pid,
+                    name: stat.comm,
+                    memory_mb,
+                    thread_count: stat.num_threads,
+                    cpu_usage: cpu_map.get(&pid).copied().unwrap_or(0.0),
+                    company,
+                    description,
+                    version,
+                    parent_pid: stat.ppid,
+                    session_id: Some(stat.session),
+                    user: uid
+                        .map(|uid| users.get(&uid).cloned().unwrap_or_else(|| uid.to_string())),
+                    user_sid: None,
+                    integrity_level: None,
+                    command_line,
+                    start_time,
+                    arch: elf_arch(&exe_path),
+                    is_wow64: false,
+                    exe_path,
+                    stats,
+                })
+            })
+            .collect()
+    }
+
+    fn counters(&self) -> Vec<ProcessCounters> {
+        let boot_time = boot_time();
+
+        numeric_entries("/proc")
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|pid| {
+                let stat = read_stat(&format!("/proc/{}/stat", pid))?;
+                let status =
+                    fs::read_to_string(format!("/proc/{}/status", pid)).unwrap_or_default();
+
+                Some(ProcessCounters {
+                    pid,
+                    start_time: boot_time
+                        .map(|boot| boot * 1000 + stat.start_ticks * 1000 / CLOCK_TICKS_PER_SECOND),
+                    cpu_time_ms: stat.cpu_ticks * 1000 / CLOCK_TICKS_PER_SECOND,
+                    working_set_bytes: status_bytes(&status, "VmRSS").unwrap_or(0),
+                    thread_count: stat.num_threads,
+                    stats: process_stats(pid, &stat, &status),
+                })
+            })
+            .collect()
+    }
+
+    fn threads(&self, pid: u32) -> Result<Vec<ThreadInfo>, ProcessError> {
+        let path = format!("/proc/{}/task", pid);
+        let tids = numeric_entries(&path).map_err(|error| proc_error(pid, path, error))?;
+        Ok(tids
+            .into_iter()
+            .filter_map(|tid| {
+                let stat = read_stat(&format!("/proc/{}/task/{}/stat", pid, tid))?;
+                Some(ThreadInfo {
+                    thread_id: tid,
+                    owner_pid: pid,
+                    base_priority: stat.priority,
+                    priority: priority_from_nice(stat.nice),
+                })
+            })
+            .collect())
+    }
+
+    fn modules(&self, pid: u32) -> Result<Vec<ModuleInfo>, ProcessError> {
+        let mut modules: Vec<ModuleInfo> = image_ranges(&read_maps(pid)?)
+            .into_iter()
+            .map(|(path, (start, end))| {
+                let (company, description, version) = version_fields(&path);
+                ModuleInfo {
+                    name: path.rsplit('/').next().unwrap_or(&path).to_string(),
+                    base_address: start,
+                    size: (end - start).min(u32::MAX as usize) as u32,
+                    path,
+                    company,
+                    description,
+                    version,
+                }
+            })
+            .collect();
+        modules.sort_by_key(|module| module.base_address);
+        Ok(modules)
+    }
+
+    fn memory_regions(&self, pid: u32) -> Result<Vec<MemoryRegionInfo>, ProcessError> {
+        let maps = read_maps(pid)?;
+        let images = image_ranges(&maps);
+        let mut file_bases: HashMap<&str, usize> = HashMap::new();
+        for mapping in &maps {
+            if let Some(path) = &mapping.path {
+                file_bases.entry(path.as_str()).or_insert(mapping.start);
+            }
+        }
+
+        let mut regions = Vec::with_capacity(maps.len() * 2);
+        let mut previous_end = 0usize;
+        for mapping in &maps {
+            // Unmapped gaps are reported as free, as VirtualQueryEx does
+            if mapping.start > previous_end {
+                regions.push(MemoryRegionInfo {
+                    base_address: previous_end,
+                    allocation_base: 0,
+                    region_size: mapping.start - previous_end,
+                    state: MEM_FREE,
+                    mem_type: 0,
+                    protect: PAGE_NOACCESS,
+                    allocation_protect: 0,
+                });
+            }
+            previous_end = previous_end.max(mapping.end);
+
+            let (mem_type, allocation_base) = match &mapping.path {
+                Some(path) if images.contains_key(path) => (MEM_IMAGE, images[path].0),
+                Some(path) => (MEM_MAPPED, file_bases[path.as_str()]),
+                None => (MEM_PRIVATE, mapping.start),
+            };
+            let protect = page_protection(mapping);
+            regions.push(MemoryRegionInfo {
+                base_address: mapping.start,
+                allocation_base,
+                region_size: mapping.end - mapping.start,
+                state: MEM_COMMIT,
+                mem_type,
+                protect,
+                allocation_protect: protect,
+            });
+        }
+        Ok(regions)
+    }
+
+    fn read_memory(&self, pid: u32, address: usize, size: usize) -> Result<Vec<u8>, ProcessError> {
+        let path = format!("/proc/{}/mem", pid);
+        let file = fs::File::open(&path).map_err(|error| proc_error(pid, path, error))?;
+
+        let mut buffer = vec![0u8; size];
+        let mut filled = 0;
+        while filled < size {
+            match file.read_at(&mut buffer[filled..], (address + filled) as u64) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => filled += n,
+            }
+        }
+        if filled == 0 {
+            return Err(ProcessError::Unreadable { pid, address });
+        }
+        buffer.truncate(filled);
+        Ok(buffer)
+    }
+}
+
+/// Send `signal` to a process, the `/proc` counterpart of the Win32 process
+/// controls
+fn signal_process(
+    pid: u32,
+    signal: libc::c_int,
+    operation: &'static str,
+) -> Result<(), ProcessError> {
+    // 0 and negative PIDs would signal process groups or every process
+    let target = i32::try_from(pid)
+        .ok()
+        .filter(|&target| target > 0)
+        .ok_or(ProcessError::NotFound(pid))?;
+    if unsafe { libc::kill(target, signal) } == 0 {
+        return Ok(());
+    }
+    // Read errno before anything else can overwrite it
+    let error = io::Error::last_os_error();
+    if error.raw_os_error() == Some(libc::ESRCH) {
+        Err(ProcessError::NotFound(pid))
+    } else {
+        Err(ProcessError::Os { operation, error })
+    }
+}
+
+/// Kill a process by PID (`SIGKILL`)
+pub fn kill_process(pid: u32) -> Result<(), ProcessError> {
+    signal_process(pid, libc::SIGKILL, "kill")
+}
+
+/// Suspend a process by PID (`SIGSTOP`)
+pub fn suspend_process(pid: u32) -> Result<(), ProcessError> {
+    signal_process(pid, libc::SIGSTOP, "SIGSTOP")
+}
+
+/// Resume a suspended process by PID (`SIGCONT`)
+pub fn resume_process(pid: u32) -> Result<(), ProcessError> {
+    signal_process(pid, libc::SIGCONT, "SIGCONT")
+}
+
+/// Unsupported: threads can't be signalled individually without `tgkill`
+pub fn suspend_thread(_thread_id: u32) -> Result<(), ProcessError> {
+    Err(ProcessError::Unsupported("Suspending a thread"))
+}
+
+/// Unsupported, see [`suspend_thread`]
+pub fn resume_thread(_thread_id: u32) -> Result<(), ProcessError> {
+    Err(ProcessError::Unsupported("Resuming a thread"))
+}
+
+/// Unsupported, see [`suspend_thread`]
+pub fn kill_thread(_thread_id: u32) -> Result<(), ProcessError> {
+    Err(ProcessError::Unsupported("Terminating a thread"))
+}
+
+/// Unsupported: handles are NT object manager entries
+pub fn get_process_handles(_pid: u32) -> Result<Vec<HandleInfo>, ProcessError> {
+    Err(ProcessError::Unsupported("Handle enumeration"))
+}
+
+/// Unsupported, see [`get_process_handles`]
+pub fn get_all_process_handles() -> Result<HashMap<u32, Vec<HandleInfo>>, ProcessError> {
+    Err(ProcessError::Unsupported("Handle enumeration"))
+}
+
+/// Unsupported, see [`get_process_handles`]
+pub fn close_process_handle(_pid: u32, _handle_value: u16) -> Result<(), ProcessError> {
+    Err(ProcessError::Unsupported("Closing a handle"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::Command;
+
+    #[test]
+    fn signals_a_child_process() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let pid = child.id();
+
+        suspend_process(pid).unwrap();
+        resume_process(pid).unwrap();
+        kill_process(pid).unwrap();
+        assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGKILL));
+    }
+
+    #[test]
+    fn never_signals_process_groups() {
+        assert!(matches!(kill_process(0), Err(ProcessError::NotFound(0))));
+        assert!(matches!(
+            kill_process(u32::MAX),
+            Err(ProcessError::NotFound(u32::MAX))
+        ));
+    }
+
+    #[test]
+    fn reads_the_architecture_of_this_test_binary() {
+        let exe = std::env::current_exe().unwrap();
+        let exe = exe.to_str().unwrap();
+        let arch = elf_arch(exe);
+        assert!(arch.is_some());
+        assert_eq!(elf_arch(exe), arch);
+        assert_eq!(elf_arch(""), None);
+        assert_eq!(elf_arch("/proc/self/status"), None);
+    }
+
+    #[test]
+    fn reads_e_machine_in_the_header_byte_order() {
+        let path = std::env::temp_dir().join(format!("dioprocess-elf-{}", std::process::id()));
+        let path_str = path.to_str().unwrap();
+        let mut header = [0u8; 20];
+        header[..4].copy_from_slice(b"\x7FELF");
+
+        // aarch64 (183) little-endian, then big-endian
+        header[5] = 1;
+        header[18..].copy_from_slice(&183u16.to_le_bytes());
+        fs::write(&path, header).unwrap();
+        assert_eq!(read_elf_arch(path_str), Some(ProcessArch::Arm64));
+
+        header[5] = 2;
+        header[18..].copy_from_slice(&183u16.to_be_bytes());
+        fs::write(&path, header).unwrap();
+        assert_eq!(read_elf_arch(path_str), Some(ProcessArch::Arm64));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
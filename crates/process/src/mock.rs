@@ -0,0 +1,241 @@
+//! In-memory backend that replays recorded process snapshots
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    MemoryRegionInfo, ModuleInfo, ProcessBackend, ProcessCounters, ProcessError, ProcessInfo,
+    ThreadInfo,
+};
+
+/// Everything a backend reports at one point in time
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessSnapshot {
+    pub processes: Vec<ProcessInfo>,
+    /// Threads keyed by owning PID
+    #[serde(default)]
+    pub threads: HashMap<u32, Vec<ThreadInfo>>,
+    /// Modules keyed by owning PID
+    #[serde(default)]
+    pub modules: HashMap<u32, Vec<ModuleInfo>>,
+    /// Memory regions keyed by owning PID
+    #[serde(default)]
+    pub memory_regions: HashMap<u32, Vec<MemoryRegionInfo>>,
+    /// Readable memory contents
+    #[serde(default)]
+    pub memory: Vec<MemoryBlock>,
+}
+
+/// Bytes recorded at an address of one process
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MemoryBlock {
+    pub pid: u32,
+    pub address: usize,
+    pub bytes: Vec<u8>,
+}
+
+/// Backend that serves a fixed sequence of snapshots.
+///
+/// Each call to [`ProcessBackend::processes`] moves to the next snapshot and
+/// stays on the last one once the sequence is exhausted. Counters, threads,
+/// modules, memory regions and memory reads come from the snapshot most recently
+/// returned by `processes` (the first one before any call); asking about a
+/// PID that snapshot does not list is [`ProcessError::NotFound`].
+pub struct MockBackend {
+    snapshots: Vec<ProcessSnapshot>,
+    /// One past the snapshot last returned by `processes`
+    cursor: Mutex<usize>,
+}
+
+impl MockBackend {
+    pub fn new(snapshots: Vec<ProcessSnapshot>) -> Self {
+        Self {
+            snapshots,
+            cursor: Mutex::new(0),
+        }
+    }
+
+    /// Load a JSON array of [`ProcessSnapshot`]s
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+
+    /// Go back to the first snapshot
+    pub fn rewind(&self) {
+        *self.cursor.lock().unwrap() = 0;
+    }
+
+    fn current(&self) -> Option<&ProcessSnapshot> {
+        let cursor = *self.cursor.lock().unwrap();
+        self.snapshots.get(cursor.saturating_sub(1))
+    }
+
+    /// The current snapshot, if it lists `pid`
+    fn recorded(&self, pid: u32) -> Result<&ProcessSnapshot, ProcessError> {
+        self.current()
+            .filter(|snapshot| snapshot.processes.iter().any(|p| p.pid == pid))
+            .ok_or(ProcessError::NotFound(pid))
+    }
+}
+
+impl ProcessBackend for MockBackend {
+    fn processes(&self) -> Vec<ProcessInfo> {
+        let mut cursor = self.cursor.lock().unwrap();
+        let index = (*cursor).min(self.snapshots.len().saturating_sub(1));
+        *cursor = index + 1;
+        self.snapshots
+            .get(index)
+            .map(|snapshot| snapshot.processes.clone())
+            .unwrap_or_default()
+    }
+
+    /// Counters of the current snapshot's processes; CPU time is not
+    /// recorded, so it stays at zero
+    fn counters(&self) -> Vec<ProcessCounters> {
+        self.current()
+            .map(|snapshot| {
+                snapshot
+                    .processes
+                    .iter()
+                    .map(ProcessCounters::from_info)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn threads(&self, pid: u32) -> Result<Vec<ThreadInfo>, ProcessError> {
+        let snapshot = self.recorded(pid)?;
+        Ok(snapshot.threads.get(&pid).cloned().unwrap_or_default())
+    }
+
+    fn modules(&self, pid: u32) -> Result<Vec<ModuleInfo>, ProcessError> {
+        let snapshot = self.recorded(pid)?;
+        Ok(snapshot.modules.get(&pid).cloned().unwrap_or_default())
+    }
+
+    fn memory_regions(&self, pid: u32) -> Result<Vec<MemoryRegionInfo>, ProcessError> {
+        let snapshot = self.recorded(pid)?;
+        Ok(snapshot
+            .memory_regions
+            .get(&pid)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn read_memory(&self, pid: u32, address: usize, size: usize) -> Result<Vec<u8>, ProcessError> {
+        self.recorded(pid)?
+            .memory
+            .iter()
+            .filter(|block| block.pid == pid && block.address <= address)
+            .find_map(|block| {
+                let offset = address - block.address;
+                (offset < block.bytes.len()).then(|| {
+                    let end = block.bytes.len().min(offset + size);
+                    block.bytes[offset..end].to_vec()
+                })
+            })
+            .ok_or(ProcessError::Unreadable { pid, address })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{IntegrityLevel, ProcessArch, ProcessStats};
+
+    const FIXTURE: &str = include_str!("../fixtures/processes.json");
+
+    fn fixture() -> MockBackend {
+        MockBackend::from_json(FIXTURE).expect("fixture parses")
+    }
+
+    #[test]
+    fn replays_snapshots_and_sticks_at_the_last() {
+        let backend = fixture();
+        let first = backend.processes();
+        let second = backend.processes();
+        let third = backend.processes();
+
+        assert!(first.iter().any(|p| p.name == "notepad.exe"));
+        assert!(!second.iter().any(|p| p.name == "notepad.exe"));
+        assert!(second.iter().any(|p| p.name == "calc.exe"));
+        assert_eq!(second, third);
+
+        backend.rewind();
+        assert_eq!(backend.processes(), first);
+    }
+
+    #[test]
+    fn details_follow_the_current_snapshot() {
+        let backend = fixture();
+        assert_eq!(backend.threads(4120).unwrap().len(), 2);
+        backend.processes();
+        assert_eq!(backend.modules(4120).unwrap()[0].name, "notepad.exe");
+        assert_eq!(backend.memory_regions(4120).unwrap().len(), 2);
+        assert!(backend.threads(4).unwrap().is_empty());
+        assert!(matches!(
+            backend.threads(9999),
+            Err(ProcessError::NotFound(9999))
+        ));
+    }
+
+    #[test]
+    fn reads_recorded_memory() {
+        let backend = fixture();
+        backend.processes();
+        assert_eq!(
+            backend.read_memory(4120, 0x7ff6_0000_0000, 2).unwrap(),
+            b"MZ"
+        );
+        assert_eq!(
+            backend.read_memory(4120, 0x7ff6_0000_0001, 64).unwrap(),
+            b"Z\x90\x00"
+        );
+        assert!(matches!(
+            backend.read_memory(4120, 0x1000, 4),
+            Err(ProcessError::Unreadable {
+                pid: 4120,
+                address: 0x1000
+            })
+        ));
+        assert!(backend.read_memory(1, 0x7ff6_0000_0000, 4).is_err());
+    }
+
+    #[test]
+    fn empty_backend_reports_nothing() {
+        let backend = MockBackend::new(Vec::new());
+        assert!(backend.processes().is_empty());
+        assert!(backend.threads(4).is_err());
+        assert!(backend.read_memory(4, 0, 16).is_err());
+    }
+
+    #[test]
+    fn fixture_fields_round_trip() {
+        let backend = fixture();
+        let processes = backend.processes();
+        let notepad = processes.iter().find(|p| p.pid == 4120).unwrap();
+        assert_eq!(notepad.integrity_level, Some(IntegrityLevel::Medium));
+        assert_eq!(notepad.arch, Some(ProcessArch::X64));
+        assert_eq!(notepad.user.as_deref(), Some("DESKTOP\\alice"));
+        assert_eq!(notepad.stats.private_bytes, 4 << 20);
+        assert_eq!(notepad.stats.handle_count, 240);
+        assert_eq!(notepad.stats.gdi_objects, 38);
+        // Stats missing from the fixture read as zero
+        let system = processes.iter().find(|p| p.pid == 4).unwrap();
+        assert_eq!(system.stats, ProcessStats::default());
+
+        // The history sampler sees the same stats as the process table
+        let counters = backend.counters();
+        let notepad_counters = counters.iter().find(|c| c.pid == 4120).unwrap();
+        assert_eq!(notepad_counters.stats, notepad.stats);
+        assert_eq!(notepad_counters.thread_count, notepad.thread_count);
+
+        let json = serde_json::to_string(&backend.snapshots).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<ProcessSnapshot>>(&json).unwrap(),
+            backend.snapshots
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Start and exit highlighting: which processes appeared or disappeared
+//! recently enough to still be marked in the process table
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use crate::{ProcessInfo, RefreshEvent};
+
+/// Difference highlight of a process row after a refresh
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RowHighlight {
+    #[default]
+    Normal,
+    /// Started within the highlight period
+    Started,
+    /// Exited within the highlight period, still shown as last seen
+    Exited,
+}
+
+/// Processes that started or exited within the highlight period. Exited
+/// processes are kept as last seen so their rows stay visible until it ends.
+#[derive(Default)]
+pub struct RecentChanges {
+    started: HashMap<(u32, Option<u64>), Instant>,
+    exited: Vec<(ProcessInfo, Instant)>,
+}
+
+impl RecentChanges {
+    pub fn is_empty(&self) -> bool {
+        self.started.is_empty() && self.exited.is_empty()
+    }
+
+    /// Note the starts and exits of a refresh, then forget everything older
+    /// than `linger`
+    pub fn record(&mut self, events: &[RefreshEvent<ProcessInfo>], now: Instant, linger: Duration) {
+        for event in events {
+            match event {
+                RefreshEvent::Added(p) => {
+                    self.started.insert(p.key(), now);
+                }
+                RefreshEvent::Removed(p) => {
+                    self.started.remove(&p.key());
+                    self.exited.push((p.clone(), now));
+                }
+                RefreshEvent::Changed { .. } => {}
+            }
+        }
+        self.prune(now, linger);
+    }
+
+    /// Whether any start or exit is older than `linger`
+    pub fn has_expired(&self, now: Instant, linger: Duration) -> bool {
+        self.started
+            .values()
+            .chain(self.exited.iter().map(|(_, at)| at))
+            .any(|at| now.duration_since(*at) >= linger)
+    }
+
+    /// Forget every start and exit older than `linger`
+    pub fn prune(&mut self, now: Instant, linger: Duration) {
+        self.started
+            .retain(|_, at| now.duration_since(*at) < linger);
+        self.exited
+            .retain(|(_, at)| now.duration_since(*at) < linger);
+    }
+
+    pub fn highlight(&self, p: &ProcessInfo) -> RowHighlight {
+        let key = p.key();
+        if self.exited.iter().any(|(exited, _)| exited.key() == key) {
+            RowHighlight::Exited
+        } else if self.started.contains_key(&key) {
+            RowHighlight::Started
+        } else {
+            RowHighlight::Normal
+        }
+    }
+
+    /// Whether `pid` is shown only as a lingering exited process, so there is
+    /// nothing left to act on
+    pub fn lingers(&self, pid: u32, processes: &[ProcessInfo]) -> bool {
+        self.exited.iter().any(|(p, _)| p.pid == pid) && !processes.iter().any(|p| p.pid == pid)
+    }
+
+    /// The listing plus the exited processes still lingering, except those
+    /// whose PID has already been reused
+    pub fn with_exited(&self, processes: &[ProcessInfo]) -> Vec<ProcessInfo> {
+        let live: HashSet<u32> = processes.iter().map(|p| p.pid).collect();
+        let mut shown = processes.to_vec();
+        shown.extend(
+            self.exited
+                .iter()
+                .map(|(p, _)| p)
+                .filter(|p| !live.contains(&p.pid))
+                .cloned(),
+        );
+        shown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockBackend, ProcessBackend, RefreshEngine};
+
+    /// Replays the fixture's refreshes, one per `processes()` call
+    fn backend() -> MockBackend {
+        MockBackend::from_json(include_str!("../fixtures/processes.json")).expect("fixture parses")
+    }
+
+    #[test]
+    fn started_and_exited_processes_linger_then_clear() {
+        let backend = backend();
+        let mut engine = RefreshEngine::new(ProcessInfo::key);
+        engine.reset(backend.processes());
+        let events = engine.update(backend.processes());
+        let listing = engine.items().to_vec();
+
+        let linger = Duration::from_secs(5);
+        let start = Instant::now();
+        let mut recent = RecentChanges::default();
+        recent.record(&events, start, linger);
+
+        // notepad (4120) exited and stays visible; calc (6100) started
+        let shown = recent.with_exited(&listing);
+        assert_eq!(shown.len(), listing.len() + 1);
+        let highlight_of = |pid: u32| {
+            let p = shown.iter().find(|p| p.pid == pid).unwrap();
+            recent.highlight(p)
+        };
+        assert_eq!(highlight_of(4120), RowHighlight::Exited);
+        assert_eq!(highlight_of(6100), RowHighlight::Started);
+        assert_eq!(highlight_of(5220), RowHighlight::Normal);
+        assert!(recent.lingers(4120, &listing));
+        assert!(!recent.lingers(6100, &listing));
+        assert!(!recent.lingers(5220, &listing));
+
+        recent.record(&[], start + Duration::from_secs(3), linger);
+        assert_eq!(recent.with_exited(&listing).len(), listing.len() + 1);
+
+        recent.record(&[], start + Duration::from_secs(6), linger);
+        assert!(recent.is_empty());
+        assert_eq!(recent.with_exited(&listing).len(), listing.len());
+    }
+
+    #[test]
+    fn highlights_expire_without_a_refresh() {
+        let backend = backend();
+        let mut engine = RefreshEngine::new(ProcessInfo::key);
+        engine.reset(backend.processes());
+        let events = engine.update(backend.processes());
+
+        let linger = Duration::from_secs(5);
+        let start = Instant::now();
+        let mut recent = RecentChanges::default();
+        recent.record(&events, start, linger);
+
+        // Nothing due yet, so the timer leaves the rows alone
+        assert!(!recent.has_expired(start + Duration::from_secs(4), linger));
+
+        // A shorter period picked after the exit applies to it too
+        let shorter = Duration::from_secs(1);
+        assert!(recent.has_expired(start + Duration::from_secs(2), shorter));
+        recent.prune(start + Duration::from_secs(2), shorter);
+        assert!(recent.is_empty());
+        assert!(!recent.has_expired(start + Duration::from_secs(2), shorter));
+    }
+}
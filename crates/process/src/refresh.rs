@@ -0,0 +1,164 @@
+//! Incremental refresh: diff each new listing against the previous one
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// What happened to one entity between two refreshes
+#[derive(Clone, Debug, PartialEq)]
+pub enum RefreshEvent<T> {
+    /// Listed now but not in the previous listing
+    Added(T),
+    /// Listed previously but gone now
+    Removed(T),
+    /// Listed both times with different values
+    Changed { old: T, new: T },
+}
+
+impl<T> RefreshEvent<T> {
+    /// The entity as it is now, or as it was last seen if it was removed
+    pub fn item(&self) -> &T {
+        match self {
+            RefreshEvent::Added(item) | RefreshEvent::Removed(item) => item,
+            RefreshEvent::Changed { new, .. } => new,
+        }
+    }
+}
+
+/// Keeps the previous listing of processes, connections or services and
+/// turns each new listing into [`RefreshEvent`]s.
+///
+/// Entities are matched by `key`, e.g. [`ProcessInfo::key`](crate::ProcessInfo::key)
+/// so that a reused PID is a removal plus an addition. Entities sharing a key
+/// are treated as one.
+pub struct RefreshEngine<T, K> {
+    key: fn(&T) -> K,
+    items: Vec<T>,
+}
+
+impl<T: Clone + PartialEq, K: Eq + Hash> RefreshEngine<T, K> {
+    pub fn new(key: fn(&T) -> K) -> Self {
+        Self {
+            key,
+            items: Vec::new(),
+        }
+    }
+
+    /// The latest listing, in the order the source returned it
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Replace the listing without reporting anything, for the first load or
+    /// after switching to a different source
+    pub fn reset(&mut self, items: Vec<T>) {
+        self.items = items;
+    }
+
+    /// Replace the listing and return what changed: removals in the previous
+    /// listing's order, then additions and changes in the new order
+    pub fn update(&mut self, items: Vec<T>) -> Vec<RefreshEvent<T>> {
+        let key = self.key;
+        let mut previous: HashMap<K, &T> = HashMap::with_capacity(self.items.len());
+        for item in &self.items {
+            previous.entry(key(item)).or_insert(item);
+        }
+        let current: HashSet<K> = items.iter().map(key).collect();
+
+        let mut events = Vec::new();
+        let mut removed = HashSet::new();
+        for item in &self.items {
+            let item_key = key(item);
+            if !current.contains(&item_key) && removed.insert(item_key) {
+                events.push(RefreshEvent::Removed(item.clone()));
+            }
+        }
+
+        let mut seen = HashSet::with_capacity(items.len());
+        for item in &items {
+            let item_key = key(item);
+            match previous.get(&item_key) {
+                _ if seen.contains(&item_key) => {}
+                None => events.push(RefreshEvent::Added(item.clone())),
+                Some(old) if *old != item => events.push(RefreshEvent::Changed {
+                    old: (*old).clone(),
+                    new: item.clone(),
+                }),
+                Some(_) => {}
+            }
+            seen.insert(item_key);
+        }
+
+        self.items = items;
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockBackend, ProcessBackend, ProcessInfo};
+
+    fn engine() -> RefreshEngine<ProcessInfo, (u32, Option<u64>)> {
+        RefreshEngine::new(ProcessInfo::key)
+    }
+
+    fn summary(events: &[RefreshEvent<ProcessInfo>]) -> Vec<(&'static str, u32)> {
+        events
+            .iter()
+            .map(|event| match event {
+                RefreshEvent::Added(p) => ("added", p.pid),
+                RefreshEvent::Removed(p) => ("removed", p.pid),
+                RefreshEvent::Changed { new, .. } => ("changed", new.pid),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_started_exited_and_changed_processes() {
+        let backend = MockBackend::from_json(include_str!("../fixtures/processes.json")).unwrap();
+        let mut engine = engine();
+        engine.reset(backend.processes());
+
+        let events = engine.update(backend.processes());
+        assert_eq!(
+            summary(&events),
+            vec![("removed", 4120), ("changed", 5220), ("added", 6100)]
+        );
+        let RefreshEvent::Changed { old, new } = &events[1] else {
+            panic!("expected a change");
+        };
+        assert_eq!((old.cpu_usage, new.cpu_usage), (12.5, 27.0));
+        assert_eq!(engine.items().last().unwrap().name, "calc.exe");
+
+        // The fixture sticks at its last snapshot, so nothing changes again
+        assert!(engine.update(backend.processes()).is_empty());
+    }
+
+    #[test]
+    fn reused_pid_is_an_exit_and_a_start() {
+        let backend = MockBackend::from_json(include_str!("../fixtures/processes.json")).unwrap();
+        let before = backend.processes();
+        let mut after = before.clone();
+        after[6].start_time = Some(1_760_000_500_000);
+
+        let mut engine = engine();
+        engine.reset(before);
+        assert_eq!(
+            summary(&engine.update(after)),
+            vec![("removed", 4120), ("added", 4120)]
+        );
+    }
+
+    #[test]
+    fn duplicate_keys_count_once() {
+        let mut engine = RefreshEngine::new(|n: &(u32, char)| n.0);
+        assert_eq!(
+            engine.update(vec![(1, 'a'), (1, 'b'), (2, 'c')]),
+            vec![RefreshEvent::Added((1, 'a')), RefreshEvent::Added((2, 'c'))]
+        );
+        assert_eq!(
+            engine.update(vec![(2, 'c')]),
+            vec![RefreshEvent::Removed((1, 'a'))]
+        );
+    }
+}
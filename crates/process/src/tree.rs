@@ -0,0 +1,205 @@
+//! Parent/child process tree built from a process snapshot
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ProcessInfo;
+
+/// A process and the processes it created
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProcessTreeNode {
+    pub process: ProcessInfo,
+    pub children: Vec<ProcessTreeNode>,
+    /// The parent PID names a process that has exited, or whose PID now
+    /// belongs to a newer process
+    pub orphan: bool,
+}
+
+impl ProcessTreeNode {
+    /// Number of processes below this node
+    pub fn descendant_count(&self) -> usize {
+        self.children
+            .iter()
+            .map(|child| 1 + child.descendant_count())
+            .sum()
+    }
+}
+
+/// Whether `parent` can be the creator of `child`.
+///
+/// A process that started after the child holds a reused PID. Missing start
+/// times (access denied) are given the benefit of the doubt.
+fn is_parent_of(parent: &ProcessInfo, child: &ProcessInfo) -> bool {
+    match (parent.start_time, child.start_time) {
+        (Some(parent_start), Some(child_start)) => parent_start <= child_start,
+        _ => true,
+    }
+}
+
+/// Nest processes under their parents.
+///
+/// Roots are processes without a parent (parent PID 0 or their own PID) and
+/// orphans whose parent exited or whose parent PID was reused. Children keep
+/// the order of `processes`. Parent loops, which only stale snapshots can
+/// produce, are broken at the first process of the loop in snapshot order.
+pub fn build_process_tree(processes: Vec<ProcessInfo>) -> Vec<ProcessTreeNode> {
+    let index_of: HashMap<u32, usize> = processes
+        .iter()
+        .enumerate()
+        .map(|(index, p)| (p.pid, index))
+        .collect();
+
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); processes.len()];
+    let mut roots = Vec::new();
+    let mut orphan = vec![false; processes.len()];
+
+    for (index, process) in processes.iter().enumerate() {
+        if process.parent_pid == 0 || process.parent_pid == process.pid {
+            roots.push(index);
+            continue;
+        }
+        match index_of.get(&process.parent_pid) {
+            Some(&parent) if is_parent_of(&processes[parent], process) => {
+                children[parent].push(index)
+            }
+            _ => {
+                orphan[index] = true;
+                roots.push(index);
+            }
+        }
+    }
+
+    let mut slots: Vec<Option<ProcessInfo>> = processes.into_iter().map(Some).collect();
+    let mut tree: Vec<ProcessTreeNode> = roots
+        .into_iter()
+        .map(|root| build_node(root, &children, &orphan, &mut slots))
+        .collect();
+
+    // Anything not reached from a root is part of a parent loop
+    for index in 0..slots.len() {
+        if slots[index].is_some() {
+            tree.push(build_node(index, &children, &orphan, &mut slots));
+        }
+    }
+
+    tree
+}
+
+fn build_node(
+    index: usize,
+    children: &[Vec<usize>],
+    orphan: &[bool],
+    slots: &mut [Option<ProcessInfo>],
+) -> ProcessTreeNode {
+    let process = slots[index].take().expect("process visited twice");
+    let mut nodes = Vec::new();
+    for &child in &children[index] {
+        if slots[child].is_some() {
+            nodes.push(build_node(child, children, orphan, slots));
+        }
+    }
+
+    ProcessTreeNode {
+        process,
+        children: nodes,
+        orphan: orphan[index],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProcessStats;
+
+    fn process(pid: u32, name: &str, parent_pid: u32, start_time: Option<u64>) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            name: name.to_string(),
+            memory_mb: 0.0,
+            thread_count: 1,
+            exe_path: String::new(),
+            cpu_usage: 0.0,
+            company: None,
+            description: None,
+            version: None,
+            parent_pid,
+            session_id: None,
+            user: None,
+            user_sid: None,
+            integrity_level: None,
+            command_line: None,
+            start_time,
+            arch: None,
+            is_wow64: false,
+            stats: ProcessStats::default(),
+        }
+    }
+
+    /// Boot processes plus a desktop session whose explorer.exe parent
+    /// (userinit.exe, PID 3700) has exited
+    fn fixture() -> Vec<ProcessInfo> {
+        vec![
+            process(0, "System Idle Process", 0, None),
+            process(4, "System", 0, Some(1_760_000_000_000)),
+            process(120, "Registry", 4, Some(1_760_000_000_100)),
+            process(560, "smss.exe", 4, Some(1_760_000_002_000)),
+            process(700, "csrss.exe", 560, Some(1_760_000_004_000)),
+            process(3760, "explorer.exe", 3700, Some(1_760_000_060_000)),
+            process(4120, "notepad.exe", 3760, Some(1_760_000_120_000)),
+            process(5220, "chrome.exe", 3760, Some(1_760_000_180_000)),
+            process(5300, "chrome.exe", 5220, Some(1_760_000_181_000)),
+        ]
+    }
+
+    fn pids(nodes: &[ProcessTreeNode]) -> Vec<u32> {
+        nodes.iter().map(|node| node.process.pid).collect()
+    }
+
+    #[test]
+    fn nests_children_under_parents() {
+        let tree = build_process_tree(fixture());
+        // System Idle, System, and explorer.exe whose parent has exited
+        assert_eq!(pids(&tree), vec![0, 4, 3760]);
+
+        let system = &tree[1];
+        assert!(!system.orphan);
+        assert_eq!(pids(&system.children), vec![120, 560]);
+        assert_eq!(system.descendant_count(), 3);
+
+        let explorer = &tree[2];
+        assert!(explorer.orphan);
+        assert_eq!(pids(&explorer.children), vec![4120, 5220]);
+        assert_eq!(pids(&explorer.children[1].children), vec![5300]);
+        assert_eq!(explorer.descendant_count(), 3);
+    }
+
+    #[test]
+    fn reused_parent_pid_makes_an_orphan() {
+        let mut processes = fixture();
+        // explorer.exe's PID now belongs to a process younger than notepad.exe
+        let explorer = processes.iter_mut().find(|p| p.pid == 3760).unwrap();
+        explorer.start_time = explorer.start_time.map(|start| start + 10_000_000);
+
+        let tree = build_process_tree(processes);
+        let roots = pids(&tree);
+        assert!(roots.contains(&4120));
+        assert!(roots.contains(&5220));
+        assert_eq!(tree.iter().filter(|node| node.orphan).count(), 3);
+    }
+
+    #[test]
+    fn parent_loop_is_broken() {
+        let mut processes = fixture();
+        processes.retain(|p| p.pid == 4120 || p.pid == 5220);
+        processes[0].parent_pid = 5220;
+        processes[1].parent_pid = 4120;
+        processes[0].start_time = None;
+        processes[1].start_time = None;
+
+        let tree = build_process_tree(processes);
+        assert_eq!(pids(&tree), vec![4120]);
+        assert_eq!(pids(&tree[0].children), vec![5220]);
+        assert_eq!(tree[0].descendant_count(), 1);
+    }
+}
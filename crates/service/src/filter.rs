@@ -0,0 +1,195 @@
+//! Filtering and ordering of service listings for the services table
+
+use std::cmp::Ordering;
+
+use crate::{ServiceInfo, ServiceStartType, ServiceStatus};
+
+/// Sort column for service table
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ServiceSortColumn {
+    Name,
+    DisplayName,
+    Status,
+    StartType,
+    Pid,
+    BinaryPath,
+    Description,
+}
+
+/// Sort order
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// Whether a service passes the status and start type dropdowns and the
+/// search box (`query` already lowercased)
+pub fn matches_service(
+    s: &ServiceInfo,
+    status_filter: &str,
+    start_type_filter: &str,
+    query: &str,
+) -> bool {
+    let status_match = match status_filter {
+        "running" => s.status == ServiceStatus::Running,
+        "stopped" => s.status == ServiceStatus::Stopped,
+        "paused" => s.status == ServiceStatus::Paused,
+        _ => true,
+    };
+
+    let start_match = match start_type_filter {
+        "auto" => s.start_type == ServiceStartType::Auto,
+        "manual" => s.start_type == ServiceStartType::Manual,
+        "disabled" => s.start_type == ServiceStartType::Disabled,
+        _ => true,
+    };
+
+    let search_match = query.is_empty()
+        || s.name.to_lowercase().contains(query)
+        || s.display_name.to_lowercase().contains(query)
+        || s.description.to_lowercase().contains(query)
+        || s.binary_path.to_lowercase().contains(query)
+        || s.pid.to_string().contains(query);
+
+    status_match && start_match && search_match
+}
+
+/// Order two services by the selected column
+pub fn compare_services(
+    a: &ServiceInfo,
+    b: &ServiceInfo,
+    column: ServiceSortColumn,
+    order: SortOrder,
+) -> Ordering {
+    let cmp = match column {
+        ServiceSortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        ServiceSortColumn::DisplayName => a
+            .display_name
+            .to_lowercase()
+            .cmp(&b.display_name.to_lowercase()),
+        ServiceSortColumn::Status => a.status.to_string().cmp(&b.status.to_string()),
+        ServiceSortColumn::StartType => a.start_type.to_string().cmp(&b.start_type.to_string()),
+        ServiceSortColumn::Pid => a.pid.cmp(&b.pid),
+        ServiceSortColumn::BinaryPath => a
+            .binary_path
+            .to_lowercase()
+            .cmp(&b.binary_path.to_lowercase()),
+        ServiceSortColumn::Description => a
+            .description
+            .to_lowercase()
+            .cmp(&b.description.to_lowercase()),
+    };
+    match order {
+        SortOrder::Ascending => cmp,
+        SortOrder::Descending => cmp.reverse(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockServices, ServiceBackend};
+
+    fn fixture() -> MockServices {
+        MockServices::from_json(include_str!("../fixtures/services.json")).expect("fixture parses")
+    }
+
+    fn names(services: &[ServiceInfo]) -> Vec<&str> {
+        services.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    fn filter(status: &str, start_type: &str, query: &str) -> Vec<String> {
+        fixture()
+            .services()
+            .unwrap()
+            .into_iter()
+            .filter(|s| matches_service(s, status, start_type, query))
+            .map(|s| s.name)
+            .collect()
+    }
+
+    fn sorted(column: ServiceSortColumn, order: SortOrder) -> Vec<ServiceInfo> {
+        let mut services = fixture().services().unwrap();
+        services.sort_by(|a, b| compare_services(a, b, column, order));
+        services
+    }
+
+    #[test]
+    fn filters_by_status_and_start_type() {
+        assert_eq!(filter("", "", "").len(), 7);
+        assert_eq!(filter("all", "all", "").len(), 7);
+        assert_eq!(filter("running", "", ""), vec!["Dhcp", "RpcSs"]);
+        assert_eq!(filter("paused", "", ""), vec!["Spooler"]);
+        assert_eq!(
+            filter("stopped", "", ""),
+            vec!["wuauserv", "RemoteRegistry", "edgeupdate"]
+        );
+        assert_eq!(filter("", "disabled", ""), vec!["RemoteRegistry"]);
+        assert_eq!(filter("stopped", "auto", ""), vec!["edgeupdate"]);
+    }
+
+    #[test]
+    fn searches_names_descriptions_paths_and_pid() {
+        assert_eq!(filter("", "", "svchost").len(), 5);
+        assert_eq!(filter("", "", "print"), vec!["Spooler"]);
+        assert_eq!(filter("", "", "bluetooth"), vec!["bthserv"]);
+        assert_eq!(filter("", "", "edgeupdate.exe"), vec!["edgeupdate"]);
+        assert_eq!(filter("", "", "2210"), vec!["Spooler"]);
+        assert_eq!(filter("running", "", "svchost"), vec!["Dhcp", "RpcSs"]);
+    }
+
+    #[test]
+    fn sorts_by_each_column() {
+        assert_eq!(
+            names(&sorted(ServiceSortColumn::Name, SortOrder::Ascending)),
+            vec![
+                "bthserv",
+                "Dhcp",
+                "edgeupdate",
+                "RemoteRegistry",
+                "RpcSs",
+                "Spooler",
+                "wuauserv"
+            ]
+        );
+        let by_display_name = sorted(ServiceSortColumn::DisplayName, SortOrder::Descending);
+        assert_eq!(names(&by_display_name)[..2], ["wuauserv", "RemoteRegistry"]);
+
+        let pids: Vec<u32> = sorted(ServiceSortColumn::Pid, SortOrder::Descending)
+            .iter()
+            .map(|s| s.pid)
+            .collect();
+        assert_eq!(pids, vec![2480, 2210, 1340, 1012, 0, 0, 0]);
+
+        // Status and start type sort by their display text
+        let statuses: Vec<String> = sorted(ServiceSortColumn::Status, SortOrder::Ascending)
+            .iter()
+            .map(|s| s.status.to_string())
+            .collect();
+        assert_eq!(
+            statuses[..4],
+            ["Paused", "Running", "Running", "Start Pending"]
+        );
+        assert!(statuses[4..].iter().all(|s| s == "Stopped"));
+        let start_types = sorted(ServiceSortColumn::StartType, SortOrder::Ascending);
+        assert_eq!(start_types[0].start_type, ServiceStartType::Auto);
+        assert_eq!(start_types[6].start_type, ServiceStartType::Manual);
+    }
+
+    #[test]
+    fn replays_refreshes() {
+        let backend = fixture();
+        let before = backend.services().unwrap();
+        let after = backend.services().unwrap();
+        let running = |services: &[ServiceInfo]| {
+            services
+                .iter()
+                .filter(|s| matches_service(s, "running", "", ""))
+                .count()
+        };
+        assert_eq!(running(&before), 2);
+        assert_eq!(running(&after), 4);
+        assert_eq!(backend.services().unwrap(), after);
+    }
+}
@@ -0,0 +1,38 @@
+//! Linux stand-in: there is no Service Control Manager, so every operation
+//! fails with [`ServiceError::Unsupported`]
+
+use crate::{ServiceBackend, ServiceError, ServiceInfo, ServiceStartType};
+
+/// Backend that reports service enumeration as unsupported
+pub struct LinuxBackend;
+
+impl ServiceBackend for LinuxBackend {
+    fn services(&self) -> Result<Vec<ServiceInfo>, ServiceError> {
+        Err(ServiceError::Unsupported("Service enumeration"))
+    }
+}
+
+/// Unsupported: start a service by name
+pub fn start_service(_name: &str) -> Result<(), ServiceError> {
+    Err(ServiceError::Unsupported("Starting a service"))
+}
+
+/// Unsupported: stop a service by name
+pub fn stop_service(_name: &str) -> Result<(), ServiceError> {
+    Err(ServiceError::Unsupported("Stopping a service"))
+}
+
+/// Unsupported: delete a service by name
+pub fn delete_service(_name: &str) -> Result<(), ServiceError> {
+    Err(ServiceError::Unsupported("Deleting a service"))
+}
+
+/// Unsupported: create a new service
+pub fn create_service(
+    _name: &str,
+    _display_name: &str,
+    _binary_path: &str,
+    _start_type: ServiceStartType,
+) -> Result<(), ServiceError> {
+    Err(ServiceError::Unsupported("Creating a service"))
+}
@@ -0,0 +1,43 @@
+//! In-memory backend that replays recorded service lists
+
+use std::sync::Mutex;
+
+use crate::{ServiceBackend, ServiceError, ServiceInfo};
+
+/// Backend that serves a fixed sequence of service lists.
+///
+/// Each call to [`ServiceBackend::services`] moves to the next list and
+/// stays on the last one once the sequence is exhausted.
+pub struct MockServices {
+    snapshots: Vec<Vec<ServiceInfo>>,
+    /// Index of the list the next call returns
+    cursor: Mutex<usize>,
+}
+
+impl MockServices {
+    pub fn new(snapshots: Vec<Vec<ServiceInfo>>) -> Self {
+        Self {
+            snapshots,
+            cursor: Mutex::new(0),
+        }
+    }
+
+    /// Load a JSON array of service lists
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+
+    /// Go back to the first list
+    pub fn rewind(&self) {
+        *self.cursor.lock().unwrap() = 0;
+    }
+}
+
+impl ServiceBackend for MockServices {
+    fn services(&self) -> Result<Vec<ServiceInfo>, ServiceError> {
+        let mut cursor = self.cursor.lock().unwrap();
+        let index = (*cursor).min(self.snapshots.len().saturating_sub(1));
+        *cursor = index + 1;
+        Ok(self.snapshots.get(index).cloned().unwrap_or_default())
+    }
+}
@@ -0,0 +1,529 @@
+//! What changed between two snapshots
+//!
+//! Processes are matched by PID and start time, so a PID reused by a new
+//! process shows up as one exit and one start. Module and memory changes are
+//! only reported for processes present in both snapshots, and only when both
+//! snapshots managed to capture that process's modules or memory map.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use network::{NetworkConnection, Protocol, TcpState};
+use process::{
+    get_memory_protect_name, MemoryRegionInfo, ModuleInfo, ProcessInfo, MEM_COMMIT, PAGE_EXECUTE,
+    PAGE_EXECUTE_READ, PAGE_EXECUTE_READWRITE, PAGE_EXECUTE_WRITECOPY,
+};
+use serde::{Deserialize, Serialize};
+use service::{ServiceStartType, ServiceStatus};
+
+use crate::Snapshot;
+
+const PAGE_EXECUTE_ANY: u32 =
+    PAGE_EXECUTE | PAGE_EXECUTE_READ | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY;
+
+/// Differences from an earlier snapshot to a later one
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    /// Processes only in the later snapshot
+    pub new_processes: Vec<ProcessInfo>,
+    /// Processes only in the earlier snapshot
+    pub exited_processes: Vec<ProcessInfo>,
+    /// Modules loaded or unloaded, per process
+    pub module_changes: Vec<ModuleChanges>,
+    /// Listening TCP sockets and bound UDP endpoints only in the later snapshot
+    pub new_listening_ports: Vec<NetworkConnection>,
+    /// Services whose status or start type differs
+    pub service_changes: Vec<ServiceChange>,
+    /// Committed executable regions that were missing or not executable before
+    pub new_executable_regions: Vec<ExecutableRegion>,
+}
+
+/// Modules that appeared in or disappeared from one process
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModuleChanges {
+    pub pid: u32,
+    pub process_name: String,
+    pub loaded: Vec<ModuleInfo>,
+    pub unloaded: Vec<ModuleInfo>,
+}
+
+/// A service present in both snapshots whose state differs
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ServiceChange {
+    pub name: String,
+    pub display_name: String,
+    pub old_status: ServiceStatus,
+    pub new_status: ServiceStatus,
+    pub old_start_type: ServiceStartType,
+    pub new_start_type: ServiceStartType,
+}
+
+/// An executable memory region in the later snapshot
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExecutableRegion {
+    pub pid: u32,
+    pub process_name: String,
+    pub region: MemoryRegionInfo,
+    /// Protection at the same base address before, if a region started there
+    pub old_protect: Option<u32>,
+}
+
+/// Category of a [`Change`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangeKind {
+    ProcessStarted,
+    ProcessExited,
+    ModuleLoaded,
+    ModuleUnloaded,
+    PortOpened,
+    ServiceChanged,
+    ExecutableMemory,
+}
+
+impl ChangeKind {
+    /// Every category, in display order
+    pub const ALL: [ChangeKind; 7] = [
+        ChangeKind::ProcessStarted,
+        ChangeKind::ProcessExited,
+        ChangeKind::ModuleLoaded,
+        ChangeKind::ModuleUnloaded,
+        ChangeKind::PortOpened,
+        ChangeKind::ServiceChanged,
+        ChangeKind::ExecutableMemory,
+    ];
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ChangeKind::ProcessStarted => "Process started",
+            ChangeKind::ProcessExited => "Process exited",
+            ChangeKind::ModuleLoaded => "Module loaded",
+            ChangeKind::ModuleUnloaded => "Module unloaded",
+            ChangeKind::PortOpened => "Port opened",
+            ChangeKind::ServiceChanged => "Service changed",
+            ChangeKind::ExecutableMemory => "Executable memory",
+        };
+        f.write_str(name)
+    }
+}
+
+/// One line of a diff, flattened for listings and exports
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// Process the change belongs to, if any
+    pub pid: Option<u32>,
+    /// Process name, or the service name for service changes
+    pub name: String,
+    pub detail: String,
+}
+
+impl Snapshot {
+    /// What changed from `self` (earlier) to `later`
+    pub fn diff(&self, later: &Snapshot) -> SnapshotDiff {
+        let before = process_keys(self);
+        let after = process_keys(later);
+        let in_both: Vec<&ProcessInfo> = later
+            .processes
+            .iter()
+            .filter(|p| before.contains(&p.key()))
+            .collect();
+
+        SnapshotDiff {
+            new_processes: later
+                .processes
+                .iter()
+                .filter(|p| !before.contains(&p.key()))
+                .cloned()
+                .collect(),
+            exited_processes: self
+                .processes
+                .iter()
+                .filter(|p| !after.contains(&p.key()))
+                .cloned()
+                .collect(),
+            module_changes: in_both
+                .iter()
+                .filter_map(|p| module_changes(p, self.modules(p.pid)?, later.modules(p.pid)?))
+                .collect(),
+            new_listening_ports: new_listening_ports(&self.connections, &later.connections),
+            service_changes: service_changes(self, later),
+            new_executable_regions: in_both
+                .iter()
+                .filter_map(|p| {
+                    let (before, after) =
+                        (self.memory_regions(p.pid)?, later.memory_regions(p.pid)?);
+                    Some(executable_regions(p, before, after))
+                })
+                .flatten()
+                .collect(),
+        }
+    }
+}
+
+impl SnapshotDiff {
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.new_processes.is_empty()
+            && self.exited_processes.is_empty()
+            && self.module_changes.is_empty()
+            && self.new_listening_ports.is_empty()
+            && self.service_changes.is_empty()
+            && self.new_executable_regions.is_empty()
+    }
+
+    /// Every change as one flat row, in the order of the fields above
+    pub fn changes(&self) -> Vec<Change> {
+        let mut changes = Vec::new();
+        for (kind, processes) in [
+            (ChangeKind::ProcessStarted, &self.new_processes),
+            (ChangeKind::ProcessExited, &self.exited_processes),
+        ] {
+            changes.extend(processes.iter().map(|p| Change {
+                kind,
+                pid: Some(p.pid),
+                name: p.name.clone(),
+                detail: p.exe_path.clone(),
+            }));
+        }
+        for process in &self.module_changes {
+            for (kind, modules) in [
+                (ChangeKind::ModuleLoaded, &process.loaded),
+                (ChangeKind::ModuleUnloaded, &process.unloaded),
+            ] {
+                changes.extend(modules.iter().map(|m| Change {
+                    kind,
+                    pid: Some(process.pid),
+                    name: process.process_name.clone(),
+                    detail: format!("{} at 0x{:X}", m.path, m.base_address),
+                }));
+            }
+        }
+        changes.extend(self.new_listening_ports.iter().map(|c| Change {
+            kind: ChangeKind::PortOpened,
+            pid: Some(c.pid),
+            name: c.process_name.clone(),
+            detail: format!("{} {}:{}", c.protocol, c.local_addr, c.local_port),
+        }));
+        changes.extend(self.service_changes.iter().map(|s| {
+            let mut parts = Vec::new();
+            if s.old_status != s.new_status {
+                parts.push(format!("status {} → {}", s.old_status, s.new_status));
+            }
+            if s.old_start_type != s.new_start_type {
+                parts.push(format!(
+                    "start type {} → {}",
+                    s.old_start_type, s.new_start_type
+                ));
+            }
+            Change {
+                kind: ChangeKind::ServiceChanged,
+                pid: None,
+                name: s.name.clone(),
+                detail: parts.join(", "),
+            }
+        }));
+        changes.extend(self.new_executable_regions.iter().map(|r| {
+            let new = get_memory_protect_name(r.region.protect);
+            let detail = match r.old_protect {
+                Some(old) => format!(
+                    "0x{:X} ({} bytes) {} → {}",
+                    r.region.base_address,
+                    r.region.region_size,
+                    get_memory_protect_name(old),
+                    new
+                ),
+                None => format!(
+                    "0x{:X} ({} bytes) {}",
+                    r.region.base_address, r.region.region_size, new
+                ),
+            };
+            Change {
+                kind: ChangeKind::ExecutableMemory,
+                pid: Some(r.pid),
+                name: r.process_name.clone(),
+                detail,
+            }
+        }));
+        changes
+    }
+}
+
+fn process_keys(snapshot: &Snapshot) -> HashSet<(u32, Option<u64>)> {
+    snapshot.processes.iter().map(ProcessInfo::key).collect()
+}
+
+fn module_changes(
+    process: &ProcessInfo,
+    before: &[ModuleInfo],
+    after: &[ModuleInfo],
+) -> Option<ModuleChanges> {
+    let key = |m: &ModuleInfo| (m.base_address, m.path.to_lowercase());
+    let before_keys: HashSet<_> = before.iter().map(key).collect();
+    let after_keys: HashSet<_> = after.iter().map(key).collect();
+
+    let loaded: Vec<ModuleInfo> = after
+        .iter()
+        .filter(|m| !before_keys.contains(&key(m)))
+        .cloned()
+        .collect();
+    let unloaded: Vec<ModuleInfo> = before
+        .iter()
+        .filter(|m| !after_keys.contains(&key(m)))
+        .cloned()
+        .collect();
+    if loaded.is_empty() && unloaded.is_empty() {
+        return None;
+    }
+    Some(ModuleChanges {
+        pid: process.pid,
+        process_name: process.name.clone(),
+        loaded,
+        unloaded,
+    })
+}
+
+/// Listening TCP sockets and all UDP endpoints, which have no connection state
+fn is_listening(connection: &NetworkConnection) -> bool {
+    match connection.protocol {
+        Protocol::Tcp => connection.state == Some(TcpState::Listen),
+        Protocol::Udp => true,
+    }
+}
+
+fn new_listening_ports(
+    before: &[NetworkConnection],
+    after: &[NetworkConnection],
+) -> Vec<NetworkConnection> {
+    let key = |c: &NetworkConnection| {
+        (
+            c.protocol == Protocol::Tcp,
+            c.local_addr.clone(),
+            c.local_port,
+        )
+    };
+    let open: HashSet<_> = before.iter().filter(|c| is_listening(c)).map(key).collect();
+    after
+        .iter()
+        .filter(|c| is_listening(c) && !open.contains(&key(c)))
+        .cloned()
+        .collect()
+}
+
+fn service_changes(before: &Snapshot, after: &Snapshot) -> Vec<ServiceChange> {
+    let old: HashMap<&str, _> = before
+        .services
+        .iter()
+        .map(|s| (s.name.as_str(), s))
+        .collect();
+    after
+        .services
+        .iter()
+        .filter_map(|new| {
+            let old = old.get(new.name.as_str())?;
+            if old.status == new.status && old.start_type == new.start_type {
+                return None;
+            }
+            Some(ServiceChange {
+                name: new.name.clone(),
+                display_name: new.display_name.clone(),
+                old_status: old.status,
+                new_status: new.status,
+                old_start_type: old.start_type,
+                new_start_type: new.start_type,
+            })
+        })
+        .collect()
+}
+
+fn is_executable(region: &MemoryRegionInfo) -> bool {
+    region.state == MEM_COMMIT && region.protect & PAGE_EXECUTE_ANY != 0
+}
+
+fn executable_regions(
+    process: &ProcessInfo,
+    before: &[MemoryRegionInfo],
+    after: &[MemoryRegionInfo],
+) -> Vec<ExecutableRegion> {
+    let old: HashMap<usize, &MemoryRegionInfo> =
+        before.iter().map(|r| (r.base_address, r)).collect();
+    after
+        .iter()
+        .filter(|region| is_executable(region))
+        .filter_map(|region| {
+            let previous = old.get(&region.base_address);
+            if previous.is_some_and(|r| is_executable(r)) {
+                return None;
+            }
+            Some(ExecutableRegion {
+                pid: process.pid,
+                process_name: process.name.clone(),
+                region: region.clone(),
+                old_protect: previous.map(|r| r.protect),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata;
+
+    fn region(base_address: usize, protect: u32) -> MemoryRegionInfo {
+        MemoryRegionInfo {
+            base_address,
+            allocation_base: base_address,
+            region_size: 0x1000,
+            state: MEM_COMMIT,
+            mem_type: process::MEM_PRIVATE,
+            protect,
+            allocation_protect: protect,
+        }
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_changes() {
+        let snapshot = testdata::snapshot(0);
+        let diff = snapshot.diff(&snapshot);
+        assert!(diff.is_empty());
+        assert!(diff.changes().is_empty());
+    }
+
+    #[test]
+    fn processes_ports_and_services_between_recordings() {
+        let diff = testdata::snapshot(0).diff(&testdata::snapshot(1));
+
+        let started: Vec<u32> = diff.new_processes.iter().map(|p| p.pid).collect();
+        let exited: Vec<u32> = diff.exited_processes.iter().map(|p| p.pid).collect();
+        assert_eq!(started, [6100]);
+        assert_eq!(exited, [4120]);
+
+        // The new connection is an outbound one, not a listener
+        assert!(diff.new_listening_ports.is_empty());
+
+        let services: Vec<(&str, ServiceStatus)> = diff
+            .service_changes
+            .iter()
+            .map(|s| (s.name.as_str(), s.new_status))
+            .collect();
+        assert_eq!(
+            services,
+            [
+                ("wuauserv", ServiceStatus::Running),
+                ("bthserv", ServiceStatus::Running)
+            ]
+        );
+    }
+
+    #[test]
+    fn reused_pid_is_an_exit_and_a_start() {
+        let before = testdata::snapshot(0);
+        let mut after = before.clone();
+        after.processes[6].start_time = Some(1_760_000_500_000);
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.new_processes.len(), 1);
+        assert_eq!(diff.exited_processes.len(), 1);
+        assert_eq!(diff.new_processes[0].pid, diff.exited_processes[0].pid);
+        // Details of a different process are not compared
+        assert!(diff.module_changes.is_empty());
+    }
+
+    #[test]
+    fn modules_ports_and_executable_memory() {
+        let before = testdata::snapshot(0);
+        let mut after = before.clone();
+        let pid = 4120;
+
+        let modules = after.modules.get_mut(&pid).unwrap();
+        let unloaded = modules.pop().unwrap();
+        let mut loaded = unloaded.clone();
+        loaded.name = String::from("evil.dll");
+        loaded.path = String::from("C:\\Users\\Public\\evil.dll");
+        loaded.base_address = 0x1_8000_0000;
+        modules.push(loaded);
+
+        let mut listener = after.connections[0].clone();
+        listener.local_port = 4444;
+        listener.pid = pid;
+        after.connections.push(listener);
+
+        let regions = after.memory_regions.get_mut(&pid).unwrap();
+        let flipped = regions[0].base_address;
+        regions[0].protect = PAGE_EXECUTE_READ;
+        regions.push(region(0x2000_0000, PAGE_EXECUTE_READWRITE));
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.module_changes.len(), 1);
+        assert_eq!(diff.module_changes[0].loaded[0].name, "evil.dll");
+        assert_eq!(diff.module_changes[0].unloaded, [unloaded]);
+        assert_eq!(diff.new_listening_ports.len(), 1);
+        assert_eq!(diff.new_listening_ports[0].local_port, 4444);
+
+        let regions: Vec<(usize, Option<u32>)> = diff
+            .new_executable_regions
+            .iter()
+            .map(|r| (r.region.base_address, r.old_protect))
+            .collect();
+        assert_eq!(regions, [(flipped, Some(0x02)), (0x2000_0000, None)]);
+
+        let kinds: Vec<ChangeKind> = diff.changes().iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                ChangeKind::ModuleLoaded,
+                ChangeKind::ModuleUnloaded,
+                ChangeKind::PortOpened,
+                ChangeKind::ExecutableMemory,
+                ChangeKind::ExecutableMemory,
+            ]
+        );
+    }
+
+    #[test]
+    fn modules_not_captured_are_not_compared() {
+        let before = testdata::snapshot(0);
+        let pid = 4120;
+
+        // Capture failed later on: nothing was loaded or unloaded
+        let mut after = before.clone();
+        after.modules.remove(&pid);
+        assert!(before.diff(&after).module_changes.is_empty());
+        assert!(after.diff(&before).module_changes.is_empty());
+
+        // Captured but empty is still a real list
+        after.modules.insert(pid, Vec::new());
+        let diff = before.diff(&after);
+        assert_eq!(diff.module_changes.len(), 1);
+        assert_eq!(diff.module_changes[0].unloaded, before.modules[&pid]);
+    }
+
+    #[test]
+    fn memory_not_captured_is_not_compared() {
+        let before = testdata::snapshot(0);
+        let pid = *before.memory_regions.keys().next().unwrap();
+
+        // An earlier snapshot that could not read the process must not turn
+        // every executable region into new executable memory
+        let mut unreadable = before.clone();
+        unreadable.memory_regions.remove(&pid);
+        let mut after = before.clone();
+        after
+            .memory_regions
+            .get_mut(&pid)
+            .unwrap()
+            .push(region(0x2000_0000, PAGE_EXECUTE_READWRITE));
+        assert!(unreadable.diff(&after).new_executable_regions.is_empty());
+        assert!(after.diff(&unreadable).new_executable_regions.is_empty());
+
+        // Captured on both sides, the new region is reported
+        let diff = before.diff(&after);
+        assert_eq!(diff.new_executable_regions.len(), 1);
+        assert_eq!(
+            diff.new_executable_regions[0].region.base_address,
+            0x2000_0000
+        );
+    }
+}
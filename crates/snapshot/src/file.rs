@@ -0,0 +1,163 @@
+//! On-disk snapshot format: gzip-compressed JSON
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Deserialize;
+
+use crate::Snapshot;
+
+/// Layout version written by this build; older versions stay readable
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Extension used for snapshot files, without the dot
+pub const FILE_EXTENSION: &str = "dpsnap";
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Errors that can occur while saving or loading a snapshot
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    /// The file is not gzip-compressed, so it cannot be a snapshot
+    NotASnapshot,
+    /// The decompressed contents are not a valid snapshot
+    Corrupt(serde_json::Error),
+    /// The file was written by a newer version
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(e) => write!(f, "Failed to access snapshot file: {}", e),
+            SnapshotError::NotASnapshot => write!(f, "Not a snapshot file"),
+            SnapshotError::Corrupt(e) => write!(f, "Corrupt snapshot file: {}", e),
+            SnapshotError::UnsupportedVersion(version) => write!(
+                f,
+                "Snapshot format version {} is newer than supported version {}",
+                version, FORMAT_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<io::Error> for SnapshotError {
+    fn from(e: io::Error) -> Self {
+        SnapshotError::Io(e)
+    }
+}
+
+/// Just enough of a snapshot to check its version before a full parse
+#[derive(Deserialize)]
+struct Header {
+    format_version: u32,
+}
+
+impl Snapshot {
+    /// Write the snapshot to `path`, replacing any existing file
+    pub fn save(&self, path: &Path) -> Result<(), SnapshotError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a snapshot written by [`Snapshot::save`]
+    pub fn load(path: &Path) -> Result<Self, SnapshotError> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Compress the snapshot into `writer`
+    pub fn write_to<W: Write>(&self, writer: W) -> Result<(), SnapshotError> {
+        let mut encoder = GzEncoder::new(writer, Compression::default());
+        serde_json::to_writer(&mut encoder, self).map_err(io::Error::from)?;
+        encoder.finish()?;
+        Ok(())
+    }
+
+    /// Decompress and parse a snapshot from `reader`
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, SnapshotError> {
+        let mut compressed = Vec::new();
+        reader.read_to_end(&mut compressed)?;
+        if !compressed.starts_with(&GZIP_MAGIC) {
+            return Err(SnapshotError::NotASnapshot);
+        }
+
+        let mut json = Vec::new();
+        GzDecoder::new(compressed.as_slice()).read_to_end(&mut json)?;
+
+        let header: Header = serde_json::from_slice(&json).map_err(SnapshotError::Corrupt)?;
+        if header.format_version > FORMAT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(header.format_version));
+        }
+        serde_json::from_slice(&json).map_err(SnapshotError::Corrupt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata;
+
+    fn compressed(snapshot: &Snapshot) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        snapshot.write_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn round_trips_through_compression() {
+        let snapshot = testdata::snapshot(0);
+        let bytes = compressed(&snapshot);
+        assert!(bytes.starts_with(&GZIP_MAGIC));
+        assert!(bytes.len() < serde_json::to_vec(&snapshot).unwrap().len());
+        assert_eq!(Snapshot::read_from(bytes.as_slice()).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn saves_and_loads_files() {
+        let path = std::env::temp_dir().join(format!(
+            "dioprocess-snapshot-test-{}.{}",
+            std::process::id(),
+            FILE_EXTENSION
+        ));
+        let snapshot = testdata::snapshot(1);
+        snapshot.save(&path).unwrap();
+        let loaded = Snapshot::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), snapshot);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let json = serde_json::to_vec(&testdata::snapshot(0)).unwrap();
+        assert!(matches!(
+            Snapshot::read_from(json.as_slice()),
+            Err(SnapshotError::NotASnapshot)
+        ));
+
+        let bytes = compressed(&testdata::snapshot(0));
+        assert!(matches!(
+            Snapshot::read_from(&bytes[..bytes.len() / 2]),
+            Err(SnapshotError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let mut snapshot = testdata::snapshot(0);
+        snapshot.format_version = FORMAT_VERSION + 1;
+        assert!(matches!(
+            Snapshot::read_from(compressed(&snapshot).as_slice()),
+            Err(SnapshotError::UnsupportedVersion(v)) if v == FORMAT_VERSION + 1
+        ));
+    }
+}
@@ -0,0 +1,153 @@
+//! Whole-system snapshots
+//! Processes with their threads, modules, handles and memory maps, plus
+//! network connections and services, captured at one point in time and
+//! stored as a single compressed file for offline analysis
+
+mod diff;
+mod file;
+
+pub use diff::{Change, ChangeKind, ExecutableRegion, ModuleChanges, ServiceChange, SnapshotDiff};
+pub use file::{SnapshotError, FILE_EXTENSION, FORMAT_VERSION};
+
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use network::NetworkConnection;
+use process::{HandleInfo, MemoryRegionInfo, ModuleInfo, ProcessInfo, ThreadInfo};
+use serde::{Deserialize, Serialize};
+use service::ServiceInfo;
+
+/// State of a host at one point in time
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Version of the file layout this snapshot was written with
+    pub format_version: u32,
+    /// Capture time in milliseconds since the Unix epoch
+    pub taken_at: u64,
+    /// Name of the captured machine
+    pub host: String,
+    pub processes: Vec<ProcessInfo>,
+    /// Threads keyed by owning PID; like `modules`, a process has an entry
+    /// only if its threads could be read
+    #[serde(default)]
+    pub threads: HashMap<u32, Vec<ThreadInfo>>,
+    /// Modules keyed by owning PID; a process whose modules could not be
+    /// read has no entry, one that was read always has one, even if empty
+    #[serde(default)]
+    pub modules: HashMap<u32, Vec<ModuleInfo>>,
+    /// Handles keyed by owning PID; every process has an entry if the
+    /// system-wide handle table could be read, none if it could not
+    #[serde(default)]
+    pub handles: HashMap<u32, Vec<HandleInfo>>,
+    /// Memory regions keyed by owning PID; like `modules`, a process has an
+    /// entry only if its memory map could be read
+    #[serde(default)]
+    pub memory_regions: HashMap<u32, Vec<MemoryRegionInfo>>,
+    #[serde(default)]
+    pub connections: Vec<NetworkConnection>,
+    #[serde(default)]
+    pub services: Vec<ServiceInfo>,
+}
+
+impl Snapshot {
+    /// Record the live system.
+    ///
+    /// Walks every process, so this takes a few seconds on a busy host.
+    /// Processes that cannot be opened are kept without thread, module or
+    /// memory lists, so they are not mistaken for empty ones, and a connection
+    /// or service table that cannot be read is left empty.
+    pub fn capture() -> Self {
+        let processes = process::get_processes();
+
+        let mut threads = HashMap::new();
+        let mut modules = HashMap::new();
+        let mut memory_regions = HashMap::new();
+        for p in &processes {
+            let pid = p.pid;
+            if let Ok(process_threads) = process::get_process_threads(pid) {
+                threads.insert(pid, process_threads);
+            }
+            if let Ok(process_modules) = process::get_process_modules(pid) {
+                modules.insert(pid, process_modules);
+            }
+            if let Ok(regions) = process::get_process_memory_regions(pid) {
+                memory_regions.insert(pid, regions);
+            }
+        }
+        // One system-wide query, restricted to the processes listed above
+        let pids: HashSet<u32> = processes.iter().map(|p| p.pid).collect();
+        let handles = match process::get_all_process_handles() {
+            Ok(mut all) => pids
+                .iter()
+                .map(|pid| (*pid, all.remove(pid).unwrap_or_default()))
+                .collect(),
+            Err(_) => HashMap::new(),
+        };
+
+        Self {
+            format_version: FORMAT_VERSION,
+            taken_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            host: host_name(),
+            processes,
+            threads,
+            modules,
+            handles,
+            memory_regions,
+            connections: network::get_network_connections().unwrap_or_default(),
+            services: service::get_services().unwrap_or_default(),
+        }
+    }
+
+    /// The process with this PID, if it was running
+    pub fn process(&self, pid: u32) -> Option<&ProcessInfo> {
+        self.processes.iter().find(|p| p.pid == pid)
+    }
+
+    /// Threads of a process, or `None` if they could not be captured
+    pub fn threads(&self, pid: u32) -> Option<&[ThreadInfo]> {
+        self.threads.get(&pid).map(Vec::as_slice)
+    }
+
+    /// Modules of a process, or `None` if they could not be captured
+    pub fn modules(&self, pid: u32) -> Option<&[ModuleInfo]> {
+        self.modules.get(&pid).map(Vec::as_slice)
+    }
+
+    /// Handles of a process, or `None` if they could not be captured
+    pub fn handles(&self, pid: u32) -> Option<&[HandleInfo]> {
+        self.handles.get(&pid).map(Vec::as_slice)
+    }
+
+    /// Memory regions of a process, or `None` if they could not be captured
+    pub fn memory_regions(&self, pid: u32) -> Option<&[MemoryRegionInfo]> {
+        self.memory_regions.get(&pid).map(Vec::as_slice)
+    }
+}
+
+fn host_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod testdata;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uncaptured_details_are_none() {
+        let snapshot = testdata::snapshot(0);
+        assert_eq!(snapshot.process(4120).unwrap().name, "notepad.exe");
+        assert!(!snapshot.threads(4120).unwrap().is_empty());
+        assert_eq!(snapshot.handles(4120).unwrap().len(), 1);
+        assert!(snapshot.process(9999).is_none());
+        assert!(snapshot.modules(9999).is_none());
+        assert!(snapshot.memory_regions(4).is_none());
+    }
+}
@@ -0,0 +1,45 @@
+//! Snapshots assembled from the recorded process, network and service fixtures
+
+use std::collections::HashMap;
+
+use network::NetworkConnection;
+use process::{HandleInfo, ProcessSnapshot};
+use service::ServiceInfo;
+
+use crate::{Snapshot, FORMAT_VERSION};
+
+/// The `index`th recording of each fixture (0 or 1) as one snapshot
+pub fn snapshot(index: usize) -> Snapshot {
+    let processes: Vec<ProcessSnapshot> =
+        serde_json::from_str(include_str!("../../process/fixtures/processes.json")).unwrap();
+    let connections: Vec<Vec<NetworkConnection>> =
+        serde_json::from_str(include_str!("../../network/fixtures/connections.json")).unwrap();
+    let services: Vec<Vec<ServiceInfo>> =
+        serde_json::from_str(include_str!("../../service/fixtures/services.json")).unwrap();
+
+    let recorded = processes[index].clone();
+    Snapshot {
+        format_version: FORMAT_VERSION,
+        taken_at: 1_760_000_000_000 + index as u64 * 60_000,
+        host: String::from("DESKTOP-FIXTURE"),
+        handles: recorded
+            .threads
+            .keys()
+            .map(|&pid| {
+                let handle = HandleInfo {
+                    handle_value: 0x44,
+                    object_type_index: 37,
+                    object_type_name: String::from("File"),
+                    granted_access: 0x0012_019F,
+                };
+                (pid, vec![handle])
+            })
+            .collect::<HashMap<_, _>>(),
+        processes: recorded.processes,
+        threads: recorded.threads,
+        modules: recorded.modules,
+        memory_regions: recorded.memory_regions,
+        connections: connections[index].clone(),
+        services: services[index].clone(),
+    }
+}
@@ -1,14 +1,116 @@
 //! Main application component with routing
 
+use std::sync::Arc;
+
 use dioxus::prelude::*;
-use process::{format_uptime, get_system_stats};
+use process::{format_start_time, format_uptime, get_system_stats, start_history_sampler};
+use snapshot::{Snapshot, FILE_EXTENSION};
 
+use crate::components::DiffWindow;
 use crate::routes::Route;
+use crate::source;
+use crate::state::{DIFF_WINDOW_STATE, OPEN_SNAPSHOT};
 use crate::styles::CUSTOM_STYLES;
 
+/// Capture the live system and write it to a file picked by the user
+async fn save_snapshot() -> Option<String> {
+    let file = rfd::AsyncFileDialog::new()
+        .add_filter("DioProcess Snapshot", &[FILE_EXTENSION])
+        .set_file_name(format!("snapshot.{}", FILE_EXTENSION))
+        .set_title("Save Snapshot")
+        .save_file()
+        .await?;
+    let path = file.path().to_path_buf();
+    let result = tokio::task::spawn_blocking(move || {
+        let snapshot = Snapshot::capture();
+        snapshot
+            .save(&path)
+            .map(|()| (snapshot.processes.len(), path))
+    })
+    .await;
+    Some(match result {
+        Ok(Ok((count, path))) => format!(
+            "✓ Saved snapshot of {} processes to {}",
+            count,
+            path.display()
+        ),
+        Ok(Err(e)) => format!("✗ Snapshot failed: {}", e),
+        Err(e) => format!("✗ Snapshot failed: {}", e),
+    })
+}
+
+/// Load a snapshot file picked by the user and switch the views to it
+async fn open_snapshot() -> Option<String> {
+    let file = rfd::AsyncFileDialog::new()
+        .add_filter("DioProcess Snapshot", &[FILE_EXTENSION])
+        .set_title("Open Snapshot")
+        .pick_file()
+        .await?;
+    let path = file.path().to_path_buf();
+    let result = tokio::task::spawn_blocking(move || Snapshot::load(&path)).await;
+    match result {
+        Ok(Ok(snapshot)) => {
+            source::set_snapshot(Some(snapshot));
+            None
+        }
+        Ok(Err(e)) => Some(format!("✗ Cannot open snapshot: {}", e)),
+        Err(e) => Some(format!("✗ Cannot open snapshot: {}", e)),
+    }
+}
+
+/// Host and capture time of a saved snapshot
+fn snapshot_label(snapshot: &Snapshot) -> String {
+    format!(
+        "{} @ {}",
+        snapshot.host,
+        format_start_time(Some(snapshot.taken_at))
+    )
+}
+
+/// Compare a snapshot file picked by the user with the open snapshot, or with
+/// a fresh capture of the live system, and show the changes
+async fn compare_snapshot() -> Option<String> {
+    let file = rfd::AsyncFileDialog::new()
+        .add_filter("DioProcess Snapshot", &[FILE_EXTENSION])
+        .set_title("Compare With Snapshot")
+        .pick_file()
+        .await?;
+    let path = file.path().to_path_buf();
+    let open = OPEN_SNAPSHOT.read().clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let picked = Snapshot::load(&path)?;
+        let picked_label = snapshot_label(&picked);
+        let (current, current_label) = match open {
+            Some(snapshot) => {
+                let label = snapshot_label(&snapshot);
+                (snapshot, label)
+            }
+            None => (Arc::new(Snapshot::capture()), String::from("Live system")),
+        };
+        // Always diff from the older capture to the newer one
+        Ok::<_, snapshot::SnapshotError>(if picked.taken_at <= current.taken_at {
+            (picked_label, current_label, picked.diff(&current))
+        } else {
+            (current_label, picked_label, current.diff(&picked))
+        })
+    })
+    .await;
+    match result {
+        Ok(Ok((before, after, diff))) => {
+            *DIFF_WINDOW_STATE.write() = Some((before, after, Arc::new(diff)));
+            None
+        }
+        Ok(Err(e)) => Some(format!("✗ Cannot compare snapshots: {}", e)),
+        Err(e) => Some(format!("✗ Cannot compare snapshots: {}", e)),
+    }
+}
+
 /// Main application component
 #[component]
 pub fn App() -> Element {
+    // Record per-process history from launch, so graphs cover time before they were opened
+    use_hook(start_history_sampler);
+
     rsx! {
         Router::<Route> {}
     }
@@ -19,6 +121,8 @@ pub fn App() -> Element {
 pub fn Layout() -> Element {
     let mut system_stats = use_signal(|| get_system_stats());
     let mut about_popup = use_signal(|| false);
+    let mut snapshot_message = use_signal(String::new);
+    let mut snapshot_busy = use_signal(|| false);
     let route: Route = use_route();
 
     // Auto-refresh system stats every 3 seconds
@@ -31,6 +135,25 @@ pub fn Layout() -> Element {
 
     let stats = system_stats.read().clone();
     let version = option_env!("CARGO_PKG_VERSION").unwrap_or("unknown");
+    let open_snapshot_info = OPEN_SNAPSHOT.read().as_deref().map(|snapshot| {
+        (
+            snapshot.host.clone(),
+            format_start_time(Some(snapshot.taken_at)),
+            snapshot.processes.len(),
+            snapshot.connections.len(),
+            snapshot.services.len(),
+        )
+    });
+    let busy = *snapshot_busy.read();
+
+    // Show a snapshot outcome for a few seconds
+    let mut show_snapshot_message = move |message: String| {
+        snapshot_message.set(message);
+        spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+            snapshot_message.set(String::new());
+        });
+    };
 
     // Determine active tab
     let is_process_tab = matches!(route, Route::ProcessTab {});
@@ -101,38 +224,66 @@ pub fn Layout() -> Element {
                     }
                 }
 
-                // System Stats Bar
-                div { class: "stats-bar",
-                    div { class: "stat-item",
-                        span { class: "stat-label", "CPU" }
-                        div { class: "stat-bar",
-                            div {
-                                class: "stat-bar-fill stat-bar-cpu",
-                                style: "width: {stats.cpu_usage}%",
-                            }
+                // Snapshot banner replaces the live stats while offline
+                if let Some((host, taken_at, process_count, connection_count, service_count)) = open_snapshot_info.clone() {
+                    div { class: "stats-bar snapshot-banner",
+                        span { class: "snapshot-badge", "📂 SNAPSHOT" }
+                        div { class: "stat-item",
+                            span { class: "stat-label", "Host" }
+                            span { class: "stat-value stat-value-cyan", "{host}" }
+                        }
+                        div { class: "stat-item",
+                            span { class: "stat-label", "Taken" }
+                            span { class: "stat-value stat-value-green", "{taken_at}" }
+                        }
+                        div { class: "stat-item",
+                            span { class: "stat-label", "Processes" }
+                            span { class: "stat-value stat-value-yellow", "{process_count}" }
+                        }
+                        div { class: "stat-item",
+                            span { class: "stat-label", "Connections" }
+                            span { class: "stat-value stat-value-yellow", "{connection_count}" }
+                        }
+                        div { class: "stat-item",
+                            span { class: "stat-label", "Services" }
+                            span { class: "stat-value stat-value-yellow", "{service_count}" }
                         }
-                        span { class: "stat-value stat-value-cyan", "{stats.cpu_usage:.1}%" }
+                        span { class: "stat-item-right snapshot-read-only", "read-only" }
                     }
+                } else {
+                    // System Stats Bar
+                    div { class: "stats-bar",
+                        div { class: "stat-item",
+                            span { class: "stat-label", "CPU" }
+                            div { class: "stat-bar",
+                                div {
+                                    class: "stat-bar-fill stat-bar-cpu",
+                                    style: "width: {stats.cpu_usage}%",
+                                }
+                            }
+                            span { class: "stat-value stat-value-cyan", "{stats.cpu_usage:.1}%" }
+                        }
 
-                    div { class: "stat-item",
-                        span { class: "stat-label", "RAM" }
-                        div { class: "stat-bar",
-                            div {
-                                class: "stat-bar-fill stat-bar-ram",
-                                style: "width: {stats.memory_percent}%",
+                        div { class: "stat-item",
+                            span { class: "stat-label", "RAM" }
+                            div { class: "stat-bar",
+                                div {
+                                    class: "stat-bar-fill stat-bar-ram",
+                                    style: "width: {stats.memory_percent}%",
+                                }
                             }
+                            span { class: "stat-value stat-value-purple", "{stats.used_memory_gb:.1}/{stats.total_memory_gb:.1} GB" }
                         }
-                        span { class: "stat-value stat-value-purple", "{stats.used_memory_gb:.1}/{stats.total_memory_gb:.1} GB" }
-                    }
 
-                    div { class: "stat-item",
-                        span { class: "stat-label", "Uptime" }
-                        span { class: "stat-value stat-value-green", "{format_uptime(stats.uptime_seconds)}" }
-                    }
+                        div { class: "stat-item",
+                            span { class: "stat-label", "Uptime" }
+                            span { class: "stat-value stat-value-green", "{format_uptime(stats.uptime_seconds)}" }
+                        }
 
-                    div { class: "stat-item stat-item-right",
-                        span { class: "stat-label", "Processes" }
-                        span { class: "stat-value stat-value-yellow", "{stats.process_count}" }
+                        div { class: "stat-item stat-item-right",
+                            span { class: "stat-label", "Processes" }
+                            span { class: "stat-value stat-value-yellow", "{stats.process_count}" }
+                        }
                     }
                 }
 
@@ -153,6 +304,62 @@ pub fn Layout() -> Element {
                         class: if is_service_tab { "tab-item tab-active" } else { "tab-item" },
                         "⚙️ Services"
                     }
+
+                    div { class: "tab-bar-actions",
+                        if !snapshot_message.read().is_empty() {
+                            span { class: "snapshot-message", "{snapshot_message}" }
+                        }
+                        if open_snapshot_info.is_some() {
+                            button {
+                                class: "btn btn-secondary",
+                                onclick: move |_| source::set_snapshot(None),
+                                "✕ Close Snapshot"
+                            }
+                        } else {
+                            button {
+                                class: "btn btn-secondary",
+                                disabled: busy,
+                                title: "Capture processes, threads, modules, handles, memory maps, connections and services to a file",
+                                onclick: move |_| {
+                                    snapshot_busy.set(true);
+                                    spawn(async move {
+                                        if let Some(message) = save_snapshot().await {
+                                            show_snapshot_message(message);
+                                        }
+                                        snapshot_busy.set(false);
+                                    });
+                                },
+                                if busy { "⏳ Capturing..." } else { "💾 Save Snapshot" }
+                            }
+                        }
+                        button {
+                            class: "btn btn-secondary",
+                            disabled: busy,
+                            onclick: move |_| {
+                                spawn(async move {
+                                    if let Some(message) = open_snapshot().await {
+                                        show_snapshot_message(message);
+                                    }
+                                });
+                            },
+                            "📂 Open Snapshot"
+                        }
+                        button {
+                            class: "btn btn-secondary",
+                            disabled: busy,
+                            title: "Show what changed between a saved snapshot and the current view",
+                            onclick: move |_| {
+                                snapshot_busy.set(true);
+                                spawn(async move {
+                                    if let Some(message) = compare_snapshot().await {
+                                        show_snapshot_message(message);
+                                    }
+                                    snapshot_busy.set(false);
+                                });
+                            },
+                            "🔀 Compare"
+                        }
+                    }
                 }
 
                 // Content Area with Router Outlet
@@ -160,6 +367,10 @@ pub fn Layout() -> Element {
                     Outlet::<Route> {}
                 }
 
+                if let Some((before, after, diff)) = DIFF_WINDOW_STATE.read().clone() {
+                    DiffWindow { before: before, after: after, diff: diff }
+                }
+
                 if *about_popup.read() {
 
             div {
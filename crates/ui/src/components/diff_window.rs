@@ -0,0 +1,193 @@
+//! Snapshot diff window component
+
+use std::sync::Arc;
+
+use dioxus::prelude::*;
+use snapshot::{Change, ChangeKind, SnapshotDiff};
+
+use crate::helpers::{copy_to_clipboard, export_records};
+use crate::state::DIFF_WINDOW_STATE;
+
+/// CSS class colouring a change category
+fn kind_class(kind: ChangeKind) -> &'static str {
+    match kind {
+        ChangeKind::ProcessStarted | ChangeKind::ModuleLoaded | ChangeKind::PortOpened => {
+            "diff-kind diff-kind-added"
+        }
+        ChangeKind::ProcessExited | ChangeKind::ModuleUnloaded => "diff-kind diff-kind-removed",
+        ChangeKind::ServiceChanged => "diff-kind diff-kind-changed",
+        ChangeKind::ExecutableMemory => "diff-kind diff-kind-suspicious",
+    }
+}
+
+/// Diff Window component
+#[component]
+pub fn DiffWindow(before: String, after: String, diff: Arc<SnapshotDiff>) -> Element {
+    let changes = use_hook(|| diff.changes());
+    let mut kind_filter = use_signal(|| String::from("all"));
+    let mut filter_text = use_signal(String::new);
+    let mut status_message = use_signal(String::new);
+
+    let kind = kind_filter.read().clone();
+    let filter = filter_text.read().to_lowercase();
+    let change_list: Vec<Change> = changes
+        .iter()
+        .filter(|c| kind == "all" || format!("{:?}", c.kind) == kind)
+        .filter(|c| {
+            filter.is_empty()
+                || c.name.to_lowercase().contains(&filter)
+                || c.detail.to_lowercase().contains(&filter)
+                || c.pid.is_some_and(|pid| pid.to_string().contains(&filter))
+        })
+        .cloned()
+        .collect();
+    let change_count = change_list.len();
+    let total_changes = changes.len();
+    let export_changes = change_list.clone();
+
+    rsx! {
+        // Modal overlay
+        div {
+            class: "thread-modal-overlay",
+            onclick: move |_| {
+                *DIFF_WINDOW_STATE.write() = None;
+            },
+
+            // Modal window
+            div {
+                class: "thread-modal diff-modal",
+                onclick: move |e| e.stop_propagation(),
+
+                // Header
+                div {
+                    class: "thread-modal-header",
+                    div {
+                        class: "thread-modal-title",
+                        "🔀 Changes - {before} → {after}"
+                    }
+                    button {
+                        class: "thread-modal-close",
+                        onclick: move |_| {
+                            *DIFF_WINDOW_STATE.write() = None;
+                        },
+                        "✕"
+                    }
+                }
+
+                // Per-category totals
+                div {
+                    class: "diff-summary",
+                    span { class: "diff-kind-added", "+{diff.new_processes.len()} processes" }
+                    span { class: "diff-kind-removed", "−{diff.exited_processes.len()} processes" }
+                    span { "{diff.module_changes.len()} processes with module changes" }
+                    span { class: "diff-kind-added", "+{diff.new_listening_ports.len()} listening ports" }
+                    span { class: "diff-kind-changed", "{diff.service_changes.len()} services changed" }
+                    span { class: "diff-kind-suspicious", "{diff.new_executable_regions.len()} new executable regions" }
+                }
+
+                // Controls
+                div {
+                    class: "thread-controls",
+                    span { class: "thread-count", "Changes: {change_count}/{total_changes}" }
+
+                    input {
+                        class: "handle-filter-input",
+                        r#type: "text",
+                        placeholder: "Filter by PID, name or detail...",
+                        value: "{filter_text}",
+                        oninput: move |e| filter_text.set(e.value().clone()),
+                    }
+
+                    select {
+                        class: "filter-select",
+                        value: "{kind_filter}",
+                        onchange: move |e| kind_filter.set(e.value().clone()),
+                        option { value: "all", "All Changes" }
+                        for kind in ChangeKind::ALL {
+                            option { value: "{kind:?}", "{kind}" }
+                        }
+                    }
+
+                    button {
+                        class: "btn btn-small btn-primary",
+                        onclick: {
+                            let export_changes = export_changes.clone();
+                            move |_| {
+                                let export_changes = export_changes.clone();
+                                spawn(async move {
+                                    let message = export_records(
+                                        "Export Changes",
+                                        "snapshot_diff",
+                                        "changes",
+                                        move || export_changes,
+                                    )
+                                    .await;
+                                    if let Some(message) = message {
+                                        status_message.set(message);
+                                        spawn(async move {
+                                            tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                            status_message.set(String::new());
+                                        });
+                                    }
+                                });
+                            }
+                        },
+                        "💾 Export"
+                    }
+                }
+
+                // Status message
+                if !status_message.read().is_empty() {
+                    div { class: "thread-status-message", "{status_message}" }
+                }
+
+                // Change table
+                div {
+                    class: "thread-table-container",
+                    if total_changes == 0 {
+                        div { class: "diff-empty", "No changes between the two snapshots" }
+                    }
+                    table {
+                        class: "thread-table",
+                        thead {
+                            tr {
+                                th { class: "th", "Change" }
+                                th { class: "th", "PID" }
+                                th { class: "th", "Name" }
+                                th { class: "th", "Detail" }
+                            }
+                        }
+                        tbody {
+                            for (index, change) in change_list.into_iter().enumerate() {
+                                {
+                                    let pid = change.pid.map(|pid| pid.to_string()).unwrap_or_default();
+                                    let detail = change.detail.clone();
+
+                                    rsx! {
+                                        tr {
+                                            key: "{index}",
+                                            class: "thread-row",
+                                            title: "Double-click to copy the detail",
+                                            ondoubleclick: move |_| {
+                                                copy_to_clipboard(&detail);
+                                                status_message.set(String::from("📋 Detail copied"));
+                                                spawn(async move {
+                                                    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                                                    status_message.set(String::new());
+                                                });
+                                            },
+                                            td { class: "cell {kind_class(change.kind)}", "{change.kind}" }
+                                            td { class: "cell cell-handle", "{pid}" }
+                                            td { class: "cell", "{change.name}" }
+                                            td { class: "cell diff-detail", "{change.detail}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! File details pane (version info, signer, packing score, hashes)
+
+use dioxus::prelude::*;
+use process::{get_file_hashes, get_file_version_info};
+
+use crate::helpers::{copy_to_clipboard, packer_cell, signer_cell};
+
+/// Details pane for an executable or module file on disk
+#[component]
+pub fn FileDetailsPane(title: String, path: String) -> Element {
+    let mut copied = use_signal(|| None::<&'static str>);
+
+    let version_info = get_file_version_info(&path);
+    let hashes = get_file_hashes(&path);
+    let (signer, signer_class, signer_tooltip) = signer_cell(&path);
+    let (packer, packer_class, packer_tooltip) = packer_cell(&path);
+
+    let info_fields: Vec<(&str, String)> = vec![
+        (
+            "Description",
+            version_info
+                .as_ref()
+                .and_then(|v| v.file_description())
+                .unwrap_or("")
+                .to_string(),
+        ),
+        (
+            "Company",
+            version_info
+                .as_ref()
+                .and_then(|v| v.company_name())
+                .unwrap_or("")
+                .to_string(),
+        ),
+        (
+            "File Version",
+            version_info
+                .as_ref()
+                .and_then(|v| v.file_version())
+                .unwrap_or("")
+                .to_string(),
+        ),
+        (
+            "Original Filename",
+            version_info
+                .as_ref()
+                .and_then(|v| v.original_filename())
+                .unwrap_or("")
+                .to_string(),
+        ),
+    ];
+
+    let hash_fields: Vec<(&'static str, String)> = match &hashes {
+        Some(h) => vec![
+            ("MD5", h.md5.clone()),
+            ("SHA-1", h.sha1.clone()),
+            ("SHA-256", h.sha256.clone()),
+            ("Imphash", h.imphash.clone().unwrap_or_default()),
+        ],
+        None => Vec::new(),
+    };
+
+    rsx! {
+        div {
+            class: "file-details-pane",
+            div { class: "module-inspect-panel-title", "{title}" }
+            div { class: "pe-field-row",
+                span { class: "pe-field-name", "Path" }
+                span { class: "pe-field-value file-details-value", title: "{path}", "{path}" }
+            }
+            for (name, value) in info_fields {
+                div { class: "pe-field-row",
+                    span { class: "pe-field-name", "{name}" }
+                    span { class: "pe-field-value file-details-value", "{value}" }
+                }
+            }
+            div { class: "pe-field-row",
+                span { class: "pe-field-name", "Signer" }
+                span { class: "{signer_class}", title: "{signer_tooltip}", "{signer}" }
+            }
+            div { class: "pe-field-row",
+                span { class: "pe-field-name", "Packing Score" }
+                span { class: "{packer_class}", title: "{packer_tooltip}", "{packer}" }
+            }
+            if hashes.is_none() {
+                div { class: "pe-field-row",
+                    span { class: "pe-field-name", "Hashes" }
+                    span { class: "pe-field-value", "File not accessible" }
+                }
+            }
+            for (name, value) in hash_fields {
+                div { class: "pe-field-row",
+                    span { class: "pe-field-name", "{name}" }
+                    span { class: "pe-field-value file-details-hash", "{value}" }
+                    if !value.is_empty() {
+                        button {
+                            class: "file-details-copy",
+                            title: "Copy {name}",
+                            onclick: {
+                                let value = value.clone();
+                                move |_| {
+                                    if copy_to_clipboard(&value) {
+                                        copied.set(Some(name));
+                                        spawn(async move {
+                                            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                                            copied.set(None);
+                                        });
+                                    }
+                                }
+                            },
+                            if *copied.read() == Some(name) { "✓" } else { "📋" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
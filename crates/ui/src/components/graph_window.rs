@@ -1,60 +1,162 @@
-//! Process graph window component - real-time CPU and memory monitoring
+//! Process graph window component - CPU, memory, handle, thread and I/O history
+
+use std::time::Duration;
 
 use dioxus::prelude::*;
-use process::get_process_stats;
+use process::{get_process_history, ProcessMetric, ProcessSample, SAMPLE_INTERVAL};
 
 use crate::state::GRAPH_WINDOW_STATE;
 
-const GRAPH_HISTORY_SIZE: usize = 60; // 60 data points (1 minute at 1s interval)
 const GRAPH_WIDTH: f64 = 400.0;
 const GRAPH_HEIGHT: f64 = 120.0;
 
+/// Selectable time ranges in seconds, with their labels
+const GRAPH_RANGES: [(u64, &str); 4] = [
+    (60, "Last 1 minute"),
+    (5 * 60, "Last 5 minutes"),
+    (15 * 60, "Last 15 minutes"),
+    (60 * 60, "Last hour"),
+];
+
+/// One graph of the window, ready to render
+struct GraphSeries {
+    metric: ProcessMetric,
+    line: String,
+    fill: String,
+    current: String,
+    max_label: String,
+    half_label: String,
+}
+
+/// CSS suffix giving each series its colour
+fn metric_class(metric: ProcessMetric) -> &'static str {
+    match metric {
+        ProcessMetric::Cpu => "cpu",
+        ProcessMetric::WorkingSet => "mem",
+        ProcessMetric::PrivateBytes => "private",
+        ProcessMetric::Handles => "handles",
+        ProcessMetric::Threads => "threads",
+        ProcessMetric::IoBytes => "io",
+    }
+}
+
+/// A value of `metric` with its unit
+fn format_metric(metric: ProcessMetric, value: f64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    match metric {
+        ProcessMetric::Cpu => format!("{:.1}%", value),
+        ProcessMetric::WorkingSet | ProcessMetric::PrivateBytes => {
+            format!("{:.1} MB", value / MB)
+        }
+        ProcessMetric::Handles | ProcessMetric::Threads => format!("{:.0}", value),
+        ProcessMetric::IoBytes if value >= MB => format!("{:.1} MB/s", value / MB),
+        ProcessMetric::IoBytes => format!("{:.1} KB/s", value / 1024.0),
+    }
+}
+
+/// Label of a y axis gridline; CPU is a share of every logical processor,
+/// as in the process table
+fn axis_label(metric: ProcessMetric, value: f64) -> String {
+    match metric {
+        ProcessMetric::Cpu => format!("{:.0}% of all CPUs", value),
+        _ => format_metric(metric, value),
+    }
+}
+
+/// Top of the y axis: CPU is always out of 100%, the rest leave headroom
+/// above the highest value in range
+fn graph_max(metric: ProcessMetric, samples: &[ProcessSample]) -> f64 {
+    if metric == ProcessMetric::Cpu {
+        return 100.0;
+    }
+    let highest = samples
+        .iter()
+        .map(|sample| metric.value(sample))
+        .fold(0.0f64, f64::max);
+    (highest * 1.2).max(1.0)
+}
+
+/// SVG line and fill paths of `metric`, with the newest sample at the right
+/// edge and `range_ms` across the width
+fn generate_graph_paths(
+    samples: &[ProcessSample],
+    metric: ProcessMetric,
+    range_ms: u64,
+    max_value: f64,
+) -> (String, String) {
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+        return (String::new(), String::new());
+    };
+    let start = last.time.saturating_sub(range_ms);
+    let x_of = |time: u64| time.saturating_sub(start) as f64 / range_ms as f64 * GRAPH_WIDTH;
+
+    let mut path = String::new();
+    for (i, sample) in samples.iter().enumerate() {
+        let x = x_of(sample.time);
+        let y = GRAPH_HEIGHT - (metric.value(sample) / max_value * GRAPH_HEIGHT).min(GRAPH_HEIGHT);
+
+        if i == 0 {
+            path.push_str(&format!("M {} {}", x, y));
+        } else {
+            path.push_str(&format!(" L {} {}", x, y));
+        }
+    }
+
+    let fill = format!(
+        "{} L {} {} L {} {} Z",
+        path,
+        x_of(last.time),
+        GRAPH_HEIGHT,
+        x_of(first.time),
+        GRAPH_HEIGHT
+    );
+    (path, fill)
+}
+
 /// Graph Window component
 #[component]
 pub fn GraphWindow(pid: u32, process_name: String) -> Element {
-    let mut cpu_history = use_signal(|| vec![0.0f32; GRAPH_HISTORY_SIZE]);
-    let mut mem_history = use_signal(|| vec![0.0f64; GRAPH_HISTORY_SIZE]);
-    let mut current_stats = use_signal(|| get_process_stats(pid));
-    let mut max_memory = use_signal(|| 100.0f64); // Track max memory for scaling
+    let mut range_secs = use_signal(|| GRAPH_RANGES[0].0);
+    let mut samples =
+        use_signal(move || get_process_history(pid, Duration::from_secs(GRAPH_RANGES[0].0)));
+    let mut shown = use_signal(|| vec![ProcessMetric::Cpu, ProcessMetric::WorkingSet]);
     let mut paused = use_signal(|| false);
 
-    // Update every second
+    // Pick up new samples from the background sampler every second
     use_future(move || async move {
         loop {
             tokio::time::sleep(std::time::Duration::from_secs(1)).await;
             if !*paused.read() {
-                let stats = get_process_stats(pid);
-                current_stats.set(stats.clone());
-
-                // Update CPU history
-                let mut cpu = cpu_history.write();
-                cpu.remove(0);
-                cpu.push(stats.cpu_usage);
-
-                // Update memory history
-                let mut mem = mem_history.write();
-                mem.remove(0);
-                mem.push(stats.memory_mb);
-
-                // Update max memory for scaling
-                let current_max = mem.iter().cloned().fold(0.0f64, f64::max);
-                if current_max > *max_memory.read() * 0.8 || current_max < *max_memory.read() * 0.3 {
-                    max_memory.set((current_max * 1.2).max(10.0));
-                }
+                let range = Duration::from_secs(*range_secs.read());
+                samples.set(get_process_history(pid, range));
             }
         }
     });
 
-    let stats = current_stats.read().clone();
-    let cpu_data = cpu_history.read().clone();
-    let mem_data = mem_history.read().clone();
-    let mem_max = *max_memory.read();
     let is_paused = *paused.read();
-
-    // Generate SVG path for CPU graph
-    let cpu_path = generate_graph_path(&cpu_data, 100.0);
-    // Generate SVG path for memory graph
-    let mem_path = generate_graph_path_f64(&mem_data, mem_max);
+    let range_ms = *range_secs.read() * 1000;
+    let data = samples.read().clone();
+    let sample_secs = SAMPLE_INTERVAL.as_secs();
+    let graphs: Vec<GraphSeries> = ProcessMetric::ALL
+        .into_iter()
+        .filter(|metric| shown.read().contains(metric))
+        .map(|metric| {
+            let max_value = graph_max(metric, &data);
+            let (line, fill) = generate_graph_paths(&data, metric, range_ms, max_value);
+            let current = data
+                .last()
+                .map(|sample| format_metric(metric, metric.value(sample)))
+                .unwrap_or_else(|| "—".to_string());
+            GraphSeries {
+                metric,
+                line,
+                fill,
+                current,
+                max_label: axis_label(metric, max_value),
+                half_label: axis_label(metric, max_value / 2.0),
+            }
+        })
+        .collect();
 
     rsx! {
         // Modal overlay
@@ -97,86 +199,88 @@ pub fn GraphWindow(pid: u32, process_name: String) -> Element {
                             onclick: move |_| paused.set(!is_paused),
                             if is_paused { "Resume" } else { "Pause" }
                         }
+                        select {
+                            class: "filter-select",
+                            value: "{range_secs}",
+                            onchange: move |e| {
+                                let secs = e.value().parse().unwrap_or(GRAPH_RANGES[0].0);
+                                range_secs.set(secs);
+                                samples.set(get_process_history(pid, Duration::from_secs(secs)));
+                            },
+                            for (secs, label) in GRAPH_RANGES {
+                                option { value: "{secs}", "{label}" }
+                            }
+                        }
                         span {
                             class: "graph-interval",
-                            "Update: 1s | History: 60s"
+                            "Sampled every {sample_secs}s"
                         }
                     }
 
-                    // CPU Graph
+                    // Series toggles
                     div {
-                        class: "graph-section",
-                        div {
-                            class: "graph-header",
-                            span { class: "graph-label", "CPU Usage" }
-                            span { class: "graph-value graph-value-cpu", "{stats.cpu_usage:.1}%" }
-                        }
-                        div {
-                            class: "graph-container",
-                            svg {
-                                width: "100%",
-                                height: "{GRAPH_HEIGHT}",
-                                view_box: "0 0 {GRAPH_WIDTH} {GRAPH_HEIGHT}",
-                                preserve_aspect_ratio: "none",
-                                // Background grid
-                                line { x1: "0", y1: "{GRAPH_HEIGHT * 0.25}", x2: "{GRAPH_WIDTH}", y2: "{GRAPH_HEIGHT * 0.25}", class: "graph-grid" }
-                                line { x1: "0", y1: "{GRAPH_HEIGHT * 0.5}", x2: "{GRAPH_WIDTH}", y2: "{GRAPH_HEIGHT * 0.5}", class: "graph-grid" }
-                                line { x1: "0", y1: "{GRAPH_HEIGHT * 0.75}", x2: "{GRAPH_WIDTH}", y2: "{GRAPH_HEIGHT * 0.75}", class: "graph-grid" }
-                                // Graph line
-                                path {
-                                    d: "{cpu_path}",
-                                    class: "graph-line graph-line-cpu"
+                        class: "graph-series",
+                        for metric in ProcessMetric::ALL {
+                            label { class: "checkbox-label",
+                                input {
+                                    r#type: "checkbox",
+                                    class: "checkbox",
+                                    checked: shown.read().contains(&metric),
+                                    onchange: move |e| {
+                                        let mut shown = shown.write();
+                                        if e.checked() {
+                                            shown.push(metric);
+                                        } else {
+                                            shown.retain(|m| *m != metric);
+                                        }
+                                    },
                                 }
-                                // Fill area
-                                path {
-                                    d: "{cpu_path} L {GRAPH_WIDTH} {GRAPH_HEIGHT} L 0 {GRAPH_HEIGHT} Z",
-                                    class: "graph-fill graph-fill-cpu"
-                                }
-                            }
-                            div {
-                                class: "graph-y-labels",
-                                span { "100%" }
-                                span { "50%" }
-                                span { "0%" }
+                                span { "{metric.label()}" }
                             }
                         }
                     }
 
-                    // Memory Graph
-                    div {
-                        class: "graph-section",
-                        div {
-                            class: "graph-header",
-                            span { class: "graph-label", "Memory Usage" }
-                            span { class: "graph-value graph-value-mem", "{stats.memory_mb:.1} MB" }
-                        }
+                    if data.is_empty() {
+                        div { class: "graph-empty", "No samples recorded for this process yet" }
+                    }
+
+                    for GraphSeries { metric, line, fill, current, max_label, half_label } in graphs {
                         div {
-                            class: "graph-container",
-                            svg {
-                                width: "100%",
-                                height: "{GRAPH_HEIGHT}",
-                                view_box: "0 0 {GRAPH_WIDTH} {GRAPH_HEIGHT}",
-                                preserve_aspect_ratio: "none",
-                                // Background grid
-                                line { x1: "0", y1: "{GRAPH_HEIGHT * 0.25}", x2: "{GRAPH_WIDTH}", y2: "{GRAPH_HEIGHT * 0.25}", class: "graph-grid" }
-                                line { x1: "0", y1: "{GRAPH_HEIGHT * 0.5}", x2: "{GRAPH_WIDTH}", y2: "{GRAPH_HEIGHT * 0.5}", class: "graph-grid" }
-                                line { x1: "0", y1: "{GRAPH_HEIGHT * 0.75}", x2: "{GRAPH_WIDTH}", y2: "{GRAPH_HEIGHT * 0.75}", class: "graph-grid" }
-                                // Graph line
-                                path {
-                                    d: "{mem_path}",
-                                    class: "graph-line graph-line-mem"
-                                }
-                                // Fill area
-                                path {
-                                    d: "{mem_path} L {GRAPH_WIDTH} {GRAPH_HEIGHT} L 0 {GRAPH_HEIGHT} Z",
-                                    class: "graph-fill graph-fill-mem"
-                                }
+                            key: "{metric.label()}",
+                            class: "graph-section",
+                            div {
+                                class: "graph-header",
+                                span { class: "graph-label", "{metric.label()}" }
+                                span { class: "graph-value graph-value-{metric_class(metric)}", "{current}" }
                             }
                             div {
-                                class: "graph-y-labels",
-                                span { "{mem_max:.0} MB" }
-                                span { "{mem_max / 2.0:.0} MB" }
-                                span { "0 MB" }
+                                class: "graph-container",
+                                svg {
+                                    width: "100%",
+                                    height: "{GRAPH_HEIGHT}",
+                                    view_box: "0 0 {GRAPH_WIDTH} {GRAPH_HEIGHT}",
+                                    preserve_aspect_ratio: "none",
+                                    // Background grid
+                                    line { x1: "0", y1: "{GRAPH_HEIGHT * 0.25}", x2: "{GRAPH_WIDTH}", y2: "{GRAPH_HEIGHT * 0.25}", class: "graph-grid" }
+                                    line { x1: "0", y1: "{GRAPH_HEIGHT * 0.5}", x2: "{GRAPH_WIDTH}", y2: "{GRAPH_HEIGHT * 0.5}", class: "graph-grid" }
+                                    line { x1: "0", y1: "{GRAPH_HEIGHT * 0.75}", x2: "{GRAPH_WIDTH}", y2: "{GRAPH_HEIGHT * 0.75}", class: "graph-grid" }
+                                    // Graph line
+                                    path {
+                                        d: "{line}",
+                                        class: "graph-line graph-line-{metric_class(metric)}"
+                                    }
+                                    // Fill area
+                                    path {
+                                        d: "{fill}",
+                                        class: "graph-fill graph-fill-{metric_class(metric)}"
+                                    }
+                                }
+                                div {
+                                    class: "graph-y-labels",
+                                    span { "{max_label}" }
+                                    span { "{half_label}" }
+                                    span { "0" }
+                                }
                             }
                         }
                     }
@@ -185,49 +289,3 @@ pub fn GraphWindow(pid: u32, process_name: String) -> Element {
         }
     }
 }
-
-/// Generate SVG path from f32 data points (0-max range)
-fn generate_graph_path(data: &[f32], max_value: f32) -> String {
-    if data.is_empty() {
-        return String::new();
-    }
-
-    let step = GRAPH_WIDTH / (data.len() - 1) as f64;
-    let mut path = String::new();
-
-    for (i, &value) in data.iter().enumerate() {
-        let x = i as f64 * step;
-        let y = GRAPH_HEIGHT - (value as f64 / max_value as f64 * GRAPH_HEIGHT).min(GRAPH_HEIGHT);
-
-        if i == 0 {
-            path.push_str(&format!("M {} {}", x, y));
-        } else {
-            path.push_str(&format!(" L {} {}", x, y));
-        }
-    }
-
-    path
-}
-
-/// Generate SVG path from f64 data points (0-max range)
-fn generate_graph_path_f64(data: &[f64], max_value: f64) -> String {
-    if data.is_empty() || max_value <= 0.0 {
-        return String::new();
-    }
-
-    let step = GRAPH_WIDTH / (data.len() - 1) as f64;
-    let mut path = String::new();
-
-    for (i, &value) in data.iter().enumerate() {
-        let x = i as f64 * step;
-        let y = GRAPH_HEIGHT - (value / max_value * GRAPH_HEIGHT).min(GRAPH_HEIGHT);
-
-        if i == 0 {
-            path.push_str(&format!("M {} {}", x, y));
-        } else {
-            path.push_str(&format!(" L {} {}", x, y));
-        }
-    }
-
-    path
-}
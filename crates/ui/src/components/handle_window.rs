@@ -1,15 +1,16 @@
 //! Handle window component
 
 use dioxus::prelude::*;
-use process::{close_process_handle, get_handle_type_category, get_process_handles, HandleInfo};
+use process::{close_process_handle, get_handle_type_category, HandleInfo};
 
-use crate::helpers::copy_to_clipboard;
+use crate::helpers::{copy_to_clipboard, export_records, load_error};
+use crate::source;
 use crate::state::{HandleContextMenuState, HANDLE_WINDOW_STATE};
 
 /// Handle Window component
 #[component]
 pub fn HandleWindow(pid: u32, process_name: String) -> Element {
-    let mut handles = use_signal(|| get_process_handles(pid));
+    let mut handles = use_signal(|| source::handles(pid));
     let mut selected_handle = use_signal(|| None::<u16>);
     let mut context_menu = use_signal(|| HandleContextMenuState::default());
     let mut status_message = use_signal(|| String::new());
@@ -21,18 +22,22 @@ pub fn HandleWindow(pid: u32, process_name: String) -> Element {
         loop {
             tokio::time::sleep(std::time::Duration::from_secs(3)).await;
             if *auto_refresh.read() {
-                handles.set(get_process_handles(pid));
+                handles.set(source::handles(pid));
             }
         }
     });
 
+    let read_only = source::is_read_only();
     let ctx_menu = context_menu.read().clone();
     let filter = filter_type.read().clone();
 
+    let error_message = load_error(&handles.read());
+
     // Filter handles by type
     let handle_list: Vec<HandleInfo> = handles
         .read()
         .iter()
+        .flatten()
         .filter(|h| {
             if filter.is_empty() {
                 true
@@ -45,7 +50,8 @@ pub fn HandleWindow(pid: u32, process_name: String) -> Element {
         .cloned()
         .collect();
     let handle_count = handle_list.len();
-    let total_handles = handles.read().len();
+    let export_handles = handle_list.clone();
+    let total_handles = handles.read().as_ref().map_or(0, Vec::len);
 
     rsx! {
         // Modal overlay
@@ -102,10 +108,37 @@ pub fn HandleWindow(pid: u32, process_name: String) -> Element {
                     button {
                         class: "btn btn-small btn-primary",
                         onclick: move |_| {
-                            handles.set(get_process_handles(pid));
+                            handles.set(source::handles(pid));
                         },
                         "🔄 Refresh"
                     }
+
+                    button {
+                        class: "btn btn-small btn-primary",
+                        onclick: {
+                            let export_handles = export_handles.clone();
+                            move |_| {
+                                let export_handles = export_handles.clone();
+                                spawn(async move {
+                                    let message = export_records(
+                                        "Export Handles",
+                                        &format!("handles_{}", pid),
+                                        "handles",
+                                        move || export_handles,
+                                    )
+                                    .await;
+                                    if let Some(message) = message {
+                                        status_message.set(message);
+                                        spawn(async move {
+                                            tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                            status_message.set(String::new());
+                                        });
+                                    }
+                                });
+                            }
+                        },
+                        "💾 Export"
+                    }
                 }
 
                 // Status message
@@ -113,6 +146,11 @@ pub fn HandleWindow(pid: u32, process_name: String) -> Element {
                     div { class: "thread-status-message", "{status_message}" }
                 }
 
+                // Why the handle list is empty, if it could not be read
+                if let Some(error) = error_message {
+                    div { class: "load-error", "{error}" }
+                }
+
                 // Handle table
                 div {
                     class: "thread-table-container",
@@ -162,23 +200,28 @@ pub fn HandleWindow(pid: u32, process_name: String) -> Element {
                                             td { class: "cell {type_class}", "{handle.object_type_name}" }
                                             td { class: "cell cell-access", "0x{handle.granted_access:08X}" }
                                             td { class: "cell cell-actions",
-                                                button {
-                                                    class: "action-btn action-btn-danger",
-                                                    title: "Close Handle (Dangerous!)",
-                                                    onclick: move |e| {
-                                                        e.stop_propagation();
-                                                        if close_process_handle(pid, hval) {
-                                                            status_message.set(format!("✓ Handle 0x{:04X} closed", hval));
-                                                            handles.set(get_process_handles(pid));
-                                                        } else {
-                                                            status_message.set(format!("✗ Failed to close handle 0x{:04X}", hval));
-                                                        }
-                                                        spawn(async move {
-                                                            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-                                                            status_message.set(String::new());
-                                                        });
-                                                    },
-                                                    "✕"
+                                                if !read_only {
+                                                    button {
+                                                        class: "action-btn action-btn-danger",
+                                                        title: "Close Handle (Dangerous!)",
+                                                        onclick: move |e| {
+                                                            e.stop_propagation();
+                                                            match close_process_handle(pid, hval) {
+                                                                Ok(()) => {
+                                                                    status_message.set(format!("✓ Handle 0x{:04X} closed", hval));
+                                                                    handles.set(source::handles(pid));
+                                                                }
+                                                                Err(e) => {
+                                                                    status_message.set(format!("✗ Failed to close handle 0x{:04X}: {}", hval, e));
+                                                                }
+                                                            }
+                                                            spawn(async move {
+                                                                tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                                                                status_message.set(String::new());
+                                                            });
+                                                        },
+                                                        "✕"
+                                                    }
                                                 }
                                             }
                                         }
@@ -196,28 +239,33 @@ pub fn HandleWindow(pid: u32, process_name: String) -> Element {
                         style: "left: {ctx_menu.x}px; top: {ctx_menu.y}px;",
                         onclick: move |e| e.stop_propagation(),
 
-                        button {
-                            class: "context-menu-item context-menu-item-danger",
-                            onclick: move |_| {
-                                if let Some(hval) = ctx_menu.handle_value {
-                                    if close_process_handle(pid, hval) {
-                                        status_message.set(format!("✓ Handle 0x{:04X} closed", hval));
-                                        handles.set(get_process_handles(pid));
-                                    } else {
-                                        status_message.set(format!("✗ Failed to close handle 0x{:04X}", hval));
+                        if !read_only {
+                            button {
+                                class: "context-menu-item context-menu-item-danger",
+                                onclick: move |_| {
+                                    if let Some(hval) = ctx_menu.handle_value {
+                                        match close_process_handle(pid, hval) {
+                                            Ok(()) => {
+                                                status_message.set(format!("✓ Handle 0x{:04X} closed", hval));
+                                                handles.set(source::handles(pid));
+                                            }
+                                            Err(e) => {
+                                                status_message.set(format!("✗ Failed to close handle 0x{:04X}: {}", hval, e));
+                                            }
+                                        }
+                                        spawn(async move {
+                                            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                                            status_message.set(String::new());
+                                        });
                                     }
-                                    spawn(async move {
-                                        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-                                        status_message.set(String::new());
-                                    });
-                                }
-                                context_menu.set(HandleContextMenuState::default());
-                            },
-                            span { "✕" }
-                            span { "Close Handle" }
-                        }
+                                    context_menu.set(HandleContextMenuState::default());
+                                },
+                                span { "✕" }
+                                span { "Close Handle" }
+                            }
 
-                        div { class: "context-menu-separator" }
+                            div { class: "context-menu-separator" }
+                        }
 
                         button {
                             class: "context-menu-item",
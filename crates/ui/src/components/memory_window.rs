@@ -4,20 +4,40 @@ use std::collections::HashMap;
 
 use dioxus::prelude::*;
 use process::{
-    get_memory_protect_name, get_memory_state_name, get_memory_type_name,
-    get_process_memory_regions, get_process_modules, read_process_memory, MemoryRegionInfo,
+    get_memory_protect_name, get_memory_state_name, get_memory_type_name, read_process_memory,
+    MemoryRegionInfo,
 };
 
-use crate::helpers::copy_to_clipboard;
+use crate::helpers::{copy_to_clipboard, export_records, load_error};
+use crate::records::MemoryRegionRecord;
+use crate::source;
 use crate::state::{MemoryContextMenuState, MEMORY_WINDOW_STATE};
 
 const HEX_PAGE_SIZE: usize = 4096;
 
+/// Export rows for `regions`, naming image regions after their module
+fn region_records(
+    regions: &[MemoryRegionInfo],
+    module_map: &HashMap<usize, (String, String)>,
+) -> Vec<MemoryRegionRecord> {
+    regions
+        .iter()
+        .map(|r| {
+            // MEM_IMAGE = 0x1000000
+            let module = (r.mem_type == 0x1000000)
+                .then(|| module_map.get(&r.allocation_base))
+                .flatten()
+                .map(|(name, _)| name.as_str());
+            MemoryRegionRecord::new(r, module)
+        })
+        .collect()
+}
+
 /// Memory Window component
 #[component]
 pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
-    let mut regions = use_signal(|| get_process_memory_regions(pid));
-    let mut modules = use_signal(|| get_process_modules(pid));
+    let mut regions = use_signal(|| source::memory_regions(pid));
+    let mut modules = use_signal(|| source::modules(pid));
     let mut selected_region = use_signal(|| None::<usize>);
     let mut context_menu = use_signal(|| MemoryContextMenuState::default());
     let mut status_message = use_signal(|| String::new());
@@ -26,14 +46,15 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
     let mut show_free = use_signal(|| false);
     let mut inspecting = use_signal(|| None::<(usize, Vec<u8>)>);
     let mut hex_page = use_signal(|| 0usize);
+    let read_only = source::is_read_only();
 
     // Auto-refresh every 3 seconds (if enabled)
     use_future(move || async move {
         loop {
             tokio::time::sleep(std::time::Duration::from_secs(3)).await;
             if *auto_refresh.read() {
-                regions.set(get_process_memory_regions(pid));
-                modules.set(get_process_modules(pid));
+                regions.set(source::memory_regions(pid));
+                modules.set(source::modules(pid));
             }
         }
     });
@@ -46,13 +67,17 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
     let module_map: HashMap<usize, (String, String)> = modules
         .read()
         .iter()
+        .flatten()
         .map(|m| (m.base_address, (m.name.clone(), m.path.clone())))
         .collect();
 
+    let error_message = load_error(&regions.read());
+
     // Filter regions
     let region_list: Vec<MemoryRegionInfo> = regions
         .read()
         .iter()
+        .flatten()
         .filter(|r| {
             // Hide free regions unless show_free is checked
             if !show_free_val && r.state == 0x10000 {
@@ -87,7 +112,8 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
         .collect();
 
     let region_count = region_list.len();
-    let total_regions = regions.read().len();
+    let export_regions = region_list.clone();
+    let total_regions = regions.read().as_ref().map_or(0, Vec::len);
 
     let inspect_state = inspecting.read().clone();
 
@@ -287,11 +313,40 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
                         button {
                             class: "btn btn-small btn-primary",
                             onclick: move |_| {
-                                regions.set(get_process_memory_regions(pid));
-                                modules.set(get_process_modules(pid));
+                                regions.set(source::memory_regions(pid));
+                                modules.set(source::modules(pid));
                             },
                             "🔄 Refresh"
                         }
+
+                        button {
+                            class: "btn btn-small btn-primary",
+                            onclick: {
+                                let export_regions = export_regions.clone();
+                                let export_modules = module_map.clone();
+                                move |_| {
+                                    let (export_regions, export_modules) =
+                                        (export_regions.clone(), export_modules.clone());
+                                    spawn(async move {
+                                        let message = export_records(
+                                            "Export Memory Regions",
+                                            &format!("memory_regions_{}", pid),
+                                            "regions",
+                                            move || region_records(&export_regions, &export_modules),
+                                        )
+                                        .await;
+                                        if let Some(message) = message {
+                                            status_message.set(message);
+                                            spawn(async move {
+                                                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                                status_message.set(String::new());
+                                            });
+                                        }
+                                    });
+                                }
+                            },
+                            "💾 Export"
+                        }
                     }
 
                     // Status message
@@ -299,6 +354,11 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
                         div { class: "thread-status-message", "{status_message}" }
                     }
 
+                    // Why the memory map is empty, if it could not be read
+                    if let Some(error) = error_message {
+                        div { class: "load-error", "{error}" }
+                    }
+
                     // Memory region table
                     div {
                         class: "thread-table-container",
@@ -409,30 +469,32 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
                                                 td { class: "cell", style: "font-size: 12px; color: #d1d5db;", "{protect_name}" }
                                                 td { class: "cell cell-actions",
                                                     // Inspect button (committed only)
-                                                    if is_committed {
+                                                    if is_committed && !read_only {
                                                         button {
                                                             class: "action-btn action-btn-warning",
                                                             title: "Inspect Memory",
                                                             onclick: move |e: Event<MouseData>| {
                                                                 e.stop_propagation();
                                                                 let read_size = size.min(1024 * 1024);
-                                                                let data = read_process_memory(pid, base, read_size);
-                                                                if data.is_empty() {
-                                                                    status_message.set(format!("✗ Failed to read memory at 0x{:X}", base));
-                                                                    spawn(async move {
-                                                                        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-                                                                        status_message.set(String::new());
-                                                                    });
-                                                                } else {
-                                                                    hex_page.set(0);
-                                                                    inspecting.set(Some((base, data)));
+                                                                match read_process_memory(pid, base, read_size) {
+                                                                    Ok(data) => {
+                                                                        hex_page.set(0);
+                                                                        inspecting.set(Some((base, data)));
+                                                                    }
+                                                                    Err(e) => {
+                                                                        status_message.set(format!("✗ Failed to read memory at 0x{:X}: {}", base, e));
+                                                                        spawn(async move {
+                                                                            tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                                                            status_message.set(String::new());
+                                                                        });
+                                                                    }
                                                                 }
                                                             },
                                                             "🔍"
                                                         }
                                                     }
                                                     // Dump button (committed only)
-                                                    if is_committed {
+                                                    if is_committed && !read_only {
                                                         button {
                                                             class: "action-btn action-btn-primary",
                                                             title: "Dump to .bin file",
@@ -447,26 +509,28 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
                                                                         .await;
                                                                     if let Some(file) = file {
                                                                         let read_size = size.min(1024 * 1024);
-                                                                        let data = read_process_memory(pid, base, read_size);
-                                                                        if data.is_empty() {
-                                                                            status_message.set(format!("✗ Failed to read memory at 0x{:X}", base));
-                                                                        } else {
-                                                                            let path = file.path().to_path_buf();
-                                                                            match std::fs::write(&path, &data) {
-                                                                                Ok(()) => {
-                                                                                    status_message.set(format!(
-                                                                                        "✓ Dumped {} bytes to {}",
-                                                                                        data.len(),
-                                                                                        path.display()
-                                                                                    ));
-                                                                                }
-                                                                                Err(e) => {
-                                                                                    status_message.set(format!(
-                                                                                        "✗ Dump failed: {}",
-                                                                                        e
-                                                                                    ));
+                                                                        match read_process_memory(pid, base, read_size) {
+                                                                            Ok(data) => {
+                                                                                let path = file.path().to_path_buf();
+                                                                                match std::fs::write(&path, &data) {
+                                                                                    Ok(()) => {
+                                                                                        status_message.set(format!(
+                                                                                            "✓ Dumped {} bytes to {}",
+                                                                                            data.len(),
+                                                                                            path.display()
+                                                                                        ));
+                                                                                    }
+                                                                                    Err(e) => {
+                                                                                        status_message.set(format!(
+                                                                                            "✗ Dump failed: {}",
+                                                                                            e
+                                                                                        ));
+                                                                                    }
                                                                                 }
                                                                             }
+                                                                            Err(e) => {
+                                                                                status_message.set(format!("✗ Failed to read memory at 0x{:X}: {}", base, e));
+                                                                            }
                                                                         }
                                                                         spawn(async move {
                                                                             tokio::time::sleep(std::time::Duration::from_secs(3)).await;
@@ -479,7 +543,7 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
                                                         }
                                                     }
                                                     // Commit button (reserved only)
-                                                    if is_reserved {
+                                                    if is_reserved && !read_only {
                                                         button {
                                                             class: "action-btn action-btn-success",
                                                             title: "Commit Region",
@@ -488,7 +552,7 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
                                                                 match misc::commit_memory(pid, base, size) {
                                                                     Ok(()) => {
                                                                         status_message.set(format!("✓ Committed 0x{:X}", base));
-                                                                        regions.set(get_process_memory_regions(pid));
+                                                                        regions.set(source::memory_regions(pid));
                                                                     }
                                                                     Err(err) => {
                                                                         status_message.set(format!("✗ Commit failed: {}", err));
@@ -503,7 +567,7 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
                                                         }
                                                     }
                                                     // Decommit button (committed only)
-                                                    if is_committed {
+                                                    if is_committed && !read_only {
                                                         button {
                                                             class: "action-btn action-btn-warning",
                                                             title: "Decommit Region",
@@ -512,7 +576,7 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
                                                                 match misc::decommit_memory(pid, base, size) {
                                                                     Ok(()) => {
                                                                         status_message.set(format!("✓ Decommitted 0x{:X}", base));
-                                                                        regions.set(get_process_memory_regions(pid));
+                                                                        regions.set(source::memory_regions(pid));
                                                                     }
                                                                     Err(err) => {
                                                                         status_message.set(format!("✗ Decommit failed: {}", err));
@@ -527,7 +591,7 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
                                                         }
                                                     }
                                                     // Free button (committed or reserved only)
-                                                    if is_committed || is_reserved {
+                                                    if (is_committed || is_reserved) && !read_only {
                                                         button {
                                                             class: "action-btn action-btn-danger",
                                                             title: "Free Region",
@@ -536,7 +600,7 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
                                                                 match misc::free_memory(pid, alloc_base) {
                                                                     Ok(()) => {
                                                                         status_message.set(format!("✓ Freed allocation at 0x{:X}", alloc_base));
-                                                                        regions.set(get_process_memory_regions(pid));
+                                                                        regions.set(source::memory_regions(pid));
                                                                     }
                                                                     Err(err) => {
                                                                         status_message.set(format!("✗ Free failed: {}", err));
@@ -550,7 +614,7 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
                                                             "✕"
                                                         }
                                                     }
-                                                    if is_free {
+                                                    if is_free || read_only {
                                                         span { style: "color: #4b5563; font-size: 12px;", "-" }
                                                     }
                                                 }
@@ -578,21 +642,23 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
                                     style: "left: {ctx_menu.x}px; top: {ctx_menu.y}px;",
                                     onclick: move |e| e.stop_propagation(),
 
-                                    if ctx_is_committed {
+                                    if ctx_is_committed && !read_only {
                                         button {
                                             class: "context-menu-item",
                                             onclick: move |_| {
                                                 let read_size = ctx_size.min(1024 * 1024);
-                                                let data = read_process_memory(pid, ctx_base, read_size);
-                                                if data.is_empty() {
-                                                    status_message.set(format!("✗ Failed to read memory at 0x{:X}", ctx_base));
-                                                    spawn(async move {
-                                                        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-                                                        status_message.set(String::new());
-                                                    });
-                                                } else {
-                                                    hex_page.set(0);
-                                                    inspecting.set(Some((ctx_base, data)));
+                                                match read_process_memory(pid, ctx_base, read_size) {
+                                                    Ok(data) => {
+                                                        hex_page.set(0);
+                                                        inspecting.set(Some((ctx_base, data)));
+                                                    }
+                                                    Err(e) => {
+                                                        status_message.set(format!("✗ Failed to read memory at 0x{:X}: {}", ctx_base, e));
+                                                        spawn(async move {
+                                                            tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                                            status_message.set(String::new());
+                                                        });
+                                                    }
                                                 }
                                                 context_menu.set(MemoryContextMenuState::default());
                                             },
@@ -613,26 +679,28 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
                                                         .await;
                                                     if let Some(file) = file {
                                                         let read_size = ctx_size.min(1024 * 1024);
-                                                        let data = read_process_memory(pid, ctx_base, read_size);
-                                                        if data.is_empty() {
-                                                            status_message.set(format!("✗ Failed to read memory at 0x{:X}", ctx_base));
-                                                        } else {
-                                                            let path = file.path().to_path_buf();
-                                                            match std::fs::write(&path, &data) {
-                                                                Ok(()) => {
-                                                                    status_message.set(format!(
-                                                                        "✓ Dumped {} bytes to {}",
-                                                                        data.len(),
-                                                                        path.display()
-                                                                    ));
-                                                                }
-                                                                Err(e) => {
-                                                                    status_message.set(format!(
-                                                                        "✗ Dump failed: {}",
-                                                                        e
-                                                                    ));
+                                                        match read_process_memory(pid, ctx_base, read_size) {
+                                                            Ok(data) => {
+                                                                let path = file.path().to_path_buf();
+                                                                match std::fs::write(&path, &data) {
+                                                                    Ok(()) => {
+                                                                        status_message.set(format!(
+                                                                            "✓ Dumped {} bytes to {}",
+                                                                            data.len(),
+                                                                            path.display()
+                                                                        ));
+                                                                    }
+                                                                    Err(e) => {
+                                                                        status_message.set(format!(
+                                                                            "✗ Dump failed: {}",
+                                                                            e
+                                                                        ));
+                                                                    }
                                                                 }
                                                             }
+                                                            Err(e) => {
+                                                                status_message.set(format!("✗ Failed to read memory at 0x{:X}: {}", ctx_base, e));
+                                                            }
                                                         }
                                                         spawn(async move {
                                                             tokio::time::sleep(std::time::Duration::from_secs(3)).await;
@@ -646,14 +714,14 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
                                         }
                                     }
 
-                                    if ctx_is_reserved {
+                                    if ctx_is_reserved && !read_only {
                                         button {
                                             class: "context-menu-item context-menu-item-success",
                                             onclick: move |_| {
                                                 match misc::commit_memory(pid, ctx_base, ctx_size) {
                                                     Ok(()) => {
                                                         status_message.set(format!("✓ Committed 0x{:X}", ctx_base));
-                                                        regions.set(get_process_memory_regions(pid));
+                                                        regions.set(source::memory_regions(pid));
                                                     }
                                                     Err(err) => {
                                                         status_message.set(format!("✗ Commit failed: {}", err));
@@ -670,14 +738,14 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
                                         }
                                     }
 
-                                    if ctx_is_committed {
+                                    if ctx_is_committed && !read_only {
                                         button {
                                             class: "context-menu-item context-menu-item-warning",
                                             onclick: move |_| {
                                                 match misc::decommit_memory(pid, ctx_base, ctx_size) {
                                                     Ok(()) => {
                                                         status_message.set(format!("✓ Decommitted 0x{:X}", ctx_base));
-                                                        regions.set(get_process_memory_regions(pid));
+                                                        regions.set(source::memory_regions(pid));
                                                     }
                                                     Err(err) => {
                                                         status_message.set(format!("✗ Decommit failed: {}", err));
@@ -694,14 +762,14 @@ pub fn MemoryWindow(pid: u32, process_name: String) -> Element {
                                         }
                                     }
 
-                                    if ctx_is_committed || ctx_is_reserved {
+                                    if (ctx_is_committed || ctx_is_reserved) && !read_only {
                                         button {
                                             class: "context-menu-item context-menu-item-danger",
                                             onclick: move |_| {
                                                 match misc::free_memory(pid, ctx_alloc_base) {
                                                     Ok(()) => {
                                                         status_message.set(format!("✓ Freed allocation at 0x{:X}", ctx_alloc_base));
-                                                        regions.set(get_process_memory_regions(pid));
+                                                        regions.set(source::memory_regions(pid));
                                                     }
                                                     Err(err) => {
                                                         status_message.set(format!("✗ Free failed: {}", err));
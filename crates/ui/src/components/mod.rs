@@ -1,22 +1,34 @@
 //! UI Components
 
 mod app;
+mod diff_window;
+mod file_details_pane;
 mod graph_window;
 mod handle_window;
 mod memory_window;
+mod module_integrity_view;
+mod module_resources_view;
 mod module_window;
 mod network_tab;
+mod pe_headers_view;
+mod process_details_pane;
 mod process_row;
 mod process_tab;
 mod service_tab;
 mod thread_window;
 
 pub use app::{App, Layout};
+pub use diff_window::DiffWindow;
+pub use file_details_pane::FileDetailsPane;
 pub use graph_window::GraphWindow;
 pub use handle_window::HandleWindow;
 pub use memory_window::MemoryWindow;
+pub use module_integrity_view::ModuleIntegrityView;
+pub use module_resources_view::ModuleResourcesView;
 pub use module_window::ModuleWindow;
 pub use network_tab::NetworkTab;
+pub use pe_headers_view::PeHeadersView;
+pub use process_details_pane::ProcessDetailsPane;
 pub use process_row::ProcessRow;
 pub use process_tab::ProcessTab;
 pub use service_tab::ServiceTab;
@@ -0,0 +1,150 @@
+//! Module integrity (memory vs disk) view
+
+use dioxus::prelude::*;
+use process::ModuleIntegrity;
+
+/// Bytes shown per side of a patched range before truncating
+const PREVIEW_BYTES: usize = 16;
+
+fn hex_preview(bytes: &[u8]) -> String {
+    let mut text = bytes
+        .iter()
+        .take(PREVIEW_BYTES)
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if bytes.len() > PREVIEW_BYTES {
+        text.push_str(" …");
+    }
+    text
+}
+
+/// Module Integrity view listing compared sections and patched byte ranges
+#[component]
+pub fn ModuleIntegrityView(base: usize, integrity: ModuleIntegrity) -> Element {
+    let patch_count = integrity.patch_count();
+    let patched_bytes = integrity.patched_bytes();
+    let incomplete = integrity
+        .sections
+        .iter()
+        .filter(|s| !s.is_complete())
+        .count();
+    let summary_class = if integrity.is_modified() || incomplete > 0 {
+        "pe-mitigation pe-mitigation-off"
+    } else {
+        "pe-mitigation pe-mitigation-on"
+    };
+    let summary = if integrity.is_modified() {
+        format!(
+            "⚠ Modified: {} patched range(s), {} byte(s)",
+            patch_count, patched_bytes
+        )
+    } else if incomplete > 0 {
+        format!(
+            "? Unverified: {} section(s) could not be read in full",
+            incomplete
+        )
+    } else {
+        "✓ Read-only sections match the file on disk".to_string()
+    };
+
+    rsx! {
+        div {
+            class: "pe-headers-view",
+
+            div {
+                class: "pe-mitigations",
+                span { class: "{summary_class}", "{summary}" }
+            }
+
+            // Compared sections
+            div { class: "module-inspect-panel-title", "Sections" }
+            table {
+                class: "thread-table",
+                thead {
+                    tr {
+                        th { class: "th", "Name" }
+                        th { class: "th", "RVA" }
+                        th { class: "th", "Size" }
+                        th { class: "th", "Status" }
+                    }
+                }
+                tbody {
+                    for (index, section) in integrity.sections.iter().enumerate() {
+                        {
+                            let name = section.name.clone();
+                            let rva = section.rva;
+                            let size = section.size;
+                            let (status, status_class) = if !section.readable {
+                                ("Unreadable".to_string(), "cell integrity-unverified")
+                            } else if !section.patches.is_empty() {
+                                (format!("{} patch(es)", section.patches.len()), "cell integrity-modified")
+                            } else if !section.is_complete() {
+                                (
+                                    format!("Partly read (0x{:X} of 0x{:X})", section.compared, size),
+                                    "cell integrity-unverified",
+                                )
+                            } else {
+                                ("Clean".to_string(), "cell integrity-clean")
+                            };
+                            rsx! {
+                                tr {
+                                    key: "{index}",
+                                    class: "thread-row",
+                                    td { class: "cell", style: "font-weight: 500;", "{name}" }
+                                    td { class: "cell cell-handle", "0x{rva:08X}" }
+                                    td { class: "cell cell-handle", "0x{size:X}" }
+                                    td { class: "{status_class}", "{status}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Patched ranges
+            if patch_count > 0 {
+                div { class: "module-inspect-panel-title", "Patched Ranges ({patch_count})" }
+                table {
+                    class: "thread-table",
+                    thead {
+                        tr {
+                            th { class: "th", "Section" }
+                            th { class: "th", "Address" }
+                            th { class: "th", "RVA" }
+                            th { class: "th", "Length" }
+                            th { class: "th", "On Disk" }
+                            th { class: "th", "In Memory" }
+                        }
+                    }
+                    tbody {
+                        for section in integrity.sections.iter() {
+                            for patch in section.patches.iter() {
+                                {
+                                    let section_name = section.name.clone();
+                                    let rva = patch.rva;
+                                    let address = base + rva as usize;
+                                    let len = patch.len();
+                                    let expected = hex_preview(&patch.expected);
+                                    let actual = hex_preview(&patch.actual);
+                                    rsx! {
+                                        tr {
+                                            key: "{rva}",
+                                            class: "thread-row",
+                                            td { class: "cell", "{section_name}" }
+                                            td { class: "cell cell-handle", "0x{address:X}" }
+                                            td { class: "cell cell-handle", "0x{rva:08X}" }
+                                            td { class: "cell", "{len}" }
+                                            td { class: "cell integrity-bytes", "{expected}" }
+                                            td { class: "cell integrity-bytes integrity-modified", "{actual}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Module resource tree view
+
+use dioxus::prelude::*;
+use process::{get_module_resource_data, ModuleResource, ResourceEntry};
+use std::collections::BTreeMap;
+
+/// File extension used when saving a resource
+fn resource_extension(resource: &ModuleResource) -> &'static str {
+    match resource.format {
+        Some("PNG") => "png",
+        Some("GIF") => "gif",
+        Some("JPEG") => "jpg",
+        Some("BMP") => "bmp",
+        Some("ZIP") => "zip",
+        Some("CAB") => "cab",
+        Some("7z") => "7z",
+        Some("RAR") => "rar",
+        Some("GZIP") => "gz",
+        Some("PDF") => "pdf",
+        Some("XML") => "xml",
+        Some("HTML") => "html",
+        _ if resource.entry.type_name() == "MANIFEST" => "xml",
+        _ => "bin",
+    }
+}
+
+/// Default file name for a saved resource: `<module>_<type>_<name>_<lang>.<ext>`
+fn resource_file_name(module_name: &str, resource: &ModuleResource) -> String {
+    let stem = module_name.rsplit_once('.').map_or(module_name, |(s, _)| s);
+    let name = format!(
+        "{}_{}_{}_{}",
+        stem,
+        resource.entry.type_name(),
+        resource.entry.name,
+        resource.entry.language
+    );
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}.{}", name, resource_extension(resource))
+}
+
+/// Module Resources view listing the resource tree with per-resource save buttons
+#[component]
+pub fn ModuleResourcesView(name: String, path: String, resources: Vec<ModuleResource>) -> Element {
+    let mut status_message = use_signal(String::new);
+
+    // Group by resource type, keeping the directory order inside each type
+    let mut groups: BTreeMap<String, Vec<ModuleResource>> = BTreeMap::new();
+    for resource in &resources {
+        groups
+            .entry(resource.entry.type_name())
+            .or_default()
+            .push(resource.clone());
+    }
+    let total_size: u64 = resources.iter().map(|r| r.entry.size as u64).sum();
+    let embedded_images = resources
+        .iter()
+        .filter(|r| r.format == Some("PE image"))
+        .count();
+
+    rsx! {
+        div {
+            class: "pe-headers-view",
+
+            div {
+                class: "pe-mitigations",
+                span {
+                    class: "pe-mitigation pe-mitigation-on",
+                    "{resources.len()} resource(s), {total_size} bytes in {groups.len()} type(s)"
+                }
+                if embedded_images > 0 {
+                    span {
+                        class: "pe-mitigation pe-mitigation-off",
+                        "⚠ {embedded_images} embedded PE image(s)"
+                    }
+                }
+            }
+
+            if !status_message.read().is_empty() {
+                div { class: "thread-status-message", "{status_message}" }
+            }
+
+            if resources.is_empty() {
+                div {
+                    style: "padding: 20px; color: #6b7280; text-align: center;",
+                    "No resources (or the file could not be read)"
+                }
+            } else {
+                table {
+                    class: "thread-table",
+                    thead {
+                        tr {
+                            th { class: "th", "Name" }
+                            th { class: "th", "Language" }
+                            th { class: "th", "RVA" }
+                            th { class: "th", "Size" }
+                            th { class: "th", "Code Page" }
+                            th { class: "th", "Format" }
+                            th { class: "th", "" }
+                        }
+                    }
+                    tbody {
+                        for (type_name, entries) in groups {
+                            tr {
+                                key: "type-{type_name}",
+                                td {
+                                    class: "resource-type-row",
+                                    colspan: "7",
+                                    "📁 {type_name} ({entries.len()})"
+                                }
+                            }
+                            for resource in entries {
+                                {
+                                    let entry: ResourceEntry = resource.entry.clone();
+                                    let format = resource.format.unwrap_or("");
+                                    let format_class = if resource.format == Some("PE image") {
+                                        "cell resource-format resource-format-pe"
+                                    } else {
+                                        "cell resource-format"
+                                    };
+                                    let file_name = resource_file_name(&name, &resource);
+                                    let path = path.clone();
+                                    rsx! {
+                                        tr {
+                                            key: "{entry.type_id}-{entry.name}-{entry.language}-{entry.rva:X}",
+                                            class: "thread-row",
+                                            td { class: "cell", style: "padding-left: 24px;", "{entry.name}" }
+                                            td { class: "cell", style: "font-family: monospace;", "0x{entry.language:04X}" }
+                                            td { class: "cell", style: "font-family: monospace;", "0x{entry.rva:X}" }
+                                            td { class: "cell", "{entry.size}" }
+                                            td { class: "cell", "{entry.code_page}" }
+                                            td { class: "{format_class}", "{format}" }
+                                            td {
+                                                class: "cell",
+                                                button {
+                                                    class: "btn btn-small btn-primary",
+                                                    title: "Save resource data to a file",
+                                                    onclick: move |_| {
+                                                        let path = path.clone();
+                                                        let entry = entry.clone();
+                                                        let file_name = file_name.clone();
+                                                        spawn(async move {
+                                                            let file = rfd::AsyncFileDialog::new()
+                                                                .set_file_name(&file_name)
+                                                                .set_title("Save resource")
+                                                                .save_file()
+                                                                .await;
+                                                            if let Some(file) = file {
+                                                                match get_module_resource_data(&path, &entry) {
+                                                                    Ok(data) => {
+                                                                        let out = file.path().to_path_buf();
+                                                                        match std::fs::write(&out, &data) {
+                                                                            Ok(()) => {
+                                                                                status_message.set(format!(
+                                                                                    "✓ Saved {} bytes to {}",
+                                                                                    data.len(),
+                                                                                    out.display()
+                                                                                ));
+                                                                            }
+                                                                            Err(e) => {
+                                                                                status_message.set(format!("✗ Save failed: {}", e));
+                                                                            }
+                                                                        }
+                                                                    }
+                                                                    Err(e) => {
+                                                                        status_message.set(format!("✗ Failed to read resource data: {}", e));
+                                                                    }
+                                                                }
+                                                                spawn(async move {
+                                                                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                                                    status_message.set(String::new());
+                                                                });
+                                                            }
+                                                        });
+                                                    },
+                                                    "💾 Save"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
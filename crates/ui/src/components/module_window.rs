@@ -1,39 +1,131 @@
 //! Module window component
 
 use dioxus::prelude::*;
-use process::{get_module_imports, get_process_modules, ImportEntry, ModuleInfo};
+use process::{
+    check_module_integrity, get_module_exports, get_module_imports_with_api_sets,
+    get_file_packer_assessment, get_module_pe_headers, get_module_resources, system_api_set_schema,
+    ExportEntry, ImportEntry, ImportKind, ModuleInfo, ModuleIntegrity, ModuleResource, PeHeaders,
+};
+use std::collections::HashMap;
 
-use crate::helpers::copy_to_clipboard;
+use super::{FileDetailsPane, ModuleIntegrityView, ModuleResourcesView, PeHeadersView};
+use crate::helpers::{copy_to_clipboard, export_records, load_error, packer_cell, signer_cell};
+use crate::records::ModuleRecord;
+use crate::source;
 use crate::state::{ModuleContextMenuState, MODULE_WINDOW_STATE};
 
+/// Detail view shown in place of the module table
+#[derive(Clone, PartialEq)]
+enum ModuleDetail {
+    ImportsExports {
+        name: String,
+        imports: Vec<ImportEntry>,
+        exports: Vec<ExportEntry>,
+        /// Why the imports / exports could not be read, if they could not
+        import_error: Option<String>,
+        export_error: Option<String>,
+    },
+    Headers {
+        name: String,
+        /// The parsed headers, or why the file could not be read or parsed
+        headers: Result<Box<PeHeaders>, String>,
+    },
+    Integrity {
+        name: String,
+        base: usize,
+        /// The comparison, or why the module could not be compared
+        integrity: Result<ModuleIntegrity, String>,
+    },
+    Resources {
+        name: String,
+        path: String,
+        resources: Vec<ModuleResource>,
+        /// Why the resources could not be read, if they could not
+        error: Option<String>,
+    },
+}
+
+impl ModuleDetail {
+    fn imports_exports(name: String, path: &str) -> Self {
+        let imports = get_module_imports_with_api_sets(path, system_api_set_schema());
+        let exports = get_module_exports(path);
+        ModuleDetail::ImportsExports {
+            name,
+            import_error: load_error(&imports),
+            export_error: load_error(&exports),
+            imports: imports.unwrap_or_default(),
+            exports: exports.unwrap_or_default(),
+        }
+    }
+
+    fn headers(name: String, path: &str) -> Self {
+        ModuleDetail::Headers {
+            name,
+            headers: get_module_pe_headers(path)
+                .map(Box::new)
+                .map_err(|e| format!("⚠ {}", e)),
+        }
+    }
+
+    fn resources(name: String, path: &str) -> Self {
+        let resources = get_module_resources(path);
+        ModuleDetail::Resources {
+            name,
+            path: path.to_string(),
+            error: load_error(&resources),
+            resources: resources.unwrap_or_default(),
+        }
+    }
+
+    fn title(&self) -> String {
+        match self {
+            ModuleDetail::ImportsExports { name, .. } => format!("Imports / Exports for {}", name),
+            ModuleDetail::Headers { name, .. } => format!("PE Headers for {}", name),
+            ModuleDetail::Integrity { name, .. } => format!("Integrity of {}", name),
+            ModuleDetail::Resources { name, .. } => format!("Resources of {}", name),
+        }
+    }
+}
+
 /// Module Window component
 #[component]
 pub fn ModuleWindow(pid: u32, process_name: String) -> Element {
-    let mut modules = use_signal(|| get_process_modules(pid));
+    let mut modules = use_signal(|| source::modules(pid));
     let mut selected_module = use_signal(|| None::<usize>);
     let mut context_menu = use_signal(|| ModuleContextMenuState::default());
     let mut status_message = use_signal(|| String::new());
     let mut auto_refresh = use_signal(|| false);
     let mut filter_name = use_signal(|| String::new());
-    let mut inspecting = use_signal(|| None::<(String, Vec<ImportEntry>)>);
+    let mut packer_filter = use_signal(|| "all".to_string());
+    let mut inspecting = use_signal(|| None::<ModuleDetail>);
+    let mut integrity = use_signal(HashMap::<usize, ModuleIntegrity>::new);
 
     // Auto-refresh every 3 seconds (if enabled)
     use_future(move || async move {
         loop {
             tokio::time::sleep(std::time::Duration::from_secs(3)).await;
             if *auto_refresh.read() {
-                modules.set(get_process_modules(pid));
+                modules.set(source::modules(pid));
             }
         }
     });
 
+    let read_only = source::is_read_only();
     let ctx_menu = context_menu.read().clone();
     let filter = filter_name.read().clone();
+    let min_packer_score = match packer_filter.read().as_str() {
+        "suspicious" => Some(pe::SUSPICIOUS_SCORE),
+        "packed" => Some(pe::PACKED_SCORE),
+        _ => None,
+    };
+
+    let error_message = load_error(&modules.read());
 
     // Filter modules by name
     let module_list: Vec<ModuleInfo> = modules
         .read()
         .iter()
+        .flatten()
         .filter(|m| {
             if filter.is_empty() {
                 true
@@ -42,10 +134,18 @@ pub fn ModuleWindow(pid: u32, process_name: String) -> Element {
                     || m.path.to_lowercase().contains(&filter.to_lowercase())
             }
         })
+        .filter(|m| match min_packer_score {
+            Some(min) => get_file_packer_assessment(&m.path).is_some_and(|a| a.score >= min),
+            None => true,
+        })
         .cloned()
         .collect();
     let module_count = module_list.len();
-    let total_modules = modules.read().len();
+    let export_modules = module_list.clone();
+    let selected_details = selected_module
+        .read()
+        .and_then(|base| module_list.iter().find(|m| m.base_address == base).cloned());
+    let total_modules = modules.read().as_ref().map_or(0, Vec::len);
 
     let inspect_state = inspecting.read().clone();
 
@@ -59,7 +159,7 @@ pub fn ModuleWindow(pid: u32, process_name: String) -> Element {
 
             // Modal window
             div {
-                class: "thread-modal handle-modal",
+                class: if inspect_state.is_some() { "thread-modal handle-modal module-inspect-modal" } else { "thread-modal handle-modal" },
                 onclick: move |e| e.stop_propagation(),
 
                 // Header
@@ -78,8 +178,7 @@ pub fn ModuleWindow(pid: u32, process_name: String) -> Element {
                     }
                 }
 
-                if let Some((ref module_name, ref import_entries)) = inspect_state {
-                    // Import detail view
+                if let Some(ref detail) = inspect_state {
                     div {
                         class: "module-import-header",
                         button {
@@ -88,25 +187,119 @@ pub fn ModuleWindow(pid: u32, process_name: String) -> Element {
                             },
                             "← Back"
                         }
-                        span { "Imports for {module_name}" }
+                        span { "{detail.title()}" }
                     }
 
-                    div {
-                        class: "thread-table-container",
-                        if import_entries.is_empty() {
-                            div {
-                                style: "padding: 20px; color: #6b7280; text-align: center;",
-                                "No imports found (or unable to parse PE file)"
+                    if let ModuleDetail::Headers { ref headers, .. } = detail {
+                        // PE header detail view
+                        match headers {
+                            Ok(headers) => rsx! {
+                                PeHeadersView { headers: headers.as_ref().clone() }
+                            },
+                            Err(error) => rsx! {
+                                div { class: "load-error", "{error}" }
+                            },
+                        }
+                    }
+
+                    if let ModuleDetail::Integrity { base, ref integrity, .. } = detail {
+                        // Memory vs disk comparison view
+                        match integrity {
+                            Ok(integrity) => rsx! {
+                                ModuleIntegrityView { base: *base, integrity: integrity.clone() }
+                            },
+                            Err(error) => rsx! {
+                                div { class: "load-error", "{error}" }
+                            },
+                        }
+                    }
+
+                    if let ModuleDetail::Resources { ref name, ref path, ref resources, ref error } = detail {
+                        // Resource tree view
+                        if let Some(error) = error {
+                            div { class: "load-error", "{error}" }
+                        } else {
+                            ModuleResourcesView {
+                                name: name.clone(),
+                                path: path.clone(),
+                                resources: resources.clone(),
                             }
                         }
-                        for entry in import_entries.iter() {
-                            {
-                                let dll = entry.dll_name.clone();
-                                let funcs = entry.functions.clone();
-                                rsx! {
-                                    div { class: "module-import-dll", "{dll}" }
-                                    for func in funcs.iter() {
-                                        div { class: "module-import-fn", "{func}" }
+                    }
+
+                    if let ModuleDetail::ImportsExports { imports: ref import_entries, exports: ref export_entries, ref import_error, ref export_error, .. } = detail {
+                        // Import/export detail view
+                        div {
+                            class: "module-inspect-panels",
+
+                            // Imports panel
+                            div {
+                                class: "module-inspect-panel",
+                                div { class: "module-inspect-panel-title", "Imports ({import_entries.len()})" }
+                                if let Some(error) = import_error {
+                                    div { class: "load-error", "{error}" }
+                                } else if import_entries.is_empty() {
+                                    div {
+                                        style: "padding: 20px; color: #6b7280; text-align: center;",
+                                        "No imports found"
+                                    }
+                                }
+                                for entry in import_entries.iter() {
+                                    {
+                                        let dll = entry.dll_name.clone();
+                                        let funcs = entry.functions.clone();
+                                        let kind = entry.kind;
+                                        let host = entry.host_dll.clone();
+                                        rsx! {
+                                            div {
+                                                class: "module-import-dll",
+                                                "{dll}"
+                                                if let Some(host) = host {
+                                                    span { class: "module-import-host", " → {host}" }
+                                                }
+                                                if kind != ImportKind::Normal {
+                                                    span { class: "module-import-kind", "{kind}" }
+                                                }
+                                            }
+                                            for func in funcs.iter() {
+                                                div { class: "module-import-fn", "{func}" }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+
+                            // Exports panel
+                            div {
+                                class: "module-inspect-panel",
+                                div { class: "module-inspect-panel-title", "Exports ({export_entries.len()})" }
+                                if let Some(error) = export_error {
+                                    div { class: "load-error", "{error}" }
+                                } else if export_entries.is_empty() {
+                                    div {
+                                        style: "padding: 20px; color: #6b7280; text-align: center;",
+                                        "No exports found"
+                                    }
+                                }
+                                for export in export_entries.iter() {
+                                    {
+                                        let name = export
+                                            .name
+                                            .clone()
+                                            .unwrap_or_else(|| format!("Ordinal #{}", export.ordinal));
+                                        let ordinal = export.ordinal;
+                                        let rva = export.rva;
+                                        let forwarder = export.forwarder.clone();
+                                        rsx! {
+                                            div { class: "module-export-row",
+                                                span { class: "module-export-ordinal", "#{ordinal}" }
+                                                span { class: "module-export-rva", "0x{rva:08X}" }
+                                                span { class: "module-export-name", "{name}" }
+                                                if let Some(target) = forwarder {
+                                                    span { class: "module-export-forwarder", "→ {target}" }
+                                                }
+                                            }
+                                        }
                                     }
                                 }
                             }
@@ -126,6 +319,16 @@ pub fn ModuleWindow(pid: u32, process_name: String) -> Element {
                             oninput: move |e| filter_name.set(e.value().clone()),
                         }
 
+                        select {
+                            class: "filter-select",
+                            value: "{packer_filter}",
+                            title: "Filter by packer / obfuscation score",
+                            onchange: move |e| packer_filter.set(e.value().clone()),
+                            option { value: "all", "All Modules" }
+                            option { value: "suspicious", "Suspicious (score ≥ {pe::SUSPICIOUS_SCORE})" }
+                            option { value: "packed", "Likely Packed (score ≥ {pe::PACKED_SCORE})" }
+                        }
+
                         label { class: "checkbox-label",
                             input {
                                 r#type: "checkbox",
@@ -139,10 +342,81 @@ pub fn ModuleWindow(pid: u32, process_name: String) -> Element {
                         button {
                             class: "btn btn-small btn-primary",
                             onclick: move |_| {
-                                modules.set(get_process_modules(pid));
+                                modules.set(source::modules(pid));
                             },
                             "🔄 Refresh"
                         }
+
+                        button {
+                            class: "btn btn-small btn-primary",
+                            onclick: {
+                                let export_modules = export_modules.clone();
+                                move |_| {
+                                    let export_modules = export_modules.clone();
+                                    spawn(async move {
+                                        let message = export_records(
+                                            "Export Modules",
+                                            &format!("modules_{}", pid),
+                                            "modules",
+                                            move || export_modules.into_iter().map(ModuleRecord::new).collect(),
+                                        )
+                                        .await;
+                                        if let Some(message) = message {
+                                            status_message.set(message);
+                                            spawn(async move {
+                                                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                                status_message.set(String::new());
+                                            });
+                                        }
+                                    });
+                                }
+                            },
+                            "💾 Export"
+                        }
+
+                        if !read_only {
+                            button {
+                                class: "btn btn-small btn-primary",
+                                title: "Compare read-only sections in memory against the files on disk",
+                                onclick: move |_| {
+                                    let mut results = HashMap::new();
+                                    let mut failures = Vec::new();
+                                    for module in modules.read().iter().flatten() {
+                                        match check_module_integrity(pid, module) {
+                                            Ok(result) => {
+                                                results.insert(module.base_address, result);
+                                            }
+                                            Err(e) => failures.push(format!("{}: {}", module.name, e)),
+                                        }
+                                    }
+                                    let modified = results.values().filter(|r| r.is_modified()).count();
+                                    let unverified = results
+                                        .values()
+                                        .filter(|r| !r.is_modified() && !r.is_complete())
+                                        .count();
+                                    let mut message = format!(
+                                        "🛡 Checked {} modules, {} modified, {} unverified",
+                                        results.len(),
+                                        modified,
+                                        unverified
+                                    );
+                                    if let Some(first) = failures.first() {
+                                        message.push_str(&format!(
+                                            ", {} failed (✗ {})",
+                                            failures.len(),
+                                            first
+                                        ));
+                                    }
+                                    status_message.set(message);
+                                    integrity.set(results);
+                                    spawn(async move {
+                                        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                        status_message.set(String::new());
+                                    });
+                                },
+                                "🛡 Check Integrity"
+                            }
+                        }
                     }
 
                     // Status message
@@ -150,6 +424,11 @@ pub fn ModuleWindow(pid: u32, process_name: String) -> Element {
                         div { class: "thread-status-message", "{status_message}" }
                     }
 
+                    // Why the module list is empty, if it could not be read
+                    if let Some(error) = error_message {
+                        div { class: "load-error", "{error}" }
+                    }
+
                     // Module table
                     div {
                         class: "thread-table-container",
@@ -160,6 +439,11 @@ pub fn ModuleWindow(pid: u32, process_name: String) -> Element {
                                     th { class: "th", "Name" }
                                     th { class: "th", "Base Address" }
                                     th { class: "th", "Size" }
+                                    th { class: "th", "Description" }
+                                    th { class: "th", "Company" }
+                                    th { class: "th", "Version" }
+                                    th { class: "th", title: "Packer / obfuscation score (0-100)", "Packing" }
+                                    th { class: "th", "Signer" }
                                     th { class: "th", "Path" }
                                     th { class: "th", "Actions" }
                                 }
@@ -175,6 +459,12 @@ pub fn ModuleWindow(pid: u32, process_name: String) -> Element {
                                         let mod_name_inspect = module.name.clone();
                                         let is_selected = *selected_module.read() == Some(base);
                                         let row_class = if is_selected { "thread-row selected" } else { "thread-row" };
+                                        let integrity_state = integrity.read().get(&base).map(|r| (r.is_modified(), r.is_complete(), r.patch_count()));
+                                        let (signer, signer_class, signer_tooltip) = signer_cell(&module.path);
+                                        let (packer, packer_class, packer_tooltip) = packer_cell(&module.path);
+                                        let description = module.description.clone().unwrap_or_default();
+                                        let company = module.company.clone().unwrap_or_default();
+                                        let version = module.version.clone().unwrap_or_default();
                                         let size_display = if module.size >= 1024 * 1024 {
                                             format!("{:.1} MB", module.size as f64 / (1024.0 * 1024.0))
                                         } else {
@@ -208,45 +498,77 @@ pub fn ModuleWindow(pid: u32, process_name: String) -> Element {
                                                         });
                                                     }
                                                 },
-                                                td { class: "cell", style: "font-weight: 500;", "{mod_name}" }
+                                                td { class: "cell", style: "font-weight: 500;",
+                                                    "{mod_name}"
+                                                    match integrity_state {
+                                                        Some((true, _, patches)) => rsx! {
+                                                            span {
+                                                                class: "module-integrity-badge module-integrity-modified",
+                                                                title: "{patches} patched range(s) in read-only sections",
+                                                                "modified"
+                                                            }
+                                                        },
+                                                        Some((false, false, _)) => rsx! {
+                                                            span {
+                                                                class: "module-integrity-badge module-integrity-unverified",
+                                                                title: "Some read-only sections could not be read from memory",
+                                                                "unverified"
+                                                            }
+                                                        },
+                                                        Some((false, true, _)) => rsx! {
+                                                            span {
+                                                                class: "module-integrity-badge module-integrity-clean",
+                                                                title: "Read-only sections match the file on disk",
+                                                                "✓"
+                                                            }
+                                                        },
+                                                        None => rsx! {},
+                                                    }
+                                                }
                                                 td { class: "cell cell-handle", "0x{base:X}" }
                                                 td { class: "cell", style: "font-family: monospace; color: #9ca3af;", "{size_display}" }
+                                                td { class: "cell cell-description", title: "{description}", "{description}" }
+                                                td { class: "cell cell-company", title: "{company}", "{company}" }
+                                                td { class: "cell", style: "font-family: monospace; color: #9ca3af;", "{version}" }
+                                                td { class: "{packer_class}", title: "{packer_tooltip}", "{packer}" }
+                                                td { class: "{signer_class}", title: "{signer_tooltip}", "{signer}" }
                                                 td { class: "cell cell-path", title: "{mod_path}", "{mod_path}" }
                                                 td { class: "cell cell-actions",
                                                     button {
                                                         class: "action-btn action-btn-warning",
-                                                        title: "Inspect Imports",
+                                                        title: "Inspect Imports / Exports",
                                                         onclick: {
                                                             let path = mod_path_inspect.clone();
                                                             let name = mod_name_inspect.clone();
                                                             move |e: Event<MouseData>| {
                                                                 e.stop_propagation();
-                                                                let entries = get_module_imports(&path);
-                                                                inspecting.set(Some((name.clone(), entries)));
+                                                                inspecting.set(Some(ModuleDetail::imports_exports(name.clone(), &path)));
                                                             }
                                                         },
                                                         "🔍"
                                                     }
-                                                    button {
-                                                        class: "action-btn action-btn-danger",
-                                                        title: "Unload Module",
-                                                        onclick: move |e: Event<MouseData>| {
-                                                            e.stop_propagation();
-                                                            match misc::unload_module(pid, base) {
-                                                                Ok(()) => {
-                                                                    status_message.set(format!("✓ Module at 0x{:X} unloaded", base));
-                                                                    modules.set(get_process_modules(pid));
-                                                                }
-                                                                Err(err) => {
-                                                                    status_message.set(format!("✗ Unload failed: {}", err));
+                                                    if !read_only {
+                                                        button {
+                                                            class: "action-btn action-btn-danger",
+                                                            title: "Unload Module",
+                                                            onclick: move |e: Event<MouseData>| {
+                                                                e.stop_propagation();
+                                                                match misc::unload_module(pid, base) {
+                                                                    Ok(()) => {
+                                                                        status_message.set(format!("✓ Module at 0x{:X} unloaded", base));
+                                                                        modules.set(source::modules(pid));
+                                                                    }
+                                                                    Err(err) => {
+                                                                        status_message.set(format!("✗ Unload failed: {}", err));
+                                                                    }
                                                                 }
-                                                            }
-                                                            spawn(async move {
-                                                                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-                                                                status_message.set(String::new());
-                                                            });
-                                                        },
-                                                        "✕"
+                                                                spawn(async move {
+                                                                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                                                    status_message.set(String::new());
+                                                                });
+                                                            },
+                                                            "✕"
+                                                        }
                                                     }
                                                 }
                                             }
@@ -257,6 +579,14 @@ pub fn ModuleWindow(pid: u32, process_name: String) -> Element {
                         }
                     }
 
+                    // Details of the selected module's file
+                    if let Some(module) = selected_details {
+                        FileDetailsPane {
+                            title: format!("{} at 0x{:X}", module.name, module.base_address),
+                            path: module.path.clone(),
+                        }
+                    }
+
                     // Context menu for modules
                     if ctx_menu.visible {
                         div {
@@ -264,31 +594,51 @@ pub fn ModuleWindow(pid: u32, process_name: String) -> Element {
                             style: "left: {ctx_menu.x}px; top: {ctx_menu.y}px;",
                             onclick: move |e| e.stop_propagation(),
 
-                            button {
-                                class: "context-menu-item context-menu-item-danger",
-                                onclick: {
-                                    let ctx_base = ctx_menu.module_base;
-                                    move |_| {
-                                        if let Some(base) = ctx_base {
-                                            match misc::unload_module(pid, base) {
-                                                Ok(()) => {
-                                                    status_message.set(format!("✓ Module at 0x{:X} unloaded", base));
-                                                    modules.set(get_process_modules(pid));
-                                                }
-                                                Err(err) => {
-                                                    status_message.set(format!("✗ Unload failed: {}", err));
+                            if !read_only {
+                                button {
+                                    class: "context-menu-item context-menu-item-danger",
+                                    onclick: {
+                                        let ctx_base = ctx_menu.module_base;
+                                        move |_| {
+                                            if let Some(base) = ctx_base {
+                                                match misc::unload_module(pid, base) {
+                                                    Ok(()) => {
+                                                        status_message.set(format!("✓ Module at 0x{:X} unloaded", base));
+                                                        modules.set(source::modules(pid));
+                                                    }
+                                                    Err(err) => {
+                                                        status_message.set(format!("✗ Unload failed: {}", err));
+                                                    }
                                                 }
+                                                spawn(async move {
+                                                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                                    status_message.set(String::new());
+                                                });
                                             }
-                                            spawn(async move {
-                                                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-                                                status_message.set(String::new());
-                                            });
+                                            context_menu.set(ModuleContextMenuState::default());
                                         }
+                                    },
+                                    span { "✕" }
+                                    span { "Unload Module" }
+                                }
+                            }
+
+                            button {
+                                class: "context-menu-item",
+                                onclick: {
+                                    let ctx_path = ctx_menu.module_path.clone();
+                                    move |_| {
+                                        let name = ctx_path
+                                            .rsplit('\\')
+                                            .next()
+                                            .unwrap_or(&ctx_path)
+                                            .to_string();
+                                        inspecting.set(Some(ModuleDetail::imports_exports(name, &ctx_path)));
                                         context_menu.set(ModuleContextMenuState::default());
                                     }
                                 },
-                                span { "✕" }
-                                span { "Unload Module" }
+                                span { "🔍" }
+                                span { "Inspect Imports / Exports" }
                             }
 
                             button {
@@ -296,18 +646,66 @@ pub fn ModuleWindow(pid: u32, process_name: String) -> Element {
                                 onclick: {
                                     let ctx_path = ctx_menu.module_path.clone();
                                     move |_| {
-                                        let entries = get_module_imports(&ctx_path);
                                         let name = ctx_path
                                             .rsplit('\\')
                                             .next()
                                             .unwrap_or(&ctx_path)
                                             .to_string();
-                                        inspecting.set(Some((name, entries)));
+                                        inspecting.set(Some(ModuleDetail::headers(name, &ctx_path)));
                                         context_menu.set(ModuleContextMenuState::default());
                                     }
                                 },
-                                span { "🔍" }
-                                span { "Inspect Imports" }
+                                span { "🧾" }
+                                span { "PE Headers" }
+                            }
+
+                            button {
+                                class: "context-menu-item",
+                                onclick: {
+                                    let ctx_path = ctx_menu.module_path.clone();
+                                    move |_| {
+                                        let name = ctx_path
+                                            .rsplit('\\')
+                                            .next()
+                                            .unwrap_or(&ctx_path)
+                                            .to_string();
+                                        inspecting.set(Some(ModuleDetail::resources(name, &ctx_path)));
+                                        context_menu.set(ModuleContextMenuState::default());
+                                    }
+                                },
+                                span { "📦" }
+                                span { "Resources" }
+                            }
+
+                            if !read_only {
+                                button {
+                                    class: "context-menu-item",
+                                    onclick: {
+                                        let ctx_path = ctx_menu.module_path.clone();
+                                        let ctx_base = ctx_menu.module_base;
+                                        move |_| {
+                                            if let Some(base) = ctx_base {
+                                                let module = modules.read().iter().flatten().find(|m| m.base_address == base).cloned();
+                                                let result = match module {
+                                                    Some(m) => check_module_integrity(pid, &m).map_err(|e| format!("⚠ {}", e)),
+                                                    None => Err(format!("⚠ Module at 0x{:X} is no longer loaded", base)),
+                                                };
+                                                if let Ok(ref result) = result {
+                                                    integrity.write().insert(base, result.clone());
+                                                }
+                                                let name = ctx_path
+                                                    .rsplit('\\')
+                                                    .next()
+                                                    .unwrap_or(&ctx_path)
+                                                    .to_string();
+                                                inspecting.set(Some(ModuleDetail::Integrity { name, base, integrity: result }));
+                                            }
+                                            context_menu.set(ModuleContextMenuState::default());
+                                        }
+                                    },
+                                    span { "🛡" }
+                                    span { "Check Integrity" }
+                                }
                             }
 
                             div { class: "context-menu-separator" }
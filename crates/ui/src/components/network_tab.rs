@@ -1,10 +1,14 @@
 //! Network connections tab component
 
 use dioxus::prelude::*;
-use network::{get_network_connections, NetworkConnection, Protocol, TcpState};
-use process::{kill_process, open_file_location};
+use network::{
+    compare_connections, matches_connection, NetworkConnection, NetworkError, NetworkSortColumn,
+    Protocol, SortOrder, TcpState,
+};
+use process::{kill_process, open_file_location, RefreshEngine};
 
-use crate::helpers::copy_to_clipboard;
+use crate::helpers::{copy_to_clipboard, export_records, load_error};
+use crate::source;
 
 /// Network context menu state
 #[derive(Clone, Debug, Default)]
@@ -17,30 +21,11 @@ struct NetworkContextMenuState {
     port: u16,
 }
 
-/// Sort column for network table
-#[derive(Clone, Copy, PartialEq, Debug)]
-enum NetworkSortColumn {
-    Protocol,
-    LocalAddr,
-    LocalPort,
-    RemoteAddr,
-    RemotePort,
-    State,
-    Process,
-    Pid,
-}
-
-/// Sort order
-#[derive(Clone, Copy, PartialEq, Debug)]
-enum SortOrder {
-    Ascending,
-    Descending,
-}
-
 /// Network Tab component
 #[component]
 pub fn NetworkTab() -> Element {
-    let mut connections = use_signal(|| get_network_connections());
+    let mut connections: Signal<Result<Vec<NetworkConnection>, NetworkError>> =
+        use_signal(|| Ok(Vec::new()));
     let mut search_query = use_signal(|| String::new());
     let mut sort_column = use_signal(|| NetworkSortColumn::LocalPort);
     let mut sort_order = use_signal(|| SortOrder::Ascending);
@@ -51,12 +36,35 @@ pub fn NetworkTab() -> Element {
     let mut protocol_filter = use_signal(|| String::new()); // "", "tcp", "udp"
     let mut state_filter = use_signal(|| String::new()); // "", "listen", "established", etc.
 
+    // Previous table, so a refresh only touches the signal when something changed
+    let mut engine = use_hook(|| CopyValue::new(RefreshEngine::new(NetworkConnection::key)));
+    let mut refresh = move || match source::connections() {
+        Ok(listing) => {
+            let changed = !engine.write().update(listing.clone()).is_empty();
+            if changed || connections.read().is_err() {
+                connections.set(Ok(listing));
+            }
+        }
+        Err(e) => connections.set(Err(e)),
+    };
+
+    // Load the table, and reload it whenever a snapshot is opened or closed
+    use_effect(move || {
+        let listing = source::connections();
+        engine
+            .write()
+            .reset(listing.as_ref().cloned().unwrap_or_default());
+        connections.set(listing);
+        selected_row.set(None);
+    });
+    let read_only = source::is_read_only();
+
     // Auto-refresh every 3 seconds
     use_future(move || async move {
         loop {
             tokio::time::sleep(std::time::Duration::from_secs(3)).await;
             if *auto_refresh.read() {
-                connections.set(get_network_connections());
+                refresh();
             }
         }
     });
@@ -69,19 +77,22 @@ pub fn NetworkTab() -> Element {
         }
 
         if e.key() == Key::F5 {
-            connections.set(get_network_connections());
+            refresh();
             return;
         }
 
-        if e.key() == Key::Delete {
+        if e.key() == Key::Delete && !read_only {
             let row_to_kill = *selected_row.read();
             if let Some((pid, _)) = row_to_kill {
-                if kill_process(pid) {
-                    status_message.set(format!("✓ Process {} terminated", pid));
-                    connections.set(get_network_connections());
-                    selected_row.set(None);
-                } else {
-                    status_message.set(format!("✗ Failed to terminate process {}", pid));
+                match kill_process(pid) {
+                    Ok(()) => {
+                        status_message.set(format!("✓ Process {} terminated", pid));
+                        refresh();
+                        selected_row.set(None);
+                    }
+                    Err(e) => {
+                        status_message.set(format!("✗ Failed to terminate process {}: {}", pid, e));
+                    }
                 }
                 spawn(async move {
                     tokio::time::sleep(std::time::Duration::from_secs(3)).await;
@@ -91,77 +102,24 @@ pub fn NetworkTab() -> Element {
         }
     };
 
+    let error_message = load_error(&connections.read());
+
     // Filter and sort connections
+    let query = search_query.read().to_lowercase();
     let mut filtered_connections: Vec<NetworkConnection> = connections
         .read()
         .iter()
-        .filter(|c| {
-            // Protocol filter
-            let proto_match = match protocol_filter.read().as_str() {
-                "tcp" => c.protocol == Protocol::Tcp,
-                "udp" => c.protocol == Protocol::Udp,
-                "all" => true,
-                _ => true,
-            };
-
-            // State filter
-            let state_match = if state_filter.read().as_str() == "all" {
-                true
-            } else {
-                match &c.state {
-                    Some(state) => state
-                        .to_string()
-                        .to_lowercase()
-                        .contains(&state_filter.read().to_lowercase()),
-                    None => state_filter.read().is_empty(),
-                }
-            };
-
-            // Search filter
-            let query = search_query.read().to_lowercase();
-            let search_match = if query.is_empty() {
-                true
-            } else {
-                c.local_addr.to_lowercase().contains(&query)
-                    || c.local_port.to_string().contains(&query)
-                    || c.remote_addr.to_lowercase().contains(&query)
-                    || c.remote_port.to_string().contains(&query)
-                    || c.process_name.to_lowercase().contains(&query)
-                    || c.pid.to_string().contains(&query)
-            };
-
-            proto_match && state_match && search_match
-        })
+        .flatten()
+        .filter(|c| matches_connection(c, &protocol_filter.read(), &state_filter.read(), &query))
         .cloned()
         .collect();
 
-    // Sort
-    filtered_connections.sort_by(|a, b| {
-        let cmp = match *sort_column.read() {
-            NetworkSortColumn::Protocol => a.protocol.to_string().cmp(&b.protocol.to_string()),
-            NetworkSortColumn::LocalAddr => a.local_addr.cmp(&b.local_addr),
-            NetworkSortColumn::LocalPort => a.local_port.cmp(&b.local_port),
-            NetworkSortColumn::RemoteAddr => a.remote_addr.cmp(&b.remote_addr),
-            NetworkSortColumn::RemotePort => a.remote_port.cmp(&b.remote_port),
-            NetworkSortColumn::State => {
-                let a_state = a.state.map(|s| s.to_string()).unwrap_or_default();
-                let b_state = b.state.map(|s| s.to_string()).unwrap_or_default();
-                a_state.cmp(&b_state)
-            }
-            NetworkSortColumn::Process => a
-                .process_name
-                .to_lowercase()
-                .cmp(&b.process_name.to_lowercase()),
-            NetworkSortColumn::Pid => a.pid.cmp(&b.pid),
-        };
-        match *sort_order.read() {
-            SortOrder::Ascending => cmp,
-            SortOrder::Descending => cmp.reverse(),
-        }
-    });
+    let column = *sort_column.read();
+    let order = *sort_order.read();
+    filtered_connections.sort_by(|a, b| compare_connections(a, b, column, order));
 
     let connection_count = filtered_connections.len();
-    let total_count = connections.read().len();
+    let total_count = connections.read().as_ref().map_or(0, Vec::len);
 
     let current_sort_col = *sort_column.read();
     let current_sort_ord = *sort_order.read();
@@ -196,6 +154,11 @@ pub fn NetworkTab() -> Element {
                 if !status_message.read().is_empty() {
                     div { class: "status-message", "{status_message}" }
                 }
+
+                // Why the connection table is empty, if it could not be read
+                if let Some(error) = error_message {
+                    div { class: "load-error", "{error}" }
+                }
             }
 
             // Controls
@@ -241,31 +204,36 @@ pub fn NetworkTab() -> Element {
                 button {
                     class: "btn btn-primary",
                     onclick: move |_| {
-                        connections.set(get_network_connections());
+                        refresh();
                     },
                     "🔄 Refresh"
                 }
 
-                button {
-                    class: "btn btn-danger",
-                    disabled: selected_row.read().is_none(),
-                    onclick: move |_| {
-                        let row_to_kill = *selected_row.read();
-                        if let Some((pid, _)) = row_to_kill {
-                            if kill_process(pid) {
-                                status_message.set(format!("✓ Process {} terminated", pid));
-                                connections.set(get_network_connections());
-                                selected_row.set(None);
-                            } else {
-                                status_message.set(format!("✗ Failed to terminate process {}", pid));
+                if !read_only {
+                    button {
+                        class: "btn btn-danger",
+                        disabled: selected_row.read().is_none(),
+                        onclick: move |_| {
+                            let row_to_kill = *selected_row.read();
+                            if let Some((pid, _)) = row_to_kill {
+                                match kill_process(pid) {
+                                    Ok(()) => {
+                                        status_message.set(format!("✓ Process {} terminated", pid));
+                                        refresh();
+                                        selected_row.set(None);
+                                    }
+                                    Err(e) => {
+                                        status_message.set(format!("✗ Failed to terminate process {}: {}", pid, e));
+                                    }
+                                }
+                                spawn(async move {
+                                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                    status_message.set(String::new());
+                                });
                             }
-                            spawn(async move {
-                                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-                                status_message.set(String::new());
-                            });
-                        }
-                    },
-                    "Kill Process"
+                        },
+                        "Kill Process"
+                    }
                 }
 
                 button {
@@ -275,37 +243,15 @@ pub fn NetworkTab() -> Element {
                         move |_| {
                             let conns = conns.clone();
                             spawn(async move {
-                                let file = rfd::AsyncFileDialog::new()
-                                    .add_filter("CSV", &["csv"])
-                                    .set_file_name("network_connections.csv")
-                                    .set_title("Export Network Connections")
-                                    .save_file()
-                                    .await;
-                                if let Some(file) = file {
-                                    let path = file.path().to_path_buf();
-                                    let mut csv = String::from("Protocol,Local Address,Local Port,Remote Address,Remote Port,State,PID,Process\n");
-                                    for c in &conns {
-                                        let state_str = c.state.map(|s| s.to_string()).unwrap_or_default();
-                                        csv.push_str(&format!(
-                                            "{},{},{},{},{},{},{},\"{}\"\n",
-                                            c.protocol,
-                                            c.local_addr,
-                                            c.local_port,
-                                            c.remote_addr,
-                                            c.remote_port,
-                                            state_str,
-                                            c.pid,
-                                            c.process_name.replace('"', "\"\"")
-                                        ));
-                                    }
-                                    match std::fs::write(&path, csv) {
-                                        Ok(()) => {
-                                            status_message.set(format!("Exported {} connections to {}", conns.len(), path.display()));
-                                        }
-                                        Err(e) => {
-                                            status_message.set(format!("Export failed: {}", e));
-                                        }
-                                    }
+                                let message = export_records(
+                                    "Export Network Connections",
+                                    "network_connections",
+                                    "connections",
+                                    move || conns,
+                                )
+                                .await;
+                                if let Some(message) = message {
+                                    status_message.set(message);
                                     spawn(async move {
                                         tokio::time::sleep(std::time::Duration::from_secs(3)).await;
                                         status_message.set(String::new());
@@ -314,7 +260,7 @@ pub fn NetworkTab() -> Element {
                             });
                         }
                     },
-                    "Export CSV"
+                    "Export"
                 }
             }
 
@@ -507,29 +453,34 @@ pub fn NetworkTab() -> Element {
                     style: "left: {ctx_menu.x}px; top: {ctx_menu.y}px;",
                     onclick: move |e| e.stop_propagation(),
 
-                    button {
-                        class: "context-menu-item context-menu-item-danger",
-                        onclick: move |_| {
-                            if let Some(pid) = ctx_menu.pid {
-                                if kill_process(pid) {
-                                    status_message.set(format!("✓ Process {} terminated", pid));
-                                    connections.set(get_network_connections());
-                                    selected_row.set(None);
-                                } else {
-                                    status_message.set(format!("✗ Failed to terminate process {}", pid));
+                    if !read_only {
+                        button {
+                            class: "context-menu-item context-menu-item-danger",
+                            onclick: move |_| {
+                                if let Some(pid) = ctx_menu.pid {
+                                    match kill_process(pid) {
+                                        Ok(()) => {
+                                            status_message.set(format!("✓ Process {} terminated", pid));
+                                            refresh();
+                                            selected_row.set(None);
+                                        }
+                                        Err(e) => {
+                                            status_message.set(format!("✗ Failed to terminate process {}: {}", pid, e));
+                                        }
+                                    }
+                                    spawn(async move {
+                                        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                        status_message.set(String::new());
+                                    });
                                 }
-                                spawn(async move {
-                                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-                                    status_message.set(String::new());
-                                });
-                            }
-                            context_menu.set(NetworkContextMenuState::default());
-                        },
-                        span { "☠️" }
-                        span { "Kill Process" }
-                    }
+                                context_menu.set(NetworkContextMenuState::default());
+                            },
+                            span { "☠️" }
+                            span { "Kill Process" }
+                        }
 
-                    div { class: "context-menu-separator" }
+                        div { class: "context-menu-separator" }
+                    }
 
                     button {
                         class: "context-menu-item",
@@ -0,0 +1,636 @@
+//! PE header inspector view
+
+use dioxus::prelude::*;
+use pe::{
+    data_directory_name, dll_characteristics_names, file_characteristics_names, format_timestamp,
+    machine_name, section_characteristics_names, section_protection, subsystem_name,
+    DLL_DYNAMIC_BASE, DLL_GUARD_CF, DLL_HIGH_ENTROPY_VA, DLL_NX_COMPAT, UNW_FLAG_CHAININFO,
+    UNW_FLAG_EHANDLER, UNW_FLAG_UHANDLER,
+};
+use process::PeHeaders;
+
+/// Runtime functions listed before truncating the exception table
+const MAX_RUNTIME_FUNCTION_ROWS: usize = 500;
+
+/// Format an optional load config pointer field
+fn optional_va(value: Option<u64>) -> String {
+    match value {
+        Some(va) => format!("0x{:X}", va),
+        None => "-".to_string(),
+    }
+}
+
+/// Names of the UNW_FLAG_* bits
+fn unwind_flag_names(flags: u8) -> String {
+    let names: Vec<&str> = [
+        (UNW_FLAG_EHANDLER, "EHANDLER"),
+        (UNW_FLAG_UHANDLER, "UHANDLER"),
+        (UNW_FLAG_CHAININFO, "CHAININFO"),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| flags & flag != 0)
+    .map(|(_, name)| name)
+    .collect();
+    names.join(" | ")
+}
+
+/// PE Headers view (DOS, COFF, optional header, data directories, sections)
+#[component]
+pub fn PeHeadersView(headers: PeHeaders) -> Element {
+    let dos = &headers.dos_header;
+    let file = &headers.file_header;
+    let opt = &headers.optional_header;
+
+    let dos_fields: Vec<(&str, String)> = vec![
+        ("e_magic", format!("0x{:04X} (MZ)", dos.magic)),
+        ("e_cblp", format!("0x{:04X}", dos.bytes_on_last_page)),
+        ("e_cp", format!("0x{:04X}", dos.pages_in_file)),
+        ("e_crlc", format!("0x{:04X}", dos.relocations)),
+        ("e_cparhdr", format!("0x{:04X}", dos.header_paragraphs)),
+        ("e_minalloc", format!("0x{:04X}", dos.min_extra_paragraphs)),
+        ("e_maxalloc", format!("0x{:04X}", dos.max_extra_paragraphs)),
+        (
+            "e_ss / e_sp",
+            format!("0x{:04X} / 0x{:04X}", dos.initial_ss, dos.initial_sp),
+        ),
+        ("e_csum", format!("0x{:04X}", dos.checksum)),
+        (
+            "e_cs / e_ip",
+            format!("0x{:04X} / 0x{:04X}", dos.initial_cs, dos.initial_ip),
+        ),
+        ("e_lfarlc", format!("0x{:04X}", dos.relocation_table_offset)),
+        ("e_ovno", format!("0x{:04X}", dos.overlay_number)),
+        (
+            "e_oemid / e_oeminfo",
+            format!("0x{:04X} / 0x{:04X}", dos.oem_id, dos.oem_info),
+        ),
+        ("e_lfanew", format!("0x{:08X}", dos.pe_offset)),
+    ];
+
+    let file_fields: Vec<(&str, String)> = vec![
+        (
+            "Machine",
+            format!("0x{:04X} {}", file.machine, machine_name(file.machine)),
+        ),
+        ("NumberOfSections", file.number_of_sections.to_string()),
+        (
+            "TimeDateStamp",
+            format!(
+                "0x{:08X} ({})",
+                file.time_date_stamp,
+                format_timestamp(file.time_date_stamp)
+            ),
+        ),
+        (
+            "PointerToSymbolTable",
+            format!("0x{:08X}", file.pointer_to_symbol_table),
+        ),
+        ("NumberOfSymbols", file.number_of_symbols.to_string()),
+        (
+            "SizeOfOptionalHeader",
+            format!("0x{:04X}", file.size_of_optional_header),
+        ),
+        ("Characteristics", format!("0x{:04X}", file.characteristics)),
+    ];
+    let file_flags = file_characteristics_names(file.characteristics);
+
+    let mut opt_fields: Vec<(&str, String)> = vec![
+        (
+            "Magic",
+            format!(
+                "0x{:03X} ({})",
+                opt.magic,
+                if opt.is_pe32_plus() { "PE32+" } else { "PE32" }
+            ),
+        ),
+        (
+            "LinkerVersion",
+            format!("{}.{}", opt.major_linker_version, opt.minor_linker_version),
+        ),
+        ("SizeOfCode", format!("0x{:X}", opt.size_of_code)),
+        (
+            "AddressOfEntryPoint",
+            format!("0x{:08X}", opt.address_of_entry_point),
+        ),
+        ("BaseOfCode", format!("0x{:08X}", opt.base_of_code)),
+    ];
+    if let Some(base_of_data) = opt.base_of_data {
+        opt_fields.push(("BaseOfData", format!("0x{:08X}", base_of_data)));
+    }
+    opt_fields.extend([
+        ("ImageBase", format!("0x{:X}", opt.image_base)),
+        ("SectionAlignment", format!("0x{:X}", opt.section_alignment)),
+        ("FileAlignment", format!("0x{:X}", opt.file_alignment)),
+        (
+            "OperatingSystemVersion",
+            format!(
+                "{}.{}",
+                opt.major_operating_system_version, opt.minor_operating_system_version
+            ),
+        ),
+        (
+            "ImageVersion",
+            format!("{}.{}", opt.major_image_version, opt.minor_image_version),
+        ),
+        (
+            "SubsystemVersion",
+            format!(
+                "{}.{}",
+                opt.major_subsystem_version, opt.minor_subsystem_version
+            ),
+        ),
+        ("SizeOfImage", format!("0x{:X}", opt.size_of_image)),
+        ("SizeOfHeaders", format!("0x{:X}", opt.size_of_headers)),
+        ("CheckSum", format!("0x{:08X}", opt.checksum)),
+        (
+            "Subsystem",
+            format!("{} ({})", opt.subsystem, subsystem_name(opt.subsystem)),
+        ),
+        (
+            "DllCharacteristics",
+            format!("0x{:04X}", opt.dll_characteristics),
+        ),
+        (
+            "SizeOfStackReserve",
+            format!("0x{:X}", opt.size_of_stack_reserve),
+        ),
+        (
+            "SizeOfStackCommit",
+            format!("0x{:X}", opt.size_of_stack_commit),
+        ),
+        (
+            "SizeOfHeapReserve",
+            format!("0x{:X}", opt.size_of_heap_reserve),
+        ),
+        (
+            "SizeOfHeapCommit",
+            format!("0x{:X}", opt.size_of_heap_commit),
+        ),
+        ("LoaderFlags", format!("0x{:08X}", opt.loader_flags)),
+        (
+            "NumberOfRvaAndSizes",
+            opt.number_of_rva_and_sizes.to_string(),
+        ),
+    ]);
+    let dll_flags = dll_characteristics_names(opt.dll_characteristics);
+
+    // Exploit mitigations at a glance
+    let mut mitigations = vec![
+        ("ASLR", opt.dll_characteristics & DLL_DYNAMIC_BASE != 0),
+        ("DEP", opt.dll_characteristics & DLL_NX_COMPAT != 0),
+        ("CFG", opt.dll_characteristics & DLL_GUARD_CF != 0),
+    ];
+    if opt.is_pe32_plus() {
+        mitigations.insert(
+            1,
+            (
+                "High Entropy VA",
+                opt.dll_characteristics & DLL_HIGH_ENTROPY_VA != 0,
+            ),
+        );
+    }
+
+    let image_base = opt.image_base;
+    let section_name = |rva: u32| {
+        headers
+            .sections
+            .iter()
+            .find(|s| s.header.contains_rva(rva))
+            .map(|s| s.header.name.clone())
+            .unwrap_or_default()
+    };
+
+    // TLS callbacks run before the entry point
+    let tls_fields: Vec<(&str, String)> = headers
+        .tls
+        .as_ref()
+        .map(|tls| {
+            vec![
+                (
+                    "StartAddressOfRawData",
+                    format!("0x{:X}", tls.start_address_of_raw_data),
+                ),
+                (
+                    "EndAddressOfRawData",
+                    format!("0x{:X}", tls.end_address_of_raw_data),
+                ),
+                ("AddressOfIndex", format!("0x{:X}", tls.address_of_index)),
+                (
+                    "AddressOfCallBacks",
+                    format!("0x{:X}", tls.address_of_callbacks),
+                ),
+                ("SizeOfZeroFill", format!("0x{:X}", tls.size_of_zero_fill)),
+                ("Characteristics", format!("0x{:08X}", tls.characteristics)),
+            ]
+        })
+        .unwrap_or_default();
+    let tls_callbacks: Vec<(u32, String)> = headers
+        .tls
+        .as_ref()
+        .map(|tls| {
+            tls.callbacks
+                .iter()
+                .map(|&rva| (rva, section_name(rva)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let load_config_fields: Vec<(&str, String)> = headers
+        .load_config
+        .as_ref()
+        .map(|lc| {
+            vec![
+                ("Size", format!("0x{:X}", lc.size)),
+                (
+                    "TimeDateStamp",
+                    if lc.time_date_stamp == 0 {
+                        "0".to_string()
+                    } else {
+                        format!(
+                            "0x{:08X} ({})",
+                            lc.time_date_stamp,
+                            format_timestamp(lc.time_date_stamp)
+                        )
+                    },
+                ),
+                ("SecurityCookie", optional_va(lc.security_cookie)),
+                ("SEHandlerTable", optional_va(lc.se_handler_table)),
+                ("SEHandlerCount", lc.se_handlers.len().to_string()),
+                (
+                    "GuardCFCheckFunctionPointer",
+                    optional_va(lc.guard_cf_check_function_pointer),
+                ),
+                (
+                    "GuardCFDispatchFunctionPointer",
+                    optional_va(lc.guard_cf_dispatch_function_pointer),
+                ),
+                (
+                    "GuardCFFunctionTable",
+                    optional_va(lc.guard_cf_function_table),
+                ),
+                (
+                    "GuardCFFunctionCount",
+                    lc.guard_cf_functions.len().to_string(),
+                ),
+                (
+                    "GuardFlags",
+                    lc.guard_flags
+                        .map(|flags| format!("0x{:08X}", flags))
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+            ]
+        })
+        .unwrap_or_default();
+    let se_handlers: Vec<(u32, String)> = headers
+        .load_config
+        .as_ref()
+        .map(|lc| {
+            lc.se_handlers
+                .iter()
+                .map(|&rva| (rva, section_name(rva)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let runtime_function_count = headers.runtime_functions.len();
+    let handler_count = headers
+        .runtime_functions
+        .iter()
+        .filter(|f| f.unwind_info.as_ref().is_some_and(|u| u.handler.is_some()))
+        .count();
+    let chained_count = headers
+        .runtime_functions
+        .iter()
+        .filter(|f| f.unwind_info.as_ref().is_some_and(|u| u.chained_to.is_some()))
+        .count();
+
+    rsx! {
+        div {
+            class: "pe-headers-view",
+
+            // Mitigations summary
+            div {
+                class: "pe-mitigations",
+                for (name, enabled) in mitigations {
+                    span {
+                        class: if enabled { "pe-mitigation pe-mitigation-on" } else { "pe-mitigation pe-mitigation-off" },
+                        if enabled { "✓ {name}" } else { "✗ {name}" }
+                    }
+                }
+                if !tls_callbacks.is_empty() {
+                    span {
+                        class: "pe-mitigation pe-mitigation-off",
+                        title: "TLS callbacks run before the entry point",
+                        "⚠ {tls_callbacks.len()} TLS callback(s)"
+                    }
+                }
+            }
+
+            div {
+                class: "pe-headers-grid",
+
+                // DOS header
+                div {
+                    class: "pe-header-block",
+                    div { class: "module-inspect-panel-title", "DOS Header" }
+                    for (name, value) in dos_fields {
+                        div { class: "pe-field-row",
+                            span { class: "pe-field-name", "{name}" }
+                            span { class: "pe-field-value", "{value}" }
+                        }
+                    }
+                }
+
+                // COFF file header
+                div {
+                    class: "pe-header-block",
+                    div { class: "module-inspect-panel-title", "File Header (COFF)" }
+                    for (name, value) in file_fields {
+                        div { class: "pe-field-row",
+                            span { class: "pe-field-name", "{name}" }
+                            span { class: "pe-field-value", "{value}" }
+                        }
+                    }
+                    div { class: "pe-flags",
+                        for flag in file_flags {
+                            span { class: "pe-flag", "{flag}" }
+                        }
+                    }
+                }
+
+                // Optional header
+                div {
+                    class: "pe-header-block",
+                    div { class: "module-inspect-panel-title", "Optional Header" }
+                    for (name, value) in opt_fields {
+                        div { class: "pe-field-row",
+                            span { class: "pe-field-name", "{name}" }
+                            span { class: "pe-field-value", "{value}" }
+                        }
+                    }
+                    div { class: "pe-flags",
+                        for flag in dll_flags {
+                            span { class: "pe-flag", "{flag}" }
+                        }
+                    }
+                }
+            }
+
+            // Data directories
+            div { class: "module-inspect-panel-title", "Data Directories" }
+            table {
+                class: "thread-table",
+                thead {
+                    tr {
+                        th { class: "th", "#" }
+                        th { class: "th", "Name" }
+                        th { class: "th", "RVA" }
+                        th { class: "th", "Size" }
+                    }
+                }
+                tbody {
+                    for (index, dir) in opt.data_directories.iter().enumerate() {
+                        {
+                            let name = data_directory_name(index);
+                            let rva = dir.virtual_address;
+                            let size = dir.size;
+                            let row_class = if dir.is_present() { "thread-row" } else { "thread-row pe-row-empty" };
+                            rsx! {
+                                tr {
+                                    key: "{index}",
+                                    class: "{row_class}",
+                                    td { class: "cell", "{index}" }
+                                    td { class: "cell", "{name}" }
+                                    td { class: "cell cell-handle", "0x{rva:08X}" }
+                                    td { class: "cell cell-handle", "0x{size:X}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Section table
+            div { class: "module-inspect-panel-title", "Sections ({headers.sections.len()})" }
+            table {
+                class: "thread-table",
+                thead {
+                    tr {
+                        th { class: "th", "Name" }
+                        th { class: "th", "Virtual Address" }
+                        th { class: "th", "Virtual Size" }
+                        th { class: "th", "Raw Offset" }
+                        th { class: "th", "Raw Size" }
+                        th { class: "th", "Prot" }
+                        th { class: "th", "Entropy" }
+                        th { class: "th", "Characteristics" }
+                    }
+                }
+                tbody {
+                    for (index, section) in headers.sections.iter().enumerate() {
+                        {
+                            let header = &section.header;
+                            let name = header.name.clone();
+                            let va = header.virtual_address;
+                            let vsize = header.virtual_size;
+                            let raw_offset = header.pointer_to_raw_data;
+                            let raw_size = header.size_of_raw_data;
+                            let characteristics = header.characteristics;
+                            let protection = section_protection(characteristics);
+                            let flags = section_characteristics_names(characteristics).join(" | ");
+                            let entropy = section.entropy;
+                            // Compressed or encrypted data sits close to 8 bits/byte
+                            let entropy_class = if entropy >= 7.2 { "cell pe-entropy-high" } else { "cell" };
+                            rsx! {
+                                tr {
+                                    key: "{index}",
+                                    class: "thread-row",
+                                    td { class: "cell", style: "font-weight: 500;", "{name}" }
+                                    td { class: "cell cell-handle", "0x{va:08X}" }
+                                    td { class: "cell cell-handle", "0x{vsize:X}" }
+                                    td { class: "cell cell-handle", "0x{raw_offset:08X}" }
+                                    td { class: "cell cell-handle", "0x{raw_size:X}" }
+                                    td { class: "cell", style: "font-family: monospace;", "{protection}" }
+                                    td { class: "{entropy_class}", "{entropy:.3}" }
+                                    td { class: "cell cell-path", title: "0x{characteristics:08X}", "{flags}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Rich header
+            if let Some(rich) = &headers.rich_header {
+                div {
+                    class: "module-inspect-panel-title",
+                    "Rich Header (offset 0x{rich.offset:X}, key 0x{rich.key:08X})"
+                }
+                div {
+                    class: "pe-mitigations",
+                    if rich.checksum_valid {
+                        span { class: "pe-mitigation pe-mitigation-on", "✓ Checksum matches" }
+                    } else {
+                        span {
+                            class: "pe-mitigation pe-mitigation-off",
+                            title: "The DOS stub or Rich header was modified after linking",
+                            "⚠ Checksum mismatch"
+                        }
+                    }
+                }
+                table {
+                    class: "thread-table",
+                    thead {
+                        tr {
+                            th { class: "th", "Product ID" }
+                            th { class: "th", "Build" }
+                            th { class: "th", "Count" }
+                            th { class: "th", "Tool" }
+                        }
+                    }
+                    tbody {
+                        for (index, entry) in rich.entries.iter().enumerate() {
+                            {
+                                let product_id = entry.product_id;
+                                let build = entry.build;
+                                let count = entry.count;
+                                let tool = entry.product_name().unwrap_or_else(|| "Unknown".to_string());
+                                rsx! {
+                                    tr {
+                                        key: "{index}",
+                                        class: "thread-row",
+                                        td { class: "cell cell-handle", "0x{product_id:04X}" }
+                                        td { class: "cell", "{build}" }
+                                        td { class: "cell", "{count}" }
+                                        td { class: "cell", "{tool}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // TLS directory
+            if headers.tls.is_some() {
+                div { class: "module-inspect-panel-title", "TLS Directory" }
+                div {
+                    class: "pe-headers-grid",
+                    div {
+                        class: "pe-header-block",
+                        for (name, value) in tls_fields {
+                            div { class: "pe-field-row",
+                                span { class: "pe-field-name", "{name}" }
+                                span { class: "pe-field-value", "{value}" }
+                            }
+                        }
+                    }
+                    div {
+                        class: "pe-header-block",
+                        div { class: "module-inspect-panel-title", "Callbacks ({tls_callbacks.len()})" }
+                        if tls_callbacks.is_empty() {
+                            div { class: "pe-field-row", span { class: "pe-field-name", "None" } }
+                        }
+                        for (rva, section) in tls_callbacks {
+                            div { class: "pe-field-row",
+                                span { class: "pe-field-name", "0x{image_base.wrapping_add(rva as u64):X}" }
+                                span { class: "pe-field-value", "RVA 0x{rva:08X} {section}" }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Load configuration
+            if headers.load_config.is_some() {
+                div { class: "module-inspect-panel-title", "Load Configuration" }
+                div {
+                    class: "pe-headers-grid",
+                    div {
+                        class: "pe-header-block",
+                        for (name, value) in load_config_fields {
+                            div { class: "pe-field-row",
+                                span { class: "pe-field-name", "{name}" }
+                                span { class: "pe-field-value", "{value}" }
+                            }
+                        }
+                    }
+                    if !se_handlers.is_empty() {
+                        div {
+                            class: "pe-header-block",
+                            div { class: "module-inspect-panel-title", "SafeSEH Handlers ({se_handlers.len()})" }
+                            for (rva, section) in se_handlers {
+                                div { class: "pe-field-row",
+                                    span { class: "pe-field-name", "0x{rva:08X}" }
+                                    span { class: "pe-field-value", "{section}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // x64 exception table
+            if runtime_function_count > 0 {
+                div {
+                    class: "module-inspect-panel-title",
+                    "Exception Directory ({runtime_function_count} functions, {handler_count} with handlers, {chained_count} chained)"
+                }
+                table {
+                    class: "thread-table",
+                    thead {
+                        tr {
+                            th { class: "th", "Begin" }
+                            th { class: "th", "End" }
+                            th { class: "th", "Size" }
+                            th { class: "th", "Unwind Info" }
+                            th { class: "th", "Prolog" }
+                            th { class: "th", "Flags" }
+                            th { class: "th", "Handler" }
+                        }
+                    }
+                    tbody {
+                        for (index, function) in headers.runtime_functions.iter().take(MAX_RUNTIME_FUNCTION_ROWS).enumerate() {
+                            {
+                                let begin = function.begin_address;
+                                let end = function.end_address;
+                                let size = function.len();
+                                let unwind = function.unwind_info_address;
+                                let (prolog, flags, handler) = match &function.unwind_info {
+                                    Some(info) => (
+                                        info.prolog_size.to_string(),
+                                        unwind_flag_names(info.flags),
+                                        info.handler
+                                            .or(info.chained_to)
+                                            .map(|rva| format!("0x{:08X}", rva))
+                                            .unwrap_or_default(),
+                                    ),
+                                    None => (String::new(), String::new(), String::new()),
+                                };
+                                rsx! {
+                                    tr {
+                                        key: "{index}",
+                                        class: "thread-row",
+                                        td { class: "cell cell-handle", "0x{begin:08X}" }
+                                        td { class: "cell cell-handle", "0x{end:08X}" }
+                                        td { class: "cell cell-handle", "0x{size:X}" }
+                                        td { class: "cell cell-handle", "0x{unwind:08X}" }
+                                        td { class: "cell", "{prolog}" }
+                                        td { class: "cell", "{flags}" }
+                                        td { class: "cell cell-handle", "{handler}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                if runtime_function_count > MAX_RUNTIME_FUNCTION_ROWS {
+                    div {
+                        style: "padding: 8px; color: #6b7280; text-align: center;",
+                        "Showing first {MAX_RUNTIME_FUNCTION_ROWS} of {runtime_function_count} functions"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Process details pane (parent, session, owner, integrity, command line)
+
+use dioxus::prelude::*;
+use process::{arch_label, format_start_time, ProcessInfo};
+
+use crate::helpers::copy_to_clipboard;
+
+/// Details pane for a running process
+///
+/// `parent` is the process currently holding the parent PID, if any.
+#[component]
+pub fn ProcessDetailsPane(process: ProcessInfo, parent: Option<ProcessInfo>) -> Element {
+    let mut copied = use_signal(|| false);
+
+    // A parent that started after the child is a different process reusing the PID
+    let parent_label = match &parent {
+        Some(parent)
+            if parent.pid != process.pid
+                && parent.start_time.unwrap_or(0) <= process.start_time.unwrap_or(u64::MAX) =>
+        {
+            format!("{} ({})", parent.name, parent.pid)
+        }
+        _ if process.parent_pid == 0 => String::new(),
+        _ => format!("<exited> ({})", process.parent_pid),
+    };
+
+    let fields: Vec<(&str, String)> = vec![
+        ("Parent", parent_label),
+        (
+            "Session",
+            process
+                .session_id
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+        ),
+        ("User", process.user.clone().unwrap_or_default()),
+        ("SID", process.user_sid.clone().unwrap_or_default()),
+        (
+            "Integrity",
+            process
+                .integrity_level
+                .map(|level| level.to_string())
+                .unwrap_or_default(),
+        ),
+        ("Architecture", arch_label(&process)),
+        ("Started", format_start_time(process.start_time)),
+    ];
+    let command_line = process.command_line.clone().unwrap_or_default();
+
+    rsx! {
+        div {
+            class: "file-details-pane",
+            div { class: "module-inspect-panel-title", "{process.name} (PID {process.pid})" }
+            for (name, value) in fields {
+                div { class: "pe-field-row",
+                    span { class: "pe-field-name", "{name}" }
+                    span { class: "pe-field-value file-details-value", title: "{value}", "{value}" }
+                }
+            }
+            div { class: "pe-field-row",
+                span { class: "pe-field-name", "Command Line" }
+                span { class: "pe-field-value file-details-hash", "{command_line}" }
+                if !command_line.is_empty() {
+                    button {
+                        class: "file-details-copy",
+                        title: "Copy command line",
+                        onclick: {
+                            let command_line = command_line.clone();
+                            move |_| {
+                                if copy_to_clipboard(&command_line) {
+                                    copied.set(true);
+                                    spawn(async move {
+                                        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                                        copied.set(false);
+                                    });
+                                }
+                            }
+                        },
+                        if *copied.read() { "✓" } else { "📋" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
 //! Process row component
 
 use dioxus::prelude::*;
-use process::ProcessInfo;
+use process::{ProcessInfo, RowHighlight, StatColumn, TreeRowState};
+
+use crate::helpers::signer_cell;
 
 /// Process row component
 #[component]
@@ -11,6 +13,17 @@ pub fn ProcessRow(
     max_memory: f64,
     on_select: EventHandler<u32>,
     on_context_menu: EventHandler<(i32, i32, u32, String)>,
+    /// Indentation and expander state when shown in tree mode
+    #[props(default)]
+    tree: Option<TreeRowState>,
+    #[props(default)]
+    on_toggle: EventHandler<u32>,
+    /// Whether the process just started or has exited
+    #[props(default)]
+    highlight: RowHighlight,
+    /// Optional counter columns, shown after the memory column
+    #[props(default)]
+    stat_columns: Vec<StatColumn>,
 ) -> Element {
     let memory_percent = if max_memory > 0.0 {
         process.memory_mb / max_memory * 100.0
@@ -27,6 +40,24 @@ pub fn ProcessRow(
         .unwrap_or(&process.exe_path)
         .to_string();
 
+    let (signer, signer_class, signer_tooltip) = signer_cell(&process.exe_path);
+    let description = process.description.clone().unwrap_or_default();
+    let company = process.company.clone().unwrap_or_default();
+    let user = process.user.clone().unwrap_or_default();
+    let user_tooltip = process.user_sid.clone().unwrap_or_default();
+    let name_tooltip = match &tree {
+        Some(tree) if tree.orphan => format!(
+            "Parent PID {} has exited\n{}",
+            process.parent_pid,
+            process.command_line.as_deref().unwrap_or("")
+        ),
+        _ => process.command_line.clone().unwrap_or_default(),
+    };
+    let description_tooltip = match &process.version {
+        Some(version) => format!("{} ({})", description, version),
+        None => description.clone(),
+    };
+
     // CPU usage color based on value
     let cpu_class = if process.cpu_usage > 50.0 {
         "cpu-high"
@@ -36,10 +67,15 @@ pub fn ProcessRow(
         "cpu-low"
     };
 
+    let highlight_class = match highlight {
+        RowHighlight::Normal => "",
+        RowHighlight::Started => " row-started",
+        RowHighlight::Exited => " row-exited",
+    };
     let row_class = if is_selected {
-        "process-row selected"
+        format!("process-row selected{highlight_class}")
     } else {
-        "process-row"
+        format!("process-row{highlight_class}")
     };
 
     rsx! {
@@ -53,7 +89,31 @@ pub fn ProcessRow(
                 on_context_menu.call((coords.x as i32, coords.y as i32, pid, exe_path_for_context.clone()));
             },
             td { class: "cell cell-pid", "{process.pid}" }
-            td { class: "cell cell-name", "{process.name}" }
+            if let Some(tree) = tree {
+                td {
+                    class: if tree.orphan { "cell cell-name tree-orphan" } else { "cell cell-name" },
+                    title: "{name_tooltip}",
+                    style: "padding-left: {16 + tree.depth * 18}px;",
+                    if tree.has_children {
+                        span {
+                            class: "tree-toggle",
+                            onclick: move |e| {
+                                e.stop_propagation();
+                                on_toggle.call(pid);
+                            },
+                            if tree.expanded { "▾" } else { "▸" }
+                        }
+                    } else {
+                        span { class: "tree-toggle" }
+                    }
+                    "{process.name}"
+                    if tree.hidden_descendants > 0 {
+                        span { class: "tree-hidden-count", " (+{tree.hidden_descendants})" }
+                    }
+                }
+            } else {
+                td { class: "cell cell-name", title: "{name_tooltip}", "{process.name}" }
+            }
             td { class: "cell cell-cpu {cpu_class}", "{process.cpu_usage:.1}%" }
             td { class: "cell cell-threads", "{process.thread_count}" }
             td { class: "cell cell-memory",
@@ -67,6 +127,13 @@ pub fn ProcessRow(
                     span { class: "memory-text", "{process.memory_mb:.1} MB" }
                 }
             }
+            for column in stat_columns {
+                td { class: "cell cell-stat", "{column.format(&process.stats)}" }
+            }
+            td { class: "cell cell-description", title: "{description_tooltip}", "{description}" }
+            td { class: "cell cell-company", title: "{company}", "{company}" }
+            td { class: "cell cell-user", title: "{user_tooltip}", "{user}" }
+            td { class: "{signer_class}", title: "{signer_tooltip}", "{signer}" }
             td { class: "cell cell-path", title: "{exe_path}", "{exe_filename}" }
         }
     }
@@ -3,41 +3,92 @@
 use dioxus::prelude::*;
 use misc::{inject_dll, inject_dll_manual_map, inject_dll_thread_hijack};
 use process::{
-    get_processes, get_system_stats, kill_process, open_file_location, resume_process,
-    suspend_process, ProcessInfo,
+    build_process_tree, collect_parent_pids, compare_processes, flatten_tree, get_system_stats,
+    kill_process, matches_process, open_file_location, resume_process, suspend_process,
+    ProcessInfo, ProcessSortColumn, RecentChanges, RefreshEngine, SortOrder, StatColumn,
+    TreeRowState,
 };
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
 
-use super::{GraphWindow, HandleWindow, MemoryWindow, ModuleWindow, ProcessRow, ThreadWindow};
-use crate::helpers::copy_to_clipboard;
+use super::{
+    FileDetailsPane, GraphWindow, HandleWindow, MemoryWindow, ModuleWindow, ProcessDetailsPane,
+    ProcessRow, ThreadWindow,
+};
+use crate::helpers::{copy_to_clipboard, export_records};
+use crate::records::ProcessRecord;
+use crate::source;
 use crate::state::{
-    ContextMenuState, SortColumn, SortOrder, GRAPH_WINDOW_STATE, HANDLE_WINDOW_STATE,
-    MEMORY_WINDOW_STATE, MODULE_WINDOW_STATE, THREAD_WINDOW_STATE,
+    ContextMenuState, GRAPH_WINDOW_STATE, HANDLE_WINDOW_STATE, MEMORY_WINDOW_STATE,
+    MODULE_WINDOW_STATE, THREAD_WINDOW_STATE,
 };
 
 /// Process Tab component
 #[component]
 pub fn ProcessTab() -> Element {
-    let mut processes = use_signal(|| get_processes());
+    let mut processes = use_signal(Vec::<ProcessInfo>::new);
     let mut system_stats = use_signal(|| get_system_stats());
     let mut search_query = use_signal(|| String::new());
-    let mut sort_column = use_signal(|| SortColumn::Memory);
+    let mut sort_column = use_signal(|| ProcessSortColumn::Memory);
     let mut sort_order = use_signal(|| SortOrder::Descending);
     let mut auto_refresh = use_signal(|| true);
+    let mut tree_mode = use_signal(|| false);
+    let mut collapsed = use_signal(HashSet::<u32>::new);
     let mut selected_pid = use_signal(|| None::<u32>);
     let mut status_message = use_signal(|| String::new());
     let mut context_menu = use_signal(|| ContextMenuState::default());
+    let mut recent = use_signal(RecentChanges::default);
+    let mut highlight_secs = use_signal(|| 5_u64);
+    let mut stat_columns = use_signal(Vec::<StatColumn>::new);
+    let mut show_column_picker = use_signal(|| false);
+
+    // Previous listing, so a refresh only touches the signal when something changed
+    let mut engine = use_hook(|| CopyValue::new(RefreshEngine::new(ProcessInfo::key)));
+    let mut refresh = move || {
+        let listing = source::processes();
+        let events = engine.write().update(listing.clone());
+        if !events.is_empty() || !recent.peek().is_empty() {
+            let linger = Duration::from_secs(*highlight_secs.peek());
+            recent.write().record(&events, Instant::now(), linger);
+        }
+        if !events.is_empty() {
+            processes.set(listing);
+        }
+    };
+
+    // Load the list, and reload it whenever a snapshot is opened or closed
+    use_effect(move || {
+        let listing = source::processes();
+        engine.write().reset(listing.clone());
+        processes.set(listing);
+        recent.set(RecentChanges::default());
+        selected_pid.set(None);
+    });
+    let read_only = source::is_read_only();
 
     // Auto-refresh every 3 seconds
     use_future(move || async move {
         loop {
             tokio::time::sleep(std::time::Duration::from_secs(3)).await;
             if *auto_refresh.read() {
-                processes.set(get_processes());
+                refresh();
                 system_stats.set(get_system_stats());
             }
         }
     });
 
+    // Expire highlights on time even when auto-refresh is off
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            let now = Instant::now();
+            let linger = Duration::from_secs(*highlight_secs.peek());
+            if recent.peek().has_expired(now, linger) {
+                recent.write().prune(now, linger);
+            }
+        }
+    });
+
     // Keyboard shortcuts handler
     let handle_keydown = move |e: KeyboardEvent| {
         if e.key() == Key::Escape {
@@ -46,20 +97,25 @@ pub fn ProcessTab() -> Element {
         }
 
         if e.key() == Key::F5 {
-            processes.set(get_processes());
+            refresh();
             system_stats.set(get_system_stats());
             return;
         }
 
-        if e.key() == Key::Delete {
-            let pid_to_kill = *selected_pid.read();
+        if e.key() == Key::Delete && !read_only {
+            // A row lingering after its process exited has nothing left to kill
+            let pid_to_kill = (*selected_pid.read())
+                .filter(|pid| !recent.read().lingers(*pid, &processes.read()));
             if let Some(pid) = pid_to_kill {
-                if kill_process(pid) {
-                    status_message.set(format!("✓ Process {} terminated", pid));
-                    processes.set(get_processes());
-                    selected_pid.set(None);
-                } else {
-                    status_message.set(format!("✗ Failed to terminate process {}", pid));
+                match kill_process(pid) {
+                    Ok(()) => {
+                        status_message.set(format!("✓ Process {} terminated", pid));
+                        refresh();
+                        selected_pid.set(None);
+                    }
+                    Err(e) => {
+                        status_message.set(format!("✗ Failed to terminate process {}: {}", pid, e));
+                    }
                 }
                 spawn(async move {
                     tokio::time::sleep(std::time::Duration::from_secs(3)).await;
@@ -75,51 +131,72 @@ pub fn ProcessTab() -> Element {
         .map(|p| p.memory_mb)
         .fold(0.0_f64, |a, b| a.max(b));
 
-    let mut filtered_processes: Vec<ProcessInfo> = processes
-        .read()
+    // Exited processes stay in the table, as last seen, until their highlight ends
+    let shown_processes = recent.read().with_exited(&processes.read());
+
+    let query = search_query.read().to_lowercase();
+    let mut filtered_processes: Vec<ProcessInfo> = shown_processes
         .iter()
-        .filter(|p| {
-            let query = search_query.read().to_lowercase();
-            if query.is_empty() {
-                true
-            } else {
-                p.name.to_lowercase().contains(&query)
-                    || p.pid.to_string().contains(&query)
-                    || p.exe_path.to_lowercase().contains(&query)
-            }
-        })
+        .filter(|p| matches_process(p, &query))
         .cloned()
         .collect();
 
-    filtered_processes.sort_by(|a, b| {
-        let cmp = match *sort_column.read() {
-            SortColumn::Pid => a.pid.cmp(&b.pid),
-            SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-            SortColumn::Memory => a
-                .memory_mb
-                .partial_cmp(&b.memory_mb)
-                .unwrap_or(std::cmp::Ordering::Equal),
-            SortColumn::Threads => a.thread_count.cmp(&b.thread_count),
-            SortColumn::Cpu => a
-                .cpu_usage
-                .partial_cmp(&b.cpu_usage)
-                .unwrap_or(std::cmp::Ordering::Equal),
-        };
-        match *sort_order.read() {
-            SortOrder::Ascending => cmp,
-            SortOrder::Descending => cmp.reverse(),
+    let (column, order) = (*sort_column.read(), *sort_order.read());
+    filtered_processes.sort_by(|a, b| compare_processes(a, b, column, order));
+
+    // Rows to display: the sorted list, or the tree flattened in display order
+    let process_tree = tree_mode
+        .read()
+        .then(|| build_process_tree(shown_processes.clone()));
+    let rows: Vec<(ProcessInfo, Option<TreeRowState>)> = match &process_tree {
+        Some(tree) => {
+            let mut rows = Vec::new();
+            flatten_tree(tree, 0, &collapsed.read(), &query, column, order, &mut rows);
+            rows.into_iter()
+                .map(|(process, state)| (process, Some(state)))
+                .collect()
         }
-    });
+        None => filtered_processes
+            .iter()
+            .map(|process| (process.clone(), None))
+            .collect(),
+    };
+    let parent_pids: HashSet<u32> = process_tree
+        .iter()
+        .flatten()
+        .flat_map(collect_parent_pids)
+        .collect();
 
     let process_count = filtered_processes.len();
     let total_memory: f64 = filtered_processes.iter().map(|p| p.memory_mb).sum();
 
+    // Optional columns in their fixed order, whatever order they were picked in
+    let shown_stat_columns: Vec<StatColumn> = StatColumn::ALL
+        .into_iter()
+        .filter(|column| stat_columns.read().contains(column))
+        .collect();
+
     let current_sort_col = *sort_column.read();
     let current_sort_ord = *sort_order.read();
     let ctx_menu = context_menu.read().clone();
+    // Kill, suspend and injection make no sense for a row that has already exited
+    let process_actions = !read_only
+        && !ctx_menu
+            .pid
+            .is_some_and(|pid| recent.read().lingers(pid, &processes.read()));
     let export_processes = filtered_processes.clone();
+    let selected_process = selected_pid
+        .read()
+        .and_then(|pid| filtered_processes.iter().find(|p| p.pid == pid).cloned());
+    let selected_parent = selected_process.as_ref().and_then(|selected| {
+        processes
+            .read()
+            .iter()
+            .find(|p| p.pid == selected.parent_pid)
+            .cloned()
+    });
 
-    let sort_indicator = |column: SortColumn| -> &'static str {
+    let sort_indicator = |column: ProcessSortColumn| -> &'static str {
         if current_sort_col == column {
             match current_sort_ord {
                 SortOrder::Ascending => " ▲",
@@ -155,7 +232,7 @@ pub fn ProcessTab() -> Element {
                 input {
                     class: "search-input",
                     r#type: "text",
-                    placeholder: "Search by name, PID, or path...",
+                    placeholder: "Search by name, PID, path, user or command line...",
                     value: "{search_query}",
                     oninput: move |e| search_query.set(e.value().clone()),
                 }
@@ -170,35 +247,92 @@ pub fn ProcessTab() -> Element {
                     span { "Auto-refresh" }
                 }
 
+                label { class: "checkbox-label",
+                    input {
+                        r#type: "checkbox",
+                        class: "checkbox",
+                        checked: *tree_mode.read(),
+                        onchange: move |e| tree_mode.set(e.checked()),
+                    }
+                    span { "Tree view" }
+                }
+
+                select {
+                    class: "filter-select",
+                    title: "How long started processes stay green and exited ones stay red",
+                    value: "{highlight_secs}",
+                    onchange: move |e| highlight_secs.set(e.value().parse().unwrap_or(0)),
+                    option { value: "0", "No highlighting" }
+                    option { value: "3", "Highlight 3 s" }
+                    option { value: "5", "Highlight 5 s" }
+                    option { value: "10", "Highlight 10 s" }
+                    option { value: "30", "Highlight 30 s" }
+                    option { value: "60", "Highlight 60 s" }
+                }
+
+                if *tree_mode.read() {
+                    button {
+                        class: "btn btn-secondary",
+                        onclick: move |_| collapsed.write().clear(),
+                        "⊞ Expand All"
+                    }
+                    button {
+                        class: "btn btn-secondary",
+                        onclick: {
+                            let parent_pids = parent_pids.clone();
+                            move |_| collapsed.set(parent_pids.clone())
+                        },
+                        "⊟ Collapse All"
+                    }
+                }
+
+                button {
+                    class: if *show_column_picker.read() { "btn btn-primary" } else { "btn btn-secondary" },
+                    onclick: move |_| {
+                        let shown = *show_column_picker.read();
+                        show_column_picker.set(!shown);
+                    },
+                    "☰ Columns"
+                }
+
                 button {
                     class: "btn btn-primary",
                     onclick: move |_| {
-                        processes.set(get_processes());
+                        refresh();
                         system_stats.set(get_system_stats());
                     },
                     "🔄 Refresh"
                 }
 
-                button {
-                    class: "btn btn-danger",
-                    disabled: selected_pid.read().is_none(),
-                    onclick: move |_| {
-                        let pid_to_kill = *selected_pid.read();
-                        if let Some(pid) = pid_to_kill {
-                            if kill_process(pid) {
-                                status_message.set(format!("✓ Process {} terminated", pid));
-                                processes.set(get_processes());
-                                selected_pid.set(None);
-                            } else {
-                                status_message.set(format!("✗ Failed to terminate process {}", pid));
+                if !read_only {
+                    button {
+                        class: "btn btn-danger",
+                        disabled: selected_pid.read().is_none()
+                            || selected_pid
+                                .read()
+                                .is_some_and(|pid| recent.read().lingers(pid, &processes.read())),
+                        onclick: move |_| {
+                            let pid_to_kill = (*selected_pid.read())
+                                .filter(|pid| !recent.read().lingers(*pid, &processes.read()));
+                            if let Some(pid) = pid_to_kill {
+                                match kill_process(pid) {
+                                    Ok(()) => {
+                                        status_message.set(format!("✓ Process {} terminated", pid));
+                                        refresh();
+                                        selected_pid.set(None);
+                                    }
+                                    Err(e) => {
+                                        status_message.set(format!("✗ Failed to terminate process {}: {}", pid, e));
+                                    }
+                                }
+                                spawn(async move {
+                                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                    status_message.set(String::new());
+                                });
                             }
-                            spawn(async move {
-                                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-                                status_message.set(String::new());
-                            });
-                        }
-                    },
-                    "Kill Process"
+                        },
+                        "Kill Process"
+                    }
                 }
 
                 button {
@@ -208,34 +342,15 @@ pub fn ProcessTab() -> Element {
                         move |_| {
                             let procs = procs.clone();
                             spawn(async move {
-                                let file = rfd::AsyncFileDialog::new()
-                                    .add_filter("CSV", &["csv"])
-                                    .set_file_name("processes.csv")
-                                    .set_title("Export Processes")
-                                    .save_file()
-                                    .await;
-                                if let Some(file) = file {
-                                    let path = file.path().to_path_buf();
-                                    let mut csv = String::from("PID,Name,CPU %,Threads,Memory (MB),Path\n");
-                                    for p in &procs {
-                                        csv.push_str(&format!(
-                                            "{},\"{}\",{:.1},{},{:.2},\"{}\"\n",
-                                            p.pid,
-                                            p.name.replace('"', "\"\""),
-                                            p.cpu_usage,
-                                            p.thread_count,
-                                            p.memory_mb,
-                                            p.exe_path.replace('"', "\"\"")
-                                        ));
-                                    }
-                                    match std::fs::write(&path, csv) {
-                                        Ok(()) => {
-                                            status_message.set(format!("Exported {} processes to {}", procs.len(), path.display()));
-                                        }
-                                        Err(e) => {
-                                            status_message.set(format!("Export failed: {}", e));
-                                        }
-                                    }
+                                let message = export_records(
+                                    "Export Processes",
+                                    "processes",
+                                    "processes",
+                                    move || procs.into_iter().map(ProcessRecord::new).collect(),
+                                )
+                                .await;
+                                if let Some(message) = message {
+                                    status_message.set(message);
                                     spawn(async move {
                                         tokio::time::sleep(std::time::Duration::from_secs(3)).await;
                                         status_message.set(String::new());
@@ -244,7 +359,35 @@ pub fn ProcessTab() -> Element {
                             });
                         }
                     },
-                    "Export CSV"
+                    "Export"
+                }
+            }
+
+            // Optional counter columns
+            if *show_column_picker.read() {
+                div { class: "column-picker",
+                    for column in StatColumn::ALL {
+                        label { class: "checkbox-label",
+                            input {
+                                r#type: "checkbox",
+                                class: "checkbox",
+                                checked: stat_columns.read().contains(&column),
+                                onchange: move |e| {
+                                    if e.checked() {
+                                        stat_columns.write().push(column);
+                                    } else {
+                                        stat_columns.write().retain(|c| *c != column);
+                                        // Hidden columns can't stay the sort key
+                                        if *sort_column.read() == ProcessSortColumn::Stat(column) {
+                                            sort_column.set(ProcessSortColumn::Memory);
+                                            sort_order.set(SortOrder::Descending);
+                                        }
+                                    }
+                                },
+                            }
+                            span { "{column.label()}" }
+                        }
+                    }
                 }
             }
 
@@ -256,77 +399,106 @@ pub fn ProcessTab() -> Element {
                             th {
                                 class: "th sortable",
                                 onclick: move |_| {
-                                    if *sort_column.read() == SortColumn::Pid {
+                                    if *sort_column.read() == ProcessSortColumn::Pid {
                                         let new_order = if *sort_order.read() == SortOrder::Ascending { SortOrder::Descending } else { SortOrder::Ascending };
                                         sort_order.set(new_order);
                                     } else {
-                                        sort_column.set(SortColumn::Pid);
+                                        sort_column.set(ProcessSortColumn::Pid);
                                         sort_order.set(SortOrder::Descending);
                                     }
                                 },
-                                "PID{sort_indicator(SortColumn::Pid)}"
+                                "PID{sort_indicator(ProcessSortColumn::Pid)}"
                             }
                             th {
                                 class: "th sortable",
                                 onclick: move |_| {
-                                    if *sort_column.read() == SortColumn::Name {
+                                    if *sort_column.read() == ProcessSortColumn::Name {
                                         let new_order = if *sort_order.read() == SortOrder::Ascending { SortOrder::Descending } else { SortOrder::Ascending };
                                         sort_order.set(new_order);
                                     } else {
-                                        sort_column.set(SortColumn::Name);
+                                        sort_column.set(ProcessSortColumn::Name);
                                         sort_order.set(SortOrder::Descending);
                                     }
                                 },
-                                "Name{sort_indicator(SortColumn::Name)}"
+                                "Name{sort_indicator(ProcessSortColumn::Name)}"
                             }
                             th {
                                 class: "th sortable",
+                                title: "Share of all CPUs since the previous refresh",
                                 onclick: move |_| {
-                                    if *sort_column.read() == SortColumn::Cpu {
+                                    if *sort_column.read() == ProcessSortColumn::Cpu {
                                         let new_order = if *sort_order.read() == SortOrder::Ascending { SortOrder::Descending } else { SortOrder::Ascending };
                                         sort_order.set(new_order);
                                     } else {
-                                        sort_column.set(SortColumn::Cpu);
+                                        sort_column.set(ProcessSortColumn::Cpu);
                                         sort_order.set(SortOrder::Descending);
                                     }
                                 },
-                                "CPU{sort_indicator(SortColumn::Cpu)}"
+                                "CPU{sort_indicator(ProcessSortColumn::Cpu)}"
                             }
                             th {
                                 class: "th sortable",
                                 onclick: move |_| {
-                                    if *sort_column.read() == SortColumn::Threads {
+                                    if *sort_column.read() == ProcessSortColumn::Threads {
                                         let new_order = if *sort_order.read() == SortOrder::Ascending { SortOrder::Descending } else { SortOrder::Ascending };
                                         sort_order.set(new_order);
                                     } else {
-                                        sort_column.set(SortColumn::Threads);
+                                        sort_column.set(ProcessSortColumn::Threads);
                                         sort_order.set(SortOrder::Descending);
                                     }
                                 },
-                                "Threads{sort_indicator(SortColumn::Threads)}"
+                                "Threads{sort_indicator(ProcessSortColumn::Threads)}"
                             }
                             th {
                                 class: "th sortable",
                                 onclick: move |_| {
-                                    if *sort_column.read() == SortColumn::Memory {
+                                    if *sort_column.read() == ProcessSortColumn::Memory {
                                         let new_order = if *sort_order.read() == SortOrder::Ascending { SortOrder::Descending } else { SortOrder::Ascending };
                                         sort_order.set(new_order);
                                     } else {
-                                        sort_column.set(SortColumn::Memory);
+                                        sort_column.set(ProcessSortColumn::Memory);
                                         sort_order.set(SortOrder::Descending);
                                     }
                                 },
-                                "Memory{sort_indicator(SortColumn::Memory)}"
+                                "Memory{sort_indicator(ProcessSortColumn::Memory)}"
+                            }
+                            for column in shown_stat_columns.iter().copied() {
+                                th {
+                                    class: "th sortable",
+                                    onclick: move |_| {
+                                        if *sort_column.read() == ProcessSortColumn::Stat(column) {
+                                            let new_order = if *sort_order.read() == SortOrder::Ascending { SortOrder::Descending } else { SortOrder::Ascending };
+                                            sort_order.set(new_order);
+                                        } else {
+                                            sort_column.set(ProcessSortColumn::Stat(column));
+                                            sort_order.set(SortOrder::Descending);
+                                        }
+                                    },
+                                    "{column.label()}{sort_indicator(ProcessSortColumn::Stat(column))}"
+                                }
                             }
+                            th { class: "th", "Description" }
+                            th { class: "th", "Company" }
+                            th { class: "th", "User" }
+                            th { class: "th", "Signer" }
                             th { class: "th", "Path" }
                         }
                     }
                     tbody {
-                        for process in filtered_processes {
+                        for (process, tree) in rows {
                             ProcessRow {
                                 process: process.clone(),
+                                tree: tree,
+                                highlight: recent.read().highlight(&process),
+                                on_toggle: move |pid: u32| {
+                                    let mut collapsed = collapsed.write();
+                                    if !collapsed.remove(&pid) {
+                                        collapsed.insert(pid);
+                                    }
+                                },
                                 is_selected: *selected_pid.read() == Some(process.pid),
                                 max_memory: max_memory,
+                                stat_columns: shown_stat_columns.clone(),
                                 on_select: move |pid: u32| {
                                     let current = *selected_pid.read();
                                     if current == Some(pid) {
@@ -351,6 +523,20 @@ pub fn ProcessTab() -> Element {
                 }
             }
 
+            // Details of the selected process and its executable
+            if let Some(process) = selected_process {
+                div { class: "details-panes",
+                    ProcessDetailsPane {
+                        process: process.clone(),
+                        parent: selected_parent,
+                    }
+                    FileDetailsPane {
+                        title: "Image File".to_string(),
+                        path: process.exe_path.clone(),
+                    }
+                }
+            }
+
             // Context Menu
             if ctx_menu.visible {
                 div {
@@ -358,69 +544,80 @@ pub fn ProcessTab() -> Element {
                     style: "left: {ctx_menu.x}px; top: {ctx_menu.y}px;",
                     onclick: move |e| e.stop_propagation(),
 
-                    button {
-                        class: "context-menu-item context-menu-item-danger",
-                        onclick: move |_| {
-                            if let Some(pid) = ctx_menu.pid {
-                                if kill_process(pid) {
-                                    status_message.set(format!("✓ Process {} terminated", pid));
-                                    processes.set(get_processes());
-                                    selected_pid.set(None);
-                                } else {
-                                    status_message.set(format!("✗ Failed to terminate process {}", pid));
+                    if process_actions {
+                        button {
+                            class: "context-menu-item context-menu-item-danger",
+                            onclick: move |_| {
+                                if let Some(pid) = ctx_menu.pid {
+                                    match kill_process(pid) {
+                                        Ok(()) => {
+                                            status_message.set(format!("✓ Process {} terminated", pid));
+                                            refresh();
+                                            selected_pid.set(None);
+                                        }
+                                        Err(e) => {
+                                            status_message.set(format!("✗ Failed to terminate process {}: {}", pid, e));
+                                        }
+                                    }
+                                    spawn(async move {
+                                        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                        status_message.set(String::new());
+                                    });
                                 }
-                                spawn(async move {
-                                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-                                    status_message.set(String::new());
-                                });
-                            }
-                            context_menu.set(ContextMenuState::default());
-                        },
-                        span { "☠️" }
-                        span { "Kill Process" }
-                    }
+                                context_menu.set(ContextMenuState::default());
+                            },
+                            span { "☠️" }
+                            span { "Kill Process" }
+                        }
 
-                    button {
-                        class: "context-menu-item context-menu-item-warning",
-                        onclick: move |_| {
-                            if let Some(pid) = ctx_menu.pid {
-                                if suspend_process(pid) {
-                                    status_message.set(format!("⏸️ Process {} suspended", pid));
-                                } else {
-                                    status_message.set(format!("✗ Failed to suspend process {}", pid));
+                        button {
+                            class: "context-menu-item context-menu-item-warning",
+                            onclick: move |_| {
+                                if let Some(pid) = ctx_menu.pid {
+                                    match suspend_process(pid) {
+                                        Ok(()) => {
+                                            status_message.set(format!("⏸️ Process {} suspended", pid));
+                                        }
+                                        Err(e) => {
+                                            status_message.set(format!("✗ Failed to suspend process {}: {}", pid, e));
+                                        }
+                                    }
+                                    spawn(async move {
+                                        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                        status_message.set(String::new());
+                                    });
                                 }
-                                spawn(async move {
-                                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-                                    status_message.set(String::new());
-                                });
-                            }
-                            context_menu.set(ContextMenuState::default());
-                        },
-                        span { "⏸️" }
-                        span { "Suspend Process" }
-                    }
+                                context_menu.set(ContextMenuState::default());
+                            },
+                            span { "⏸️" }
+                            span { "Suspend Process" }
+                        }
 
-                    button {
-                        class: "context-menu-item context-menu-item-success",
-                        onclick: move |_| {
-                            if let Some(pid) = ctx_menu.pid {
-                                if resume_process(pid) {
-                                    status_message.set(format!("▶️ Process {} resumed", pid));
-                                } else {
-                                    status_message.set(format!("✗ Failed to resume process {}", pid));
+                        button {
+                            class: "context-menu-item context-menu-item-success",
+                            onclick: move |_| {
+                                if let Some(pid) = ctx_menu.pid {
+                                    match resume_process(pid) {
+                                        Ok(()) => {
+                                            status_message.set(format!("▶️ Process {} resumed", pid));
+                                        }
+                                        Err(e) => {
+                                            status_message.set(format!("✗ Failed to resume process {}: {}", pid, e));
+                                        }
+                                    }
+                                    spawn(async move {
+                                        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                        status_message.set(String::new());
+                                    });
                                 }
-                                spawn(async move {
-                                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-                                    status_message.set(String::new());
-                                });
-                            }
-                            context_menu.set(ContextMenuState::default());
-                        },
-                        span { "▶️" }
-                        span { "Resume Process" }
-                    }
+                                context_menu.set(ContextMenuState::default());
+                            },
+                            span { "▶️" }
+                            span { "Resume Process" }
+                        }
 
-                    div { class: "context-menu-separator" }
+                        div { class: "context-menu-separator" }
+                    }
 
                     button {
                         class: "context-menu-item",
@@ -542,21 +739,23 @@ pub fn ProcessTab() -> Element {
                         span { "View Memory" }
                     }
 
-                    button {
-                        class: "context-menu-item",
-                        onclick: move |_| {
-                            if let Some(pid) = ctx_menu.pid {
-                                let proc_name = processes.read()
-                                    .iter()
-                                    .find(|p| p.pid == pid)
-                                    .map(|p| p.name.clone())
-                                    .unwrap_or_else(|| format!("PID {}", pid));
-                                *GRAPH_WINDOW_STATE.write() = Some((pid, proc_name));
-                            }
-                            context_menu.set(ContextMenuState::default());
-                        },
-                        span { "📈" }
-                        span { "View Performance" }
+                    if !read_only {
+                        button {
+                            class: "context-menu-item",
+                            onclick: move |_| {
+                                if let Some(pid) = ctx_menu.pid {
+                                    let proc_name = processes.read()
+                                        .iter()
+                                        .find(|p| p.pid == pid)
+                                        .map(|p| p.name.clone())
+                                        .unwrap_or_else(|| format!("PID {}", pid));
+                                    *GRAPH_WINDOW_STATE.write() = Some((pid, proc_name));
+                                }
+                                context_menu.set(ContextMenuState::default());
+                            },
+                            span { "📈" }
+                            span { "View Performance" }
+                        }
                     }
 
                     div { class: "context-menu-separator" }
@@ -564,7 +763,7 @@ pub fn ProcessTab() -> Element {
                     button {
                         class: "context-menu-item",
                         onclick: move |_| {
-                            processes.set(get_processes());
+                            refresh();
                             system_stats.set(get_system_stats());
                             context_menu.set(ContextMenuState::default());
                         },
@@ -572,157 +771,159 @@ pub fn ProcessTab() -> Element {
                         span { "Refresh List" }
                     }
 
-                    div { class: "context-menu-separator" }
+                    if process_actions {
+                        div { class: "context-menu-separator" }
 
-                    // Miscellaneous submenu
-                    div {
-                        class: "context-menu-submenu",
-                        div {
-                            class: "context-menu-submenu-trigger",
-                            span { "⚙️" }
-                            span { "Miscellaneous" }
-                            span { class: "arrow", "▶" }
-                        }
+                        // Miscellaneous submenu
                         div {
-                            class: "context-menu-submenu-content",
-                            // DLL Injection sub-submenu
+                            class: "context-menu-submenu",
                             div {
-                                class: "context-menu-submenu",
-                                div {
-                                    class: "context-menu-submenu-trigger",
-                                    span { "💉" }
-                                    span { "DLL Injection" }
-                                    span { class: "arrow", "▶" }
-                                }
+                                class: "context-menu-submenu-trigger",
+                                span { "⚙️" }
+                                span { "Miscellaneous" }
+                                span { class: "arrow", "▶" }
+                            }
+                            div {
+                                class: "context-menu-submenu-content",
+                                // DLL Injection sub-submenu
                                 div {
-                                    class: "context-menu-submenu-content",
-                                    // LoadLibrary method
-                                    button {
-                                        class: "context-menu-item",
-                                        onclick: move |_| {
-                                            let target_pid = ctx_menu.pid;
-                                            context_menu.set(ContextMenuState::default());
-
-                                            if let Some(pid) = target_pid {
-                                                spawn(async move {
-                                                    let file = rfd::AsyncFileDialog::new()
-                                                        .add_filter("DLL Files", &["dll"])
-                                                        .set_title("Select DLL to inject (LoadLibrary)")
-                                                        .pick_file()
-                                                        .await;
-
-                                                    if let Some(file) = file {
-                                                        let path = file.path().to_string_lossy().to_string();
-                                                        match inject_dll(pid, &path) {
-                                                            Ok(()) => {
-                                                                status_message.set(format!(
-                                                                    "✓ DLL injected into process {} (LoadLibrary)",
-                                                                    pid
-                                                                ));
-                                                            }
-                                                            Err(e) => {
-                                                                status_message.set(format!(
-                                                                    "✗ DLL injection failed: {}",
-                                                                    e
-                                                                ));
-                                                            }
-                                                        }
-                                                        spawn(async move {
-                                                            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-                                                            status_message.set(String::new());
-                                                        });
-                                                    }
-                                                });
-                                            }
-                                        },
+                                    class: "context-menu-submenu",
+                                    div {
+                                        class: "context-menu-submenu-trigger",
                                         span { "💉" }
-                                        span { "LoadLibrary" }
+                                        span { "DLL Injection" }
+                                        span { class: "arrow", "▶" }
                                     }
-
-                                    // Thread Hijack method
-                                    button {
-                                        class: "context-menu-item",
-                                        onclick: move |_| {
-                                            let target_pid = ctx_menu.pid;
-                                            context_menu.set(ContextMenuState::default());
-
-                                            if let Some(pid) = target_pid {
-                                                spawn(async move {
-                                                    let file = rfd::AsyncFileDialog::new()
-                                                        .add_filter("DLL Files", &["dll"])
-                                                        .set_title("Select DLL to inject (Thread Hijack)")
-                                                        .pick_file()
-                                                        .await;
-
-                                                    if let Some(file) = file {
-                                                        let path = file.path().to_string_lossy().to_string();
-                                                        match inject_dll_thread_hijack(pid, &path) {
-                                                            Ok(()) => {
-                                                                status_message.set(format!(
-                                                                    "✓ DLL injected into process {} (Thread Hijack)",
-                                                                    pid
-                                                                ));
-                                                            }
-                                                            Err(e) => {
-                                                                status_message.set(format!(
-                                                                    "✗ Thread hijack injection failed: {}",
-                                                                    e
-                                                                ));
+                                    div {
+                                        class: "context-menu-submenu-content",
+                                        // LoadLibrary method
+                                        button {
+                                            class: "context-menu-item",
+                                            onclick: move |_| {
+                                                let target_pid = ctx_menu.pid;
+                                                context_menu.set(ContextMenuState::default());
+
+                                                if let Some(pid) = target_pid {
+                                                    spawn(async move {
+                                                        let file = rfd::AsyncFileDialog::new()
+                                                            .add_filter("DLL Files", &["dll"])
+                                                            .set_title("Select DLL to inject (LoadLibrary)")
+                                                            .pick_file()
+                                                            .await;
+
+                                                        if let Some(file) = file {
+                                                            let path = file.path().to_string_lossy().to_string();
+                                                            match inject_dll(pid, &path) {
+                                                                Ok(()) => {
+                                                                    status_message.set(format!(
+                                                                        "✓ DLL injected into process {} (LoadLibrary)",
+                                                                        pid
+                                                                    ));
+                                                                }
+                                                                Err(e) => {
+                                                                    status_message.set(format!(
+                                                                        "✗ DLL injection failed: {}",
+                                                                        e
+                                                                    ));
+                                                                }
                                                             }
+                                                            spawn(async move {
+                                                                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                                                                status_message.set(String::new());
+                                                            });
                                                         }
-                                                        spawn(async move {
-                                                            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-                                                            status_message.set(String::new());
-                                                        });
-                                                    }
-                                                });
-                                            }
-                                        },
-                                        span { "🧵" }
-                                        span { "Thread Hijack" }
-                                    }
+                                                    });
+                                                }
+                                            },
+                                            span { "💉" }
+                                            span { "LoadLibrary" }
+                                        }
 
-                                    // Manual Map method
-                                    button {
-                                        class: "context-menu-item",
-                                        onclick: move |_| {
-                                            let target_pid = ctx_menu.pid;
-                                            context_menu.set(ContextMenuState::default());
-
-                                            if let Some(pid) = target_pid {
-                                                spawn(async move {
-                                                    let file = rfd::AsyncFileDialog::new()
-                                                        .add_filter("DLL Files", &["dll"])
-                                                        .set_title("Select DLL to inject (Manual Map)")
-                                                        .pick_file()
-                                                        .await;
-
-                                                    if let Some(file) = file {
-                                                        let path = file.path().to_string_lossy().to_string();
-                                                        match inject_dll_manual_map(pid, &path) {
-                                                            Ok(()) => {
-                                                                status_message.set(format!(
-                                                                    "✓ DLL injected into process {} (Manual Map)",
-                                                                    pid
-                                                                ));
+                                        // Thread Hijack method
+                                        button {
+                                            class: "context-menu-item",
+                                            onclick: move |_| {
+                                                let target_pid = ctx_menu.pid;
+                                                context_menu.set(ContextMenuState::default());
+
+                                                if let Some(pid) = target_pid {
+                                                    spawn(async move {
+                                                        let file = rfd::AsyncFileDialog::new()
+                                                            .add_filter("DLL Files", &["dll"])
+                                                            .set_title("Select DLL to inject (Thread Hijack)")
+                                                            .pick_file()
+                                                            .await;
+
+                                                        if let Some(file) = file {
+                                                            let path = file.path().to_string_lossy().to_string();
+                                                            match inject_dll_thread_hijack(pid, &path) {
+                                                                Ok(()) => {
+                                                                    status_message.set(format!(
+                                                                        "✓ DLL injected into process {} (Thread Hijack)",
+                                                                        pid
+                                                                    ));
+                                                                }
+                                                                Err(e) => {
+                                                                    status_message.set(format!(
+                                                                        "✗ Thread hijack injection failed: {}",
+                                                                        e
+                                                                    ));
+                                                                }
                                                             }
-                                                            Err(e) => {
-                                                                status_message.set(format!(
-                                                                    "✗ Manual map injection failed: {}",
-                                                                    e
-                                                                ));
+                                                            spawn(async move {
+                                                                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                                                                status_message.set(String::new());
+                                                            });
+                                                        }
+                                                    });
+                                                }
+                                            },
+                                            span { "🧵" }
+                                            span { "Thread Hijack" }
+                                        }
+
+                                        // Manual Map method
+                                        button {
+                                            class: "context-menu-item",
+                                            onclick: move |_| {
+                                                let target_pid = ctx_menu.pid;
+                                                context_menu.set(ContextMenuState::default());
+
+                                                if let Some(pid) = target_pid {
+                                                    spawn(async move {
+                                                        let file = rfd::AsyncFileDialog::new()
+                                                            .add_filter("DLL Files", &["dll"])
+                                                            .set_title("Select DLL to inject (Manual Map)")
+                                                            .pick_file()
+                                                            .await;
+
+                                                        if let Some(file) = file {
+                                                            let path = file.path().to_string_lossy().to_string();
+                                                            match inject_dll_manual_map(pid, &path) {
+                                                                Ok(()) => {
+                                                                    status_message.set(format!(
+                                                                        "✓ DLL injected into process {} (Manual Map)",
+                                                                        pid
+                                                                    ));
+                                                                }
+                                                                Err(e) => {
+                                                                    status_message.set(format!(
+                                                                        "✗ Manual map injection failed: {}",
+                                                                        e
+                                                                    ));
+                                                                }
                                                             }
+                                                            spawn(async move {
+                                                                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                                                                status_message.set(String::new());
+                                                            });
                                                         }
-                                                        spawn(async move {
-                                                            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-                                                            status_message.set(String::new());
-                                                        });
-                                                    }
-                                                });
-                                            }
-                                        },
-                                        span { "🗺️" }
-                                        span { "Manual Map" }
+                                                    });
+                                                }
+                                            },
+                                            span { "🗺️" }
+                                            span { "Manual Map" }
+                                        }
                                     }
                                 }
                             }
@@ -1,12 +1,15 @@
 //! Windows services tab component
 
 use dioxus::prelude::*;
+use process::RefreshEngine;
 use service::{
-    create_service, delete_service, get_services, start_service, stop_service, ServiceInfo,
-    ServiceStartType, ServiceStatus,
+    compare_services, create_service, delete_service, matches_service, start_service,
+    stop_service, ServiceError, ServiceInfo, ServiceSortColumn, ServiceStartType, ServiceStatus,
+    SortOrder,
 };
 
-use crate::helpers::copy_to_clipboard;
+use crate::helpers::{copy_to_clipboard, export_records, load_error};
+use crate::source;
 
 /// Service context menu state
 #[derive(Clone, Debug, Default)]
@@ -19,25 +22,6 @@ struct ServiceContextMenuState {
     status: Option<ServiceStatus>,
 }
 
-/// Sort column for service table
-#[derive(Clone, Copy, PartialEq, Debug)]
-enum ServiceSortColumn {
-    Name,
-    DisplayName,
-    Status,
-    StartType,
-    Pid,
-    BinaryPath,
-    Description,
-}
-
-/// Sort order
-#[derive(Clone, Copy, PartialEq, Debug)]
-enum SortOrder {
-    Ascending,
-    Descending,
-}
-
 /// Create service form state
 #[derive(Clone, Debug, Default)]
 struct CreateServiceForm {
@@ -51,7 +35,8 @@ struct CreateServiceForm {
 /// Service Tab component
 #[component]
 pub fn ServiceTab() -> Element {
-    let mut services = use_signal(|| get_services());
+    let mut services: Signal<Result<Vec<ServiceInfo>, ServiceError>> =
+        use_signal(|| Ok(Vec::new()));
     let mut search_query = use_signal(|| String::new());
     let mut sort_column = use_signal(|| ServiceSortColumn::Name);
     let mut sort_order = use_signal(|| SortOrder::Ascending);
@@ -63,12 +48,35 @@ pub fn ServiceTab() -> Element {
     let mut start_type_filter = use_signal(|| String::new()); // "", "auto", "manual", "disabled"
     let mut create_form = use_signal(|| CreateServiceForm::default());
 
+    // Previous list, so a refresh only touches the signal when something changed
+    let mut engine = use_hook(|| CopyValue::new(RefreshEngine::new(ServiceInfo::key)));
+    let mut refresh = move || match source::services() {
+        Ok(listing) => {
+            let changed = !engine.write().update(listing.clone()).is_empty();
+            if changed || services.read().is_err() {
+                services.set(Ok(listing));
+            }
+        }
+        Err(e) => services.set(Err(e)),
+    };
+
+    // Load the list, and reload it whenever a snapshot is opened or closed
+    use_effect(move || {
+        let listing = source::services();
+        engine
+            .write()
+            .reset(listing.as_ref().cloned().unwrap_or_default());
+        services.set(listing);
+        selected_service.set(None);
+    });
+    let read_only = source::is_read_only();
+
     // Auto-refresh every 3 seconds
     use_future(move || async move {
         loop {
             tokio::time::sleep(std::time::Duration::from_secs(3)).await;
             if *auto_refresh.read() {
-                services.set(get_services());
+                refresh();
             }
         }
     });
@@ -86,19 +94,22 @@ pub fn ServiceTab() -> Element {
         }
 
         if e.key() == Key::F5 {
-            services.set(get_services());
+            refresh();
             return;
         }
 
-        if e.key() == Key::Delete {
+        if e.key() == Key::Delete && !read_only {
             let svc = selected_service.read().clone();
             if let Some(name) = svc {
-                if delete_service(&name) {
-                    status_message.set(format!("✓ Service '{}' deleted", name));
-                    services.set(get_services());
-                    selected_service.set(None);
-                } else {
-                    status_message.set(format!("✗ Failed to delete service '{}'", name));
+                match delete_service(&name) {
+                    Ok(()) => {
+                        status_message.set(format!("✓ Service '{}' deleted", name));
+                        refresh();
+                        selected_service.set(None);
+                    }
+                    Err(e) => {
+                        status_message.set(format!("✗ Failed to delete service '{}': {}", name, e));
+                    }
                 }
                 spawn(async move {
                     tokio::time::sleep(std::time::Duration::from_secs(3)).await;
@@ -108,74 +119,24 @@ pub fn ServiceTab() -> Element {
         }
     };
 
+    let error_message = load_error(&services.read());
+
     // Filter and sort services
+    let query = search_query.read().to_lowercase();
     let mut filtered_services: Vec<ServiceInfo> = services
         .read()
         .iter()
-        .filter(|s| {
-            // Status filter
-            let status_match = match status_filter.read().as_str() {
-                "running" => s.status == ServiceStatus::Running,
-                "stopped" => s.status == ServiceStatus::Stopped,
-                "paused" => s.status == ServiceStatus::Paused,
-                "all" | "" => true,
-                _ => true,
-            };
-
-            // Start type filter
-            let start_match = match start_type_filter.read().as_str() {
-                "auto" => s.start_type == ServiceStartType::Auto,
-                "manual" => s.start_type == ServiceStartType::Manual,
-                "disabled" => s.start_type == ServiceStartType::Disabled,
-                "all" | "" => true,
-                _ => true,
-            };
-
-            // Search filter
-            let query = search_query.read().to_lowercase();
-            let search_match = if query.is_empty() {
-                true
-            } else {
-                s.name.to_lowercase().contains(&query)
-                    || s.display_name.to_lowercase().contains(&query)
-                    || s.description.to_lowercase().contains(&query)
-                    || s.binary_path.to_lowercase().contains(&query)
-                    || s.pid.to_string().contains(&query)
-            };
-
-            status_match && start_match && search_match
-        })
+        .flatten()
+        .filter(|s| matches_service(s, &status_filter.read(), &start_type_filter.read(), &query))
         .cloned()
         .collect();
 
-    // Sort
-    filtered_services.sort_by(|a, b| {
-        let cmp = match *sort_column.read() {
-            ServiceSortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-            ServiceSortColumn::DisplayName => a
-                .display_name
-                .to_lowercase()
-                .cmp(&b.display_name.to_lowercase()),
-            ServiceSortColumn::Status => a.status.to_string().cmp(&b.status.to_string()),
-            ServiceSortColumn::StartType => {
-                a.start_type.to_string().cmp(&b.start_type.to_string())
-            }
-            ServiceSortColumn::Pid => a.pid.cmp(&b.pid),
-            ServiceSortColumn::BinaryPath => {
-                a.binary_path.to_lowercase().cmp(&b.binary_path.to_lowercase())
-            }
-            ServiceSortColumn::Description => {
-                a.description.to_lowercase().cmp(&b.description.to_lowercase())
-            }
-        };
-        match *sort_order.read() {
-            SortOrder::Ascending => cmp,
-            SortOrder::Descending => cmp.reverse(),
-        }
-    });
+    let column = *sort_column.read();
+    let order = *sort_order.read();
+    filtered_services.sort_by(|a, b| compare_services(a, b, column, order));
 
     let service_count = filtered_services.len();
-    let total_count = services.read().len();
+    let total_count = services.read().as_ref().map_or(0, Vec::len);
 
     let current_sort_col = *sort_column.read();
     let current_sort_ord = *sort_order.read();
@@ -228,6 +189,11 @@ pub fn ServiceTab() -> Element {
                 if !status_message.read().is_empty() {
                     div { class: "status-message", "{status_message}" }
                 }
+
+                // Why the service list is empty, if it could not be read
+                if let Some(error) = error_message {
+                    div { class: "load-error", "{error}" }
+                }
             }
 
             // Controls
@@ -273,23 +239,25 @@ pub fn ServiceTab() -> Element {
                 button {
                     class: "btn btn-primary",
                     onclick: move |_| {
-                        services.set(get_services());
+                        refresh();
                     },
                     "🔄 Refresh"
                 }
 
-                button {
-                    class: "btn btn-primary",
-                    onclick: move |_| {
-                        create_form.set(CreateServiceForm {
-                            visible: true,
-                            name: String::new(),
-                            display_name: String::new(),
-                            binary_path: String::new(),
-                            start_type: "manual".to_string(),
-                        });
-                    },
-                    "+ Create Service"
+                if !read_only {
+                    button {
+                        class: "btn btn-primary",
+                        onclick: move |_| {
+                            create_form.set(CreateServiceForm {
+                                visible: true,
+                                name: String::new(),
+                                display_name: String::new(),
+                                binary_path: String::new(),
+                                start_type: "manual".to_string(),
+                            });
+                        },
+                        "+ Create Service"
+                    }
                 }
 
                 button {
@@ -299,35 +267,15 @@ pub fn ServiceTab() -> Element {
                         move |_| {
                             let svcs = svcs.clone();
                             spawn(async move {
-                                let file = rfd::AsyncFileDialog::new()
-                                    .add_filter("CSV", &["csv"])
-                                    .set_file_name("services.csv")
-                                    .set_title("Export Services")
-                                    .save_file()
-                                    .await;
-                                if let Some(file) = file {
-                                    let path = file.path().to_path_buf();
-                                    let mut csv = String::from("Name,Display Name,Status,Start Type,PID,Binary Path,Description\n");
-                                    for s in &svcs {
-                                        csv.push_str(&format!(
-                                            "\"{}\",\"{}\",{},{},{},\"{}\",\"{}\"\n",
-                                            s.name.replace('"', "\"\""),
-                                            s.display_name.replace('"', "\"\""),
-                                            s.status,
-                                            s.start_type,
-                                            s.pid,
-                                            s.binary_path.replace('"', "\"\""),
-                                            s.description.replace('"', "\"\"")
-                                        ));
-                                    }
-                                    match std::fs::write(&path, csv) {
-                                        Ok(()) => {
-                                            status_message.set(format!("Exported {} services to {}", svcs.len(), path.display()));
-                                        }
-                                        Err(e) => {
-                                            status_message.set(format!("Export failed: {}", e));
-                                        }
-                                    }
+                                let message = export_records(
+                                    "Export Services",
+                                    "services",
+                                    "services",
+                                    move || svcs,
+                                )
+                                .await;
+                                if let Some(message) = message {
+                                    status_message.set(message);
                                     spawn(async move {
                                         tokio::time::sleep(std::time::Duration::from_secs(3)).await;
                                         status_message.set(String::new());
@@ -336,7 +284,7 @@ pub fn ServiceTab() -> Element {
                             });
                         }
                     },
-                    "Export CSV"
+                    "Export"
                 }
             }
 
@@ -468,81 +416,92 @@ pub fn ServiceTab() -> Element {
                     style: "left: {ctx_menu.x}px; top: {ctx_menu.y}px;",
                     onclick: move |e| e.stop_propagation(),
 
-                    // Start service
-                    button {
-                        class: "context-menu-item context-menu-item-success",
-                        disabled: ctx_menu.status == Some(ServiceStatus::Running),
-                        onclick: {
-                            let name = ctx_menu.name.clone();
-                            move |_| {
-                                if start_service(&name) {
-                                    status_message.set(format!("✓ Service '{}' started", name));
-                                    services.set(get_services());
-                                } else {
-                                    status_message.set(format!("✗ Failed to start service '{}'", name));
+                    if !read_only {
+                        // Start service
+                        button {
+                            class: "context-menu-item context-menu-item-success",
+                            disabled: ctx_menu.status == Some(ServiceStatus::Running),
+                            onclick: {
+                                let name = ctx_menu.name.clone();
+                                move |_| {
+                                    match start_service(&name) {
+                                        Ok(()) => {
+                                            status_message.set(format!("✓ Service '{}' started", name));
+                                            refresh();
+                                        }
+                                        Err(e) => {
+                                            status_message.set(format!("✗ Failed to start service '{}': {}", name, e));
+                                        }
+                                    }
+                                    spawn(async move {
+                                        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                        status_message.set(String::new());
+                                    });
+                                    context_menu.set(ServiceContextMenuState::default());
                                 }
-                                spawn(async move {
-                                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-                                    status_message.set(String::new());
-                                });
-                                context_menu.set(ServiceContextMenuState::default());
-                            }
-                        },
-                        span { "▶" }
-                        span { "Start Service" }
-                    }
+                            },
+                            span { "▶" }
+                            span { "Start Service" }
+                        }
 
-                    // Stop service
-                    button {
-                        class: "context-menu-item context-menu-item-warning",
-                        disabled: ctx_menu.status == Some(ServiceStatus::Stopped),
-                        onclick: {
-                            let name = ctx_menu.name.clone();
-                            move |_| {
-                                if stop_service(&name) {
-                                    status_message.set(format!("✓ Service '{}' stopped", name));
-                                    services.set(get_services());
-                                } else {
-                                    status_message.set(format!("✗ Failed to stop service '{}'", name));
+                        // Stop service
+                        button {
+                            class: "context-menu-item context-menu-item-warning",
+                            disabled: ctx_menu.status == Some(ServiceStatus::Stopped),
+                            onclick: {
+                                let name = ctx_menu.name.clone();
+                                move |_| {
+                                    match stop_service(&name) {
+                                        Ok(()) => {
+                                            status_message.set(format!("✓ Service '{}' stopped", name));
+                                            refresh();
+                                        }
+                                        Err(e) => {
+                                            status_message.set(format!("✗ Failed to stop service '{}': {}", name, e));
+                                        }
+                                    }
+                                    spawn(async move {
+                                        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                        status_message.set(String::new());
+                                    });
+                                    context_menu.set(ServiceContextMenuState::default());
                                 }
-                                spawn(async move {
-                                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-                                    status_message.set(String::new());
-                                });
-                                context_menu.set(ServiceContextMenuState::default());
-                            }
-                        },
-                        span { "⏹" }
-                        span { "Stop Service" }
-                    }
+                            },
+                            span { "⏹" }
+                            span { "Stop Service" }
+                        }
 
-                    div { class: "context-menu-separator" }
+                        div { class: "context-menu-separator" }
 
-                    // Delete service
-                    button {
-                        class: "context-menu-item context-menu-item-danger",
-                        onclick: {
-                            let name = ctx_menu.name.clone();
-                            move |_| {
-                                if delete_service(&name) {
-                                    status_message.set(format!("✓ Service '{}' deleted", name));
-                                    services.set(get_services());
-                                    selected_service.set(None);
-                                } else {
-                                    status_message.set(format!("✗ Failed to delete service '{}'", name));
+                        // Delete service
+                        button {
+                            class: "context-menu-item context-menu-item-danger",
+                            onclick: {
+                                let name = ctx_menu.name.clone();
+                                move |_| {
+                                    match delete_service(&name) {
+                                        Ok(()) => {
+                                            status_message.set(format!("✓ Service '{}' deleted", name));
+                                            refresh();
+                                            selected_service.set(None);
+                                        }
+                                        Err(e) => {
+                                            status_message.set(format!("✗ Failed to delete service '{}': {}", name, e));
+                                        }
+                                    }
+                                    spawn(async move {
+                                        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                        status_message.set(String::new());
+                                    });
+                                    context_menu.set(ServiceContextMenuState::default());
                                 }
-                                spawn(async move {
-                                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-                                    status_message.set(String::new());
-                                });
-                                context_menu.set(ServiceContextMenuState::default());
-                            }
-                        },
-                        span { "🗑" }
-                        span { "Delete Service" }
-                    }
+                            },
+                            span { "🗑" }
+                            span { "Delete Service" }
+                        }
 
-                    div { class: "context-menu-separator" }
+                        div { class: "context-menu-separator" }
+                    }
 
                     // Copy Name
                     button {
@@ -719,11 +678,14 @@ pub fn ServiceTab() -> Element {
                                         f.display_name.clone()
                                     };
 
-                                    if create_service(&f.name, &display, &f.binary_path, start_type) {
-                                        status_message.set(format!("✓ Service '{}' created", f.name));
-                                        services.set(get_services());
-                                    } else {
-                                        status_message.set(format!("✗ Failed to create service '{}'", f.name));
+                                    match create_service(&f.name, &display, &f.binary_path, start_type) {
+                                        Ok(()) => {
+                                            status_message.set(format!("✓ Service '{}' created", f.name));
+                                            refresh();
+                                        }
+                                        Err(e) => {
+                                            status_message.set(format!("✗ Failed to create service '{}': {}", f.name, e));
+                                        }
                                     }
                                     spawn(async move {
                                         tokio::time::sleep(std::time::Duration::from_secs(3)).await;
@@ -1,17 +1,16 @@
 //! Thread window component
 
 use dioxus::prelude::*;
-use process::{
-    get_priority_name, get_process_threads, kill_thread, resume_thread, suspend_thread, ThreadInfo,
-};
+use process::{get_priority_name, kill_thread, resume_thread, suspend_thread, ThreadInfo};
 
-use crate::helpers::copy_to_clipboard;
+use crate::helpers::{copy_to_clipboard, export_records, load_error};
+use crate::source;
 use crate::state::{ThreadContextMenuState, THREAD_WINDOW_STATE};
 
 /// Thread Window component
 #[component]
 pub fn ThreadWindow(pid: u32, process_name: String) -> Element {
-    let mut threads = use_signal(|| get_process_threads(pid));
+    let mut threads = use_signal(|| source::threads(pid));
     let mut selected_thread = use_signal(|| None::<u32>);
     let mut context_menu = use_signal(|| ThreadContextMenuState::default());
     let mut status_message = use_signal(|| String::new());
@@ -22,14 +21,17 @@ pub fn ThreadWindow(pid: u32, process_name: String) -> Element {
         loop {
             tokio::time::sleep(std::time::Duration::from_secs(2)).await;
             if *auto_refresh.read() {
-                threads.set(get_process_threads(pid));
+                threads.set(source::threads(pid));
             }
         }
     });
 
+    let read_only = source::is_read_only();
     let ctx_menu = context_menu.read().clone();
-    let thread_list: Vec<ThreadInfo> = threads.read().clone();
+    let error_message = load_error(&threads.read());
+    let thread_list: Vec<ThreadInfo> = threads.read().as_ref().cloned().unwrap_or_default();
     let thread_count = thread_list.len();
+    let export_threads = thread_list.clone();
 
     rsx! {
         // Modal overlay
@@ -78,10 +80,37 @@ pub fn ThreadWindow(pid: u32, process_name: String) -> Element {
                     button {
                         class: "btn btn-small btn-primary",
                         onclick: move |_| {
-                            threads.set(get_process_threads(pid));
+                            threads.set(source::threads(pid));
                         },
                         "🔄 Refresh"
                     }
+
+                    button {
+                        class: "btn btn-small btn-primary",
+                        onclick: {
+                            let export_threads = export_threads.clone();
+                            move |_| {
+                                let export_threads = export_threads.clone();
+                                spawn(async move {
+                                    let message = export_records(
+                                        "Export Threads",
+                                        &format!("threads_{}", pid),
+                                        "threads",
+                                        move || export_threads,
+                                    )
+                                    .await;
+                                    if let Some(message) = message {
+                                        status_message.set(message);
+                                        spawn(async move {
+                                            tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                                            status_message.set(String::new());
+                                        });
+                                    }
+                                });
+                            }
+                        },
+                        "💾 Export"
+                    }
                 }
 
                 // Status message
@@ -89,6 +118,11 @@ pub fn ThreadWindow(pid: u32, process_name: String) -> Element {
                     div { class: "thread-status-message", "{status_message}" }
                 }
 
+                // Why the thread list is empty, if it could not be read
+                if let Some(error) = error_message {
+                    div { class: "load-error", "{error}" }
+                }
+
                 // Thread table
                 div {
                     class: "thread-table-container",
@@ -136,57 +170,68 @@ pub fn ThreadWindow(pid: u32, process_name: String) -> Element {
                                             td { class: "cell", "{thread.base_priority}" }
                                             td { class: "cell", "{get_priority_name(thread.priority)}" }
                                             td { class: "cell cell-actions",
-                                                button {
-                                                    class: "action-btn action-btn-warning",
-                                                    title: "Suspend Thread",
-                                                    onclick: move |e| {
-                                                        e.stop_propagation();
-                                                        if suspend_thread(tid) {
-                                                            status_message.set(format!("⏸️ Thread {} suspended", tid));
-                                                        } else {
-                                                            status_message.set(format!("✗ Failed to suspend thread {}", tid));
-                                                        }
-                                                        spawn(async move {
-                                                            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-                                                            status_message.set(String::new());
-                                                        });
-                                                    },
-                                                    "⏸️"
-                                                }
-                                                button {
-                                                    class: "action-btn action-btn-success",
-                                                    title: "Resume Thread",
-                                                    onclick: move |e| {
-                                                        e.stop_propagation();
-                                                        if resume_thread(tid) {
-                                                            status_message.set(format!("▶️ Thread {} resumed", tid));
-                                                        } else {
-                                                            status_message.set(format!("✗ Failed to resume thread {}", tid));
-                                                        }
-                                                        spawn(async move {
-                                                            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-                                                            status_message.set(String::new());
-                                                        });
-                                                    },
-                                                    "▶️"
-                                                }
-                                                button {
-                                                    class: "action-btn action-btn-danger",
-                                                    title: "Kill Thread (Dangerous!)",
-                                                    onclick: move |e| {
-                                                        e.stop_propagation();
-                                                        if kill_thread(tid) {
-                                                            status_message.set(format!("☠️ Thread {} terminated", tid));
-                                                            threads.set(get_process_threads(pid));
-                                                        } else {
-                                                            status_message.set(format!("✗ Failed to terminate thread {}", tid));
-                                                        }
-                                                        spawn(async move {
-                                                            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-                                                            status_message.set(String::new());
-                                                        });
-                                                    },
-                                                    "☠️"
+                                                if !read_only {
+                                                    button {
+                                                        class: "action-btn action-btn-warning",
+                                                        title: "Suspend Thread",
+                                                        onclick: move |e| {
+                                                            e.stop_propagation();
+                                                            match suspend_thread(tid) {
+                                                                Ok(()) => {
+                                                                    status_message.set(format!("⏸️ Thread {} suspended", tid));
+                                                                }
+                                                                Err(e) => {
+                                                                    status_message.set(format!("✗ Failed to suspend thread {}: {}", tid, e));
+                                                                }
+                                                            }
+                                                            spawn(async move {
+                                                                tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                                                                status_message.set(String::new());
+                                                            });
+                                                        },
+                                                        "⏸️"
+                                                    }
+                                                    button {
+                                                        class: "action-btn action-btn-success",
+                                                        title: "Resume Thread",
+                                                        onclick: move |e| {
+                                                            e.stop_propagation();
+                                                            match resume_thread(tid) {
+                                                                Ok(()) => {
+                                                                    status_message.set(format!("▶️ Thread {} resumed", tid));
+                                                                }
+                                                                Err(e) => {
+                                                                    status_message.set(format!("✗ Failed to resume thread {}: {}", tid, e));
+                                                                }
+                                                            }
+                                                            spawn(async move {
+                                                                tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                                                                status_message.set(String::new());
+                                                            });
+                                                        },
+                                                        "▶️"
+                                                    }
+                                                    button {
+                                                        class: "action-btn action-btn-danger",
+                                                        title: "Kill Thread (Dangerous!)",
+                                                        onclick: move |e| {
+                                                            e.stop_propagation();
+                                                            match kill_thread(tid) {
+                                                                Ok(()) => {
+                                                                    status_message.set(format!("☠️ Thread {} terminated", tid));
+                                                                    threads.set(source::threads(pid));
+                                                                }
+                                                                Err(e) => {
+                                                                    status_message.set(format!("✗ Failed to terminate thread {}: {}", tid, e));
+                                                                }
+                                                            }
+                                                            spawn(async move {
+                                                                tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                                                                status_message.set(String::new());
+                                                            });
+                                                        },
+                                                        "☠️"
+                                                    }
                                                 }
                                             }
                                         }
@@ -204,70 +249,81 @@ pub fn ThreadWindow(pid: u32, process_name: String) -> Element {
                         style: "left: {ctx_menu.x}px; top: {ctx_menu.y}px;",
                         onclick: move |e| e.stop_propagation(),
 
-                        button {
-                            class: "context-menu-item context-menu-item-warning",
-                            onclick: move |_| {
-                                if let Some(tid) = ctx_menu.thread_id {
-                                    if suspend_thread(tid) {
-                                        status_message.set(format!("⏸️ Thread {} suspended", tid));
-                                    } else {
-                                        status_message.set(format!("✗ Failed to suspend thread {}", tid));
+                        if !read_only {
+                            button {
+                                class: "context-menu-item context-menu-item-warning",
+                                onclick: move |_| {
+                                    if let Some(tid) = ctx_menu.thread_id {
+                                        match suspend_thread(tid) {
+                                            Ok(()) => {
+                                                status_message.set(format!("⏸️ Thread {} suspended", tid));
+                                            }
+                                            Err(e) => {
+                                                status_message.set(format!("✗ Failed to suspend thread {}: {}", tid, e));
+                                            }
+                                        }
+                                        spawn(async move {
+                                            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                                            status_message.set(String::new());
+                                        });
                                     }
-                                    spawn(async move {
-                                        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-                                        status_message.set(String::new());
-                                    });
-                                }
-                                context_menu.set(ThreadContextMenuState::default());
-                            },
-                            span { "⏸️" }
-                            span { "Suspend Thread" }
-                        }
+                                    context_menu.set(ThreadContextMenuState::default());
+                                },
+                                span { "⏸️" }
+                                span { "Suspend Thread" }
+                            }
 
-                        button {
-                            class: "context-menu-item context-menu-item-success",
-                            onclick: move |_| {
-                                if let Some(tid) = ctx_menu.thread_id {
-                                    if resume_thread(tid) {
-                                        status_message.set(format!("▶️ Thread {} resumed", tid));
-                                    } else {
-                                        status_message.set(format!("✗ Failed to resume thread {}", tid));
+                            button {
+                                class: "context-menu-item context-menu-item-success",
+                                onclick: move |_| {
+                                    if let Some(tid) = ctx_menu.thread_id {
+                                        match resume_thread(tid) {
+                                            Ok(()) => {
+                                                status_message.set(format!("▶️ Thread {} resumed", tid));
+                                            }
+                                            Err(e) => {
+                                                status_message.set(format!("✗ Failed to resume thread {}: {}", tid, e));
+                                            }
+                                        }
+                                        spawn(async move {
+                                            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                                            status_message.set(String::new());
+                                        });
                                     }
-                                    spawn(async move {
-                                        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-                                        status_message.set(String::new());
-                                    });
-                                }
-                                context_menu.set(ThreadContextMenuState::default());
-                            },
-                            span { "▶️" }
-                            span { "Resume Thread" }
-                        }
+                                    context_menu.set(ThreadContextMenuState::default());
+                                },
+                                span { "▶️" }
+                                span { "Resume Thread" }
+                            }
 
-                        div { class: "context-menu-separator" }
+                            div { class: "context-menu-separator" }
 
-                        button {
-                            class: "context-menu-item context-menu-item-danger",
-                            onclick: move |_| {
-                                if let Some(tid) = ctx_menu.thread_id {
-                                    if kill_thread(tid) {
-                                        status_message.set(format!("☠️ Thread {} terminated", tid));
-                                        threads.set(get_process_threads(pid));
-                                    } else {
-                                        status_message.set(format!("✗ Failed to terminate thread {}", tid));
+                            button {
+                                class: "context-menu-item context-menu-item-danger",
+                                onclick: move |_| {
+                                    if let Some(tid) = ctx_menu.thread_id {
+                                        match kill_thread(tid) {
+                                            Ok(()) => {
+                                                status_message.set(format!("☠️ Thread {} terminated", tid));
+                                                threads.set(source::threads(pid));
+                                            }
+                                            Err(e) => {
+                                                status_message.set(format!("✗ Failed to terminate thread {}: {}", tid, e));
+                                            }
+                                        }
+                                        spawn(async move {
+                                            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                                            status_message.set(String::new());
+                                        });
                                     }
-                                    spawn(async move {
-                                        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-                                        status_message.set(String::new());
-                                    });
-                                }
-                                context_menu.set(ThreadContextMenuState::default());
-                            },
-                            span { "☠️" }
-                            span { "Kill Thread" }
-                        }
+                                    context_menu.set(ThreadContextMenuState::default());
+                                },
+                                span { "☠️" }
+                                span { "Kill Thread" }
+                            }
 
-                        div { class: "context-menu-separator" }
+                            div { class: "context-menu-separator" }
+                        }
 
                         button {
                             class: "context-menu-item",
@@ -1,6 +1,10 @@
 //! Helper functions for the UI
 
 use arboard::Clipboard;
+use export::ExportFormat;
+use pe::hex_digest;
+use process::{get_file_packer_assessment, get_file_signature, SignatureStatus};
+use serde::Serialize;
 
 /// Copy text to clipboard
 pub fn copy_to_clipboard(text: &str) -> bool {
@@ -10,3 +14,93 @@ pub fn copy_to_clipboard(text: &str) -> bool {
         false
     }
 }
+
+/// Text, CSS class and tooltip for the "Signer" column of a file
+pub fn signer_cell(path: &str) -> (String, &'static str, String) {
+    let status = match get_file_signature(path) {
+        Some(status) => status,
+        None => return (String::new(), "cell cell-signer", String::new()),
+    };
+
+    let class = match &status {
+        SignatureStatus::Signed(sig) if sig.hash_matches() => "cell cell-signer signer-valid",
+        SignatureStatus::Unsigned => "cell cell-signer signer-unsigned",
+        _ => "cell cell-signer signer-invalid",
+    };
+
+    let tooltip = match &status {
+        SignatureStatus::Signed(sig) => format!(
+            "Subject: {}\nIssuer: {}\nSerial: {}\nTimestamp: {}\n{} digest: {}\nImage hash: {}\n\
+             Only the image hash is checked; the signature and certificate chain are not verified",
+            sig.signer_subject,
+            sig.signer_issuer,
+            sig.signer_serial,
+            sig.timestamp.as_deref().unwrap_or("none"),
+            sig.digest_algorithm,
+            hex_digest(&sig.signed_digest),
+            if sig.hash_matches() { "matches" } else { "MISMATCH" },
+        ),
+        SignatureStatus::Unsigned => {
+            "No embedded Authenticode signature (catalog signatures are not checked)".to_string()
+        }
+        SignatureStatus::Malformed => "Security directory could not be parsed".to_string(),
+    };
+
+    (status.signer_label(), class, tooltip)
+}
+
+/// Text, CSS class and tooltip for the packer score column of a file
+pub fn packer_cell(path: &str) -> (String, &'static str, String) {
+    let assessment = match get_file_packer_assessment(path) {
+        Some(assessment) => assessment,
+        None => return (String::new(), "cell cell-packer", String::new()),
+    };
+
+    let class = match assessment.verdict() {
+        "Likely packed" => "cell cell-packer packer-packed",
+        "Suspicious" => "cell cell-packer packer-suspicious",
+        _ => "cell cell-packer packer-clean",
+    };
+    let mut tooltip = format!("{} (score {})", assessment.verdict(), assessment.score);
+    for finding in &assessment.findings {
+        tooltip.push_str(&format!("\n+{} {}", finding.weight, finding.reason));
+    }
+
+    (assessment.score.to_string(), class, tooltip)
+}
+
+/// Reason a list could not be loaded, shown above its (empty) table
+pub fn load_error<T, E: std::fmt::Display>(result: &Result<Vec<T>, E>) -> Option<String> {
+    result.as_ref().err().map(|e| format!("⚠ {}", e))
+}
+
+/// Ask where to save an export, then write the records built by `records` in
+/// the format matching the chosen file's extension. Returns the status line
+/// to show, or `None` if the dialog was cancelled.
+pub async fn export_records<T: Serialize>(
+    title: &str,
+    file_stem: &str,
+    noun: &str,
+    records: impl FnOnce() -> Vec<T>,
+) -> Option<String> {
+    let mut dialog = rfd::AsyncFileDialog::new()
+        .set_file_name(format!("{}.csv", file_stem))
+        .set_title(title);
+    for format in ExportFormat::ALL {
+        dialog = dialog.add_filter(format.to_string(), &[format.extension()]);
+    }
+    let file = dialog.save_file().await?;
+    let path = file.path();
+
+    let records = records();
+    Some(match export::save(path, &records) {
+        Ok(format) => format!(
+            "Exported {} {} to {} ({})",
+            records.len(),
+            noun,
+            path.display(),
+            format
+        ),
+        Err(e) => format!("Export failed: {}", e),
+    })
+}
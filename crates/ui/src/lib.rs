@@ -3,7 +3,9 @@
 
 mod components;
 mod helpers;
+mod records;
 pub mod routes;
+mod source;
 mod state;
 mod styles;
 
@@ -0,0 +1,88 @@
+//! Export rows that add display columns to the library types
+
+use process::{
+    format_start_time, get_file_hashes, get_memory_protect_name, get_memory_state_name,
+    get_memory_type_name, MemoryRegionInfo, ModuleInfo, ProcessInfo,
+};
+use serde::Serialize;
+
+/// A process with its formatted start time and file hashes
+#[derive(Serialize)]
+pub struct ProcessRecord {
+    #[serde(flatten)]
+    pub process: ProcessInfo,
+    pub started: String,
+    pub sha256: String,
+    pub sha1: String,
+    pub md5: String,
+    pub imphash: String,
+}
+
+impl ProcessRecord {
+    /// Hashes the executable, so only build these for an export
+    pub fn new(process: ProcessInfo) -> Self {
+        let hashes = get_file_hashes(&process.exe_path).unwrap_or_default();
+        Self {
+            started: format_start_time(process.start_time),
+            sha256: hashes.sha256,
+            sha1: hashes.sha1,
+            md5: hashes.md5,
+            imphash: hashes.imphash.unwrap_or_default(),
+            process,
+        }
+    }
+}
+
+/// A module with its file hashes
+#[derive(Serialize)]
+pub struct ModuleRecord {
+    #[serde(flatten)]
+    pub module: ModuleInfo,
+    pub sha256: String,
+    pub sha1: String,
+    pub md5: String,
+    pub imphash: String,
+}
+
+impl ModuleRecord {
+    /// Hashes the module file, so only build these for an export
+    pub fn new(module: ModuleInfo) -> Self {
+        let hashes = get_file_hashes(&module.path).unwrap_or_default();
+        Self {
+            sha256: hashes.sha256,
+            sha1: hashes.sha1,
+            md5: hashes.md5,
+            imphash: hashes.imphash.unwrap_or_default(),
+            module,
+        }
+    }
+}
+
+/// A memory region with hex addresses and named state, type and protection
+#[derive(Serialize)]
+pub struct MemoryRegionRecord {
+    pub base_address: String,
+    pub allocation_base: String,
+    pub region_size: usize,
+    pub state: &'static str,
+    pub mem_type: &'static str,
+    pub protect: String,
+    pub allocation_protect: String,
+    /// Module mapped at the allocation base, for image regions
+    pub module: String,
+}
+
+impl MemoryRegionRecord {
+    pub fn new(region: &MemoryRegionInfo, module: Option<&str>) -> Self {
+        Self {
+            base_address: format!("0x{:X}", region.base_address),
+            allocation_base: format!("0x{:X}", region.allocation_base),
+            region_size: region.region_size,
+            state: get_memory_state_name(region.state),
+            mem_type: get_memory_type_name(region.mem_type),
+            protect: get_memory_protect_name(region.protect),
+            allocation_protect: get_memory_protect_name(region.allocation_protect),
+            module: module.unwrap_or_default().to_string(),
+        }
+    }
+}
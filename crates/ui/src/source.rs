@@ -0,0 +1,97 @@
+//! Data behind the views: the live system, or a snapshot opened read-only
+
+use std::sync::Arc;
+
+use dioxus::prelude::*;
+use network::{get_network_connections, NetworkConnection, NetworkError};
+use process::{
+    get_process_handles, get_process_memory_regions, get_process_modules, get_process_threads,
+    get_processes, pause_history_sampler, HandleInfo, MemoryRegionInfo, ModuleInfo, ProcessError,
+    ProcessInfo, ThreadInfo,
+};
+use service::{get_services, ServiceError, ServiceInfo};
+use snapshot::Snapshot;
+
+use crate::state::{
+    GRAPH_WINDOW_STATE, HANDLE_WINDOW_STATE, MEMORY_WINDOW_STATE, MODULE_WINDOW_STATE,
+    OPEN_SNAPSHOT, THREAD_WINDOW_STATE,
+};
+
+/// Whether a saved snapshot is being viewed, in which case every action
+/// that would touch the live system is hidden
+pub fn is_read_only() -> bool {
+    OPEN_SNAPSHOT.read().is_some()
+}
+
+/// Switch the views to `snapshot`, or back to the live system with `None`
+pub fn set_snapshot(snapshot: Option<Snapshot>) {
+    // Detail windows belong to the previous source
+    *THREAD_WINDOW_STATE.write() = None;
+    *HANDLE_WINDOW_STATE.write() = None;
+    *MODULE_WINDOW_STATE.write() = None;
+    *MEMORY_WINDOW_STATE.write() = None;
+    *GRAPH_WINDOW_STATE.write() = None;
+    // Live history is not shown for a snapshot, so stop collecting it meanwhile
+    pause_history_sampler(snapshot.is_some());
+    *OPEN_SNAPSHOT.write() = snapshot.map(Arc::new);
+}
+
+pub fn processes() -> Vec<ProcessInfo> {
+    match OPEN_SNAPSHOT.read().as_deref() {
+        Some(snapshot) => snapshot.processes.clone(),
+        None => get_processes(),
+    }
+}
+
+/// A detail list from the open snapshot, or an error saying it was not captured
+fn recorded<T: Clone>(
+    items: Option<&[T]>,
+    pid: u32,
+    what: &'static str,
+) -> Result<Vec<T>, ProcessError> {
+    items
+        .map(<[T]>::to_vec)
+        .ok_or(ProcessError::NotCaptured { pid, what })
+}
+
+pub fn threads(pid: u32) -> Result<Vec<ThreadInfo>, ProcessError> {
+    match OPEN_SNAPSHOT.read().as_deref() {
+        Some(snapshot) => recorded(snapshot.threads(pid), pid, "Threads"),
+        None => get_process_threads(pid),
+    }
+}
+
+pub fn modules(pid: u32) -> Result<Vec<ModuleInfo>, ProcessError> {
+    match OPEN_SNAPSHOT.read().as_deref() {
+        Some(snapshot) => recorded(snapshot.modules(pid), pid, "Modules"),
+        None => get_process_modules(pid),
+    }
+}
+
+pub fn handles(pid: u32) -> Result<Vec<HandleInfo>, ProcessError> {
+    match OPEN_SNAPSHOT.read().as_deref() {
+        Some(snapshot) => recorded(snapshot.handles(pid), pid, "Handles"),
+        None => get_process_handles(pid),
+    }
+}
+
+pub fn memory_regions(pid: u32) -> Result<Vec<MemoryRegionInfo>, ProcessError> {
+    match OPEN_SNAPSHOT.read().as_deref() {
+        Some(snapshot) => recorded(snapshot.memory_regions(pid), pid, "Memory regions"),
+        None => get_process_memory_regions(pid),
+    }
+}
+
+pub fn connections() -> Result<Vec<NetworkConnection>, NetworkError> {
+    match OPEN_SNAPSHOT.read().as_deref() {
+        Some(snapshot) => Ok(snapshot.connections.clone()),
+        None => get_network_connections(),
+    }
+}
+
+pub fn services() -> Result<Vec<ServiceInfo>, ServiceError> {
+    match OPEN_SNAPSHOT.read().as_deref() {
+        Some(snapshot) => Ok(snapshot.services.clone()),
+        None => get_services(),
+    }
+}
@@ -1,6 +1,9 @@
 //! State types and enums for the UI
 
+use std::sync::Arc;
+
 use dioxus::prelude::*;
+use snapshot::{Snapshot, SnapshotDiff};
 
 /// Thread window state - stores PID and process name to open in new window
 pub static THREAD_WINDOW_STATE: GlobalSignal<Option<(u32, String)>> = Signal::global(|| None);
@@ -17,22 +20,12 @@ pub static MEMORY_WINDOW_STATE: GlobalSignal<Option<(u32, String)>> = Signal::gl
 /// Graph window state - stores PID and process name to open in new window
 pub static GRAPH_WINDOW_STATE: GlobalSignal<Option<(u32, String)>> = Signal::global(|| None);
 
-/// Sort column options
-#[derive(Clone, Copy, PartialEq, Debug)]
-pub enum SortColumn {
-    Pid,
-    Name,
-    Memory,
-    Threads,
-    Cpu,
-}
+/// Diff window state - stores the labels of the compared snapshots and what changed
+pub static DIFF_WINDOW_STATE: GlobalSignal<Option<(String, String, Arc<SnapshotDiff>)>> =
+    Signal::global(|| None);
 
-/// Sort order options
-#[derive(Clone, Copy, PartialEq, Debug)]
-pub enum SortOrder {
-    Ascending,
-    Descending,
-}
+/// Snapshot opened for offline viewing; `None` while showing the live system
+pub static OPEN_SNAPSHOT: GlobalSignal<Option<Arc<Snapshot>>> = Signal::global(|| None);
 
 /// Context menu state for main process list
 #[derive(Clone, Debug, Default)]
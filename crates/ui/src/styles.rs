@@ -221,6 +221,16 @@ pub const CUSTOM_STYLES: &str = r#"
         cursor: pointer;
         user-select: none;
     }
+    .column-picker {
+        display: flex;
+        flex-wrap: wrap;
+        gap: 8px 16px;
+        margin-bottom: 16px;
+        padding: 12px 16px;
+        border-radius: 8px;
+        background: rgba(255, 255, 255, 0.05);
+        flex-shrink: 0;
+    }
     .checkbox {
         width: 16px;
         height: 16px;
@@ -318,6 +328,13 @@ pub const CUSTOM_STYLES: &str = r#"
     .process-row.selected:hover {
         background: rgba(239, 68, 68, 0.3);
     }
+    .process-row.row-started {
+        background: rgba(34, 197, 94, 0.25);
+    }
+    .process-row.row-exited {
+        background: rgba(220, 38, 38, 0.4);
+        color: #fecaca;
+    }
     .cell {
         padding: 12px 16px;
     }
@@ -329,6 +346,23 @@ pub const CUSTOM_STYLES: &str = r#"
     .cell-name {
         font-weight: 500;
     }
+    .tree-toggle {
+        display: inline-block;
+        width: 16px;
+        color: #9ca3af;
+        cursor: pointer;
+        user-select: none;
+    }
+    .tree-toggle:hover {
+        color: #22d3ee;
+    }
+    .tree-orphan {
+        font-style: italic;
+    }
+    .tree-hidden-count {
+        font-size: 11px;
+        color: #6b7280;
+    }
     .cell-cpu {
         font-family: monospace;
         width: 80px;
@@ -343,6 +377,12 @@ pub const CUSTOM_STYLES: &str = r#"
     .cell-memory {
         width: 176px;
     }
+    .cell-stat {
+        font-family: monospace;
+        color: #d1d5db;
+        text-align: right;
+        white-space: nowrap;
+    }
     .cell-path {
         font-size: 12px;
         color: #6b7280;
@@ -354,6 +394,45 @@ pub const CUSTOM_STYLES: &str = r#"
     .cell-path:hover {
         color: #9ca3af;
     }
+    .cell-description,
+    .cell-company,
+    .cell-user {
+        font-size: 12px;
+        color: #d1d5db;
+        max-width: 200px;
+        overflow: hidden;
+        text-overflow: ellipsis;
+        white-space: nowrap;
+    }
+    .cell-packer {
+        font-family: 'Cascadia Code', 'Consolas', monospace;
+        font-size: 12px;
+        text-align: right;
+    }
+    .packer-clean {
+        color: #6b7280;
+    }
+    .packer-suspicious {
+        color: #fbbf24;
+        font-weight: 600;
+    }
+    .packer-packed {
+        color: #f87171;
+        font-weight: 600;
+    }
+    .cell-signer {
+        font-size: 12px;
+        max-width: 180px;
+        overflow: hidden;
+        text-overflow: ellipsis;
+        white-space: nowrap;
+    }
+    .signer-valid { color: #4ade80; }
+    .signer-unsigned { color: #6b7280; }
+    .signer-invalid {
+        color: #f87171;
+        font-weight: 600;
+    }
 
     /* CPU Colors */
     .cpu-low { color: #4ade80; }
@@ -534,6 +613,248 @@ pub const CUSTOM_STYLES: &str = r#"
         color: #d1d5db;
         background: rgba(255, 255, 255, 0.03);
     }
+    .module-import-host {
+        font-weight: 400;
+        color: #9ca3af;
+    }
+    .module-import-kind {
+        margin-left: 8px;
+        padding: 1px 6px;
+        font-size: 10px;
+        font-weight: 600;
+        text-transform: uppercase;
+        color: #22d3ee;
+        background: rgba(34, 211, 238, 0.1);
+        border: 1px solid rgba(34, 211, 238, 0.3);
+        border-radius: 4px;
+    }
+
+    /* Module Export View */
+    .module-inspect-modal {
+        width: 1100px;
+    }
+    .module-inspect-panels {
+        display: flex;
+        flex: 1;
+        min-height: 0;
+    }
+    .module-inspect-panel {
+        flex: 1;
+        overflow-y: auto;
+        padding-bottom: 20px;
+    }
+    .module-inspect-panel + .module-inspect-panel {
+        border-left: 1px solid rgba(34, 211, 238, 0.2);
+    }
+    .module-inspect-panel-title {
+        position: sticky;
+        top: 0;
+        padding: 8px 20px;
+        font-size: 13px;
+        font-weight: 600;
+        color: #22d3ee;
+        background: #1a1a2e;
+        border-bottom: 1px solid rgba(34, 211, 238, 0.2);
+    }
+    .module-export-row {
+        display: flex;
+        gap: 12px;
+        padding: 3px 20px;
+        font-family: 'Cascadia Code', 'Consolas', monospace;
+        font-size: 12px;
+        color: #9ca3af;
+    }
+    .module-export-row:hover {
+        color: #d1d5db;
+        background: rgba(255, 255, 255, 0.03);
+    }
+    .module-export-ordinal {
+        min-width: 48px;
+        color: #6b7280;
+    }
+    .module-export-rva {
+        min-width: 84px;
+        color: #facc15;
+    }
+    .module-export-forwarder {
+        color: #a855f7;
+    }
+
+    /* PE Headers View */
+    .pe-headers-view {
+        flex: 1;
+        overflow-y: auto;
+        padding: 12px 20px 20px;
+    }
+    .pe-headers-view .thread-table {
+        margin-bottom: 16px;
+    }
+    .pe-mitigations {
+        display: flex;
+        gap: 8px;
+        margin-bottom: 12px;
+    }
+    .pe-mitigation {
+        padding: 3px 10px;
+        font-size: 12px;
+        font-weight: 600;
+        border-radius: 4px;
+    }
+    .pe-mitigation-on {
+        color: #4ade80;
+        background: rgba(74, 222, 128, 0.1);
+        border: 1px solid rgba(74, 222, 128, 0.3);
+    }
+    .pe-mitigation-off {
+        color: #f87171;
+        background: rgba(248, 113, 113, 0.1);
+        border: 1px solid rgba(248, 113, 113, 0.3);
+    }
+    .pe-headers-grid {
+        display: grid;
+        grid-template-columns: repeat(3, 1fr);
+        gap: 12px;
+        margin-bottom: 16px;
+    }
+    .pe-header-block {
+        background: rgba(255, 255, 255, 0.02);
+        border: 1px solid rgba(255, 255, 255, 0.06);
+        border-radius: 6px;
+        padding-bottom: 8px;
+    }
+    .pe-field-row {
+        display: flex;
+        justify-content: space-between;
+        gap: 12px;
+        padding: 3px 12px;
+        font-size: 12px;
+    }
+    .pe-field-row:hover {
+        background: rgba(255, 255, 255, 0.03);
+    }
+    .pe-field-name {
+        color: #9ca3af;
+    }
+    .pe-field-value {
+        font-family: 'Cascadia Code', 'Consolas', monospace;
+        color: #e5e7eb;
+        text-align: right;
+    }
+    .pe-flags {
+        display: flex;
+        flex-wrap: wrap;
+        gap: 4px;
+        padding: 6px 12px 0;
+    }
+    .pe-flag {
+        padding: 1px 6px;
+        font-size: 10px;
+        color: #22d3ee;
+        background: rgba(34, 211, 238, 0.1);
+        border-radius: 4px;
+    }
+    .pe-row-empty td {
+        color: #4b5563;
+    }
+    .pe-entropy-high {
+        color: #f87171;
+        font-weight: 600;
+    }
+
+    /* Module Integrity */
+    .module-integrity-badge {
+        margin-left: 8px;
+        padding: 1px 6px;
+        font-size: 10px;
+        font-weight: 600;
+        border-radius: 4px;
+    }
+    .module-integrity-modified {
+        color: #f87171;
+        background: rgba(248, 113, 113, 0.1);
+        border: 1px solid rgba(248, 113, 113, 0.3);
+        text-transform: uppercase;
+    }
+    .module-integrity-clean {
+        color: #4ade80;
+    }
+    .module-integrity-unverified {
+        color: #fbbf24;
+        background: rgba(251, 191, 36, 0.1);
+        border: 1px solid rgba(251, 191, 36, 0.3);
+        text-transform: uppercase;
+    }
+    .integrity-clean {
+        color: #4ade80;
+    }
+    .integrity-modified {
+        color: #f87171;
+        font-weight: 600;
+    }
+    .integrity-unverified {
+        color: #fbbf24;
+    }
+    .integrity-bytes {
+        font-family: 'Cascadia Code', 'Consolas', monospace;
+        font-size: 11px;
+    }
+
+    /* Module Resources */
+    .resource-type-row {
+        padding: 6px 10px;
+        font-weight: 600;
+        color: #22d3ee;
+        background: rgba(34, 211, 238, 0.05);
+    }
+    .resource-format {
+        color: #9ca3af;
+    }
+    .resource-format-pe {
+        color: #f87171;
+        font-weight: 600;
+    }
+
+    /* File Details Pane */
+    .file-details-pane {
+        flex-shrink: 0;
+        margin-top: 12px;
+        padding: 12px 16px;
+        max-height: 240px;
+        overflow-y: auto;
+        background: rgba(255, 255, 255, 0.05);
+        border-radius: 12px;
+    }
+    .file-details-value {
+        overflow: hidden;
+        text-overflow: ellipsis;
+        white-space: nowrap;
+    }
+    .file-details-hash {
+        font-family: 'Cascadia Code', 'Consolas', monospace;
+        font-size: 11px;
+        user-select: text;
+    }
+    .file-details-copy {
+        margin-left: 8px;
+        padding: 0 6px;
+        background: transparent;
+        border: 1px solid rgba(255, 255, 255, 0.1);
+        border-radius: 4px;
+        color: #9ca3af;
+        cursor: pointer;
+    }
+    .file-details-copy:hover {
+        color: #22d3ee;
+        border-color: rgba(34, 211, 238, 0.4);
+    }
+    .details-panes {
+        display: flex;
+        gap: 12px;
+    }
+    .details-panes > .file-details-pane {
+        flex: 1;
+        min-width: 0;
+    }
 
     /* Thread Modal */
     .thread-modal-overlay {
@@ -605,6 +926,15 @@ pub const CUSTOM_STYLES: &str = r#"
         font-size: 14px;
         color: #22d3ee;
     }
+    .load-error {
+        margin: 8px 20px;
+        padding: 8px 16px;
+        background: rgba(239, 68, 68, 0.15);
+        border-left: 4px solid #ef4444;
+        border-radius: 6px;
+        font-size: 14px;
+        color: #f87171;
+    }
     .thread-table-container {
         flex: 1;
         overflow-y: auto;
@@ -771,6 +1101,68 @@ pub const CUSTOM_STYLES: &str = r#"
         background: rgba(34, 211, 238, 0.1);
     }
 
+    .tab-bar-actions {
+        margin-left: auto;
+        display: flex;
+        align-items: center;
+        gap: 8px;
+    }
+    .snapshot-message {
+        color: #9ca3af;
+        font-size: 12px;
+        max-width: 360px;
+        overflow: hidden;
+        text-overflow: ellipsis;
+        white-space: nowrap;
+    }
+
+    /* Snapshot Banner */
+    .snapshot-banner {
+        background: linear-gradient(to right, rgba(120, 53, 15, 0.6), rgba(30, 41, 59, 0.8));
+        border-bottom-color: rgba(250, 204, 21, 0.3);
+    }
+    .snapshot-badge {
+        color: #facc15;
+        font-weight: 600;
+        letter-spacing: 0.5px;
+    }
+    .snapshot-read-only {
+        color: #fbbf24;
+        border: 1px solid rgba(251, 191, 36, 0.4);
+        border-radius: 4px;
+        padding: 2px 8px;
+    }
+
+    /* Snapshot Diff */
+    .diff-modal {
+        width: 1000px;
+    }
+    .diff-summary {
+        display: flex;
+        flex-wrap: wrap;
+        gap: 16px;
+        padding: 8px 20px;
+        font-size: 13px;
+        color: #9ca3af;
+        border-bottom: 1px solid rgba(34, 211, 238, 0.1);
+    }
+    .diff-kind { font-weight: 500; white-space: nowrap; }
+    .diff-kind-added { color: #4ade80; }
+    .diff-kind-removed { color: #f87171; }
+    .diff-kind-changed { color: #facc15; }
+    .diff-kind-suspicious { color: #fb923c; }
+    .diff-detail {
+        font-family: monospace;
+        font-size: 12px;
+        color: #d1d5db;
+        word-break: break-all;
+    }
+    .diff-empty {
+        padding: 24px;
+        text-align: center;
+        color: #6b7280;
+    }
+
     /* Tab Content */
     .process-tab,
     .network-tab,
@@ -1215,7 +1607,9 @@ pub const CUSTOM_STYLES: &str = r#"
 
     /* Graph Window */
     .graph-modal {
-        width: 500px;
+        width: 560px;
+        max-height: 90vh;
+        overflow-y: auto;
     }
     .graph-content {
         padding: 16px 20px;
@@ -1233,6 +1627,16 @@ pub const CUSTOM_STYLES: &str = r#"
         font-size: 12px;
         margin-left: auto;
     }
+    .graph-series {
+        display: flex;
+        flex-wrap: wrap;
+        gap: 4px 16px;
+    }
+    .graph-empty {
+        color: #6b7280;
+        font-size: 13px;
+        text-align: center;
+    }
     .graph-section {
         display: flex;
         flex-direction: column;
@@ -1259,6 +1663,18 @@ pub const CUSTOM_STYLES: &str = r#"
     .graph-value-mem {
         color: #a855f7;
     }
+    .graph-value-private {
+        color: #f472b6;
+    }
+    .graph-value-handles {
+        color: #fbbf24;
+    }
+    .graph-value-threads {
+        color: #4ade80;
+    }
+    .graph-value-io {
+        color: #60a5fa;
+    }
     .graph-container {
         position: relative;
         background: rgba(0, 0, 0, 0.3);
@@ -1283,6 +1699,18 @@ pub const CUSTOM_STYLES: &str = r#"
     .graph-line-mem {
         stroke: #a855f7;
     }
+    .graph-line-private {
+        stroke: #f472b6;
+    }
+    .graph-line-handles {
+        stroke: #fbbf24;
+    }
+    .graph-line-threads {
+        stroke: #4ade80;
+    }
+    .graph-line-io {
+        stroke: #60a5fa;
+    }
     .graph-fill {
         opacity: 0.2;
     }
@@ -1292,6 +1720,18 @@ pub const CUSTOM_STYLES: &str = r#"
     .graph-fill-mem {
         fill: #a855f7;
     }
+    .graph-fill-private {
+        fill: #f472b6;
+    }
+    .graph-fill-handles {
+        fill: #fbbf24;
+    }
+    .graph-fill-threads {
+        fill: #4ade80;
+    }
+    .graph-fill-io {
+        fill: #60a5fa;
+    }
     .graph-y-labels {
         position: absolute;
         right: 12px;